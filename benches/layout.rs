@@ -0,0 +1,181 @@
+//! layout and draw benchmarks over large synthetic trees. run with:
+//!
+//! cargo bench --features instrumentation
+//!
+//! with the instrumentation feature, the work done in one frame of each tree
+//! is also printed
+
+use std::{cell::Cell, fs::File, io::Read, path::Path};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use sdl2::{pixels::Color, render::ClippingRect};
+use tiny_sdl2_gui::{
+    layout::{horizontal_layout::HorizontalLayout, vertical_layout::VerticalLayout},
+    util::{
+        focus::FocusManager,
+        font::{FontManager, SingleLineTextRenderType, TextRenderer},
+        length::{AspectRatioPreferredDirection, MaxLen},
+        perf::{perf_counters, take_perf_counters},
+        rect::FRect,
+        rust::CellRefOrCell,
+    },
+    widget::{
+        draw_gui, place, single_line_label::SingleLineLabel, strut::Strut, update_gui, Widget,
+        WidgetUpdateEvent,
+    },
+};
+
+const AREA: FRect = FRect {
+    x: 0.,
+    y: 0.,
+    w: 800.,
+    h: 600.,
+};
+
+/// lay out and update a tree once, without a window
+fn layout_frame(widget: &mut dyn Widget, focus_manager: &mut FocusManager) {
+    let aspect_ratio_priority = AspectRatioPreferredDirection::default();
+    let position = place(widget, AREA, aspect_ratio_priority).unwrap();
+    widget
+        .update(WidgetUpdateEvent {
+            focus_manager,
+            position,
+            clipping_rect: ClippingRect::None,
+            window_id: 0,
+            aspect_ratio_priority,
+            events: &mut [],
+            window_area: AREA,
+            input: Default::default(),
+        })
+        .unwrap();
+}
+
+fn print_counters(name: &str) {
+    if cfg!(feature = "instrumentation") {
+        println!("{}: {:?}", name, perf_counters());
+    }
+}
+
+/// many siblings: 1k struts in rows of 10
+fn wide_tree<'sdl>() -> VerticalLayout<'sdl> {
+    let mut rows = VerticalLayout::default();
+    for _ in 0..100 {
+        let mut row = HorizontalLayout::default();
+        for _ in 0..10 {
+            row.elems
+                .push(Box::new(Strut::shrinkable(MaxLen(20.), MaxLen(5.))));
+        }
+        rows.elems.push(Box::new(row));
+    }
+    rows
+}
+
+/// deep nesting: alternating layouts, each containing the next
+fn deep_tree<'sdl>(depth: usize) -> Box<dyn Widget + 'sdl> {
+    let mut widget: Box<dyn Widget + 'sdl> = Box::new(Strut::shrinkable(MaxLen::LAX, MaxLen::LAX));
+    for i in 0..depth {
+        widget = if i % 2 == 0 {
+            let mut layout = VerticalLayout::default();
+            layout.elems.push(widget);
+            Box::new(layout)
+        } else {
+            let mut layout = HorizontalLayout::default();
+            layout.elems.push(widget);
+            Box::new(layout)
+        };
+    }
+    widget
+}
+
+fn layout_benchmarks(c: &mut Criterion) {
+    let mut focus_manager = FocusManager::default();
+
+    let mut wide = wide_tree();
+    take_perf_counters();
+    layout_frame(&mut wide, &mut focus_manager);
+    print_counters("1k struts");
+    c.bench_function("layout 1k struts", |b| {
+        b.iter(|| layout_frame(&mut wide, &mut focus_manager))
+    });
+
+    let mut deep = deep_tree(200);
+    take_perf_counters();
+    layout_frame(deep.as_mut(), &mut focus_manager);
+    print_counters("200 deep");
+    c.bench_function("layout 200 deep", |b| {
+        b.iter(|| layout_frame(deep.as_mut(), &mut focus_manager))
+    });
+}
+
+/// needs a video device. skipped if one isn't available
+fn label_benchmarks(c: &mut Criterion) {
+    let sdl_context = match sdl2::init() {
+        Ok(v) => v,
+        Err(msg) => {
+            println!("skipping label benchmarks: {}", msg);
+            return;
+        }
+    };
+    let video = match sdl_context.video() {
+        Ok(v) => v,
+        Err(msg) => {
+            println!("skipping label benchmarks: {}", msg);
+            return;
+        }
+    };
+    let window = video
+        .window("bench", AREA.w as u32, AREA.h as u32)
+        .hidden()
+        .build()
+        .unwrap();
+    let mut canvas = window.into_canvas().software().build().unwrap();
+    let texture_creator = canvas.texture_creator();
+    let ttf_context = sdl2::ttf::init().unwrap();
+
+    let mut font_file_contents: Vec<u8> = Vec::new();
+    File::open(
+        Path::new(".")
+            .join("examples")
+            .join("assets")
+            .join("TEMPSITC-REDUCED.TTF"),
+    )
+    .unwrap()
+    .read_to_end(&mut font_file_contents)
+    .unwrap();
+    let font_manager = Cell::new(Some(FontManager::new(&ttf_context, &font_file_contents)));
+
+    let mut rows = VerticalLayout::default();
+    for i in 0..100 {
+        let mut row = HorizontalLayout::default();
+        for j in 0..10 {
+            row.elems.push(Box::new(SingleLineLabel::new(
+                CellRefOrCell::Cell(Cell::new(format!("{}", i * 10 + j))),
+                SingleLineTextRenderType::Blended(Color::WHITE),
+                Box::new(TextRenderer::new(&font_manager)),
+                &texture_creator,
+            )));
+        }
+        rows.elems.push(Box::new(row));
+    }
+
+    let mut focus_manager = FocusManager::default();
+    let mut frame = |canvas: &mut sdl2::render::WindowCanvas| {
+        update_gui(&mut rows, &mut [], &mut focus_manager, canvas).unwrap();
+        draw_gui(&mut rows, canvas, &focus_manager).unwrap();
+    };
+
+    // the first frame renders every texture
+    take_perf_counters();
+    frame(&mut canvas);
+    print_counters("1k labels, first frame");
+    take_perf_counters();
+    frame(&mut canvas);
+    print_counters("1k labels, cached frame");
+
+    c.bench_function("update and draw 1k labels", |b| {
+        b.iter(|| frame(&mut canvas))
+    });
+}
+
+criterion_group!(benches, layout_benchmarks, label_benchmarks);
+criterion_main!(benches);
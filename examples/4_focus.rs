@@ -2,20 +2,7 @@ use std::{cell::Cell, fs::File, io::Read, path::Path, time::Duration};
 
 use example_common::gui_loop::gui_loop;
 use sdl2::{mouse::MouseButton, pixels::Color};
-use tiny_sdl2_gui::{
-    layout::{horizontal_layout::HorizontalLayout, vertical_layout::VerticalLayout},
-    util::{
-        focus::{FocusID, FocusManager},
-        font::{FontManager, SingleLineTextRenderType, TextRenderer},
-        length::{MaxLen, MaxLenPolicy},
-    },
-    widget::{
-        button::{Button, LabelButtonStyle},
-        checkbox::{CheckBox, DefaultCheckBoxStyle},
-        single_line_label::SingleLineLabel,
-        update_gui, Widget,
-    },
-};
+use tiny_sdl2_gui::prelude::*;
 
 #[path = "example_common/mod.rs"]
 mod example_common;
@@ -170,6 +170,7 @@ fn main() -> std::process::ExitCode {
         .position_centered()
         .build()
         .unwrap();
+    example_common::gui_loop::use_display_scale(&sdl_video_subsystem, &window);
     let mut canvas = window.into_canvas().present_vsync().build().unwrap();
     let texture_creator = canvas.texture_creator();
     let mut event_pump = sdl_context.event_pump().unwrap();
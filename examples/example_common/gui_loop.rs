@@ -1,7 +1,26 @@
 use std::time::{Duration, Instant};
 
-use sdl2::EventPump;
-use tiny_sdl2_gui::widget::SDLEvent;
+use sdl2::{video::Window, EventPump, VideoSubsystem};
+use tiny_sdl2_gui::{
+    util::{
+        animation::take_redraw_request,
+        scale::{display_dpi_scale, set_ui_scale},
+    },
+    widget::SDLEvent,
+};
+
+/// set the ui scale from the dpi of the display the window is on. leaves the
+/// scale at 1 if the dpi can't be queried
+#[allow(dead_code)]
+pub fn use_display_scale(video: &VideoSubsystem, window: &Window) {
+    let scale = window
+        .display_index()
+        .map_err(Into::into)
+        .and_then(|display_index| display_dpi_scale(video, display_index));
+    if let Ok(scale) = scale {
+        set_ui_scale(scale);
+    }
+}
 
 /// a helper for the examples. but could do done in a variety of ways
 #[allow(dead_code)]
@@ -12,13 +31,20 @@ where
     // accumulate the events for this frame
     let mut events_accumulator: Vec<SDLEvent> = Vec::new();
     'running: loop {
-        // wait forever since nothing has happened yet!
-        let event = event_pump.wait_event();
+        let event = if take_redraw_request() {
+            // something is animating. don't wait longer than a frame
+            event_pump.wait_event_timeout(max_delay.as_millis() as u32)
+        } else {
+            // wait forever since nothing has happened yet!
+            Some(event_pump.wait_event())
+        };
         let oldest_event = Instant::now(); // immediately after event received
-        if let sdl2::event::Event::Quit { .. } = event {
-            break 'running;
+        if let Some(event) = event {
+            if let sdl2::event::Event::Quit { .. } = event {
+                break 'running;
+            }
+            events_accumulator.push(SDLEvent::new(event));
         }
-        events_accumulator.push(SDLEvent::new(event));
 
         // don't send off the event immediately! wait a bit and accumulate
         // several events to be processed together. max bound on waiting so that
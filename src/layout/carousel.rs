@@ -0,0 +1,629 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use sdl2::{
+    event::WindowEvent, keyboard::Keycode, mouse::MouseButton, pixels::Color, render::ClippingRect,
+};
+
+use crate::{
+    util::{
+        animation::{request_redraw, Easing},
+        clip::clipping_rect_intersection,
+        error::UiResult,
+        focus::{DefaultFocusBehaviorArg, FocusID, FocusManager},
+        hit::{HitShape, HitTest},
+        length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+        rect::FRect,
+        render::{interpolate_color, CanvasStateGuard},
+        wheel::WheelSteps,
+    },
+    widget::{ConsumedStatus, Widget, WidgetUpdateEvent},
+};
+
+use super::scroller::drag_past_deadzone;
+
+/// draws the page indicator below a carousel's pages
+pub trait CarouselIndicatorStyle {
+    /// the height of the strip below the pages. in logical pixels (scaled by
+    /// the ui scale)
+    fn height(&self) -> f32;
+
+    /// progress is the index of the page being shown. it's fractional while
+    /// moving between pages
+    fn draw(
+        &mut self,
+        page_count: usize,
+        progress: f32,
+        position: FRect,
+        canvas: &mut sdl2::render::WindowCanvas,
+    ) -> UiResult<()>;
+}
+
+/// a row of dots, one per page, centered in the strip
+pub struct DotIndicatorStyle {
+    pub color: Color,
+    pub current_color: Color,
+    /// in logical pixels (scaled by the ui scale)
+    pub dot_size: f32,
+    /// in logical pixels (scaled by the ui scale)
+    pub spacing: f32,
+}
+
+impl Default for DotIndicatorStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::RGB(100, 100, 100),
+            current_color: Color::WHITE,
+            dot_size: 6.,
+            spacing: 6.,
+        }
+    }
+}
+
+impl CarouselIndicatorStyle for DotIndicatorStyle {
+    fn height(&self) -> f32 {
+        self.dot_size * 3.
+    }
+
+    fn draw(
+        &mut self,
+        page_count: usize,
+        progress: f32,
+        position: FRect,
+        canvas: &mut sdl2::render::WindowCanvas,
+    ) -> UiResult<()> {
+        let dot_size = crate::util::scale::scaled(self.dot_size);
+        let spacing = crate::util::scale::scaled(self.spacing);
+        let total_w = page_count as f32 * dot_size + page_count.saturating_sub(1) as f32 * spacing;
+        let start_x = position.x + (position.w - total_w) / 2.;
+        let y = position.y + (position.h - dot_size) / 2.;
+        for i in 0..page_count {
+            let distance = (i as f32 - progress).abs().min(1.);
+            canvas.set_draw_color(interpolate_color(self.current_color, self.color, distance));
+            let dot = FRect {
+                x: start_x + i as f32 * (dot_size + spacing),
+                y,
+                w: dot_size,
+                h: dot_size,
+            };
+            let dot: Option<sdl2::rect::Rect> = dot.into();
+            if let Some(dot) = dot {
+                canvas.fill_rect(dot)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DragState {
+    None,
+    /// waiting for mouse to move far enough before beginning dragging
+    DragStart((i32, i32)),
+    /// contains the x position of the mouse, minus the offset
+    Dragging(i32),
+}
+
+/// the offset easing back to zero
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    from: f32,
+    start: Instant,
+}
+
+/// shows one page at a time. moves between pages by dragging (swiping) left or
+/// right, or with the arrow keys when focused. the pages slide into place.
+///
+/// the carousel is sized to fit every page, so it doesn't change size when
+/// the page changes. the preferred sizing comes from the page being shown
+pub struct Carousel<'sdl, 'state> {
+    pub pages: Vec<Box<dyn Widget + 'sdl>>,
+    /// the index of the page being shown. it can be set elsewhere, in which
+    /// case the carousel slides to that page
+    pub page: &'state Cell<usize>,
+    /// when focused (via tab), the left and right arrow keys change the page
+    pub focus_id: Option<FocusID>,
+    /// manhattan distance that the mouse must travel before it's considered a
+    /// click and drag
+    pub drag_deadzone: u32,
+    /// portion (from 0 to 1) of the width that a drag must cover to change the
+    /// page once released
+    pub swipe_threshold: f32,
+    pub transition_duration: Duration,
+    pub transition_easing: Easing,
+    /// drawn in a strip below the pages. none for no indicator
+    pub indicator: Option<Box<dyn CarouselIndicatorStyle + 'sdl>>,
+    /// if set, the mouse wheel over the carousel changes the page (wheel down
+    /// for the next page)
+    pub wheel: Option<WheelSteps>,
+
+    drag_state: DragState,
+    /// horizontal distance of the shown page from where it rests, from a drag
+    /// or a transition
+    offset: f32,
+    transition: Option<Transition>,
+    /// the page as of the most recent update
+    shown_page: usize,
+    /// pages which were updated, and so can be drawn
+    updated_pages: Vec<usize>,
+
+    /// calculated during update, stored for draw
+    previous_clipping_rect_from_update: ClippingRect,
+    /// where the pages are placed, excluding the indicator strip
+    pages_position_from_update: FRect,
+    indicator_position_from_update: FRect,
+}
+
+impl<'sdl, 'state> Carousel<'sdl, 'state> {
+    pub fn new(pages: Vec<Box<dyn Widget + 'sdl>>, page: &'state Cell<usize>) -> Self {
+        Self {
+            pages,
+            page,
+            focus_id: None,
+            drag_deadzone: 10,
+            swipe_threshold: 0.25,
+            transition_duration: Duration::from_millis(250),
+            transition_easing: Default::default(),
+            indicator: Some(Box::new(DotIndicatorStyle::default())),
+            wheel: None,
+            drag_state: DragState::None,
+            offset: 0.,
+            transition: None,
+            shown_page: page.get(),
+            updated_pages: Vec::new(),
+            previous_clipping_rect_from_update: ClippingRect::None,
+            pages_position_from_update: Default::default(),
+            indicator_position_from_update: Default::default(),
+        }
+    }
+
+    /// go to the next page, if there is one
+    pub fn next(&self) {
+        if self.page.get() + 1 < self.pages.len() {
+            self.page.set(self.page.get() + 1);
+        }
+    }
+
+    /// go to the previous page, if there is one
+    pub fn previous(&self) {
+        self.page.set(self.page.get().saturating_sub(1));
+    }
+
+    fn indicator_height(&self) -> f32 {
+        match &self.indicator {
+            Some(indicator) => crate::util::scale::scaled(indicator.height()),
+            None => 0.,
+        }
+    }
+
+    /// where the page is, given where the pages are placed
+    fn page_position(&self, page: usize, pages_position: FRect) -> FRect {
+        let index_delta = page as f32 - self.shown_page as f32;
+        FRect {
+            x: pages_position.x + self.offset + index_delta * pages_position.w,
+            ..pages_position
+        }
+    }
+
+    /// keep the shown page in sync with the page cell. the offset is adjusted
+    /// so the pages don't jump, and then eases back to zero
+    fn sync_page(&mut self) {
+        let page = self.page.get().min(self.pages.len().saturating_sub(1));
+        self.page.set(page);
+        if page == self.shown_page {
+            return;
+        }
+        // the new page comes in from the side it's on. a jump of several pages
+        // slides like a jump of one
+        let w = self.pages_position_from_update.w;
+        if page > self.shown_page {
+            self.offset += w;
+        } else {
+            self.offset -= w;
+        }
+        self.shown_page = page;
+        self.transition = Some(Transition {
+            from: self.offset,
+            start: Instant::now(),
+        });
+    }
+
+    /// limit dragging past the first or last page
+    fn clamp_offset(&self, offset: f32) -> f32 {
+        let w = self.pages_position_from_update.w;
+        let max = if self.shown_page > 0 { w } else { 0. };
+        let min = if self.shown_page + 1 < self.pages.len() {
+            -w
+        } else {
+            0.
+        };
+        offset.clamp(min, max.max(min))
+    }
+
+    /// a drag was released. change the page if it was dragged far enough
+    fn drag_released(&mut self) {
+        let threshold = self.swipe_threshold * self.pages_position_from_update.w;
+        if self.offset < -threshold {
+            self.next();
+        } else if self.offset > threshold {
+            self.previous();
+        }
+        self.sync_page();
+        if self.transition.is_none() && self.offset != 0. {
+            self.transition = Some(Transition {
+                from: self.offset,
+                start: Instant::now(),
+            });
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for Carousel<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        self.pages
+            .iter_mut()
+            .map(|p| p.as_mut() as &mut dyn Widget)
+            .collect()
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let mut w = MinLen::LAX;
+        let mut h = MinLen::LAX;
+        for page in self.pages.iter_mut() {
+            let (page_w, page_h) = page.min()?;
+            w = w.strictest(page_w);
+            h = h.strictest(page_h);
+        }
+        Ok((w, h.combined(MinLen(self.indicator_height()))))
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.min_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.min_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let mut w = MaxLen::LAX;
+        let mut h = MaxLen::LAX;
+        for page in self.pages.iter_mut() {
+            let (page_w, page_h) = page.max()?;
+            w = w.strictest(page_w);
+            h = h.strictest(page_h);
+        }
+        Ok((w, h.combined(MaxLen(self.indicator_height()))))
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.max_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.max_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.preferred_portion(),
+            None => Default::default(),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        let pages_h = pref_h - self.indicator_height();
+        self.pages
+            .get_mut(self.shown_page)?
+            .preferred_width_from_height(pages_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        let indicator_height = self.indicator_height();
+        self.pages
+            .get_mut(self.shown_page)?
+            .preferred_height_from_width(pref_w)
+            .map(|some| some.map(|ok| ok + indicator_height))
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match self.pages.get(self.shown_page) {
+            Some(page) => page.preferred_link_allowed_exceed_portion(),
+            None => false,
+        }
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let indicator_height = self.indicator_height();
+        let pages_position = FRect {
+            h: (event.position.h - indicator_height).max(0.),
+            ..event.position
+        };
+        self.indicator_position_from_update = FRect {
+            y: pages_position.bottom(),
+            h: event.position.h - pages_position.h,
+            ..event.position
+        };
+        self.pages_position_from_update = pages_position;
+        self.previous_clipping_rect_from_update = event.clipping_rect;
+        self.updated_pages.clear();
+
+        if self.pages.is_empty() {
+            return Ok(());
+        }
+
+        self.sync_page();
+
+        if let Some(transition) = self.transition {
+            let t = if self.transition_duration.is_zero() {
+                1.
+            } else {
+                (transition.start.elapsed().as_secs_f32() / self.transition_duration.as_secs_f32())
+                    .min(1.)
+            };
+            self.offset = transition.from * (1. - self.transition_easing.apply(t));
+            if t >= 1. {
+                self.transition = None;
+            } else {
+                request_redraw();
+            }
+        }
+
+        if let DragState::Dragging(_) = self.drag_state {
+            // consume related events if currently dragging. do this before
+            // passing event to pages
+            event
+                .events
+                .iter_mut()
+                .filter(|e| e.available())
+                .for_each(|e| match e.e {
+                    sdl2::event::Event::MouseButtonDown { .. }
+                    | sdl2::event::Event::MouseMotion { .. }
+                    | sdl2::event::Event::MouseButtonUp { .. } => {
+                        e.set_consumed();
+                    }
+                    _ => {}
+                });
+        }
+
+        let clip_rect_for_pages =
+            clipping_rect_intersection(event.clipping_rect, pages_position.into());
+
+        // the shown page gets the events. while moving between pages, the
+        // neighbouring pages are also updated (without events) so they can be
+        // drawn
+        let moving = self.offset != 0. || matches!(self.drag_state, DragState::Dragging(_));
+        let first = if moving {
+            self.shown_page.saturating_sub(1)
+        } else {
+            self.shown_page
+        };
+        let last = if moving {
+            (self.shown_page + 1).min(self.pages.len() - 1)
+        } else {
+            self.shown_page
+        };
+        let mut before_update_positions: Vec<(usize, FRect)> = Vec::new();
+        for i in first..=last {
+            let position = self.page_position(i, pages_position);
+            let mut sub_event = event.sub_event(position);
+            sub_event.clipping_rect = clip_rect_for_pages;
+            if i != self.shown_page {
+                sub_event.events = &mut [];
+            }
+            self.pages[i].update(sub_event)?;
+            before_update_positions.push((i, position));
+            self.updated_pages.push(i);
+        }
+
+        let hit = HitTest::new(pages_position, clip_rect_for_pages, event.window_id);
+
+        // handle dragging after the pages are updated, like the scroller
+        for sdl_event in event.events.iter_mut() {
+            if let ConsumedStatus::ConsumedByLayout = sdl_event.consumed_status() {
+                continue;
+            }
+            match sdl_event.e {
+                sdl2::event::Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    window_id,
+                    ..
+                } if hit.contains(window_id, x, y) => {
+                    sdl_event.set_consumed_by_layout();
+                    if let DragState::None = self.drag_state {
+                        self.drag_state = DragState::DragStart((x, y));
+                    }
+                }
+                sdl2::event::Event::MouseMotion {
+                    x,
+                    y,
+                    mousestate,
+                    window_id,
+                    ..
+                } => {
+                    if !mousestate.left() {
+                        // released somewhere this widget didn't see
+                        if let DragState::Dragging(_) = self.drag_state {
+                            self.drag_released();
+                        }
+                        self.drag_state = DragState::None;
+                    }
+                    if let DragState::None = self.drag_state {
+                        continue;
+                    }
+                    if event.window_id != window_id {
+                        continue;
+                    }
+                    sdl_event.set_consumed_by_layout();
+                    if let DragState::DragStart(start) = self.drag_state {
+                        if drag_past_deadzone(start, (x, y), self.drag_deadzone, true, false) {
+                            // grabbed mid transition continues from there
+                            self.transition = None;
+                            self.drag_state = DragState::Dragging(x - self.offset as i32);
+                        }
+                    }
+                    if let DragState::Dragging(drag_x) = self.drag_state {
+                        self.offset = self.clamp_offset((x - drag_x) as f32);
+                    }
+                }
+                sdl2::event::Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                } => match self.drag_state {
+                    DragState::None => {}
+                    DragState::DragStart(_) => self.drag_state = DragState::None,
+                    DragState::Dragging(_) => {
+                        self.drag_state = DragState::None;
+                        sdl_event.set_consumed_by_layout();
+                        self.drag_released();
+                    }
+                },
+                sdl2::event::Event::Window {
+                    win_event:
+                        WindowEvent::Hidden
+                        | WindowEvent::Minimized
+                        | WindowEvent::Leave
+                        | WindowEvent::FocusLost
+                        | WindowEvent::Close,
+                    ..
+                } => {
+                    // same as mouse button up, but don't consume the event
+                    if let DragState::Dragging(_) = self.drag_state {
+                        self.drag_released();
+                    }
+                    self.drag_state = DragState::None;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(focus_id) = &self.focus_id {
+            event.focus_manager.report_rect(focus_id, event.position);
+            for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+                if let sdl2::event::Event::MouseMotion { .. } = sdl_event.e {
+                    // hovering would take focus from the widgets on the page
+                    continue;
+                }
+                FocusManager::default_widget_focus_behavior(
+                    focus_id,
+                    DefaultFocusBehaviorArg {
+                        focus_manager: event.focus_manager,
+                        position: event.position,
+                        event: sdl_event,
+                        clipping_rect: event.clipping_rect,
+                        window_id: event.window_id,
+                        hit_expansion: 0.,
+                        hit_shape: &HitShape::Rect,
+                    },
+                );
+                if sdl_event.consumed() || !event.focus_manager.is_focused(focus_id) {
+                    continue;
+                }
+                if let sdl2::event::Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } = sdl_event.e
+                {
+                    match keycode {
+                        Keycode::Left => {
+                            sdl_event.set_consumed();
+                            self.previous();
+                        }
+                        Keycode::Right => {
+                            sdl_event.set_consumed();
+                            self.next();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if let Some(wheel) = self.wheel.as_mut() {
+            let focused = self
+                .focus_id
+                .as_ref()
+                .is_some_and(|focus_id| event.focus_manager.is_focused(focus_id));
+            let hit = HitTest::from_event(&event);
+            let steps = wheel.update(&mut event, &hit, focused) as i64;
+            if steps != 0 {
+                let page = (self.page.get() as i64 - steps).clamp(0, self.pages.len() as i64 - 1);
+                self.page.set(page as usize);
+            }
+        }
+
+        // the page may have changed from a key press or the wheel
+        self.sync_page();
+
+        // account for changes between when the pages were updated and the
+        // events were handled
+        for (i, before) in before_update_positions {
+            let after = self.page_position(i, pages_position);
+            self.pages[i].update_adjust_position(((after.x - before.x) as i32, 0));
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        for position in [
+            &mut self.pages_position_from_update,
+            &mut self.indicator_position_from_update,
+        ] {
+            position.x += pos_delta.0 as f32;
+            position.y += pos_delta.1 as f32;
+        }
+        for i in self.updated_pages.iter() {
+            self.pages[*i].update_adjust_position(pos_delta);
+        }
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        canvas.set_clip_rect(clipping_rect_intersection(
+            self.previous_clipping_rect_from_update,
+            self.pages_position_from_update.into(),
+        ));
+        let mut draw_result = Ok(());
+        for i in self.updated_pages.iter() {
+            draw_result = self.pages[*i].draw(&mut canvas, focus_manager);
+            if draw_result.is_err() {
+                break;
+            }
+        }
+        canvas.set_clip_rect(self.previous_clipping_rect_from_update); // restore
+        draw_result?;
+
+        let page_count = self.pages.len();
+        let w = self.pages_position_from_update.w;
+        let progress = if w > 0. {
+            self.shown_page as f32 - self.offset / w
+        } else {
+            self.shown_page as f32 // guard div
+        };
+        let indicator_position = self.indicator_position_from_update;
+        if let Some(indicator) = self.indicator.as_mut() {
+            indicator.draw(page_count, progress, indicator_position, &mut canvas)?;
+        }
+        Ok(())
+    }
+}
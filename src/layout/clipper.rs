@@ -1,6 +1,14 @@
-use sdl2::{rect::Rect, render::ClippingRect};
+use std::ops::{Deref, DerefMut};
 
-use crate::{util::focus::FocusManager, widget::Widget};
+use sdl2::{
+    rect::Rect,
+    render::{ClippingRect, WindowCanvas},
+};
+
+use crate::{
+    util::{clip, error::UiResult, focus::FocusManager, render::CanvasStateGuard},
+    widget::{Widget, WidgetUpdateEvent},
+};
 
 /// contains something. when it is draw, a clipping rect is set to not allow
 /// drawing to go past the widget's given position
@@ -21,36 +29,102 @@ impl<'sdl> Clipper<'sdl> {
     }
 }
 
-pub fn clipping_rect_intersection(
-    existing_clipping_rect: ClippingRect,
-    position: Option<Rect>,
-) -> ClippingRect {
-    match position {
-        Some(position) => {
-            match existing_clipping_rect {
-                ClippingRect::Some(rect) => match rect.intersection(position) {
-                    Some(v) => ClippingRect::Some(v),
-                    None => ClippingRect::Zero,
-                },
-                ClippingRect::Zero => ClippingRect::Zero,
-                ClippingRect::None => {
-                    // clipping rect has infinite area, so it's just whatever position is
-                    ClippingRect::Some(position)
-                }
-            }
+pub use crate::util::clip::clipping_rect_intersection;
+
+/// sets the canvas' clipping rect. it's a CanvasStateGuard, so the previous
+/// clipping rect (and the rest of the canvas' state) is restored when dropped
+pub struct ClipScope<'a> {
+    canvas: CanvasStateGuard<'a>,
+}
+
+impl<'a> ClipScope<'a> {
+    pub fn new(canvas: &'a mut WindowCanvas, clip_rect: ClippingRect) -> Self {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        canvas.set_clip_rect(clip_rect);
+        Self { canvas }
+    }
+
+    /// clip to the intersection of the current clipping rect and position
+    pub fn intersect(canvas: &'a mut WindowCanvas, position: Option<Rect>) -> Self {
+        let clip_rect = clipping_rect_intersection(canvas.clip_rect(), position);
+        Self::new(canvas, clip_rect)
+    }
+
+    /// leaves the clipping rect as is
+    pub fn unchanged(canvas: &'a mut WindowCanvas) -> Self {
+        Self {
+            canvas: CanvasStateGuard::new(canvas),
         }
-        None => {
-            // position is zero area so intersection result is zero
-            ClippingRect::Zero
+    }
+}
+
+impl<'a> Deref for ClipScope<'a> {
+    type Target = WindowCanvas;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl<'a> DerefMut for ClipScope<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+/// opt-in clipping of a container's children to the container's position,
+/// without needing a Clipper or Scroller
+#[derive(Clone, Copy)]
+pub struct ClipChildren {
+    pub enabled: bool,
+    /// calculated during update, stored for draw
+    clip_rect: ClippingRect,
+}
+
+impl Default for ClipChildren {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl ClipChildren {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            clip_rect: ClippingRect::None, // doesn't matter here
+        }
+    }
+
+    /// call at the beginning of the container's update. the children are
+    /// given a clipping rect within the container's position
+    pub fn update(&mut self, event: &mut WidgetUpdateEvent) {
+        if !self.enabled {
+            return;
+        }
+        self.clip_rect = clipping_rect_intersection(event.clipping_rect, event.position.into());
+        event.clipping_rect = self.clip_rect;
+    }
+
+    pub fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.clip_rect = clip::translated(self.clip_rect, pos_delta);
+    }
+
+    /// draw the children within this scope
+    pub fn scope<'a>(&self, canvas: &'a mut WindowCanvas) -> ClipScope<'a> {
+        if self.enabled {
+            ClipScope::new(canvas, self.clip_rect)
+        } else {
+            ClipScope::unchanged(canvas)
         }
     }
 }
 
 impl<'sdl> Widget for Clipper<'sdl> {
-    fn update(
-        &mut self,
-        mut event: crate::widget::WidgetUpdateEvent,
-    ) -> Result<(), String> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         let previous_clipping_rect = event.clipping_rect;
         // store for update step
         self.update_clip_rect =
@@ -59,14 +133,11 @@ impl<'sdl> Widget for Clipper<'sdl> {
         // after this one
         let mut event_dup = event.dup();
         event_dup.clipping_rect = self.update_clip_rect;
-        self.contained.update(event.dup())
+        self.contained.update(event_dup)
     }
 
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
-        if let ClippingRect::Some(rect) = &mut self.update_clip_rect {
-            rect.x += pos_delta.0;
-            rect.y += pos_delta.1;
-        }
+        self.update_clip_rect = clip::translated(self.update_clip_rect, pos_delta);
         self.contained.update_adjust_position(pos_delta);
     }
 
@@ -74,18 +145,13 @@ impl<'sdl> Widget for Clipper<'sdl> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
-        let previous_clipping_rect = canvas.clip_rect();
-        canvas.set_clip_rect(self.update_clip_rect);
-        let ret = self.contained.draw(canvas, focus_manager);
-        // reset clipping rect for following elements that will be drawn after
-        canvas.set_clip_rect(previous_clipping_rect);
-        ret
+    ) -> UiResult<()> {
+        // restored when dropped, for the elements drawn after this one
+        let mut canvas = ClipScope::new(canvas, self.update_clip_rect);
+        self.contained.draw(&mut canvas, focus_manager)
     }
 
-    fn min(
-        &mut self,
-    ) -> Result<(crate::util::length::MinLen, crate::util::length::MinLen), String> {
+    fn min(&mut self) -> UiResult<(crate::util::length::MinLen, crate::util::length::MinLen)> {
         self.contained.min()
     }
 
@@ -97,9 +163,7 @@ impl<'sdl> Widget for Clipper<'sdl> {
         self.contained.min_h_fail_policy()
     }
 
-    fn max(
-        &mut self,
-    ) -> Result<(crate::util::length::MaxLen, crate::util::length::MaxLen), String> {
+    fn max(&mut self) -> UiResult<(crate::util::length::MaxLen, crate::util::length::MaxLen)> {
         self.contained.max()
     }
 
@@ -120,11 +184,11 @@ impl<'sdl> Widget for Clipper<'sdl> {
         self.contained.preferred_portion()
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         self.contained.preferred_width_from_height(pref_h)
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         self.contained.preferred_height_from_width(pref_w)
     }
 
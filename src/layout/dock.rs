@@ -0,0 +1,279 @@
+use crate::{
+    util::{
+        error::UiResult,
+        focus::FocusManager,
+        length::{
+            parent_height, parent_width, with_parent_size, AspectRatioPreferredDirection,
+            MaxLen, MaxLenFailPolicy, MaxLenPolicy, MinLen, MinLenFailPolicy, MinLenPolicy,
+            PreferredPortion,
+        },
+        rect::FRect,
+    },
+    widget::{Widget, WidgetUpdateEvent},
+};
+
+/// where an element of a dock layout is placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dock {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// take up whatever space remains after the preceding elements are docked
+    Fill,
+}
+
+pub struct DockElement<'sdl> {
+    pub dock: Dock,
+    pub widget: Box<dyn Widget + 'sdl>,
+}
+
+impl<'sdl> DockElement<'sdl> {
+    pub fn new(dock: Dock, widget: Box<dyn Widget + 'sdl>) -> Self {
+        Self { dock, widget }
+    }
+}
+
+/// a classic application shell: menu bar top, status bar bottom, sidebar left,
+/// content in the center.
+///
+/// elements are docked in order. each edge docked element takes a strip from
+/// the remaining space, along the docked edge. the strip's length is the
+/// element's minimum length in that direction (so e.g. a status bar is as tall
+/// as its content needs); to make it larger, give the element a larger min len.
+/// the strip spans the remaining space in the other direction.
+///
+/// a Fill element is given all the remaining space. it should typically be
+/// last. if there are several, they overlap
+pub struct DockLayout<'sdl> {
+    pub elems: Vec<DockElement<'sdl>>,
+    pub preferred_w: PreferredPortion,
+    pub preferred_h: PreferredPortion,
+    pub min_w_fail_policy: MinLenFailPolicy,
+    pub max_w_fail_policy: MaxLenFailPolicy,
+    pub min_h_fail_policy: MinLenFailPolicy,
+    pub max_h_fail_policy: MaxLenFailPolicy,
+    pub min_w_policy: MinLenPolicy,
+    pub max_w_policy: MaxLenPolicy,
+    pub min_h_policy: MinLenPolicy,
+    pub max_h_policy: MaxLenPolicy,
+}
+
+impl<'sdl> Default for DockLayout<'sdl> {
+    fn default() -> Self {
+        Self {
+            elems: Default::default(),
+            preferred_w: Default::default(),
+            preferred_h: Default::default(),
+            min_w_fail_policy: Default::default(),
+            max_w_fail_policy: Default::default(),
+            min_h_fail_policy: Default::default(),
+            max_h_fail_policy: Default::default(),
+            min_w_policy: MinLenPolicy::Children,
+            min_h_policy: MinLenPolicy::Children,
+            max_w_policy: MaxLenPolicy::Literal(MaxLen::LAX),
+            max_h_policy: MaxLenPolicy::Literal(MaxLen::LAX),
+        }
+    }
+}
+
+impl<'sdl> DockLayout<'sdl> {
+    /// builder style. add an element at a dock position
+    pub fn with(mut self, dock: Dock, widget: Box<dyn Widget + 'sdl>) -> Self {
+        self.elems.push(DockElement::new(dock, widget));
+        self
+    }
+}
+
+impl<'sdl> Widget for DockLayout<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        self.elems
+            .iter_mut()
+            .map(|e| e.widget.as_mut() as &mut dyn Widget)
+            .collect()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        (self.preferred_w, self.preferred_h)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let w_view_children = self.min_w_policy.resolve(parent_width());
+
+        let h_view_children = self.min_h_policy.resolve(parent_height());
+
+        if let Some(w) = w_view_children {
+            if let Some(h) = h_view_children {
+                return Ok((w, h)); // no need to iterate children in this case
+            }
+        }
+
+        // work outwards, from the last docked element to the first
+        let mut width_so_far = MinLen::LAX;
+        let mut height_so_far = MinLen::LAX;
+        for elem in self.elems.iter_mut().rev() {
+            let (elem_min_w, elem_min_h) = elem.widget.min()?;
+            match elem.dock {
+                Dock::Top | Dock::Bottom => {
+                    width_so_far = width_so_far.strictest(elem_min_w);
+                    height_so_far = height_so_far.combined(elem_min_h);
+                }
+                Dock::Left | Dock::Right => {
+                    width_so_far = width_so_far.combined(elem_min_w);
+                    height_so_far = height_so_far.strictest(elem_min_h);
+                }
+                Dock::Fill => {
+                    width_so_far = width_so_far.strictest(elem_min_w);
+                    height_so_far = height_so_far.strictest(elem_min_h);
+                }
+            }
+        }
+
+        Ok((
+            match w_view_children {
+                Some(w) => w,
+                None => width_so_far,
+            },
+            match h_view_children {
+                Some(h) => h,
+                None => height_so_far,
+            },
+        ))
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.min_w_fail_policy
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.min_h_fail_policy
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let w_view_children = self.max_w_policy.resolve(parent_width());
+
+        let h_view_children = self.max_h_policy.resolve(parent_height());
+
+        if let Some(w) = w_view_children {
+            if let Some(h) = h_view_children {
+                return Ok((w, h)); // no need to iterate children in this case
+            }
+        }
+
+        // edge docked strips are their min length along the docked axis. in
+        // the other axis, the layout can't be larger than the loosest element
+        let mut width_so_far = MaxLen(0.);
+        let mut height_so_far = MaxLen(0.);
+        for elem in self.elems.iter_mut().rev() {
+            let (elem_min_w, elem_min_h) = elem.widget.min()?;
+            let (elem_max_w, elem_max_h) = elem.widget.max()?;
+            match elem.dock {
+                Dock::Top | Dock::Bottom => {
+                    width_so_far = MaxLen(width_so_far.0.max(elem_max_w.0));
+                    height_so_far = height_so_far.combined(MaxLen(elem_min_h.0));
+                }
+                Dock::Left | Dock::Right => {
+                    width_so_far = width_so_far.combined(MaxLen(elem_min_w.0));
+                    height_so_far = MaxLen(height_so_far.0.max(elem_max_h.0));
+                }
+                Dock::Fill => {
+                    width_so_far = MaxLen(width_so_far.0.max(elem_max_w.0));
+                    height_so_far = MaxLen(height_so_far.0.max(elem_max_h.0));
+                }
+            }
+        }
+
+        Ok((
+            match w_view_children {
+                Some(w) => w,
+                None => width_so_far,
+            },
+            match h_view_children {
+                Some(h) => h,
+                None => height_so_far,
+            },
+        ))
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.max_w_fail_policy
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.max_h_fail_policy
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let mut remaining = event.position;
+        let size = (event.position.w, event.position.h);
+        for elem in self.elems.iter_mut() {
+            // percentages of the parent are of this layout. once placed in its
+            // strip, they're of the strip
+            let (min_w, min_h) = with_parent_size(size, || elem.widget.min())?;
+            // strip lengths are snapped to whole pixels, so the following
+            // elements aren't placed at fractional positions
+            let (strip, ratio_priority) = match elem.dock {
+                Dock::Top => {
+                    let h = min_h.0.min(remaining.h).max(0.).ceil();
+                    let strip = FRect { h, ..remaining };
+                    remaining.y += h;
+                    remaining.h -= h;
+                    (strip, AspectRatioPreferredDirection::WidthFromHeight)
+                }
+                Dock::Bottom => {
+                    let h = min_h.0.min(remaining.h).max(0.).ceil();
+                    let strip = FRect {
+                        y: remaining.bottom() - h,
+                        h,
+                        ..remaining
+                    };
+                    remaining.h -= h;
+                    (strip, AspectRatioPreferredDirection::WidthFromHeight)
+                }
+                Dock::Left => {
+                    let w = min_w.0.min(remaining.w).max(0.).ceil();
+                    let strip = FRect { w, ..remaining };
+                    remaining.x += w;
+                    remaining.w -= w;
+                    (strip, AspectRatioPreferredDirection::HeightFromWidth)
+                }
+                Dock::Right => {
+                    let w = min_w.0.min(remaining.w).max(0.).ceil();
+                    let strip = FRect {
+                        x: remaining.right() - w,
+                        w,
+                        ..remaining
+                    };
+                    remaining.w -= w;
+                    (strip, AspectRatioPreferredDirection::HeightFromWidth)
+                }
+                Dock::Fill => (remaining, event.aspect_ratio_priority),
+            };
+            remaining.w = remaining.w.max(0.);
+            remaining.h = remaining.h.max(0.);
+
+            let position = crate::widget::place(elem.widget.as_mut(), strip, ratio_priority)?;
+            let mut sub_event = event.sub_event(position);
+            sub_event.aspect_ratio_priority = ratio_priority;
+            elem.widget.update(sub_event)?;
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.elems
+            .iter_mut()
+            .for_each(|e| e.widget.update_adjust_position(pos_delta));
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        for e in self.elems.iter_mut() {
+            e.widget.draw(canvas, focus_manager)?;
+        }
+        Ok(())
+    }
+}
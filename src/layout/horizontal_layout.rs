@@ -1,14 +1,16 @@
 use crate::{
     util::{
-        focus::FocusManager,
+        error::UiResult,
+        focus::{FocusGroup, FocusManager},
         length::{
-            clamp, MaxLen, MaxLenFailPolicy, MaxLenPolicy, MinLen, MinLenFailPolicy, MinLenPolicy,
-            PreferredPortion,
+            clamp, parent_height, parent_width, with_parent_size, MaxLen, MaxLenFailPolicy,
+            MaxLenPolicy, MinLen, MinLenFailPolicy, MinLenPolicy, PreferredPortion,
         },
     },
     widget::{Widget, WidgetUpdateEvent},
 };
 
+use super::clipper::ClipChildren;
 use super::vertical_layout::{direction_conditional_iter_mut, MajorAxisMaxLenPolicy};
 
 pub struct HorizontalLayout<'sdl> {
@@ -16,6 +18,10 @@ pub struct HorizontalLayout<'sdl> {
     /// reverse the order IN TIME that elements are updated and drawn in. this
     /// does not affect the placement of elements in space
     pub reverse: bool,
+    /// clip the elements to the layout's position
+    pub clip_children: ClipChildren,
+    /// keyboard navigation among the focusable descendants
+    pub focus_group: FocusGroup,
     pub preferred_w: PreferredPortion,
     pub preferred_h: PreferredPortion,
     pub min_w_fail_policy: MinLenFailPolicy,
@@ -33,6 +39,8 @@ impl<'sdl> Default for HorizontalLayout<'sdl> {
         Self {
             elems: Default::default(),
             reverse: Default::default(),
+            clip_children: Default::default(),
+            focus_group: Default::default(),
             preferred_w: Default::default(),
             preferred_h: Default::default(),
             min_w_fail_policy: Default::default(),
@@ -48,20 +56,18 @@ impl<'sdl> Default for HorizontalLayout<'sdl> {
 }
 
 impl<'sdl> Widget for HorizontalLayout<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        self.elems.iter_mut().map(|e| e.as_mut() as &mut dyn Widget).collect()
+    }
+
     fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
         (self.preferred_w, self.preferred_h)
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        let w_view_children = match self.min_w_policy {
-            MinLenPolicy::Children => None,
-            MinLenPolicy::Literal(min_len) => Some(min_len),
-        };
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let w_view_children = self.min_w_policy.resolve(parent_width());
 
-        let h_view_children = match self.min_h_policy {
-            MinLenPolicy::Children => None,
-            MinLenPolicy::Literal(min_len) => Some(min_len),
-        };
+        let h_view_children = self.min_h_policy.resolve(parent_height());
 
         if let Some(w) = w_view_children {
             if let Some(h) = h_view_children {
@@ -97,19 +103,15 @@ impl<'sdl> Widget for HorizontalLayout<'sdl> {
         self.min_h_fail_policy
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
         let w_view_children = match self.max_w_policy {
             MajorAxisMaxLenPolicy::Spread => Some(MaxLen::LAX),
-            MajorAxisMaxLenPolicy::Together(max_len_policy) => match max_len_policy {
-                MaxLenPolicy::Children => None,
-                MaxLenPolicy::Literal(max_len) => Some(max_len),
-            },
+            MajorAxisMaxLenPolicy::Together(max_len_policy) => {
+                max_len_policy.resolve(parent_width())
+            }
         };
 
-        let h_view_children = match self.max_h_policy {
-            MaxLenPolicy::Children => None,
-            MaxLenPolicy::Literal(max_len) => Some(max_len),
-        };
+        let h_view_children = self.max_h_policy.resolve(parent_height());
 
         if let Some(w) = w_view_children {
             if let Some(h) = h_view_children {
@@ -146,10 +148,41 @@ impl<'sdl> Widget for HorizontalLayout<'sdl> {
         self.max_h_fail_policy
     }
 
-    fn update(&mut self, mut event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.focus_group.update(&mut event);
+        // percentages of the parent in the elements are of this layout
+        let size = (event.position.w, event.position.h);
+        with_parent_size(size, || self.update_elems(&mut event))?;
+        self.focus_group.update_after(&mut event, false);
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.clip_children.update_adjust_position(pos_delta);
+        self.elems
+            .iter_mut()
+            .for_each(|e| e.update_adjust_position(pos_delta));
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = self.clip_children.scope(canvas);
+        for e in self.elems.iter_mut() {
+            e.draw(&mut canvas, focus_manager)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'sdl> HorizontalLayout<'sdl> {
+    fn update_elems(&mut self, event: &mut WidgetUpdateEvent) -> UiResult<()> {
         if self.elems.is_empty() {
             return Ok(());
         }
+        self.clip_children.update(event);
 
         // collect info from child components
         let mut info: Vec<ChildInfo> = vec![ChildInfo::default(); self.elems.len()];
@@ -330,23 +363,6 @@ impl<'sdl> Widget for HorizontalLayout<'sdl> {
         }
         Ok(())
     }
-
-    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
-        self.elems
-            .iter_mut()
-            .for_each(|e| e.update_adjust_position(pos_delta));
-    }
-
-    fn draw(
-        &mut self,
-        canvas: &mut sdl2::render::WindowCanvas,
-        focus_manager: &FocusManager,
-    ) -> Result<(), String> {
-        for e in self.elems.iter_mut() {
-            e.draw(canvas, focus_manager)?;
-        }
-        Ok(())
-    }
 }
 
 #[derive(Clone, Copy)]
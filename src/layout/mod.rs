@@ -1,4 +1,6 @@
+pub mod carousel;
 pub mod clipper;
+pub mod dock;
 pub mod horizontal_layout;
 pub mod scroller;
 pub mod vertical_layout;
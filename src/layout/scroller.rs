@@ -1,20 +1,132 @@
-use std::cell::Cell;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use sdl2::{
     event::WindowEvent,
+    keyboard::Mod,
     mouse::{MouseButton, SystemCursor},
-    render::ClippingRect,
+    pixels::Color,
+    rect::{Point, Rect},
+    render::{BlendMode, ClippingRect, WindowCanvas},
 };
 
 use crate::{
-    util::{focus::FocusManager, length::AspectRatioPreferredDirection, rect::FRect},
+    util::{
+        animation::{request_redraw, Easing},
+        clip::clipping_rect_intersection,
+        cursor::SystemCursorCache,
+        error::UiResult,
+        focus::FocusManager,
+        hit::HitTest,
+        length::{AspectRatioPreferredDirection, MaxLen},
+        rect::FRect,
+        render::CanvasStateGuard,
+        rust::CellRefOrCell,
+    },
     widget::{
         debug::CustomSizingControl,
         {place, ConsumedStatus, Widget, WidgetUpdateEvent},
     },
 };
 
-use super::clipper::clipping_rect_intersection;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extent_fraction() {
+        // content twice the height of the viewport, starting at the same place
+        let extent = ScrollExtent::new(0., 100., 0., 200.);
+        assert_eq!(extent, ScrollExtent { start: 0, end: -100 });
+        assert_eq!(extent.fraction(0), 0.);
+        assert_eq!(extent.fraction(-50), 0.5);
+        assert_eq!(extent.fraction(-100), 1.);
+        assert_eq!(extent.from_fraction(0.5), -50);
+        // content fits; nowhere to scroll
+        let extent = ScrollExtent::new(0., 100., 0., 100.);
+        assert_eq!(extent.fraction(0), 0.);
+    }
+
+    #[test]
+    fn extent_remaining() {
+        let extent = ScrollExtent::new(0., 100., 0., 200.);
+        assert_eq!(extent.remaining(0), (0, 100));
+        assert_eq!(extent.remaining(-30), (30, 70));
+        assert_eq!(extent.remaining(-100), (100, 0));
+        // content smaller than the viewport
+        let extent = ScrollExtent::new(0., 100., 0., 50.);
+        assert_eq!(extent.remaining(0), (0, 0));
+    }
+
+    #[test]
+    fn memory_per_key() {
+        let mut memory = ScrollMemory::default();
+        // first key. nothing to restore, and the initial scroll is kept
+        assert_eq!(memory.switch("a", (0, -10)), None);
+        // same key; nothing happens
+        assert_eq!(memory.switch("a", (0, -20)), None);
+        // new key starts at the top
+        assert_eq!(memory.switch("b", (0, -20)), Some((0, 0)));
+        // back to a, where it was left
+        assert_eq!(memory.switch("a", (0, -5)), Some((0, -20)));
+        assert_eq!(memory.switch("b", (0, -30)), Some((0, -5)));
+        memory.forget("a");
+        assert_eq!(memory.switch("a", (0, -7)), Some((0, 0)));
+    }
+
+    #[test]
+    fn into_view() {
+        // already visible
+        assert_eq!(into_view_delta(0., 100., 10., 10.), 0);
+        // above
+        assert_eq!(into_view_delta(0., 100., -30., 10.), 30);
+        // below
+        assert_eq!(into_view_delta(0., 100., 95., 10.), -5);
+        // too large, align start
+        assert_eq!(into_view_delta(0., 100., 50., 200.), -50);
+    }
+
+    #[test]
+    fn snap_interval() {
+        let policy = ScrollSnapPolicy::Paging;
+        assert_eq!(snap_offset(&policy, 0., 100., 0), Some(0.));
+        assert_eq!(snap_offset(&policy, 40., 100., 0), Some(0.));
+        assert_eq!(snap_offset(&policy, 60., 100., 0), Some(100.));
+        assert_eq!(snap_offset(&policy, 100., 100., 1), Some(200.));
+        assert_eq!(snap_offset(&policy, 100., 100., -1), Some(0.));
+        assert_eq!(snap_offset(&policy, 150., 100., 1), Some(200.));
+        assert_eq!(snap_offset(&policy, 150., 100., -1), Some(100.));
+        // nothing to snap to
+        assert_eq!(snap_offset(&policy, 10., 0., 0), None);
+        assert_eq!(snap_offset(&ScrollSnapPolicy::None, 10., 100., 0), None);
+    }
+
+    #[test]
+    fn snap_points() {
+        let policy = ScrollSnapPolicy::Points(vec![0., 30., 100.]);
+        assert_eq!(snap_offset(&policy, 20., 50., 0), Some(30.));
+        assert_eq!(snap_offset(&policy, 30., 50., 1), Some(100.));
+        assert_eq!(snap_offset(&policy, 30., 50., -1), Some(0.));
+        // at the last point
+        assert_eq!(snap_offset(&policy, 100., 50., 1), None);
+    }
+
+    #[test]
+    fn rubber_band_resists() {
+        assert_eq!(rubber_band(0., 100., 0.55), 0.);
+        // follows closely at first
+        let small = rubber_band(2., 100., 0.55);
+        assert!(small > 1. && small < 2.);
+        // further is displaced more, but never past the viewport's length
+        let far = rubber_band(1000., 100., 0.55);
+        assert!(far > small && far < 100.);
+        assert_eq!(rubber_band(-50., 100., 0.55), -rubber_band(50., 100., 0.55));
+        assert_eq!(rubber_band(50., 0., 0.55), 0.);
+    }
+}
 
 #[derive(Debug)]
 enum DragState {
@@ -25,75 +137,273 @@ enum DragState {
     Dragging((i32, i32)),
 }
 
-#[derive(Default)]
-pub enum ScrollAspectRatioDirectionPolicy {
+/// what happens to mouse wheel movement over a scroller which is nested in
+/// another scroller
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScrollChainPolicy {
+    /// if the wheel movement doesn't scroll this scroller at all (it's already
+    /// at its end), then it's left for the outer scroller
     #[default]
-    Inherit,
-    Literal(AspectRatioPreferredDirection),
+    Chain,
+    /// all wheel movement over this scroller is consumed, even if it's already
+    /// at its end
+    Capture,
+    /// captures, unless any of these modifier keys are held, in which case the
+    /// wheel movement is left for the outer scroller
+    Modifier(Mod),
 }
 
-pub enum ScrollerSizingPolicy {
-    /// inherit sizing from the contained widget
-    Children,
-    /// states literally, ignoring the contained widget. the contained widget
-    /// will then be placed within the scroll widget's bounds
-    ///
-    /// give an aspect ratio direction to be given to the contained widget
-    Custom(CustomSizingControl, ScrollAspectRatioDirectionPolicy),
+/// where a scroller comes to rest, after a drag or mouse wheel movement. the
+/// scroll then animates to that position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ScrollSnapPolicy {
+    #[default]
+    None,
+    /// multiples of the visible area's length (e.g. a page swipe ui). the
+    /// mouse wheel moves by one page
+    Paging,
+    /// multiples of a length, for content made of same sized elements (e.g. a
+    /// carousel). in logical pixels (scaled by the ui scale)
+    Interval(f32),
+    /// offsets from the start of the content (e.g. where each child starts).
+    /// widgets don't report their positions, so these are stated literally.
+    /// in logical pixels (scaled by the ui scale)
+    Points(Vec<f32>),
 }
 
-#[derive(Default)]
-struct ScrollerCursorCache {
-    /// this type is:
-    ///  - outer optional, is the cache set or not
-    ///  - inner optional, the cache is set, but None if sdl call failed (this
-    ///    api in infallible - should not err on sdl2 cursor set failure)
-    ///
-    /// when freed it clears the cursor if it is currently set
-    cursor: Option<Option<sdl2::mouse::Cursor>>,
-    /// cursor loaded is appropriate for this
-    scroll_x_enabled: bool,
-    /// cursor loaded is appropriate for this
-    scroll_y_enabled: bool,
+/// where to snap to along an axis, as an offset from the start of the content.
+/// direction is 0 for the nearest snap position, or 1 or -1 for the next one
+/// after or before the offset. none if there's nowhere to snap to
+fn snap_offset(
+    policy: &ScrollSnapPolicy,
+    offset: f32,
+    viewport_len: f32,
+    direction: i32,
+) -> Option<f32> {
+    // within half a pixel is considered to be at a position
+    const TOLERANCE: f32 = 0.5;
+    let interval = match policy {
+        ScrollSnapPolicy::None => return None,
+        ScrollSnapPolicy::Paging => viewport_len,
+        ScrollSnapPolicy::Interval(v) => crate::util::scale::scaled(*v),
+        ScrollSnapPolicy::Points(points) => {
+            let points = points.iter().map(|p| crate::util::scale::scaled(*p));
+            return match direction.signum() {
+                0 => points.min_by(|a, b| (a - offset).abs().total_cmp(&(b - offset).abs())),
+                1 => points
+                    .filter(|p| *p > offset + TOLERANCE)
+                    .min_by(|a, b| a.total_cmp(b)),
+                _ => points
+                    .filter(|p| *p < offset - TOLERANCE)
+                    .max_by(|a, b| a.total_cmp(b)),
+            };
+        }
+    };
+    if interval <= 0. {
+        return None; // guard div
+    }
+    let index = match direction.signum() {
+        0 => (offset / interval).round(),
+        1 => ((offset + TOLERANCE) / interval).floor() + 1.,
+        _ => ((offset - TOLERANCE) / interval).ceil() - 1.,
+    };
+    Some(index * interval)
 }
 
-impl ScrollerCursorCache {
-    pub fn clear(&mut self) {
-        self.cursor = None;
-    }
+/// a snap which was requested while handling events
+#[derive(Debug, Clone, Copy)]
+enum SnapRequest {
+    /// go to the nearest snap position (e.g. after a drag)
+    Nearest,
+    /// go to the next snap position in the direction that the scroll value
+    /// moved (e.g. from the mouse wheel). only the axes that moved are snapped
+    Step(i32, i32),
+}
 
-    pub fn set_or_use_cache(&mut self, scroll_x_enabled: bool, scroll_y_enabled: bool) {
-        if !scroll_x_enabled && !scroll_y_enabled {
-            self.cursor = None;
-            return;
-        }
+#[derive(Debug, Clone, Copy)]
+struct SnapAnimation {
+    from: (i32, i32),
+    to: (i32, i32),
+    start: Instant,
+    /// the scroll as of the end of the most recent update. if the scroll is
+    /// changed by something else, the animation stops
+    current: (i32, i32),
+}
 
-        if self.cursor.is_none()
-            || self.scroll_x_enabled != scroll_x_enabled
-            || self.scroll_y_enabled != scroll_y_enabled
-        {
-            self.scroll_x_enabled = scroll_x_enabled;
-            self.scroll_y_enabled = scroll_y_enabled;
+/// how far content is displaced when it's dragged some distance past its end.
+/// the further it's pulled, the less it follows, approaching the viewport's
+/// length
+fn rubber_band(distance: f32, viewport_len: f32, resistance: f32) -> f32 {
+    if viewport_len <= 0. {
+        return 0.; // guard div
+    }
+    let displaced = (1. - 1. / (distance.abs() * resistance / viewport_len + 1.)) * viewport_len;
+    displaced.copysign(distance)
+}
 
-            let cursor_to_request = if scroll_x_enabled && scroll_y_enabled {
-                SystemCursor::SizeAll
-            } else if scroll_x_enabled {
-                SystemCursor::SizeWE
-            } else {
-                SystemCursor::SizeNS
+/// draws over a scroller's content while it's overscrolled. receives the
+/// scroller's position and the overscroll (positive is content pulled right
+/// or down, past its start). the clipping rect is set to the scroller
+pub type OverscrollIndicator<'sdl> =
+    Box<dyn FnMut(&mut WindowCanvas, FRect, (f32, f32)) -> UiResult<()> + 'sdl>;
+
+/// a default provided overscroll indicator. a glow along the edge that the
+/// content is pulled away from, as deep as the overscroll
+pub fn edge_glow<'sdl>(color: Color) -> OverscrollIndicator<'sdl> {
+    Box::new(
+        move |canvas: &mut WindowCanvas, position: FRect, overscroll: (f32, f32)| {
+            let rect: Option<Rect> = position.into();
+            let rect = match rect {
+                Some(v) => v,
+                None => return Ok(()),
             };
+            let mut canvas = CanvasStateGuard::new(canvas);
+            canvas.set_blend_mode(BlendMode::Blend);
+            draw_edge_glow(&mut canvas, rect, color, overscroll)
+        },
+    )
+}
+
+fn draw_edge_glow(
+    canvas: &mut WindowCanvas,
+    rect: Rect,
+    color: Color,
+    overscroll: (f32, f32),
+) -> UiResult<()> {
+    for (amount, vertical) in [(overscroll.0, false), (overscroll.1, true)] {
+        let depth = amount.abs().round() as i32;
+        draw_edge_fade(canvas, rect, color, vertical, amount > 0., depth)?;
+    }
+    Ok(())
+}
+
+/// lines along an edge of a rect, fading out away from the edge. at_start is
+/// the left or top edge
+fn draw_edge_fade(
+    canvas: &mut WindowCanvas,
+    rect: Rect,
+    color: Color,
+    vertical: bool,
+    at_start: bool,
+    depth: i32,
+) -> UiResult<()> {
+    for i in 0..depth {
+        let alpha = color.a as f32 * (1. - i as f32 / depth as f32);
+        canvas.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha as u8));
+        let (start, end) = match (vertical, at_start) {
+            (false, true) => (
+                Point::new(rect.left() + i, rect.top()),
+                Point::new(rect.left() + i, rect.bottom() - 1),
+            ),
+            (false, false) => (
+                Point::new(rect.right() - 1 - i, rect.top()),
+                Point::new(rect.right() - 1 - i, rect.bottom() - 1),
+            ),
+            (true, true) => (
+                Point::new(rect.left(), rect.top() + i),
+                Point::new(rect.right() - 1, rect.top() + i),
+            ),
+            (true, false) => (
+                Point::new(rect.left(), rect.bottom() - 1 - i),
+                Point::new(rect.right() - 1, rect.bottom() - 1 - i),
+            ),
+        };
+        canvas.draw_line(start, end)?;
+    }
+    Ok(())
+}
+
+/// shadows at the edges of a scroller where there's more content in that
+/// direction, so it's apparent that it can be scrolled without a scrollbar
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeShadows {
+    pub color: Color,
+    /// how deep the shadows are, in logical pixels (scaled by the ui scale).
+    /// a shadow also fades in over this distance from the end of the content
+    pub length: f32,
+}
 
-            let cursor_result = sdl2::mouse::Cursor::from_system(cursor_to_request);
-            debug_assert!(cursor_result.is_ok());
-            let cursor_optional = cursor_result.ok();
-            if let Some(cursor) = cursor_optional.as_ref() {
-                cursor.set()
+impl Default for EdgeShadows {
+    fn default() -> Self {
+        Self {
+            color: Color::RGBA(0, 0, 0, 100),
+            length: 12.,
+        }
+    }
+}
+
+impl EdgeShadows {
+    fn draw(
+        &self,
+        canvas: &mut WindowCanvas,
+        rect: Rect,
+        remaining: [(i32, i32); 2],
+    ) -> UiResult<()> {
+        let length = crate::util::scale::scaled(self.length);
+        if length <= 0. {
+            return Ok(());
+        }
+        let depth = length.round() as i32;
+        for (axis_remaining, vertical) in [(remaining[0], false), (remaining[1], true)] {
+            for (distance, at_start) in [(axis_remaining.0, true), (axis_remaining.1, false)] {
+                let strength = (distance as f32 / length).min(1.);
+                if strength <= 0. {
+                    continue;
+                }
+                let mut color = self.color;
+                color.a = (color.a as f32 * strength) as u8;
+                draw_edge_fade(canvas, rect, color, vertical, at_start, depth)?;
             }
-            self.cursor = Some(cursor_optional);
         }
+        Ok(())
     }
 }
 
+/// rubber band overscroll. see Scroller::elastic
+pub struct ElasticOverscroll<'sdl> {
+    /// how closely the content follows a drag past its end. near 0 it barely
+    /// moves; at 1 it follows the drag at first
+    pub resistance: f32,
+    /// how long the content takes to return once it's released
+    pub return_duration: Duration,
+    pub return_easing: Easing,
+    pub indicator: Option<OverscrollIndicator<'sdl>>,
+}
+
+impl<'sdl> Default for ElasticOverscroll<'sdl> {
+    fn default() -> Self {
+        Self {
+            resistance: 0.55,
+            return_duration: Duration::from_millis(300),
+            return_easing: Default::default(),
+            indicator: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OverscrollReturn {
+    from: (f32, f32),
+    start: Instant,
+}
+
+#[derive(Default)]
+pub enum ScrollAspectRatioDirectionPolicy {
+    #[default]
+    Inherit,
+    Literal(AspectRatioPreferredDirection),
+}
+
+pub enum ScrollerSizingPolicy {
+    /// inherit sizing from the contained widget
+    Children,
+    /// states literally, ignoring the contained widget. the contained widget
+    /// will then be placed within the scroll widget's bounds
+    ///
+    /// give an aspect ratio direction to be given to the contained widget
+    Custom(CustomSizingControl, ScrollAspectRatioDirectionPolicy),
+}
+
 /// translates its content - facilitates scrolling. also applies clipping rect
 /// to contained content
 ///
@@ -110,7 +420,11 @@ impl ScrollerCursorCache {
 pub struct Scroller<'sdl, 'state> {
     /// for drag scrolling
     drag_state: DragState,
-    /// how many pixels to move per unit of received mouse wheel
+    /// how many pixels to move per unit of received mouse wheel. in logical
+    /// pixels (scaled by the ui scale)
+    ///
+    /// precise (fractional) wheel movement is accumulated, so trackpads scroll
+    /// smoothly
     pub mouse_wheel_sensitivity: i32,
     /// manhattan distance that the mouse must travel before it's considered a
     /// click and drag scroll
@@ -121,15 +435,46 @@ pub struct Scroller<'sdl, 'state> {
     pub scroll_y: &'state Cell<i32>,
     pub contained: Box<dyn Widget + 'sdl>,
     pub sizing_policy: ScrollerSizingPolicy,
+    /// for custom sizing. along the enabled scroll axes, the max is instead
+    /// the contained widget's min, capped by the custom max. so short content
+    /// doesn't leave an empty scroll area, and long content scrolls
+    pub shrink_wrap: bool,
     /// true restricts the scrolling to keep the contained in frame
     pub restrict_scroll: bool,
+    pub chain_policy: ScrollChainPolicy,
+    /// if set, the scroller's geometry is written here each update. allows
+    /// other widgets (e.g. a minimap) to follow the scroller
+    pub view: Option<&'state Cell<ScrollView>>,
+    /// identifies what the scroller is showing. when this changes, the scroll
+    /// position is saved for the old key and restored for the new one
+    pub content_key: Option<CellRefOrCell<'state, String>>,
+    pub on_content_key_change: Option<ContentKeyChanged<'sdl>>,
+    pub scroll_memory: ScrollMemory,
+    pub snap: ScrollSnapPolicy,
+    /// how long the scroll takes to move to a snap position
+    pub snap_duration: Duration,
+    pub snap_easing: Easing,
+    /// only with restrict_scroll. dragging past the end of the content moves
+    /// it with resistance, and it returns once released. the scroll values
+    /// stay within the restriction; the overscroll is applied on top
+    pub elastic: Option<ElasticOverscroll<'sdl>>,
+    /// drawn over the content where there's more of it past an edge
+    pub edge_shadows: Option<EdgeShadows>,
 
     /// calculated during update, stored for draw.
     /// used for clipping rect calculations
     previous_clipping_rect_from_update: ClippingRect,
     position_from_update: FRect,
+    /// where the contained is placed, before the scroll is applied
+    contained_position_from_update: FRect,
 
-    cursor_cache: ScrollerCursorCache,
+    cursor_cache: SystemCursorCache,
+    /// fractional pixels of wheel movement not yet applied to the scroll
+    wheel_remainder: (f32, f32),
+    snap_animation: Option<SnapAnimation>,
+    /// how far the content is displaced past the restriction
+    overscroll: (f32, f32),
+    overscroll_return: Option<OverscrollReturn>,
 }
 
 impl<'sdl, 'state> Scroller<'sdl, 'state> {
@@ -150,14 +495,319 @@ impl<'sdl, 'state> Scroller<'sdl, 'state> {
             scroll_y,
             contained: contains,
             restrict_scroll: true,
+            chain_policy: Default::default(),
+            view: None,
+            content_key: None,
+            on_content_key_change: None,
+            scroll_memory: Default::default(),
+            snap: Default::default(),
+            snap_duration: Duration::from_millis(200),
+            snap_easing: Default::default(),
+            elastic: None,
+            edge_shadows: None,
+            wheel_remainder: (0., 0.),
+            snap_animation: None,
+            overscroll: (0., 0.),
+            overscroll_return: None,
             sizing_policy: ScrollerSizingPolicy::Children,
+            shrink_wrap: false,
             cursor_cache: Default::default(),
             previous_clipping_rect_from_update: ClippingRect::None,
             position_from_update: Default::default(),
+            contained_position_from_update: Default::default(),
+        }
+    }
+
+    /// the scroller's geometry, as of the most recent update
+    pub fn scroll_view(&self) -> ScrollView {
+        ScrollView {
+            viewport: self.position_from_update,
+            content: self.contained_position_from_update,
+        }
+    }
+
+    /// the range of scroll values along the x axis, as of the most recent update
+    pub fn x_extent(&self) -> ScrollExtent {
+        self.scroll_view().x_extent()
+    }
+
+    /// the range of scroll values along the y axis, as of the most recent update
+    pub fn y_extent(&self) -> ScrollExtent {
+        self.scroll_view().y_extent()
+    }
+
+    /// the size of the contained widget, as of the most recent update. for
+    /// scrollbars and indicators (e.g. content larger than the scroller along
+    /// an axis can be scrolled along it)
+    pub fn content_size(&self) -> (f32, f32) {
+        (
+            self.contained_position_from_update.w,
+            self.contained_position_from_update.h,
+        )
+    }
+
+    /// how far the content is displaced past the end of the scroll, from an
+    /// elastic drag. positive is content pulled right or down, past its start
+    pub fn overscroll(&self) -> (f32, f32) {
+        self.overscroll
+    }
+
+    /// the overscroll, as applied to the contained's position
+    fn overscroll_px(&self) -> (i32, i32) {
+        (
+            self.overscroll.0.round() as i32,
+            self.overscroll.1.round() as i32,
+        )
+    }
+
+    /// once released, the overscroll animates back to nothing
+    fn release_overscroll(&mut self) {
+        if self.overscroll != (0., 0.) {
+            self.overscroll_return = Some(OverscrollReturn {
+                from: self.overscroll,
+                start: Instant::now(),
+            });
+            request_redraw();
+        }
+    }
+
+    /// set the scroll position. if restrict_scroll is set, this is restricted
+    /// on the next update
+    pub fn scroll_to(&self, x: i32, y: i32) {
+        self.scroll_x.set(x);
+        self.scroll_y.set(y);
+    }
+
+    /// how far along the scroll is, for each axis. 0 is at the start (left or
+    /// top) and 1 is at the end (right or bottom)
+    pub fn scroll_fraction(&self) -> (f32, f32) {
+        (
+            self.x_extent().fraction(self.scroll_x.get()),
+            self.y_extent().fraction(self.scroll_y.get()),
+        )
+    }
+
+    /// set the scroll from a fraction, for each axis. see scroll_fraction
+    pub fn set_scroll_fraction(&self, x: f32, y: f32) {
+        self.scroll_to(
+            self.x_extent().from_fraction(x),
+            self.y_extent().from_fraction(y),
+        );
+    }
+
+    /// scroll the minimum amount such that some rect is visible, for the
+    /// enabled axes. the rect is in the same coordinates that widgets are
+    /// updated with (e.g. from FocusManager::rect_of). if the rect is larger
+    /// than the visible area then its start is aligned with the visible area's
+    /// start
+    pub fn scroll_into_view(&self, rect: FRect) {
+        let viewport = self.position_from_update;
+        if self.scroll_x_enabled {
+            let delta = into_view_delta(viewport.x, viewport.w, rect.x, rect.w);
+            self.scroll_x.set(self.scroll_x.get() + delta);
+        }
+        if self.scroll_y_enabled {
+            let delta = into_view_delta(viewport.y, viewport.h, rect.y, rect.h);
+            self.scroll_y.set(self.scroll_y.get() + delta);
+        }
+    }
+
+    /// where a snap request leads from some scroll position. none if nothing
+    /// would change
+    fn snap_target(
+        &self,
+        request: SnapRequest,
+        from: (i32, i32),
+        view: ScrollView,
+    ) -> Option<(i32, i32)> {
+        let (step_x, step_y) = match request {
+            SnapRequest::Nearest => (0, 0),
+            SnapRequest::Step(x, y) => (x, y),
+        };
+        let axis =
+            |enabled: bool, extent: ScrollExtent, viewport_len: f32, scroll: i32, step: i32| {
+                if !enabled {
+                    return scroll;
+                }
+                // the scroll value decreases as the offset into the content
+                // increases
+                let direction = -step.signum();
+                let offset = (extent.start - scroll) as f32;
+                match snap_offset(&self.snap, offset, viewport_len, direction) {
+                    Some(offset) => {
+                        let target = extent.start - offset.round() as i32;
+                        if self.restrict_scroll {
+                            extent.clamp(target)
+                        } else {
+                            target
+                        }
+                    }
+                    None => scroll,
+                }
+            };
+        let target = (
+            axis(
+                self.scroll_x_enabled && (step_x != 0 || step_y == 0),
+                view.x_extent(),
+                view.viewport.w,
+                from.0,
+                step_x,
+            ),
+            axis(
+                self.scroll_y_enabled && (step_y != 0 || step_x == 0),
+                view.y_extent(),
+                view.viewport.h,
+                from.1,
+                step_y,
+            ),
+        );
+        if target == from {
+            None
+        } else {
+            Some(target)
+        }
+    }
+}
+
+/// remembers scroll positions per content key. for a scroller whose content
+/// is swapped out (e.g. switching tabs), so each content returns to where it
+/// was left
+#[derive(Debug, Default)]
+pub struct ScrollMemory {
+    positions: HashMap<String, (i32, i32)>,
+    /// the key currently being shown
+    current: Option<String>,
+}
+
+impl ScrollMemory {
+    /// note the current key. if it changed, the scroll is saved under the
+    /// previous key, and the scroll to restore for the new key is returned
+    pub fn switch(&mut self, key: &str, scroll: (i32, i32)) -> Option<(i32, i32)> {
+        match self.current.as_deref() {
+            Some(current) if current == key => return None,
+            Some(_) => {}
+            None => {
+                // nothing to restore from. keep whatever scroll is there
+                self.current = Some(key.to_owned());
+                return None;
+            }
+        }
+        if let Some(previous) = self.current.replace(key.to_owned()) {
+            self.positions.insert(previous, scroll);
+        }
+        Some(self.positions.get(key).copied().unwrap_or((0, 0)))
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// the next time this key is shown, it starts at the top
+    pub fn forget(&mut self, key: &str) {
+        self.positions.remove(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+    }
+}
+
+/// called when a scroller's content key changes. receives the previous key
+/// (if any) and the new key
+pub type ContentKeyChanged<'sdl> = Box<dyn FnMut(Option<&str>, &str) + 'sdl>;
+
+/// a scroller's geometry, as of its most recent update
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrollView {
+    /// the visible area
+    pub viewport: FRect,
+    /// where the contained is placed, before the scroll is applied
+    pub content: FRect,
+}
+
+impl ScrollView {
+    pub fn x_extent(&self) -> ScrollExtent {
+        ScrollExtent::new(self.viewport.x, self.viewport.w, self.content.x, self.content.w)
+    }
+
+    pub fn y_extent(&self) -> ScrollExtent {
+        ScrollExtent::new(self.viewport.y, self.viewport.h, self.content.y, self.content.h)
+    }
+}
+
+/// the range of valid scroll values along one axis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrollExtent {
+    /// the scroll value at which the start of the content is aligned with the
+    /// start of the visible area
+    pub start: i32,
+    /// the scroll value at which the end of the content is aligned with the
+    /// end of the visible area
+    pub end: i32,
+}
+
+impl ScrollExtent {
+    fn new(viewport_pos: f32, viewport_len: f32, content_pos: f32, content_len: f32) -> Self {
+        Self {
+            start: (viewport_pos - content_pos) as i32,
+            end: ((viewport_pos + viewport_len) - (content_pos + content_len)) as i32,
+        }
+    }
+
+    /// how far along a scroll value is. 0 at start, 1 at end
+    pub fn fraction(&self, scroll: i32) -> f32 {
+        if self.end == self.start {
+            return 0.; // guard div
         }
+        ((scroll - self.start) as f32 / (self.end - self.start) as f32).clamp(0., 1.)
+    }
+
+    /// restrict a scroll value to within the extent
+    pub fn clamp(&self, scroll: i32) -> i32 {
+        scroll.clamp(self.start.min(self.end), self.start.max(self.end))
+    }
+
+    /// how far a scroll value is from the start and from the end. i.e. how
+    /// much content is past the start and end of the visible area
+    pub fn remaining(&self, scroll: i32) -> (i32, i32) {
+        if self.start <= self.end {
+            return (0, 0); // content fits
+        }
+        ((self.start - scroll).max(0), (scroll - self.end).max(0))
+    }
+
+    /// inverse of fraction
+    pub fn from_fraction(&self, fraction: f32) -> i32 {
+        let fraction = fraction.clamp(0., 1.);
+        self.start + ((self.end - self.start) as f32 * fraction).round() as i32
     }
 }
 
+/// how much to scroll along an axis so a rect is within the viewport
+fn into_view_delta(viewport_pos: f32, viewport_len: f32, rect_pos: f32, rect_len: f32) -> i32 {
+    if rect_pos < viewport_pos || rect_len > viewport_len {
+        (viewport_pos - rect_pos) as i32
+    } else if rect_pos + rect_len > viewport_pos + viewport_len {
+        ((viewport_pos + viewport_len) - (rect_pos + rect_len)) as i32
+    } else {
+        0
+    }
+}
+
+/// if the mouse has moved far enough from where it was pressed for a drag to
+/// begin, along an enabled axis
+pub(crate) fn drag_past_deadzone(
+    start: (i32, i32),
+    current: (i32, i32),
+    deadzone: u32,
+    x_enabled: bool,
+    y_enabled: bool,
+) -> bool {
+    let trigger_x = x_enabled && (start.0 - current.0).unsigned_abs() > deadzone;
+    let trigger_y = y_enabled && (start.1 - current.1).unsigned_abs() > deadzone;
+    trigger_x || trigger_y
+}
+
 /// apply even if scroll is not enabled (as what if it was enabled previously
 /// and content was moved off screen)
 fn apply_scroll_restrictions(
@@ -172,65 +822,60 @@ fn apply_scroll_restrictions(
     if position_for_contained.h < event_position.h {
         // the contained thing is smaller than the parent
         let violating_top = position_for_contained.y < event_position.y;
-        let violating_bottom = position_for_contained.y + position_for_contained.h
-            > event_position.y + event_position.h;
+        let violating_bottom = position_for_contained.bottom() > event_position.bottom();
 
         if violating_top {
             *scroll_y += (event_position.y - position_for_contained.y) as i32;
         } else if violating_bottom {
-            *scroll_y -= ((position_for_contained.y + position_for_contained.h)
-                - (event_position.y + event_position.h)) as i32;
+            *scroll_y -= (position_for_contained.bottom() - event_position.bottom()) as i32;
         }
     } else {
         let down_from_top = position_for_contained.y > event_position.y;
 
-        let up_from_bottom = position_for_contained.y + position_for_contained.h
-            < event_position.y + event_position.h;
+        let up_from_bottom = position_for_contained.bottom() < event_position.bottom();
 
         if down_from_top {
             *scroll_y += (event_position.y - position_for_contained.y) as i32;
         } else if up_from_bottom {
-            *scroll_y -= ((position_for_contained.y + position_for_contained.h)
-                - (event_position.y + event_position.h)) as i32;
+            *scroll_y -= (position_for_contained.bottom() - event_position.bottom()) as i32;
         }
     }
 
     if position_for_contained.w < event_position.w {
         // the contained thing is smaller than the parent
         let violating_left = position_for_contained.x < event_position.x;
-        let violating_right = position_for_contained.x + position_for_contained.w
-            > event_position.x + event_position.w;
+        let violating_right = position_for_contained.right() > event_position.right();
 
         if violating_left {
             *scroll_x += (event_position.x - position_for_contained.x) as i32;
         } else if violating_right {
-            *scroll_x -= ((position_for_contained.x + position_for_contained.w)
-                - (event_position.x + event_position.w)) as i32;
+            *scroll_x -= (position_for_contained.right() - event_position.right()) as i32;
         }
     } else {
         let left_from_right = position_for_contained.x > event_position.x;
 
-        let right_from_left = position_for_contained.x + position_for_contained.w
-            < event_position.x + event_position.w;
+        let right_from_left = position_for_contained.right() < event_position.right();
 
         if left_from_right {
             *scroll_x += (event_position.x - position_for_contained.x) as i32;
         } else if right_from_left {
-            *scroll_x -= ((position_for_contained.x + position_for_contained.w)
-                - (event_position.x + event_position.w)) as i32;
+            *scroll_x -= (position_for_contained.right() - event_position.right()) as i32;
         }
     }
 }
 
 impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
-    fn min(
-        &mut self,
-    ) -> Result<(crate::util::length::MinLen, crate::util::length::MinLen), String> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(crate::util::length::MinLen, crate::util::length::MinLen)> {
         match &self.sizing_policy {
             ScrollerSizingPolicy::Children => self.contained.min(),
-            ScrollerSizingPolicy::Custom(scroller_literal_sizing, _) => {
-                Ok((scroller_literal_sizing.min_w, scroller_literal_sizing.min_h))
-            }
+            ScrollerSizingPolicy::Custom(scroller_literal_sizing, _) => Ok((
+                scroller_literal_sizing.min_w.scaled(),
+                scroller_literal_sizing.min_h.scaled(),
+            )),
         }
     }
 
@@ -252,13 +897,24 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
     }
 
-    fn max(
-        &mut self,
-    ) -> Result<(crate::util::length::MaxLen, crate::util::length::MaxLen), String> {
+    fn max(&mut self) -> UiResult<(crate::util::length::MaxLen, crate::util::length::MaxLen)> {
         match &self.sizing_policy {
             ScrollerSizingPolicy::Children => self.contained.max(),
             ScrollerSizingPolicy::Custom(scroller_literal_sizing, _) => {
-                Ok((scroller_literal_sizing.max_w, scroller_literal_sizing.max_h))
+                let mut max_w = scroller_literal_sizing.max_w.scaled();
+                let mut max_h = scroller_literal_sizing.max_h.scaled();
+                if self.shrink_wrap {
+                    let (content_w, content_h) = self.contained.min()?;
+                    if self.scroll_x_enabled {
+                        let min_w = scroller_literal_sizing.min_w.scaled();
+                        max_w = max_w.strictest(MaxLen(content_w.0.max(min_w.0)));
+                    }
+                    if self.scroll_y_enabled {
+                        let min_h = scroller_literal_sizing.min_h.scaled();
+                        max_h = max_h.strictest(MaxLen(content_h.0.max(min_h.0)));
+                    }
+                }
+                Ok((max_w, max_h))
             }
         }
     }
@@ -296,7 +952,7 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             ScrollerSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
             ScrollerSizingPolicy::Custom(scroller_literal_sizing, _) => {
@@ -312,7 +968,7 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             ScrollerSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
             ScrollerSizingPolicy::Custom(scroller_literal_sizing, _) => {
@@ -339,7 +995,22 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
     }
 
-    fn update(&mut self, mut event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        if let Some(content_key) = &self.content_key {
+            let key = content_key.scope_take();
+            let previous = self.scroll_memory.current().map(|v| v.to_owned());
+            let scroll = (self.scroll_x.get(), self.scroll_y.get());
+            if let Some((x, y)) = self.scroll_memory.switch(key.as_str(), scroll) {
+                self.scroll_x.set(x);
+                self.scroll_y.set(y);
+                self.drag_state = DragState::None;
+                self.snap_animation = None;
+                if let Some(on_change) = self.on_content_key_change.as_mut() {
+                    on_change(previous.as_deref(), key.as_str());
+                }
+            }
+        }
+
         if let DragState::Dragging(_) = self.drag_state {
             // consume related events if currently dragging. do this before
             // passing event to contained
@@ -361,6 +1032,57 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         let mut scroll_x = self.scroll_x.get();
         let mut scroll_y = self.scroll_y.get();
 
+        // continue moving to a snap position
+        if let Some(animation) = self.snap_animation {
+            if animation.current != (scroll_x, scroll_y) {
+                self.snap_animation = None; // scroll was set elsewhere
+            } else {
+                let t = if self.snap_duration.is_zero() {
+                    1.
+                } else {
+                    (animation.start.elapsed().as_secs_f32() / self.snap_duration.as_secs_f32())
+                        .min(1.)
+                };
+                let eased = self.snap_easing.apply(t);
+                let lerp = |from: i32, to: i32| from + ((to - from) as f32 * eased).round() as i32;
+                scroll_x = lerp(animation.from.0, animation.to.0);
+                scroll_y = lerp(animation.from.1, animation.to.1);
+                if t >= 1. {
+                    self.snap_animation = None;
+                } else {
+                    request_redraw();
+                }
+            }
+        }
+
+        // continue returning from an overscroll
+        if let Some(overscroll_return) = self.overscroll_return {
+            let (duration, easing) = match &self.elastic {
+                Some(elastic) => (elastic.return_duration, elastic.return_easing),
+                None => (Duration::ZERO, Easing::Linear),
+            };
+            let t = if duration.is_zero() {
+                1.
+            } else {
+                (overscroll_return.start.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.)
+            };
+            let remaining = 1. - easing.apply(t);
+            self.overscroll = (
+                overscroll_return.from.0 * remaining,
+                overscroll_return.from.1 * remaining,
+            );
+            if t >= 1. {
+                self.overscroll_return = None;
+                self.overscroll = (0., 0.);
+            } else {
+                request_redraw();
+            }
+        }
+        if self.elastic.is_none() || !self.restrict_scroll {
+            self.overscroll = (0., 0.);
+            self.overscroll_return = None;
+        }
+
         self.previous_clipping_rect_from_update = event.clipping_rect;
         self.position_from_update = event.position;
 
@@ -384,6 +1106,11 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
             }
         };
 
+        self.contained_position_from_update = position_for_contained;
+        if let Some(view) = self.view {
+            view.set(self.scroll_view());
+        }
+
         if self.restrict_scroll {
             // restrict here to catch all from previous frame or previous within
             // this frame. e.g. if the window is resized to be smaller so it's
@@ -397,12 +1124,11 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
 
         // shift all positions based on the scroll, and update the container
-        let position_for_contained_shifted = FRect {
-            x: position_for_contained.x + scroll_x as f32,
-            y: position_for_contained.y + scroll_y as f32,
-            w: position_for_contained.w,
-            h: position_for_contained.h,
-        };
+        let before_update_overscroll = self.overscroll_px();
+        let position_for_contained_shifted = position_for_contained.translated(
+            (scroll_x + before_update_overscroll.0) as f32,
+            (scroll_y + before_update_overscroll.1) as f32,
+        );
         let mut event_for_contained = event.sub_event(position_for_contained_shifted);
         // set clipping rect in dup as to not affect any widgets that might come
         // after this one
@@ -410,8 +1136,16 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
 
         let before_update_scroll_pos = (scroll_x, scroll_y);
 
+        let mouse_wheel_sensitivity =
+            crate::util::scale::scaled(self.mouse_wheel_sensitivity as f32);
+
         self.contained.update(event_for_contained)?;
 
+        let hit = HitTest::new(event.position, clip_rect_for_contained, event.window_id);
+
+        let mut snap_request: Option<SnapRequest> = None;
+        let keymod = event.input.keymod;
+
         // handle mouse wheel. happens after update, as it allows contained
         // to consume it first (for example, with nested scrolls)
         event
@@ -425,8 +1159,8 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
             .for_each(|e| match e.e {
                 // mouse wheel logic
                 sdl2::event::Event::MouseWheel {
-                    x,
-                    y,
+                    precise_x,
+                    precise_y,
                     mouse_x,
                     mouse_y,
                     direction,
@@ -436,44 +1170,82 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                     if event.window_id != window_id {
                         return; // not for me!
                     }
-                    let mut multiplier: i32 = match direction {
-                        sdl2::mouse::MouseWheelDirection::Flipped => -1,
-                        _ => 1,
+                    let direction_multiplier: f32 = match direction {
+                        sdl2::mouse::MouseWheelDirection::Flipped => -1.,
+                        _ => 1.,
                     };
-                    if position_for_contained.h > event.position.h {
-                        multiplier *= -1;
-                    }
+                    // sdl's positive y is up but positive x is right. content
+                    // smaller than the scroller moves the other way
+                    let x_multiplier = if position_for_contained.w > event.position.w {
+                        direction_multiplier
+                    } else {
+                        -direction_multiplier
+                    };
+                    let y_multiplier = if position_for_contained.h > event.position.h {
+                        -direction_multiplier
+                    } else {
+                        direction_multiplier
+                    };
+                    // shift + wheel scrolls horizontally, for horizontal only
+                    // scrollers. wheel down is right
+                    let shift = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+                    let (precise_x, precise_y) =
+                        if shift && self.scroll_x_enabled && !self.scroll_y_enabled {
+                            (precise_x - precise_y, 0.)
+                        } else {
+                            (precise_x, precise_y)
+                        };
                     // only look at wheel when mouse over scroll area
-                    let pos: Option<sdl2::rect::Rect> = event.position.into();
-                    if pos
-                        .map(|pos| pos.contains_point((mouse_x, mouse_y)))
-                        .unwrap_or(false)
-                    {
-                        let point_contained_in_clipping_rect = match clip_rect_for_contained {
-                            sdl2::render::ClippingRect::Some(rect) => {
-                                rect.contains_point((mouse_x, mouse_y))
+                    if hit.contains(window_id, mouse_x, mouse_y) {
+                        if let ScrollChainPolicy::Modifier(m) = self.chain_policy {
+                            if keymod.intersects(m) {
+                                return; // leave for outer scroller
                             }
-                            sdl2::render::ClippingRect::Zero => false,
-                            sdl2::render::ClippingRect::None => true,
-                        };
-                        if !point_contained_in_clipping_rect {
-                            return;
                         }
-                        e.set_consumed_by_layout();
+                        let mut new_scroll_x = scroll_x;
+                        let mut new_scroll_y = scroll_y;
+                        let mut new_remainder = self.wheel_remainder;
                         if self.scroll_x_enabled {
-                            scroll_x -= multiplier * x * self.mouse_wheel_sensitivity;
+                            let amount = x_multiplier * precise_x * mouse_wheel_sensitivity
+                                + new_remainder.0;
+                            new_remainder.0 = amount.fract();
+                            new_scroll_x -= amount.trunc() as i32;
                         }
                         if self.scroll_y_enabled {
-                            scroll_y -= multiplier * y * self.mouse_wheel_sensitivity;
+                            let amount = y_multiplier * precise_y * mouse_wheel_sensitivity
+                                + new_remainder.1;
+                            new_remainder.1 = amount.fract();
+                            new_scroll_y -= amount.trunc() as i32;
                         }
+                        let unrestricted = (new_scroll_x, new_scroll_y);
                         if self.restrict_scroll {
                             apply_scroll_restrictions(
                                 position_for_contained,
                                 event.position,
-                                &mut scroll_y,
-                                &mut scroll_x,
+                                &mut new_scroll_y,
+                                &mut new_scroll_x,
                             );
                         }
+                        // restricted all the way back to where it started
+                        if self.chain_policy == ScrollChainPolicy::Chain
+                            && (new_scroll_x, new_scroll_y) == (scroll_x, scroll_y)
+                            && unrestricted != (scroll_x, scroll_y)
+                        {
+                            return; // at end. leave for outer scroller
+                        }
+                        e.set_consumed_by_layout();
+                        self.wheel_remainder = new_remainder;
+                        if self.snap != ScrollSnapPolicy::None {
+                            // go to the next snap position instead
+                            let step = (unrestricted.0 - scroll_x, unrestricted.1 - scroll_y);
+                            if step != (0, 0) {
+                                self.wheel_remainder = (0., 0.);
+                                snap_request = Some(SnapRequest::Step(step.0, step.1));
+                            }
+                            return;
+                        }
+                        scroll_x = new_scroll_x;
+                        scroll_y = new_scroll_y;
                     }
                 }
                 sdl2::event::Event::Window {
@@ -487,6 +1259,12 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                 } => {
                     // same functionality as below for mouse button up,
                     // but don't consume the event
+                    if let DragState::Dragging(_) = self.drag_state {
+                        snap_request = Some(SnapRequest::Nearest);
+                    }
+                    if !matches!(self.drag_state, DragState::None) {
+                        self.release_overscroll();
+                    }
                     self.drag_state = DragState::None;
                     if self.restrict_scroll {
                         apply_scroll_restrictions(
@@ -504,6 +1282,10 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                     DragState::None => {}
                     _ => {
                         // reset, regardless mouse position
+                        if let DragState::Dragging(_) = self.drag_state {
+                            snap_request = Some(SnapRequest::Nearest);
+                        }
+                        self.release_overscroll();
                         self.drag_state = DragState::None;
                         e.set_consumed_by_layout();
                         if self.restrict_scroll {
@@ -523,24 +1305,12 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                     y,
                     window_id,
                     ..
-                } => {
-                    if event.window_id != window_id {
-                        return; // not for me!
-                    }
-                    let pos: Option<sdl2::rect::Rect> = event.position.into();
-                    if pos.map(|pos| pos.contains_point((x, y))).unwrap_or(false) {
-                        let point_contained_in_clipping_rect = match clip_rect_for_contained {
-                            sdl2::render::ClippingRect::Some(rect) => rect.contains_point((x, y)),
-                            sdl2::render::ClippingRect::Zero => false,
-                            sdl2::render::ClippingRect::None => true,
-                        };
-                        if !point_contained_in_clipping_rect {
-                            return;
-                        }
-                        e.set_consumed_by_layout();
-                        if let DragState::None = self.drag_state {
-                            self.drag_state = DragState::DragStart((x, y));
-                        }
+                } if hit.contains(window_id, x, y) => {
+                    e.set_consumed_by_layout();
+                    self.snap_animation = None; // grabbed
+                    self.overscroll_return = None;
+                    if let DragState::None = self.drag_state {
+                        self.drag_state = DragState::DragStart((x, y));
                     }
                 }
                 // on mouse motion apply mouse drag.
@@ -552,6 +1322,9 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                     ..
                 } => {
                     if !mousestate.left() {
+                        if !matches!(self.drag_state, DragState::None) {
+                            self.release_overscroll();
+                        }
                         self.drag_state = DragState::None;
                         // if mouse motion is detected and the left mouse button
                         // isn't pressed down, regardless of position or window,
@@ -570,13 +1343,13 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                     }
                     e.set_consumed_by_layout();
                     if let DragState::DragStart((start_x, start_y)) = self.drag_state {
-                        let dragged_far_enough_x =
-                            (start_x - x).unsigned_abs() > self.drag_deadzone;
-                        let dragged_far_enough_y =
-                            (start_y - y).unsigned_abs() > self.drag_deadzone;
-                        let trigger_x = dragged_far_enough_x && self.scroll_x_enabled;
-                        let trigger_y = dragged_far_enough_y && self.scroll_y_enabled;
-                        if trigger_x || trigger_y {
+                        if drag_past_deadzone(
+                            (start_x, start_y),
+                            (x, y),
+                            self.drag_deadzone,
+                            self.scroll_x_enabled,
+                            self.scroll_y_enabled,
+                        ) {
                             self.drag_state = DragState::Dragging((x - scroll_x, y - scroll_y));
                             // intentional fallthrough
                         }
@@ -589,22 +1362,75 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
                         if self.scroll_y_enabled {
                             scroll_y = y - drag_y;
                         }
+                        if let (Some(elastic), true) = (&self.elastic, self.restrict_scroll) {
+                            // the scroll stays restricted. past that is
+                            // overscroll
+                            let unrestricted = (scroll_x, scroll_y);
+                            apply_scroll_restrictions(
+                                position_for_contained,
+                                event.position,
+                                &mut scroll_y,
+                                &mut scroll_x,
+                            );
+                            self.overscroll = (
+                                rubber_band(
+                                    (unrestricted.0 - scroll_x) as f32,
+                                    event.position.w,
+                                    elastic.resistance,
+                                ),
+                                rubber_band(
+                                    (unrestricted.1 - scroll_y) as f32,
+                                    event.position.h,
+                                    elastic.resistance,
+                                ),
+                            );
+                        }
                     }
                 }
                 _ => {}
             });
 
+        if let Some(request) = snap_request {
+            // a step continues from where an in progress snap is going
+            let from = match self.snap_animation {
+                Some(animation) => animation.to,
+                None => (scroll_x, scroll_y),
+            };
+            if let Some(to) = self.snap_target(request, from, self.scroll_view()) {
+                if self.snap_duration.is_zero() {
+                    (scroll_x, scroll_y) = to;
+                    self.snap_animation = None;
+                } else {
+                    self.snap_animation = Some(SnapAnimation {
+                        from: (scroll_x, scroll_y),
+                        to,
+                        start: Instant::now(),
+                        current: (scroll_x, scroll_y),
+                    });
+                    request_redraw();
+                }
+            }
+        }
+
         // sync changes. the scroll_x and scroll_y local vars should not have
         // been changed if the scroll wasn't enabled, with the exception of
         // scroll restrictions (and e.g. changing window size)
         self.scroll_x.set(scroll_x);
         self.scroll_y.set(scroll_y);
+        if let Some(animation) = self.snap_animation.as_mut() {
+            animation.current = (scroll_x, scroll_y);
+        }
 
         // update cursor based on drag state
         match self.drag_state {
             DragState::Dragging(_) => {
-                self.cursor_cache
-                    .set_or_use_cache(self.scroll_x_enabled, self.scroll_y_enabled);
+                let cursor = match (self.scroll_x_enabled, self.scroll_y_enabled) {
+                    (true, true) => Some(SystemCursor::SizeAll),
+                    (true, false) => Some(SystemCursor::SizeWE),
+                    (false, true) => Some(SystemCursor::SizeNS),
+                    (false, false) => None,
+                };
+                self.cursor_cache.set_if(cursor);
             }
             _ => {
                 self.cursor_cache.clear();
@@ -612,9 +1438,10 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         }
 
         // account for changes between when update was called and the events were consumed
+        let overscroll = self.overscroll_px();
         self.contained.update_adjust_position((
-            scroll_x - before_update_scroll_pos.0,
-            scroll_y - before_update_scroll_pos.1,
+            scroll_x - before_update_scroll_pos.0 + overscroll.0 - before_update_overscroll.0,
+            scroll_y - before_update_scroll_pos.1 + overscroll.1 - before_update_overscroll.1,
         ));
         Ok(())
     }
@@ -622,6 +1449,11 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
         self.position_from_update.x += pos_delta.0 as f32;
         self.position_from_update.y += pos_delta.1 as f32;
+        self.contained_position_from_update.x += pos_delta.0 as f32;
+        self.contained_position_from_update.y += pos_delta.1 as f32;
+        if let Some(view) = self.view {
+            view.set(self.scroll_view());
+        }
         self.contained.update_adjust_position(pos_delta);
     }
 
@@ -629,14 +1461,34 @@ impl<'sdl, 'state> Widget for Scroller<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         debug_assert!(canvas.clip_rect() == self.previous_clipping_rect_from_update);
         canvas.set_clip_rect(clipping_rect_intersection(
             self.previous_clipping_rect_from_update,
             self.position_from_update.into(),
         ));
-        let draw_result = self.contained.draw(canvas, focus_manager);
-        canvas.set_clip_rect(self.previous_clipping_rect_from_update); // restore
+        let mut draw_result = self.contained.draw(&mut canvas, focus_manager);
+        if let (true, Some(edge_shadows)) = (draw_result.is_ok(), &self.edge_shadows) {
+            let rect: Option<Rect> = self.position_from_update.into();
+            if let Some(rect) = rect {
+                let remaining = [
+                    self.x_extent().remaining(self.scroll_x.get()),
+                    self.y_extent().remaining(self.scroll_y.get()),
+                ];
+                canvas.set_blend_mode(BlendMode::Blend);
+                draw_result = edge_shadows.draw(&mut canvas, rect, remaining);
+            }
+        }
+        if draw_result.is_ok() && self.overscroll != (0., 0.) {
+            if let Some(indicator) = self
+                .elastic
+                .as_mut()
+                .and_then(|elastic| elastic.indicator.as_mut())
+            {
+                draw_result = indicator(&mut canvas, self.position_from_update, self.overscroll);
+            }
+        }
         draw_result
     }
 }
@@ -1,3 +1,5 @@
 pub mod layout;
+pub mod prelude;
+pub mod testing;
 pub mod util;
 pub mod widget;
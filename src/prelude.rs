@@ -0,0 +1,50 @@
+//! the commonly needed types, for a single import:
+//!
+//! ```ignore
+//! use tiny_sdl2_gui::prelude::*;
+//! ```
+//!
+//! anything more specific (styles, policies of particular layouts, etc.) is
+//! still imported from its module
+
+pub use crate::layout::{
+    clipper::Clipper,
+    horizontal_layout::HorizontalLayout,
+    scroller::{Scroller, ScrollerSizingPolicy},
+    vertical_layout::{MajorAxisMaxLenPolicy, VerticalLayout},
+};
+
+pub use crate::util::{
+    error::{UiError, UiResult},
+    focus::{FocusID, FocusManager},
+    font::SingleLineTextRenderType,
+    length::{
+        AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MaxLenPolicy, MinLen,
+        MinLenFailPolicy, MinLenPolicy, PreferredPortion,
+    },
+    rect::FRect,
+    rust::CellRefOrCell,
+};
+
+pub use crate::widget::{
+    border::Border,
+    button::Button,
+    checkbox::{CheckBox, DefaultCheckBoxStyle, EmptyFocusPressWidgetSoundStyle},
+    draw_gui,
+    multi_line_label::MultiLineLabel,
+    padding::Padding,
+    single_line_label::SingleLineLabel,
+    single_line_text_input::SingleLineTextInput,
+    strut::Strut,
+    texture::{AspectRatioFailPolicy, Texture},
+    update_gui, GuiDriver, SDLEvent, Widget, WidgetUpdateEvent,
+};
+
+#[cfg(feature = "sdl2-ttf")]
+pub use crate::{
+    util::font::{FontManager, TextRenderer},
+    widget::button::LabelButtonStyle,
+};
+
+#[cfg(feature = "sdl2-mixer")]
+pub use crate::{util::audio::SoundManager, widget::checkbox::DefaultFocusPressWidgetSoundStyle};
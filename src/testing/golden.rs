@@ -0,0 +1,339 @@
+//! golden image tests. a widget is rendered headlessly and compared against a
+//! stored reference image, to catch unintended changes to how it's drawn.
+//!
+//! reference images are bmp files. a missing reference is written from the
+//! current rendering, and the check passes. after an intended change, set the
+//! UPDATE_GOLDEN environment variable to rewrite the references. on a
+//! mismatch, the rendering and an image of the differences are written beside
+//! the reference (e.g. button.actual.bmp and button.diff.bmp)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(v: u8) -> [u8; 4] {
+        [v, v, v, 255]
+    }
+
+    fn buffer(pixels: &[[u8; 4]]) -> Vec<u8> {
+        pixels.iter().flatten().copied().collect()
+    }
+
+    #[test]
+    fn identical() {
+        let a = buffer(&[pixel(10), pixel(20), pixel(30), pixel(40)]);
+        let (diff, _) = diff_buffers(&a, &a, 2, 0);
+        assert_eq!(diff.differing, 0);
+        assert_eq!(diff.max_channel_diff, 0);
+        assert_eq!(diff.bounds, None);
+    }
+
+    #[test]
+    fn channel_tolerance() {
+        let actual = buffer(&[pixel(10), pixel(20), pixel(30), pixel(40)]);
+        let expected = buffer(&[pixel(12), pixel(20), pixel(30), pixel(50)]);
+        let (diff, image) = diff_buffers(&actual, &expected, 2, 2);
+        // the first pixel is within the tolerance
+        assert_eq!(diff.differing, 1);
+        assert_eq!(diff.max_channel_diff, 10);
+        assert_eq!(diff.bounds, Some(Rect::new(1, 1, 1, 1)));
+        assert_eq!(&image[12..16], &DIFF_COLOR.to_ne_bytes());
+        assert_ne!(&image[0..4], &DIFF_COLOR.to_ne_bytes());
+    }
+
+    #[test]
+    fn bounds_cover_differences() {
+        let actual = buffer(&[pixel(0); 6]);
+        let mut expected = actual.clone();
+        expected[4] = 255; // (1, 0)
+        expected[5 * 4 + 1] = 255; // (2, 1)
+        let (diff, _) = diff_buffers(&actual, &expected, 3, 0);
+        assert_eq!(diff.differing, 2);
+        assert_eq!(diff.bounds, Some(Rect::new(1, 0, 2, 2)));
+    }
+}
+
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::WindowCanvas,
+    surface::Surface,
+    Sdl,
+};
+
+use crate::{
+    util::{
+        error::{UiError, UiResult},
+        export::render_to_surface,
+    },
+    widget::Widget,
+};
+
+/// differing pixels are this color in the diff image (ARGB8888)
+const DIFF_COLOR: u32 = 0xFFFF0000;
+
+/// a hidden window, for rendering without showing anything. uses sdl's dummy
+/// video driver, unless SDL_VIDEODRIVER is set.
+///
+/// widgets need a texture creator which outlives them; get one with
+/// canvas.texture_creator(). like the rest of sdl's video, it must only be
+/// used from one thread (e.g. run golden tests with --test-threads=1)
+pub struct Headless {
+    pub canvas: WindowCanvas,
+    /// renderings are drawn onto this
+    pub background: Color,
+    // dropped after the canvas
+    _sdl: Sdl,
+}
+
+impl Headless {
+    pub fn new() -> UiResult<Self> {
+        // lower priority than the environment variable
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+        let sdl = sdl2::init()?;
+        let video = sdl.video()?;
+        let window = video
+            .window("golden", 1, 1)
+            .hidden()
+            .build()
+            .map_err(|e| UiError::Sdl(e.to_string()))?;
+        let canvas = window
+            .into_canvas()
+            .software()
+            .target_texture()
+            .build()
+            .map_err(|e| UiError::Sdl(e.to_string()))?;
+        Ok(Self {
+            canvas,
+            background: Color::BLACK,
+            _sdl: sdl,
+        })
+    }
+
+    /// see util::export::render_to_surface
+    pub fn render(
+        &mut self,
+        widget: &mut dyn Widget,
+        size: (u32, u32),
+    ) -> UiResult<Surface<'static>> {
+        render_to_surface(widget, &mut self.canvas, size, self.background)
+    }
+
+    /// render a widget and compare it against a reference image. see the
+    /// module docs
+    pub fn check<P: AsRef<Path>>(
+        &mut self,
+        widget: &mut dyn Widget,
+        size: (u32, u32),
+        reference: P,
+        tolerance: Tolerance,
+    ) -> UiResult<()> {
+        let reference = reference.as_ref();
+        let actual = self.render(widget, size)?;
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() || !reference.exists() {
+            if let Some(parent) = reference.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            return actual.save_bmp(reference).map_err(UiError::Sdl);
+        }
+
+        let expected = Surface::load_bmp(reference)?;
+        let mismatch = match compare(&actual, &expected, tolerance)? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let actual_path = sibling(reference, "actual");
+        actual.save_bmp(&actual_path)?;
+        let mut written = actual_path.display().to_string();
+        if let Mismatch::Pixels { image, .. } = &mismatch {
+            let diff_path = sibling(reference, "diff");
+            image.save_bmp(&diff_path)?;
+            written = format!("{} and {}", written, diff_path.display());
+        }
+        Err(UiError::Custom(format!(
+            "{} doesn't match: {}. wrote {}",
+            reference.display(),
+            mismatch,
+            written
+        )))
+    }
+}
+
+/// a path beside the reference, e.g. button.bmp -> button.diff.bmp
+fn sibling(reference: &Path, suffix: &str) -> PathBuf {
+    reference.with_extension(format!("{}.bmp", suffix))
+}
+
+/// how different a rendering can be from its reference and still match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance {
+    /// how much a color channel can differ before the pixel counts as
+    /// different. allows for small differences between renderers
+    pub channel: u8,
+    /// how many pixels can be different
+    pub pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            pixels: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelDiff {
+    /// how many pixels are different
+    pub differing: usize,
+    /// the largest difference of any channel, over all the pixels
+    pub max_channel_diff: u8,
+    /// the smallest rect containing the differing pixels
+    pub bounds: Option<Rect>,
+}
+
+/// how a rendering doesn't match its reference
+pub enum Mismatch {
+    Size {
+        actual: (u32, u32),
+        expected: (u32, u32),
+    },
+    Pixels {
+        diff: PixelDiff,
+        /// the reference, dimmed, with the differing pixels in red
+        image: Surface<'static>,
+    },
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::Size { actual, expected } => write!(
+                f,
+                "size is {}x{}, expected {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            Mismatch::Pixels { diff, .. } => {
+                write!(
+                    f,
+                    "{} pixels differ, by up to {}",
+                    diff.differing, diff.max_channel_diff
+                )?;
+                if let Some(bounds) = diff.bounds {
+                    write!(
+                        f,
+                        ", within {}x{} at ({}, {})",
+                        bounds.width(),
+                        bounds.height(),
+                        bounds.x(),
+                        bounds.y()
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// the pixels of a surface as ARGB8888, without any padding between rows
+fn packed_pixels(surface: &Surface) -> UiResult<Vec<u8>> {
+    let surface = surface.convert_format(PixelFormatEnum::ARGB8888)?;
+    let row_len = surface.width() as usize * 4;
+    let pitch = surface.pitch() as usize;
+    Ok(surface.with_lock(|buffer| {
+        buffer
+            .chunks(pitch)
+            .take(surface.height() as usize)
+            .flat_map(|row| &row[..row_len])
+            .copied()
+            .collect()
+    }))
+}
+
+/// compare two ARGB8888 buffers of the same size, without padding between
+/// rows. also gives the diff image's pixels
+fn diff_buffers(
+    actual: &[u8],
+    expected: &[u8],
+    width: u32,
+    channel_tolerance: u8,
+) -> (PixelDiff, Vec<u8>) {
+    debug_assert_eq!(actual.len(), expected.len());
+    let mut differing = 0;
+    let mut max_channel_diff = 0u8;
+    // min x, min y, max x, max y
+    let mut bounds: Option<(i32, i32, i32, i32)> = None;
+    let mut image = Vec::with_capacity(expected.len());
+
+    for (i, (a, e)) in actual
+        .chunks_exact(4)
+        .zip(expected.chunks_exact(4))
+        .enumerate()
+    {
+        let channel_diff = a
+            .iter()
+            .zip(e.iter())
+            .map(|(a, e)| a.abs_diff(*e))
+            .max()
+            .unwrap_or(0);
+        max_channel_diff = max_channel_diff.max(channel_diff);
+        if channel_diff <= channel_tolerance {
+            let e = u32::from_ne_bytes([e[0], e[1], e[2], e[3]]);
+            // each color channel 3/8 as bright, opaque
+            let dimmed = 0xFF000000 | (((e & 0x00FCFCFC) >> 2) + ((e & 0x00F8F8F8) >> 3));
+            image.extend_from_slice(&dimmed.to_ne_bytes());
+            continue;
+        }
+        differing += 1;
+        image.extend_from_slice(&DIFF_COLOR.to_ne_bytes());
+        let x = (i % width as usize) as i32;
+        let y = (i / width as usize) as i32;
+        bounds = Some(match bounds {
+            None => (x, y, x, y),
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        });
+    }
+
+    let diff = PixelDiff {
+        differing,
+        max_channel_diff,
+        bounds: bounds
+            .map(|(x0, y0, x1, y1)| Rect::new(x0, y0, (x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32)),
+    };
+    (diff, image)
+}
+
+/// compare a rendering against its reference. none if they match
+pub fn compare(
+    actual: &Surface,
+    expected: &Surface,
+    tolerance: Tolerance,
+) -> UiResult<Option<Mismatch>> {
+    if actual.size() != expected.size() {
+        return Ok(Some(Mismatch::Size {
+            actual: actual.size(),
+            expected: expected.size(),
+        }));
+    }
+    let (w, h) = actual.size();
+    let (diff, mut image_pixels) = diff_buffers(
+        &packed_pixels(actual)?,
+        &packed_pixels(expected)?,
+        w,
+        tolerance.channel,
+    );
+    if diff.differing <= tolerance.pixels {
+        return Ok(None);
+    }
+    let image = Surface::from_data(&mut image_pixels, w, h, w * 4, PixelFormatEnum::ARGB8888)?
+        .convert_format(PixelFormatEnum::ARGB8888)?;
+    Ok(Some(Mismatch::Pixels { diff, image }))
+}
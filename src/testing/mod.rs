@@ -0,0 +1,3 @@
+//! helpers for testing guis built with this crate
+
+pub mod golden;
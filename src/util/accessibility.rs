@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn description_display() {
+        let d = AccessibleDescription {
+            role: AccessibleRole::CheckBox,
+            label: "mute".to_owned(),
+            value: None,
+            state: AccessibleState {
+                checked: Some(true),
+                pressed: false,
+            },
+        };
+        assert_eq!(d.to_string(), "mute, checkbox, checked");
+
+        let d = AccessibleDescription {
+            role: AccessibleRole::TextInput,
+            label: "".to_owned(),
+            value: Some("bob".to_owned()),
+            state: Default::default(),
+        };
+        assert_eq!(d.to_string(), "text input, bob");
+    }
+}
+
+use std::fmt::Display;
+
+/// what kind of thing a widget is, from the perspective of a screen reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessibleRole {
+    Button,
+    CheckBox,
+    TextInput,
+    Label,
+    Other,
+}
+
+impl Display for AccessibleRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AccessibleRole::Button => "button",
+            AccessibleRole::CheckBox => "checkbox",
+            AccessibleRole::TextInput => "text input",
+            AccessibleRole::Label => "label",
+            AccessibleRole::Other => "",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessibleState {
+    /// None if the widget can't be checked
+    pub checked: Option<bool>,
+    pub pressed: bool,
+}
+
+/// how a widget describes itself. see Widget::accessible_description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessibleDescription {
+    pub role: AccessibleRole,
+    /// what the widget is called (e.g. the text on a button)
+    pub label: String,
+    /// the content of the widget, if it has any (e.g. the text in a text input)
+    pub value: Option<String>,
+    pub state: AccessibleState,
+}
+
+/// called with the focused widget's description, each time it changes
+pub type AccessibilityObserver = Box<dyn FnMut(&AccessibleDescription)>;
+
+/// a single line of text which is suitable for forwarding to a text to speech
+/// engine. e.g. "mute, checkbox, checked"
+impl Display for AccessibleDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let role = self.role.to_string();
+        let checked = self.state.checked.map(|checked| match checked {
+            true => "checked",
+            false => "not checked",
+        });
+        let pressed = match self.state.pressed {
+            true => Some("pressed"),
+            false => None,
+        };
+        let parts = [
+            Some(self.label.as_str()),
+            Some(role.as_str()),
+            self.value.as_deref(),
+            checked,
+            pressed,
+        ];
+        let mut first = true;
+        for part in parts.into_iter().flatten().filter(|part| !part.is_empty()) {
+            if !first {
+                f.write_str(", ")?;
+            }
+            first = false;
+            f.write_str(part)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.), 0.);
+            assert_eq!(easing.apply(1.), 1.);
+            // clamped
+            assert_eq!(easing.apply(-1.), 0.);
+            assert_eq!(easing.apply(2.), 1.);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+    }
+
+    #[test]
+    fn redraw_request_is_taken() {
+        assert!(!take_redraw_request());
+        request_redraw();
+        assert!(take_redraw_request());
+        assert!(!take_redraw_request());
+    }
+}
+
+use std::cell::Cell;
+
+// same reasoning as the ui scale; draw doesn't have a way of passing
+// information back up to the caller
+thread_local! {
+    static REDRAW_REQUESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// called by widgets during draw if they're animating, and need another frame
+/// even if no events occur
+pub fn request_redraw() {
+    REDRAW_REQUESTED.with(|r| r.set(true));
+}
+
+/// called by the application after drawing. if true, the next frame should
+/// happen soon, instead of waiting indefinitely for the next event
+pub fn take_redraw_request() -> bool {
+    REDRAW_REQUESTED.with(|r| r.replace(false))
+}
+
+/// maps linear progress (0 to 1) to eased progress (0 to 1)
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2. - t),
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+        }
+    }
+}
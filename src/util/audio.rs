@@ -10,6 +10,8 @@ use std::{
 use sdl2::mixer::Chunk;
 use weak_table::WeakValueHashMap;
 
+use crate::util::error::UiResult;
+
 /// Wrapper for `Rc<T>` that compares and hashes by pointer location.
 struct RcKey<T>(Rc<T>);
 
@@ -90,7 +92,7 @@ impl SoundManager {
     }
 
     /// get a sound. to be immediately played
-    pub fn get(&mut self, sound_path: &Path) -> Result<Rc<Chunk>, String> {
+    pub fn get(&mut self, sound_path: &Path) -> UiResult<Rc<Chunk>> {
         if let Some(v) = self.sounds.get(sound_path) {
             self.delay_dropper.drop_later(v.clone()); // refresh duration
             return Ok(v);
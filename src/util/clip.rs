@@ -0,0 +1,118 @@
+//! operations on clipping rects, for widgets which clip what they contain
+//! (e.g. a custom scrolling area). ClippingRect::None doesn't clip anything,
+//! and ClippingRect::Zero clips everything
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersections() {
+        let a = ClippingRect::Some(Rect::new(0, 0, 10, 10));
+        let b = ClippingRect::Some(Rect::new(5, 5, 10, 10));
+        assert!(matches!(
+            intersection(a, b),
+            ClippingRect::Some(r) if r == Rect::new(5, 5, 5, 5)
+        ));
+        assert!(matches!(
+            intersection(a, ClippingRect::None),
+            ClippingRect::Some(r) if r == Rect::new(0, 0, 10, 10)
+        ));
+        assert!(matches!(
+            intersection(a, ClippingRect::Zero),
+            ClippingRect::Zero
+        ));
+        let apart = ClippingRect::Some(Rect::new(20, 20, 1, 1));
+        assert!(matches!(intersection(a, apart), ClippingRect::Zero));
+
+        assert!(matches!(
+            compose([ClippingRect::None, a, b]),
+            ClippingRect::Some(r) if r == Rect::new(5, 5, 5, 5)
+        ));
+        assert!(matches!(compose([]), ClippingRect::None));
+    }
+
+    #[test]
+    fn points_and_positions() {
+        let clip = from_position(FRect {
+            x: 0.,
+            y: 0.,
+            w: 10.,
+            h: 10.,
+        });
+        assert!(contains_point(clip, (0, 0)));
+        assert!(!contains_point(clip, (10, 5)));
+        assert!(contains_point(translated(clip, (5, 0)), (10, 5)));
+        assert!(contains_point(ClippingRect::None, (-100, 100)));
+        assert!(!contains_point(ClippingRect::Zero, (0, 0)));
+        assert!(matches!(
+            from_position(FRect {
+                x: 0.,
+                y: 0.,
+                w: 0.,
+                h: 10.,
+            }),
+            ClippingRect::Zero
+        ));
+    }
+}
+
+use sdl2::{rect::Rect, render::ClippingRect};
+
+use super::rect::FRect;
+
+/// the area within both
+pub fn intersection(a: ClippingRect, b: ClippingRect) -> ClippingRect {
+    match (a, b) {
+        (ClippingRect::Zero, _) | (_, ClippingRect::Zero) => ClippingRect::Zero,
+        (ClippingRect::None, other) | (other, ClippingRect::None) => other,
+        (ClippingRect::Some(a), ClippingRect::Some(b)) => match a.intersection(b) {
+            Some(v) => ClippingRect::Some(v),
+            None => ClippingRect::Zero,
+        },
+    }
+}
+
+/// the existing clipping rect, further restricted to a position. none is a
+/// position with zero area
+pub fn clipping_rect_intersection(
+    existing_clipping_rect: ClippingRect,
+    position: Option<Rect>,
+) -> ClippingRect {
+    let position = match position {
+        Some(position) => ClippingRect::Some(position),
+        None => ClippingRect::Zero, // position is zero area
+    };
+    intersection(existing_clipping_rect, position)
+}
+
+/// the intersection of each, e.g. the clipping rects of nested containers.
+/// nothing is clipped if empty
+pub fn compose(stack: impl IntoIterator<Item = ClippingRect>) -> ClippingRect {
+    stack.into_iter().fold(ClippingRect::None, intersection)
+}
+
+/// clips to a widget's position
+pub fn from_position(position: FRect) -> ClippingRect {
+    clipping_rect_intersection(ClippingRect::None, position.into())
+}
+
+/// would a point (e.g. the mouse) be drawn
+pub fn contains_point(clipping_rect: ClippingRect, point: (i32, i32)) -> bool {
+    match clipping_rect {
+        ClippingRect::Some(rect) => rect.contains_point(point),
+        ClippingRect::Zero => false,
+        ClippingRect::None => true,
+    }
+}
+
+/// moved by some amount, e.g. from update_adjust_position
+pub fn translated(clipping_rect: ClippingRect, (dx, dy): (i32, i32)) -> ClippingRect {
+    match clipping_rect {
+        ClippingRect::Some(mut rect) => {
+            rect.offset(dx, dy);
+            ClippingRect::Some(rect)
+        }
+        other => other,
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_line_text() {
+        assert_eq!(single_line("abc"), "abc");
+        assert_eq!(single_line("a\r\nb\nc\rd"), "a b c d");
+        assert_eq!(single_line("a\tb"), "a b");
+        assert_eq!(single_line("a\u{7}b"), "ab");
+        assert_eq!(single_line("\n"), " ");
+        assert_eq!(single_line("日本"), "日本");
+    }
+}
+
+use sdl2::{
+    clipboard::ClipboardUtil,
+    keyboard::{Keycode, Mod},
+};
+
+use crate::{
+    util::{
+        error::UiResult,
+        focus::{DefaultFocusBehaviorArg, FocusID, FocusManager},
+        hit::HitShape,
+    },
+    widget::WidgetUpdateEvent,
+};
+
+/// make text suitable for a single line. line breaks and tabs become spaces,
+/// and other control characters are removed
+pub fn single_line(text: &str) -> String {
+    let text = text.replace("\r\n", "\n");
+    text.chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// if an event is ctrl + some key being pressed
+fn ctrl_key_pressed(event: &sdl2::event::Event, key: Keycode) -> bool {
+    match event {
+        sdl2::event::Event::KeyDown {
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } => *keycode == key && (keymod.contains(Mod::LCTRLMOD) || keymod.contains(Mod::RCTRLMOD)),
+        _ => false,
+    }
+}
+
+/// lets a label be focused (hover or tab), then copied with ctrl+c
+pub struct CopyOnFocus {
+    pub focus_id: FocusID,
+    /// from VideoSubsystem::clipboard
+    pub clipboard: ClipboardUtil,
+}
+
+impl CopyOnFocus {
+    pub fn new(focus_id: FocusID, clipboard: ClipboardUtil) -> Self {
+        Self {
+            focus_id,
+            clipboard,
+        }
+    }
+
+    /// call from the label's update, with the label's text
+    pub(crate) fn update(&self, event: &mut WidgetUpdateEvent, text: &str) -> UiResult<()> {
+        event
+            .focus_manager
+            .report_rect(&self.focus_id, event.position);
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            FocusManager::default_widget_focus_behavior(
+                &self.focus_id,
+                DefaultFocusBehaviorArg {
+                    focus_manager: event.focus_manager,
+                    position: event.position,
+                    event: sdl_event,
+                    clipping_rect: event.clipping_rect,
+                    window_id: event.window_id,
+                    hit_expansion: 0.,
+                    hit_shape: &HitShape::Rect,
+                },
+            );
+            if sdl_event.consumed() || !event.focus_manager.is_focused(&self.focus_id) {
+                continue;
+            }
+            if ctrl_key_pressed(&sdl_event.e, Keycode::C) {
+                sdl_event.set_consumed();
+                self.clipboard.set_clipboard_text(text)?;
+            }
+        }
+        Ok(())
+    }
+}
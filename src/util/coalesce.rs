@@ -0,0 +1,271 @@
+//! combines redundant events before the gui sees them. e.g. a touchpad can
+//! give hundreds of mouse motion events in a frame, and each widget would
+//! otherwise look at each of them.
+//!
+//! only neighbouring events are combined, and the combined event takes the
+//! place of the last of them. so the order of events (by timestamp) is kept:
+//! motion before a click is never moved after it
+
+#[cfg(test)]
+mod tests {
+    use sdl2::mouse::{MouseButton, MouseState, MouseWheelDirection};
+
+    use super::*;
+
+    fn motion(timestamp: u32, x: i32, xrel: i32) -> Event {
+        Event::MouseMotion {
+            timestamp,
+            window_id: 1,
+            which: 0,
+            mousestate: MouseState::from_sdl_state(0),
+            x,
+            y: 0,
+            xrel,
+            yrel: 0,
+        }
+    }
+
+    fn wheel(timestamp: u32, precise_y: f32) -> Event {
+        Event::MouseWheel {
+            timestamp,
+            window_id: 1,
+            which: 0,
+            x: 0,
+            y: precise_y as i32,
+            direction: MouseWheelDirection::Normal,
+            precise_x: 0.,
+            precise_y,
+            mouse_x: 5,
+            mouse_y: 5,
+        }
+    }
+
+    #[test]
+    fn neighbours_only() {
+        let mut events: Vec<SDLEvent> = [
+            motion(1, 10, 10),
+            motion(2, 15, 5),
+            Event::MouseButtonDown {
+                timestamp: 3,
+                window_id: 1,
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x: 15,
+                y: 0,
+            },
+            motion(4, 17, 2),
+            wheel(5, 1.),
+            wheel(6, 0.5),
+        ]
+        .into_iter()
+        .map(SDLEvent::new)
+        .collect();
+        coalesce_events(&mut events, &EventCoalescing::default());
+        assert_eq!(events.len(), 4);
+        match events[0].e {
+            Event::MouseMotion {
+                timestamp, x, xrel, ..
+            } => assert_eq!((timestamp, x, xrel), (2, 15, 15)),
+            _ => panic!(),
+        }
+        assert!(matches!(events[1].e, Event::MouseButtonDown { .. }));
+        match events[3].e {
+            Event::MouseWheel {
+                timestamp,
+                y,
+                precise_y,
+                ..
+            } => assert_eq!((timestamp, y, precise_y), (6, 1, 1.5)),
+            _ => panic!(),
+        }
+
+        let mut events: Vec<SDLEvent> = [motion(1, 10, 10), motion(2, 15, 5)]
+            .into_iter()
+            .map(SDLEvent::new)
+            .collect();
+        let off = EventCoalescing {
+            mouse_motion: false,
+            ..Default::default()
+        };
+        coalesce_events(&mut events, &off);
+        assert_eq!(events.len(), 2);
+    }
+}
+
+use sdl2::event::Event;
+
+use crate::widget::SDLEvent;
+
+/// which kinds of events are combined. each is on by default
+#[derive(Debug, Clone, Copy)]
+pub struct EventCoalescing {
+    /// mouse motion (with the same buttons held) becomes the last motion, with
+    /// the relative motion added up
+    pub mouse_motion: bool,
+    /// mouse wheel events (in the same direction) are added up
+    pub mouse_wheel: bool,
+    /// motion of the same finger becomes the last motion, with the relative
+    /// motion added up
+    pub finger_motion: bool,
+    /// motion of the same controller axis becomes the last motion
+    pub controller_axis: bool,
+}
+
+impl Default for EventCoalescing {
+    fn default() -> Self {
+        Self {
+            mouse_motion: true,
+            mouse_wheel: true,
+            finger_motion: true,
+            controller_axis: true,
+        }
+    }
+}
+
+impl EventCoalescing {
+    /// an event which has the effect of an event followed by another. none if
+    /// they can't be combined
+    fn combined(&self, first: &Event, second: &Event) -> Option<Event> {
+        match (first, second) {
+            (
+                Event::MouseMotion {
+                    window_id: first_window_id,
+                    which: first_which,
+                    mousestate: first_mousestate,
+                    xrel: first_xrel,
+                    yrel: first_yrel,
+                    ..
+                },
+                Event::MouseMotion {
+                    timestamp,
+                    window_id,
+                    which,
+                    mousestate,
+                    x,
+                    y,
+                    xrel,
+                    yrel,
+                },
+            ) if self.mouse_motion
+                && first_window_id == window_id
+                && first_which == which
+                && first_mousestate.to_sdl_state() == mousestate.to_sdl_state() =>
+            {
+                Some(Event::MouseMotion {
+                    timestamp: *timestamp,
+                    window_id: *window_id,
+                    which: *which,
+                    mousestate: *mousestate,
+                    x: *x,
+                    y: *y,
+                    xrel: first_xrel + xrel,
+                    yrel: first_yrel + yrel,
+                })
+            }
+            (
+                Event::MouseWheel {
+                    window_id: first_window_id,
+                    which: first_which,
+                    x: first_x,
+                    y: first_y,
+                    direction: first_direction,
+                    precise_x: first_precise_x,
+                    precise_y: first_precise_y,
+                    ..
+                },
+                Event::MouseWheel {
+                    timestamp,
+                    window_id,
+                    which,
+                    x,
+                    y,
+                    direction,
+                    precise_x,
+                    precise_y,
+                    mouse_x,
+                    mouse_y,
+                },
+            ) if self.mouse_wheel
+                && first_window_id == window_id
+                && first_which == which
+                && first_direction == direction =>
+            {
+                Some(Event::MouseWheel {
+                    timestamp: *timestamp,
+                    window_id: *window_id,
+                    which: *which,
+                    x: first_x + x,
+                    y: first_y + y,
+                    direction: *direction,
+                    precise_x: first_precise_x + precise_x,
+                    precise_y: first_precise_y + precise_y,
+                    mouse_x: *mouse_x,
+                    mouse_y: *mouse_y,
+                })
+            }
+            (
+                Event::FingerMotion {
+                    touch_id: first_touch_id,
+                    finger_id: first_finger_id,
+                    dx: first_dx,
+                    dy: first_dy,
+                    ..
+                },
+                Event::FingerMotion {
+                    timestamp,
+                    touch_id,
+                    finger_id,
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    pressure,
+                },
+            ) if self.finger_motion
+                && first_touch_id == touch_id
+                && first_finger_id == finger_id =>
+            {
+                Some(Event::FingerMotion {
+                    timestamp: *timestamp,
+                    touch_id: *touch_id,
+                    finger_id: *finger_id,
+                    x: *x,
+                    y: *y,
+                    dx: first_dx + dx,
+                    dy: first_dy + dy,
+                    pressure: *pressure,
+                })
+            }
+            (
+                Event::ControllerAxisMotion {
+                    which: first_which,
+                    axis: first_axis,
+                    ..
+                },
+                Event::ControllerAxisMotion { which, axis, .. },
+            ) if self.controller_axis && first_which == which && first_axis == axis => {
+                Some(second.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// combine neighbouring events, per the coalescing. call on a frame's events
+/// before update_gui. events which were already consumed are left as is
+pub fn coalesce_events(events: &mut Vec<SDLEvent>, coalescing: &EventCoalescing) {
+    let mut out: Vec<SDLEvent> = Vec::with_capacity(events.len());
+    for sdl_event in events.drain(..) {
+        if let Some(last) = out.last_mut() {
+            if last.available() && sdl_event.available() {
+                if let Some(combined) = coalescing.combined(&last.e, &sdl_event.e) {
+                    last.e = combined;
+                    continue;
+                }
+            }
+        }
+        out.push(sdl_event);
+    }
+    *events = out;
+}
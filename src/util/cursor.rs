@@ -0,0 +1,47 @@
+use sdl2::mouse::{Cursor, SystemCursor};
+
+/// sets a system cursor while a widget wants it (e.g. while hovered or
+/// dragging).
+///
+/// when freed it clears the cursor if it is currently set (sdl restores the
+/// default cursor)
+#[derive(Default)]
+pub struct SystemCursorCache {
+    /// this type is:
+    ///  - outer optional, is the cache set or not
+    ///  - inner optional, the cache is set, but None if sdl call failed (this
+    ///    api in infallible - should not err on sdl2 cursor set failure)
+    cursor: Option<(SystemCursor, Option<Cursor>)>,
+}
+
+impl SystemCursorCache {
+    pub fn clear(&mut self) {
+        self.cursor = None;
+    }
+
+    /// set the cursor, unless it's already been set by this cache
+    pub fn set_or_use_cache(&mut self, which: SystemCursor) {
+        if let Some((current, _)) = &self.cursor {
+            if *current == which {
+                return;
+            }
+        }
+        let cursor_result = Cursor::from_system(which);
+        debug_assert!(cursor_result.is_ok());
+        let cursor_optional = cursor_result.ok();
+        if let Some(cursor) = cursor_optional.as_ref() {
+            cursor.set()
+        }
+        // the previous cursor is freed after, so the default isn't restored in
+        // between
+        self.cursor = Some((which, cursor_optional));
+    }
+
+    /// set the cursor if wanted, otherwise clear it
+    pub fn set_if(&mut self, wanted: Option<SystemCursor>) {
+        match wanted {
+            Some(which) => self.set_or_use_cache(which),
+            None => self.clear(),
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! construct a widget tree from a declarative document (RON or JSON) instead of
+//! from code. this allows for tooling and editing a gui without recompiling.
+//!
+//! ```ron
+//! (type: "VerticalLayout", elems: [
+//!     (type: "Label", text: "Title", sizing: (min_h: 50)),
+//!     (type: "Button", text: "Quit", action: "quit",
+//!         focus: (previous: "quit", me: "quit", next: "quit")),
+//!     (type: "Custom", factory: "my_widget", params: {"color": "red"}),
+//! ])
+//! ```
+//!
+//! state (what a button does, what a checkbox toggles) can't be described in
+//! the document, so instead it is referred to by name and provided by the
+//! BuildContext
+
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use sdl2::{render::TextureCreator, video::WindowContext};
+use serde::Deserialize;
+
+use crate::{
+    layout::{
+        horizontal_layout::HorizontalLayout,
+        vertical_layout::{MajorAxisMaxLenPolicy, VerticalLayout},
+    },
+    util::{
+        error::{UiError, UiResult},
+        focus::FocusID,
+        length::{
+            MaxLen, MaxLenFailPolicy, MaxLenPolicy, MinLen, MinLenFailPolicy, MinLenPolicy,
+            PreferredPortion,
+        },
+    },
+    widget::{
+        checkbox::{CheckBox, DefaultCheckBoxStyle, EmptyFocusPressWidgetSoundStyle},
+        debug::Debug,
+        strut::Strut,
+        Widget,
+    },
+};
+
+#[cfg(feature = "sdl2-ttf")]
+use crate::{
+    util::font::{FontManager, SingleLineTextRenderType, TextRenderer},
+    widget::{
+        button::{Button, LabelButtonStyle},
+        single_line_label::SingleLineLabel,
+    },
+};
+
+/// sizing information common to all described widgets. anything not specified
+/// uses the widget's default
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SizingDescription {
+    pub min_w: Option<f32>,
+    pub min_h: Option<f32>,
+    pub max_w: Option<f32>,
+    pub max_h: Option<f32>,
+    pub preferred_w: Option<f32>,
+    pub preferred_h: Option<f32>,
+    pub min_w_fail_policy: Option<f32>,
+    pub max_w_fail_policy: Option<f32>,
+    pub min_h_fail_policy: Option<f32>,
+    pub max_h_fail_policy: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FocusDescription {
+    pub previous: String,
+    pub me: String,
+    pub next: String,
+}
+
+impl From<FocusDescription> for FocusID {
+    fn from(value: FocusDescription) -> Self {
+        FocusID {
+            previous: value.previous,
+            me: value.me,
+            next: value.next,
+        }
+    }
+}
+
+/// RGBA
+pub type ColorDescription = (u8, u8, u8, u8);
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum WidgetDescription {
+    VerticalLayout {
+        #[serde(default)]
+        sizing: SizingDescription,
+        #[serde(default)]
+        reverse: bool,
+        elems: Vec<WidgetDescription>,
+    },
+    HorizontalLayout {
+        #[serde(default)]
+        sizing: SizingDescription,
+        #[serde(default)]
+        reverse: bool,
+        elems: Vec<WidgetDescription>,
+    },
+    Strut {
+        #[serde(default)]
+        sizing: SizingDescription,
+    },
+    Debug {
+        #[serde(default)]
+        sizing: SizingDescription,
+        #[serde(default)]
+        aspect_ratio: Option<f32>,
+    },
+    Label {
+        #[serde(default)]
+        sizing: SizingDescription,
+        text: String,
+        #[serde(default = "default_text_color")]
+        color: ColorDescription,
+    },
+    Button {
+        #[serde(default)]
+        sizing: SizingDescription,
+        text: String,
+        #[serde(default = "default_text_color")]
+        color: ColorDescription,
+        focus: FocusDescription,
+        /// name of the action in the BuildContext
+        action: String,
+    },
+    CheckBox {
+        focus: FocusDescription,
+        /// name of the state in the BuildContext
+        state: String,
+        #[serde(default)]
+        label: String,
+        #[serde(default)]
+        size: Option<f32>,
+    },
+    /// constructed by a factory registered in the BuildContext
+    Custom {
+        factory: String,
+        #[serde(default)]
+        params: HashMap<String, String>,
+        #[serde(default)]
+        elems: Vec<WidgetDescription>,
+    },
+}
+
+fn default_text_color() -> ColorDescription {
+    (255, 255, 255, 255)
+}
+
+impl WidgetDescription {
+    pub fn from_ron(s: &str) -> UiResult<Self> {
+        ron::from_str(s).map_err(|e| UiError::Custom(e.to_string()))
+    }
+
+    pub fn from_json(s: &str) -> UiResult<Self> {
+        serde_json::from_str(s).map_err(|e| UiError::Custom(e.to_string()))
+    }
+}
+
+/// constructs a custom widget from its params. the children have already been
+/// built
+pub type WidgetFactory<'sdl, 'state> = Box<
+    dyn Fn(
+            &HashMap<String, String>,
+            Vec<Box<dyn Widget + 'sdl>>,
+        ) -> UiResult<Box<dyn Widget + 'sdl>>
+        + 'state,
+>;
+
+/// everything needed to build a widget tree from a description, which can't
+/// be stated in the description itself
+pub struct BuildContext<'sdl, 'state> {
+    pub creator: &'sdl TextureCreator<WindowContext>,
+    #[cfg(feature = "sdl2-ttf")]
+    pub font_manager: &'sdl Cell<Option<FontManager<'sdl>>>,
+    /// button functionality, by name
+    pub actions: HashMap<String, Rc<dyn Fn() -> UiResult<()> + 'state>>,
+    /// checkbox state, by name
+    pub bools: HashMap<String, &'state Cell<bool>>,
+    /// custom widget factories, by name
+    pub factories: HashMap<String, WidgetFactory<'sdl, 'state>>,
+}
+
+impl<'sdl, 'state> BuildContext<'sdl, 'state> {
+    pub fn new(
+        creator: &'sdl TextureCreator<WindowContext>,
+        #[cfg(feature = "sdl2-ttf")] font_manager: &'sdl Cell<Option<FontManager<'sdl>>>,
+    ) -> Self {
+        Self {
+            creator,
+            #[cfg(feature = "sdl2-ttf")]
+            font_manager,
+            actions: Default::default(),
+            bools: Default::default(),
+            factories: Default::default(),
+        }
+    }
+
+    pub fn build(&self, description: &WidgetDescription) -> UiResult<Box<dyn Widget + 'sdl>>
+    where
+        'state: 'sdl,
+    {
+        Ok(match description {
+            WidgetDescription::VerticalLayout {
+                sizing,
+                reverse,
+                elems,
+            } => {
+                let mut layout = VerticalLayout {
+                    elems: self.build_all(elems)?,
+                    reverse: *reverse,
+                    ..Default::default()
+                };
+                if let Some(v) = sizing.min_w {
+                    layout.min_w_policy = MinLenPolicy::Literal(MinLen(v));
+                }
+                if let Some(v) = sizing.min_h {
+                    layout.min_h_policy = MinLenPolicy::Literal(MinLen(v));
+                }
+                if let Some(v) = sizing.max_w {
+                    layout.max_w_policy = MaxLenPolicy::Literal(MaxLen(v));
+                }
+                if let Some(v) = sizing.max_h {
+                    layout.max_h_policy =
+                        MajorAxisMaxLenPolicy::Together(MaxLenPolicy::Literal(MaxLen(v)));
+                }
+                apply_portion_and_fail_policies(
+                    sizing,
+                    &mut layout.preferred_w,
+                    &mut layout.preferred_h,
+                    &mut layout.min_w_fail_policy,
+                    &mut layout.max_w_fail_policy,
+                    &mut layout.min_h_fail_policy,
+                    &mut layout.max_h_fail_policy,
+                );
+                Box::new(layout)
+            }
+            WidgetDescription::HorizontalLayout {
+                sizing,
+                reverse,
+                elems,
+            } => {
+                let mut layout = HorizontalLayout {
+                    elems: self.build_all(elems)?,
+                    reverse: *reverse,
+                    ..Default::default()
+                };
+                if let Some(v) = sizing.min_w {
+                    layout.min_w_policy = MinLenPolicy::Literal(MinLen(v));
+                }
+                if let Some(v) = sizing.min_h {
+                    layout.min_h_policy = MinLenPolicy::Literal(MinLen(v));
+                }
+                if let Some(v) = sizing.max_w {
+                    layout.max_w_policy =
+                        MajorAxisMaxLenPolicy::Together(MaxLenPolicy::Literal(MaxLen(v)));
+                }
+                if let Some(v) = sizing.max_h {
+                    layout.max_h_policy = MaxLenPolicy::Literal(MaxLen(v));
+                }
+                apply_portion_and_fail_policies(
+                    sizing,
+                    &mut layout.preferred_w,
+                    &mut layout.preferred_h,
+                    &mut layout.min_w_fail_policy,
+                    &mut layout.max_w_fail_policy,
+                    &mut layout.min_h_fail_policy,
+                    &mut layout.max_h_fail_policy,
+                );
+                Box::new(layout)
+            }
+            WidgetDescription::Strut { sizing } => {
+                let mut strut = Strut::shrinkable(MaxLen::LAX, MaxLen::LAX);
+                if let Some(v) = sizing.min_w {
+                    strut.min_w = MinLen(v);
+                }
+                if let Some(v) = sizing.min_h {
+                    strut.min_h = MinLen(v);
+                }
+                if let Some(v) = sizing.max_w {
+                    strut.max_w = MaxLen(v);
+                }
+                if let Some(v) = sizing.max_h {
+                    strut.max_h = MaxLen(v);
+                }
+                if let Some(v) = sizing.preferred_w {
+                    strut.preferred_w = PreferredPortion(v);
+                }
+                if let Some(v) = sizing.preferred_h {
+                    strut.preferred_h = PreferredPortion(v);
+                }
+                Box::new(strut)
+            }
+            WidgetDescription::Debug {
+                sizing,
+                aspect_ratio,
+            } => {
+                let mut debug = Debug::default();
+                debug.aspect_ratio = *aspect_ratio;
+                if let Some(v) = sizing.min_w {
+                    debug.min_w = MinLen(v);
+                }
+                if let Some(v) = sizing.min_h {
+                    debug.min_h = MinLen(v);
+                }
+                if let Some(v) = sizing.max_w {
+                    debug.max_w = MaxLen(v);
+                }
+                if let Some(v) = sizing.max_h {
+                    debug.max_h = MaxLen(v);
+                }
+                apply_portion_and_fail_policies(
+                    sizing,
+                    &mut debug.preferred_w,
+                    &mut debug.preferred_h,
+                    &mut debug.min_w_fail_policy,
+                    &mut debug.max_w_fail_policy,
+                    &mut debug.min_h_fail_policy,
+                    &mut debug.max_h_fail_policy,
+                );
+                Box::new(debug)
+            }
+            #[cfg(feature = "sdl2-ttf")]
+            WidgetDescription::Label {
+                sizing,
+                text,
+                color,
+            } => Box::new(self.build_label(sizing, text, *color)),
+            #[cfg(feature = "sdl2-ttf")]
+            WidgetDescription::Button {
+                sizing,
+                text,
+                color,
+                focus,
+                action,
+            } => {
+                let action = match self.actions.get(action) {
+                    Some(v) => v.clone(),
+                    None => return Err(UiError::Custom(format!("no action named \"{}\"", action))),
+                };
+                let label = self.build_label(sizing, text, *color);
+                Box::new(Button::new(
+                    Box::new(move || action()),
+                    focus.clone().into(),
+                    Box::new(LabelButtonStyle { label }),
+                    Box::new(EmptyFocusPressWidgetSoundStyle {}),
+                    self.creator,
+                ))
+            }
+            #[cfg(not(feature = "sdl2-ttf"))]
+            WidgetDescription::Label { .. } | WidgetDescription::Button { .. } => {
+                return Err(UiError::Custom(
+                    "text widgets require the sdl2-ttf feature".to_owned(),
+                ));
+            }
+            WidgetDescription::CheckBox {
+                focus,
+                state,
+                label,
+                size,
+            } => {
+                let checked = match self.bools.get(state) {
+                    Some(v) => *v,
+                    None => return Err(UiError::Custom(format!("no state named \"{}\"", state))),
+                };
+                let mut checkbox = CheckBox::new(
+                    checked,
+                    focus.clone().into(),
+                    Box::<DefaultCheckBoxStyle>::default(),
+                    Box::new(EmptyFocusPressWidgetSoundStyle {}),
+                    self.creator,
+                );
+                checkbox.accessible_label = label.clone();
+                if let Some(size) = size {
+                    checkbox.size = *size;
+                }
+                Box::new(checkbox)
+            }
+            WidgetDescription::Custom {
+                factory,
+                params,
+                elems,
+            } => {
+                let factory_fn = match self.factories.get(factory) {
+                    Some(v) => v,
+                    None => {
+                        return Err(UiError::Custom(format!(
+                            "no widget factory named \"{}\"",
+                            factory
+                        )))
+                    }
+                };
+                factory_fn(params, self.build_all(elems)?)?
+            }
+        })
+    }
+
+    fn build_all(&self, descriptions: &[WidgetDescription]) -> UiResult<Vec<Box<dyn Widget + 'sdl>>>
+    where
+        'state: 'sdl,
+    {
+        descriptions.iter().map(|d| self.build(d)).collect()
+    }
+
+    #[cfg(feature = "sdl2-ttf")]
+    fn build_label(
+        &self,
+        sizing: &SizingDescription,
+        text: &str,
+        color: ColorDescription,
+    ) -> SingleLineLabel<'sdl, 'state> {
+        let (r, g, b, a) = color;
+        let mut label = SingleLineLabel::new(
+            text.into(),
+            SingleLineTextRenderType::Blended(sdl2::pixels::Color::RGBA(r, g, b, a)),
+            Box::new(TextRenderer::new(self.font_manager)),
+            self.creator,
+        );
+        if let Some(v) = sizing.min_w {
+            label.min_w_policy = MinLenPolicy::Literal(MinLen(v));
+        }
+        if let Some(v) = sizing.min_h {
+            label.min_h = MinLen(v);
+        }
+        if let Some(v) = sizing.max_w {
+            label.max_w_policy = MaxLenPolicy::Literal(MaxLen(v));
+        }
+        if let Some(v) = sizing.max_h {
+            label.max_h = MaxLen(v);
+        }
+        apply_portion_and_fail_policies(
+            sizing,
+            &mut label.preferred_w,
+            &mut label.preferred_h,
+            &mut label.min_w_fail_policy,
+            &mut label.max_w_fail_policy,
+            &mut label.min_h_fail_policy,
+            &mut label.max_h_fail_policy,
+        );
+        label
+    }
+}
+
+fn apply_portion_and_fail_policies(
+    sizing: &SizingDescription,
+    preferred_w: &mut PreferredPortion,
+    preferred_h: &mut PreferredPortion,
+    min_w_fail_policy: &mut MinLenFailPolicy,
+    max_w_fail_policy: &mut MaxLenFailPolicy,
+    min_h_fail_policy: &mut MinLenFailPolicy,
+    max_h_fail_policy: &mut MaxLenFailPolicy,
+) {
+    if let Some(v) = sizing.preferred_w {
+        *preferred_w = PreferredPortion(v);
+    }
+    if let Some(v) = sizing.preferred_h {
+        *preferred_h = PreferredPortion(v);
+    }
+    if let Some(v) = sizing.min_w_fail_policy {
+        *min_w_fail_policy = MinLenFailPolicy(v);
+    }
+    if let Some(v) = sizing.max_w_fail_policy {
+        *max_w_fail_policy = MaxLenFailPolicy(v);
+    }
+    if let Some(v) = sizing.min_h_fail_policy {
+        *min_h_fail_policy = MinLenFailPolicy(v);
+    }
+    if let Some(v) = sizing.max_h_fail_policy {
+        *max_h_fail_policy = MaxLenFailPolicy(v);
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_propagate_up() {
+        let root = DirtyFlag::new();
+        let child = root.child();
+        let grandchild = child.child();
+        let before = (
+            root.generation(),
+            child.generation(),
+            grandchild.generation(),
+        );
+
+        grandchild.mark_dirty();
+        assert_ne!(root.generation(), before.0);
+        assert_ne!(child.generation(), before.1);
+        assert_ne!(grandchild.generation(), before.2);
+
+        // but not down
+        let before = grandchild.generation();
+        root.mark_dirty();
+        assert_eq!(grandchild.generation(), before);
+    }
+
+    #[test]
+    fn clones_are_shared() {
+        let flag = DirtyFlag::new();
+        let other = flag.clone();
+        let before = flag.generation();
+        other.mark_dirty();
+        assert_ne!(flag.generation(), before);
+    }
+}
+
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Default)]
+struct DirtyFlagInner {
+    generation: Cell<u64>,
+    parent: Option<DirtyFlag>,
+}
+
+/// a generation counter shared between the code that changes some content and
+/// the caches of measurements derived from that content. a cache stores the
+/// generation it was computed at, and is stale once the generation differs.
+///
+/// marking a flag also marks its parent, and so on up the tree. so a cache
+/// around a layout is invalidated when any cache within it is
+#[derive(Clone, Default)]
+pub struct DirtyFlag(Rc<DirtyFlagInner>);
+
+impl DirtyFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// a flag which also marks this one. for a cache nested within the cache
+    /// which uses this flag
+    pub fn child(&self) -> Self {
+        Self(Rc::new(DirtyFlagInner {
+            generation: Cell::new(0),
+            parent: Some(self.clone()),
+        }))
+    }
+
+    /// call after changing something that affects the sizing of a widget
+    /// (e.g. a label's text)
+    pub fn mark_dirty(&self) {
+        let mut flag = Some(self);
+        while let Some(f) = flag {
+            f.0.generation.set(f.0.generation.get().wrapping_add(1));
+            flag = f.0.parent.as_ref();
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.0.generation.get()
+    }
+}
@@ -0,0 +1,120 @@
+//! drawing decorations before or after the gui (e.g. selection outlines, drag
+//! ghosts, or debug overlays), without changing the widgets. see
+//! GuiDriver::pre_draw and GuiDriver::post_draw
+
+#[cfg(test)]
+mod tests {
+    use sdl2::render::ClippingRect;
+
+    use crate::util::rect::FRect;
+
+    use super::{record_focus, record_layout, recording_layout};
+
+    #[test]
+    fn focus_names_laid_out() {
+        let a = FRect {
+            x: 0.,
+            y: 0.,
+            w: 10.,
+            h: 10.,
+        };
+        let b = FRect {
+            x: 10.,
+            y: 0.,
+            w: 10.,
+            h: 10.,
+        };
+        let ((), laid_out) = recording_layout(|| {
+            record_layout(a, ClippingRect::None);
+            record_layout(b, ClippingRect::None);
+            record_layout(b, ClippingRect::None);
+            record_focus("b", b);
+        });
+        assert_eq!(laid_out.len(), 3);
+        assert_eq!(laid_out[0].focus_id, None);
+        assert_eq!(laid_out[1].focus_id, None);
+        assert_eq!(laid_out[2].focus_id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn not_recording() {
+        record_focus("a", FRect::default());
+        let ((), laid_out) = recording_layout(|| {});
+        assert!(laid_out.is_empty());
+    }
+}
+
+use std::cell::RefCell;
+
+use sdl2::render::{ClippingRect, WindowCanvas};
+
+use super::{error::UiResult, rect::FRect};
+
+/// a position given to a widget during the most recent update
+#[derive(Debug, Clone)]
+pub struct LaidOut {
+    pub position: FRect,
+    pub clipping_rect: ClippingRect,
+    /// the id of the focusable widget given this position, if it reported it.
+    /// see FocusManager::report_rect
+    pub focus_id: Option<String>,
+}
+
+/// given the canvas and the positions given to widgets during the most recent
+/// update, in the order they were updated (parents before their children). a
+/// widget may appear more than once, e.g. if it passes its position on to
+/// what it contains. focusable widgets can be found by their focus_id
+pub type DrawHook<'a> = Box<dyn FnMut(&mut WindowCanvas, &[LaidOut]) -> UiResult<()> + 'a>;
+
+// only recorded while updating with a driver that has draw hooks. the gui is
+// single threaded
+thread_local! {
+    static LAYOUT_RECORD: RefCell<Option<Vec<LaidOut>>> = const { RefCell::new(None) };
+}
+
+/// note a position given to a widget, if positions are being recorded
+#[inline]
+pub(crate) fn record_layout(position: FRect, clipping_rect: ClippingRect) {
+    LAYOUT_RECORD.with(|record| {
+        if let Some(record) = record.borrow_mut().as_mut() {
+            record.push(LaidOut {
+                position,
+                clipping_rect,
+                focus_id: None,
+            });
+        }
+    });
+}
+
+/// note which focusable widget was given a position, if positions are being
+/// recorded. the most recent unnamed entry at that position is named
+pub(crate) fn record_focus(focus_id: &str, position: FRect) {
+    LAYOUT_RECORD.with(|record| {
+        let mut record = record.borrow_mut();
+        let record = match record.as_mut() {
+            Some(v) => v,
+            None => return,
+        };
+        match record
+            .iter_mut()
+            .rev()
+            .find(|v| v.focus_id.is_none() && v.position == position)
+        {
+            Some(laid_out) => laid_out.focus_id = Some(focus_id.to_owned()),
+            // wasn't given through a sub event
+            None => record.push(LaidOut {
+                position,
+                clipping_rect: ClippingRect::None,
+                focus_id: Some(focus_id.to_owned()),
+            }),
+        }
+    });
+}
+
+/// record the positions given to widgets while running f
+pub(crate) fn recording_layout<R>(f: impl FnOnce() -> R) -> (R, Vec<LaidOut>) {
+    let previous = LAYOUT_RECORD.with(|record| record.replace(Some(Vec::new())));
+    let ret = f();
+    let recorded = LAYOUT_RECORD.with(|record| record.replace(previous));
+    (ret, recorded.unwrap_or_default())
+}
@@ -0,0 +1,245 @@
+//! showing a gui in part of the window, e.g. a menu designed at 800x600 shown
+//! at the same proportions in any window size, or a gui panel beside a game's
+//! viewport
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: FRect = FRect {
+        x: 10.,
+        y: 20.,
+        w: 400.,
+        h: 200.,
+    };
+
+    #[test]
+    fn letterbox_centered() {
+        // limited by the height; bars on the left and right
+        let shown = fitted(TARGET, (800, 600), EmbeddedFit::Letterbox);
+        assert_eq!(
+            shown,
+            FRect {
+                x: 10. + (400. - 200. * 800. / 600.) / 2.,
+                y: 20.,
+                w: 200. * 800. / 600.,
+                h: 200.,
+            }
+        );
+        // limited by the width; bars on the top and bottom
+        let shown = fitted(TARGET, (800, 100), EmbeddedFit::Letterbox);
+        assert_eq!(
+            shown,
+            FRect {
+                x: 10.,
+                y: 20. + 50.,
+                w: 400.,
+                h: 50.,
+            }
+        );
+        assert_eq!(fitted(TARGET, (800, 600), EmbeddedFit::Stretch), TARGET);
+        assert_eq!(fitted(TARGET, (0, 600), EmbeddedFit::Letterbox).w, 0.);
+    }
+}
+
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    render::{BlendMode, TextureCreator, WindowCanvas},
+    video::WindowContext,
+};
+
+use crate::widget::{draw_gui, GuiDriver, MouseTransform, SDLEvent, Widget};
+
+use super::{
+    error::{UiError, UiResult},
+    focus::FocusManager,
+    rect::FRect,
+    render::{owned_texture, OwnedTexture},
+};
+
+/// how a gui with a virtual size is scaled to fit the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddedFit {
+    /// as large as fits, keeping the proportions of the virtual size. the
+    /// remaining space is left as bars on two sides
+    #[default]
+    Letterbox,
+    /// fill the target, changing the proportions
+    Stretch,
+}
+
+/// where a gui with a virtual size is shown within the target
+fn fitted(target: FRect, (virtual_w, virtual_h): (u32, u32), fit: EmbeddedFit) -> FRect {
+    if fit == EmbeddedFit::Stretch {
+        return target;
+    }
+    if virtual_w == 0 || virtual_h == 0 {
+        return FRect {
+            w: 0.,
+            h: 0.,
+            ..target
+        }; // guard div
+    }
+    let scale = (target.w / virtual_w as f32).min(target.h / virtual_h as f32);
+    let w = virtual_w as f32 * scale;
+    let h = virtual_h as f32 * scale;
+    FRect {
+        x: target.x + (target.w - w) / 2.,
+        y: target.y + (target.h - h) / 2.,
+        w,
+        h,
+    }
+}
+
+/// lays out and draws a gui within a target area of the window. with a
+/// virtual size, the gui is laid out at that size, drawn to a texture, and
+/// scaled to fit the target. mouse events are mapped to match.
+///
+/// use in place of update_gui and draw_gui
+pub struct EmbeddedGui<'sdl> {
+    /// where the gui is shown, in layout coordinates (see update_gui)
+    pub target: FRect,
+    /// lay out the gui at this size, then scale it. none lays out the gui at
+    /// the target's size, without scaling
+    pub virtual_size: Option<(u32, u32)>,
+    pub fit: EmbeddedFit,
+    /// fills the target outside of where the gui is shown (the bars when
+    /// letterboxing). none leaves it as is
+    pub letterbox_color: Option<Color>,
+    /// drawn behind the gui, when scaled
+    pub background: Color,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    /// the gui, drawn at the virtual size
+    texture: Option<OwnedTexture<'sdl>>,
+}
+
+impl<'sdl> EmbeddedGui<'sdl> {
+    pub fn new(creator: &'sdl TextureCreator<WindowContext>, target: FRect) -> Self {
+        Self {
+            target,
+            virtual_size: None,
+            fit: Default::default(),
+            letterbox_color: None,
+            background: Color::RGBA(0, 0, 0, 0),
+            creator,
+            texture: None,
+        }
+    }
+
+    /// where the gui is shown, within the target
+    pub fn shown(&self) -> FRect {
+        match self.virtual_size {
+            Some(size) => fitted(self.target, size, self.fit),
+            None => self.target,
+        }
+    }
+
+    /// same as update_gui
+    pub fn update_gui(
+        &mut self,
+        widget: &mut dyn Widget,
+        events: &mut [SDLEvent],
+        focus_manager: &mut FocusManager,
+        canvas: &WindowCanvas,
+    ) -> UiResult<()> {
+        let (virtual_w, virtual_h) = match self.virtual_size {
+            Some(v) => v,
+            None => {
+                return GuiDriver::new()
+                    .area(self.target)
+                    .clip_to_area()
+                    .update_gui(widget, events, focus_manager, canvas);
+            }
+        };
+        let shown = self.shown();
+        let scale_x = if shown.w > 0. {
+            virtual_w as f32 / shown.w
+        } else {
+            0. // guard div
+        };
+        let scale_y = if shown.h > 0. {
+            virtual_h as f32 / shown.h
+        } else {
+            0. // guard div
+        };
+        // from the window to the virtual size
+        let mouse_transform = MouseTransform::Custom(Box::new(move |x, y| {
+            (
+                ((x as f32 - shown.x) * scale_x).floor() as i32,
+                ((y as f32 - shown.y) * scale_y).floor() as i32,
+            )
+        }));
+        GuiDriver::new()
+            .area(FRect {
+                x: 0.,
+                y: 0.,
+                w: virtual_w as f32,
+                h: virtual_h as f32,
+            })
+            .mouse_transform(mouse_transform)
+            .update_gui(widget, events, focus_manager, canvas)
+    }
+
+    /// same as draw_gui
+    pub fn draw_gui(
+        &mut self,
+        widget: &mut dyn Widget,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        if let Some(color) = self.letterbox_color {
+            let target: Option<sdl2::rect::Rect> = self.target.into();
+            if let Some(target) = target {
+                canvas.set_draw_color(color);
+                canvas.fill_rect(target)?;
+            }
+        }
+
+        let (virtual_w, virtual_h) = match self.virtual_size {
+            Some(v) => v,
+            None => {
+                return GuiDriver::new().area(self.target).clip_to_area().draw_gui(
+                    widget,
+                    canvas,
+                    focus_manager,
+                );
+            }
+        };
+        if virtual_w == 0 || virtual_h == 0 {
+            return Ok(());
+        }
+
+        let shown: Option<sdl2::rect::Rect> = self.shown().into();
+        let texture = match self.texture.take() {
+            Some(texture)
+                if (texture.query().width, texture.query().height) == (virtual_w, virtual_h) =>
+            {
+                texture
+            }
+            _ => {
+                let mut texture: OwnedTexture<'sdl> = self
+                    .creator
+                    .create_texture_target(PixelFormatEnum::ARGB8888, virtual_w, virtual_h)
+                    .map(owned_texture)?;
+                texture.set_blend_mode(BlendMode::Blend);
+                texture
+            }
+        };
+        let texture = self.texture.insert(texture);
+
+        let background = self.background;
+        let mut draw_result = Ok(());
+        canvas.with_texture_canvas(texture, |canvas| {
+            canvas.set_draw_color(background);
+            canvas.clear();
+            draw_result = draw_gui(widget, canvas, focus_manager);
+        })?;
+        draw_result?;
+
+        match shown {
+            Some(shown) => canvas.copy(texture, None, shown).map_err(UiError::Sdl),
+            None => Ok(()),
+        }
+    }
+}
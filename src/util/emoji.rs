@@ -0,0 +1,568 @@
+//! color emoji inline with text. sdl2-ttf renders text from a single font,
+//! which typically has no emoji (or only monochrome ones). EmojiTextRenderer
+//! splits the text into runs of regular text and emoji; the text is rendered by
+//! a TextRenderer, and each emoji from a color emoji font or an image atlas.
+//! the runs are composed into a single texture, so widgets (including the
+//! text input's caret) measure the text as it's drawn.
+//!
+//! an emoji is a pictographic character from the supplementary planes,
+//! optionally followed by modifiers (skin tone, variation selector, tags) and
+//! joined to others by zero width joiners, or a flag (a pair of regional
+//! indicators), or a keycap. characters from the basic plane which have a text
+//! presentation by default (e.g. © or ☀) are only treated as emoji when
+//! followed by the emoji variation selector
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(&str, bool)> {
+        segments(text)
+            .into_iter()
+            .map(|(range, emoji)| (&text[range], emoji))
+            .collect()
+    }
+
+    #[test]
+    fn text_and_emoji() {
+        assert_eq!(kinds(""), vec![]);
+        assert_eq!(kinds("abc"), vec![("abc", false)]);
+        assert_eq!(
+            kinds("a😀b"),
+            vec![("a", false), ("😀", true), ("b", false)]
+        );
+        assert_eq!(kinds("😀😀"), vec![("😀", true), ("😀", true)]);
+    }
+
+    #[test]
+    fn sequences() {
+        // skin tone
+        assert_eq!(kinds("👍🏽"), vec![("👍🏽", true)]);
+        // zero width joiner
+        assert_eq!(
+            kinds("👨\u{200D}👩\u{200D}👧!"),
+            vec![("👨\u{200D}👩\u{200D}👧", true), ("!", false)]
+        );
+        // flags pair up
+        assert_eq!(kinds("🇯🇵🇺🇸"), vec![("🇯🇵", true), ("🇺🇸", true)]);
+        // keycap
+        assert_eq!(
+            kinds("1\u{FE0F}\u{20E3}1"),
+            vec![("1\u{FE0F}\u{20E3}", true), ("1", false)]
+        );
+        // text presentation unless selected
+        assert_eq!(kinds("©©\u{FE0F}"), vec![("©", false), ("©\u{FE0F}", true)]);
+    }
+
+    #[test]
+    fn pop() {
+        let mut text = "a👨\u{200D}👩\u{200D}👧".to_owned();
+        pop_char(&mut text);
+        assert_eq!(text, "a");
+        pop_char(&mut text);
+        assert_eq!(text, "");
+        pop_char(&mut text);
+        assert_eq!(text, "");
+    }
+}
+
+use std::ops::Range;
+
+#[cfg(feature = "sdl2-ttf")]
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+#[cfg(feature = "sdl2-ttf")]
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::{BlendMode, TextureCreator},
+    surface::Surface,
+    ttf::Font,
+    video::WindowContext,
+};
+
+#[cfg(feature = "sdl2-ttf")]
+use super::error::{UiError, UiResult};
+#[cfg(feature = "sdl2-ttf")]
+use super::font::{
+    line_range_rects, wrap_ranges, FontManager, MultiLineFontStyle, SingleLineFontStyle,
+    SingleLineTextRenderType, TextRenderProperties, TextRenderer,
+};
+#[cfg(feature = "sdl2-ttf")]
+use super::render::{owned_texture, OwnedTexture};
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+const COMBINING_KEYCAP: char = '\u{20E3}';
+
+/// a pictographic character which is an emoji by default
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF)
+}
+
+/// a character which is only an emoji when followed by the emoji variation
+/// selector
+fn is_text_default(c: char) -> bool {
+    matches!(
+        c as u32,
+        0xA9 | 0xAE | 0x2000..=0x2BFF | 0x3030 | 0x303D | 0x3297 | 0x3299
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// follows an emoji, and is part of it
+fn is_modifier(c: char) -> bool {
+    c == EMOJI_PRESENTATION
+        || matches!(c as u32, 0x1F3FB..=0x1F3FF) // skin tone
+        || matches!(c as u32, 0xE0020..=0xE007F) // tags (subdivision flags)
+}
+
+/// the length in bytes of the emoji at the start of the text, if any
+fn emoji_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let (_, first) = chars.next()?;
+
+    if is_regional_indicator(first) {
+        return match chars.peek() {
+            Some((i, c)) if is_regional_indicator(*c) => Some(i + c.len_utf8()),
+            _ => None,
+        };
+    }
+
+    if first.is_ascii_digit() || first == '#' || first == '*' {
+        let rest = &text[first.len_utf8()..];
+        let rest_trimmed = rest.strip_prefix(EMOJI_PRESENTATION).unwrap_or(rest);
+        return rest_trimmed
+            .starts_with(COMBINING_KEYCAP)
+            .then(|| text.len() - rest_trimmed.len() + COMBINING_KEYCAP.len_utf8());
+    }
+
+    let is_emoji = |c: char, next: Option<char>| {
+        is_pictographic(c) || (is_text_default(c) && next == Some(EMOJI_PRESENTATION))
+    };
+    let second = text[first.len_utf8()..].chars().next();
+    if !is_emoji(first, second) {
+        return None;
+    }
+
+    let mut end = first.len_utf8();
+    loop {
+        let rest = &text[end..];
+        let mut rest_chars = rest.chars();
+        match rest_chars.next() {
+            Some(c) if is_modifier(c) => end += c.len_utf8(),
+            Some(ZERO_WIDTH_JOINER) => {
+                let joined = match rest_chars.next() {
+                    Some(v) => v,
+                    None => break,
+                };
+                // the joined character can omit the variation selector
+                if !is_pictographic(joined) && !is_text_default(joined) {
+                    break;
+                }
+                end += ZERO_WIDTH_JOINER.len_utf8() + joined.len_utf8();
+            }
+            _ => break,
+        }
+    }
+    Some(end)
+}
+
+/// splits text into runs of regular text and single emoji. true indicates an
+/// emoji
+pub fn segments(text: &str) -> Vec<(Range<usize>, bool)> {
+    let mut out: Vec<(Range<usize>, bool)> = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        if let Some(len) = emoji_len(&text[i..]) {
+            out.push((i..i + len, true));
+            i += len;
+            continue;
+        }
+        let c_len = text[i..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+        match out.last_mut() {
+            Some((range, false)) => range.end = i + c_len,
+            _ => out.push((i..i + c_len, false)),
+        }
+        i += c_len;
+    }
+    out
+}
+
+#[cfg(feature = "sdl2-ttf")]
+fn has_emoji(text: &str) -> bool {
+    segments(text).iter().any(|(_, emoji)| *emoji)
+}
+
+/// removes the last character, or the whole emoji if the text ends with one
+/// (e.g. for backspace)
+pub fn pop_char(text: &mut String) {
+    match segments(text).last() {
+        Some((range, true)) => text.truncate(range.start),
+        _ => {
+            text.pop();
+        }
+    }
+}
+
+/// emoji images packed into a single surface. each emoji (as its character
+/// sequence) refers to an area of the surface
+#[cfg(feature = "sdl2-ttf")]
+pub struct EmojiAtlas {
+    /// blend mode is changed when blitting
+    surface: RefCell<Surface<'static>>,
+    rects: HashMap<String, Rect>,
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl EmojiAtlas {
+    pub fn new(surface: Surface<'static>) -> UiResult<Self> {
+        let surface = surface.convert_format(PixelFormatEnum::ARGB8888)?;
+        Ok(Self {
+            surface: RefCell::new(surface),
+            rects: Default::default(),
+        })
+    }
+
+    pub fn insert(&mut self, emoji: impl Into<String>, rect: Rect) {
+        self.rects.insert(emoji.into(), rect);
+    }
+
+    /// variation selectors are optional in the text
+    fn rect(&self, emoji: &str) -> Option<Rect> {
+        self.rects.get(emoji).copied().or_else(|| {
+            let stripped: String = emoji.chars().filter(|c| *c != EMOJI_PRESENTATION).collect();
+            self.rects.get(&stripped).copied()
+        })
+    }
+}
+
+/// where emoji images come from
+#[cfg(feature = "sdl2-ttf")]
+#[derive(Clone, Copy)]
+pub enum EmojiSource<'sdl> {
+    /// a color emoji font (e.g. noto color emoji). bitmap emoji fonts only
+    /// have some sizes, so it's rendered at point_size and then scaled to the
+    /// text
+    Font {
+        font_manager: &'sdl std::cell::Cell<Option<FontManager<'sdl>>>,
+        point_size: u16,
+    },
+    Atlas(&'sdl EmojiAtlas),
+}
+
+/// renders text with inline color emoji. emoji which the source doesn't have
+/// are rendered by the text font
+#[cfg(feature = "sdl2-ttf")]
+#[derive(Clone)]
+pub struct EmojiTextRenderer<'sdl> {
+    text: TextRenderer<'sdl>,
+    emoji: EmojiSource<'sdl>,
+    /// keeps the emoji font loaded, see FontManager
+    emoji_font: Option<Rc<Font<'sdl, 'sdl>>>,
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl> EmojiTextRenderer<'sdl> {
+    pub fn new(text: TextRenderer<'sdl>, emoji: EmojiSource<'sdl>) -> Self {
+        Self {
+            text,
+            emoji,
+            emoji_font: None,
+        }
+    }
+
+    fn emoji_font(&mut self) -> UiResult<Option<Rc<Font<'sdl, 'sdl>>>> {
+        let (font_manager, point_size) = match self.emoji {
+            EmojiSource::Font {
+                font_manager,
+                point_size,
+            } => (font_manager, point_size),
+            EmojiSource::Atlas(_) => return Ok(None),
+        };
+        if let Some(font) = self.emoji_font.as_ref() {
+            return Ok(Some(font.clone()));
+        }
+        let mut maybe_manager = font_manager.take();
+        let manager = match maybe_manager.as_mut() {
+            Some(v) => v,
+            // should never error, as it will always be returned to the cell
+            None => return Err(UiError::Font("couldn't reference font manager".to_owned())),
+        };
+        let maybe_r = manager.get(point_size);
+        font_manager.set(maybe_manager);
+        let font = maybe_r?;
+        self.emoji_font = Some(font.clone());
+        Ok(Some(font))
+    }
+
+    /// the size of an emoji's image, if the source has it
+    fn emoji_size(&mut self, emoji: &str) -> UiResult<Option<(u32, u32)>> {
+        if let EmojiSource::Atlas(atlas) = self.emoji {
+            return Ok(atlas.rect(emoji).map(|r| (r.width(), r.height())));
+        }
+        let font = match self.emoji_font()? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        match emoji.chars().next() {
+            Some(c) if font.find_glyph(c).is_some() => {}
+            _ => return Ok(None),
+        }
+        font.size_of(emoji)
+            .map(Some)
+            .map_err(|e| UiError::Font(e.to_string()))
+    }
+
+    /// an emoji's image scaled to the line height
+    fn render_emoji(
+        &mut self,
+        emoji: &str,
+        line_height: u32,
+    ) -> UiResult<Option<Surface<'static>>> {
+        let (w, h) = match self.emoji_size(emoji)? {
+            Some(v) if v.0 != 0 && v.1 != 0 => v,
+            _ => return Ok(None),
+        };
+        let width = ((w as f32 * line_height as f32 / h as f32).round() as u32).max(1);
+        let mut out = Surface::new(width, line_height, PixelFormatEnum::ARGB8888)?;
+        match self.emoji {
+            EmojiSource::Atlas(atlas) => {
+                let rect = match atlas.rect(emoji) {
+                    Some(v) => v,
+                    None => return Ok(None),
+                };
+                let mut surface = atlas.surface.borrow_mut();
+                surface.set_blend_mode(BlendMode::None)?;
+                surface.blit_scaled(rect, &mut out, None)?;
+            }
+            EmojiSource::Font { .. } => {
+                let font = match self.emoji_font()? {
+                    Some(v) => v,
+                    None => return Ok(None),
+                };
+                // color emoji ignore the color
+                let mut surface = font.render(emoji).blended(Color::WHITE)?;
+                surface.set_blend_mode(BlendMode::None)?;
+                surface.blit_scaled(None, &mut out, None)?;
+            }
+        }
+        Ok(Some(out))
+    }
+
+    fn render_text_run(
+        font: &Font,
+        text: &str,
+        render_type: SingleLineTextRenderType,
+    ) -> UiResult<Surface<'static>> {
+        let partial_render = font.render(text);
+        match render_type {
+            #[allow(deprecated)]
+            SingleLineTextRenderType::Solid(color) => partial_render.solid(color),
+            SingleLineTextRenderType::Shaded(color, background) => {
+                partial_render.shaded(color, background)
+            }
+            SingleLineTextRenderType::Blended(color) => partial_render.blended(color),
+        }
+        .map_err(|e| UiError::Font(e.to_string()))
+    }
+
+    /// a single line, composed of its text and emoji runs. None if there are
+    /// no emoji (the text renderer can be used as is)
+    fn render_line_surface(
+        &mut self,
+        text: &str,
+        properties: &TextRenderProperties,
+    ) -> UiResult<Option<Surface<'static>>> {
+        if !has_emoji(text) {
+            return Ok(None);
+        }
+        let font = self.text.font(properties.point_size)?;
+        let line_height = (font.height().max(1)) as u32;
+
+        let mut runs: Vec<Surface<'static>> = Vec::new();
+        for (range, emoji) in segments(text) {
+            let run = &text[range];
+            let surface = if emoji {
+                self.render_emoji(run, line_height)?
+            } else {
+                None
+            };
+            let surface = match surface {
+                Some(v) => v,
+                None => Self::render_text_run(&font, run, properties.render_type)?,
+            };
+            runs.push(surface);
+        }
+
+        let width = runs.iter().map(|run| run.width()).sum::<u32>().max(1);
+        let mut out = Surface::new(width, line_height, PixelFormatEnum::ARGB8888)?;
+        let background = match properties.render_type {
+            SingleLineTextRenderType::Shaded(_, background) => Some(background),
+            _ => None,
+        };
+        out.fill_rect(None, background.unwrap_or(Color::RGBA(0, 0, 0, 0)))?;
+        let mut x = 0i32;
+        for mut run in runs {
+            // runs don't overlap. copied as is over a transparent background,
+            // so edges aren't darkened
+            run.set_blend_mode(match background {
+                Some(_) => BlendMode::Blend,
+                None => BlendMode::None,
+            })?;
+            let y = (line_height as i32 - run.height() as i32) / 2;
+            run.blit(None, &mut out, Rect::new(x, y, run.width(), run.height()))?;
+            x += run.width() as i32;
+        }
+        Ok(Some(out))
+    }
+
+    fn line_width(&mut self, text: &str, point_size: u16) -> UiResult<u32> {
+        if !has_emoji(text) {
+            return self.text.render_dimensions(text, point_size).map(|v| v.0);
+        }
+        let font = self.text.font(point_size)?;
+        let line_height = font.height().max(1) as u32;
+        let mut width = 0;
+        for (range, emoji) in segments(text) {
+            let run = &text[range];
+            let emoji_size = if emoji {
+                self.emoji_size(run)?.filter(|v| v.0 != 0 && v.1 != 0)
+            } else {
+                None
+            };
+            width += match emoji_size {
+                Some((w, h)) => ((w as f32 * line_height as f32 / h as f32).round() as u32).max(1),
+                None => font.size_of(run)?.0,
+            };
+        }
+        Ok(width)
+    }
+
+    fn texture_from_surface(
+        surface: Surface,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        let mut texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map(owned_texture)?;
+        crate::util::perf::count(crate::util::perf::Counter::TextureCreation);
+        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+        Ok(texture)
+    }
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl> SingleLineFontStyle<'sdl> for EmojiTextRenderer<'sdl> {
+    fn render(
+        &mut self,
+        text: &str,
+        properties: &TextRenderProperties,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        match self.render_line_surface(text, properties)? {
+            Some(surface) => Self::texture_from_surface(surface, texture_creator),
+            None => SingleLineFontStyle::render(&mut self.text, text, properties, texture_creator),
+        }
+    }
+
+    fn render_dimensions(&mut self, text: &str, point_size: u16) -> UiResult<(u32, u32)> {
+        if !has_emoji(text) {
+            return self.text.render_dimensions(text, point_size);
+        }
+        let line_height = self.text.font(point_size)?.height().max(1) as u32;
+        Ok((self.line_width(text, point_size)?, line_height))
+    }
+
+    fn dup(&self) -> Box<dyn SingleLineFontStyle<'sdl> + 'sdl> {
+        Box::new(Self::new(self.text.clone(), self.emoji))
+    }
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl> MultiLineFontStyle<'sdl> for EmojiTextRenderer<'sdl> {
+    fn render(
+        &mut self,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        wrap_width: u32,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        if !has_emoji(text) {
+            return MultiLineFontStyle::render(
+                &mut self.text,
+                text,
+                color,
+                point_size,
+                wrap_width,
+                texture_creator,
+            );
+        }
+        let properties = TextRenderProperties {
+            point_size,
+            render_type: SingleLineTextRenderType::Blended(color),
+        };
+        let font = self.text.font(point_size)?;
+        let line_height = font.height().max(1) as u32;
+
+        let ranges = wrap_ranges(text, wrap_width, |text| self.line_width(text, point_size))?;
+        let mut lines: Vec<Option<Surface<'static>>> = Vec::new();
+        for range in ranges {
+            let line = &text[range];
+            if line.is_empty() {
+                lines.push(None); // blank line
+                continue;
+            }
+            lines.push(Some(match self.render_line_surface(line, &properties)? {
+                Some(v) => v,
+                None => Self::render_text_run(&font, line, properties.render_type)?,
+            }));
+        }
+        let width = lines
+            .iter()
+            .flatten()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let height = line_height * lines.len().max(1) as u32;
+        let mut out = Surface::new(width, height, PixelFormatEnum::ARGB8888)?;
+        out.fill_rect(None, Color::RGBA(0, 0, 0, 0))?;
+        for (i, line) in lines.into_iter().enumerate() {
+            let mut line = match line {
+                Some(v) => v,
+                None => continue,
+            };
+            line.set_blend_mode(BlendMode::None)?;
+            let dst = Rect::new(
+                0,
+                (i as u32 * line_height) as i32,
+                line.width(),
+                line.height(),
+            );
+            line.blit(None, &mut out, dst)?;
+        }
+        Self::texture_from_surface(out, texture_creator)
+    }
+
+    fn range_rects(
+        &mut self,
+        text: &str,
+        point_size: u16,
+        wrap_width: u32,
+        range: Range<usize>,
+    ) -> UiResult<Vec<Rect>> {
+        if !has_emoji(text) {
+            return self.text.range_rects(text, point_size, wrap_width, range);
+        }
+        let line_height = self.text.font(point_size)?.height().max(1) as u32;
+        let lines = wrap_ranges(text, wrap_width, |text| self.line_width(text, point_size))?;
+        line_range_rects(text, &lines, line_height, line_height, range, |text| {
+            self.line_width(text, point_size)
+        })
+    }
+}
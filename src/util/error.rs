@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let e: UiError = "oops".into();
+        assert_eq!(e, UiError::Custom("oops".to_owned()));
+        assert_eq!(e.to_string(), "oops");
+        assert_eq!(
+            UiError::Font("no glyph".to_owned()).to_string(),
+            "font error: no glyph"
+        );
+        // back to the string based api
+        let s: String = UiError::Layout("too small".to_owned()).into();
+        assert_eq!(s, "layout error: too small");
+    }
+
+    #[test]
+    fn question_mark_into_string() {
+        fn inner() -> UiResult<()> {
+            Err(UiError::Texture("lost".to_owned()))
+        }
+        fn outer() -> Result<(), String> {
+            inner()?;
+            Ok(())
+        }
+        assert_eq!(outer(), Err("texture error: lost".to_owned()));
+    }
+}
+
+use std::fmt;
+
+/// an error from the gui, with what kind of error it was kept. returned by the
+/// widget and font traits and the utilities.
+///
+/// UiError converts to and from String, so application code which uses
+/// `Result<_, String>` (e.g. a main loop, or a callback) still works with `?`
+/// in either direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiError {
+    /// from an sdl call (e.g. creating a texture, setting a render target)
+    Sdl(String),
+    /// loading a font or rendering text
+    Font(String),
+    /// creating, updating, or drawing a texture
+    Texture(String),
+    /// the gui couldn't be laid out
+    Layout(String),
+    /// anything else, including errors from application callbacks
+    Custom(String),
+}
+
+/// the result of everything fallible in the gui
+pub type UiResult<T> = Result<T, UiError>;
+
+impl UiError {
+    /// the error message, without the kind
+    pub fn message(&self) -> &str {
+        match self {
+            UiError::Sdl(msg)
+            | UiError::Font(msg)
+            | UiError::Texture(msg)
+            | UiError::Layout(msg)
+            | UiError::Custom(msg) => msg,
+        }
+    }
+}
+
+impl fmt::Display for UiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiError::Sdl(msg) => write!(f, "sdl error: {}", msg),
+            UiError::Font(msg) => write!(f, "font error: {}", msg),
+            UiError::Texture(msg) => write!(f, "texture error: {}", msg),
+            UiError::Layout(msg) => write!(f, "layout error: {}", msg),
+            UiError::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UiError {}
+
+/// errors from the string based api have no kind
+impl From<String> for UiError {
+    fn from(value: String) -> Self {
+        UiError::Custom(value)
+    }
+}
+
+impl From<&str> for UiError {
+    fn from(value: &str) -> Self {
+        UiError::Custom(value.to_owned())
+    }
+}
+
+impl From<UiError> for String {
+    fn from(value: UiError) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<sdl2::render::TextureValueError> for UiError {
+    fn from(value: sdl2::render::TextureValueError) -> Self {
+        UiError::Texture(value.to_string())
+    }
+}
+
+impl From<sdl2::render::TargetRenderError> for UiError {
+    fn from(value: sdl2::render::TargetRenderError) -> Self {
+        UiError::Sdl(value.to_string())
+    }
+}
+
+impl From<sdl2::render::UpdateTextureError> for UiError {
+    fn from(value: sdl2::render::UpdateTextureError) -> Self {
+        UiError::Texture(value.to_string())
+    }
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl From<sdl2::ttf::FontError> for UiError {
+    fn from(value: sdl2::ttf::FontError) -> Self {
+        UiError::Font(value.to_string())
+    }
+}
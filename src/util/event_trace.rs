@@ -0,0 +1,85 @@
+//! records where each event was consumed, for debugging events that don't
+//! reach a widget (e.g. a click taken by a scroller or an overlay). each
+//! SDLEvent keeps a record per consumption; after update_gui, the trace can be
+//! inspected with SDLEvent::trace or printed with event_trace_report
+
+#[cfg(test)]
+mod tests {
+    use sdl2::{event::Event, mouse::MouseButton};
+
+    use crate::widget::{ConsumedStatus, SDLEvent};
+
+    use super::*;
+
+    #[test]
+    fn records_consumption() {
+        let mut events = [
+            SDLEvent::new(Event::MouseButtonDown {
+                timestamp: 0,
+                window_id: 1,
+                which: 0,
+                mouse_btn: MouseButton::Left,
+                clicks: 1,
+                x: 10,
+                y: 20,
+            }),
+            SDLEvent::new(Event::Quit { timestamp: 0 }),
+        ];
+        events[0].set_consumed();
+        events[0].set_consumed_by_layout();
+
+        let trace = events[0].trace();
+        assert_eq!(trace.len(), 2);
+        assert!(matches!(trace[0].status, ConsumedStatus::ConsumedByWidget));
+        assert!(matches!(trace[1].status, ConsumedStatus::ConsumedByLayout));
+        // the caller, not SDLEvent
+        assert_eq!(trace[0].location.file(), file!());
+        assert!(events[1].trace().is_empty());
+
+        let report = event_trace_report(&events);
+        assert_eq!(report.lines().count(), 4);
+        assert!(report.contains("not consumed"));
+    }
+}
+
+use std::{fmt::Write, panic::Location, time::Instant};
+
+use crate::widget::{ConsumedStatus, SDLEvent};
+
+/// an event being consumed
+#[derive(Debug, Clone, Copy)]
+pub struct EventConsumption {
+    pub status: ConsumedStatus,
+    /// where set_consumed (or set_consumed_by_layout) was called. this is in
+    /// the widget which consumed the event, or a helper it shares with similar
+    /// widgets (e.g. the focus behavior of buttons and checkboxes)
+    pub location: &'static Location<'static>,
+    pub time: Instant,
+}
+
+/// a line per event, followed by a line per time it was consumed. e.g. to
+/// print after update_gui on frames where something goes wrong
+pub fn event_trace_report(events: &[SDLEvent]) -> String {
+    let mut out = String::new();
+    let start = events
+        .iter()
+        .flat_map(|e| e.trace().iter().map(|c| c.time))
+        .min();
+    for (i, sdl_event) in events.iter().enumerate() {
+        let _ = writeln!(out, "{}: {:?}", i, sdl_event.e);
+        if sdl_event.trace().is_empty() {
+            let _ = writeln!(out, "    not consumed");
+        }
+        for consumption in sdl_event.trace() {
+            let since_start = start
+                .map(|start| consumption.time.duration_since(start))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "    {:?} at {} (+{:?})",
+                consumption.status, consumption.location, since_start
+            );
+        }
+    }
+    out
+}
@@ -0,0 +1,113 @@
+//! rendering a gui offscreen, into a surface, at a size independent of the
+//! window. e.g. for screenshots in documentation, or letting the user save
+//! part of the gui as an image
+
+use std::path::Path;
+
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    render::WindowCanvas,
+    surface::Surface,
+};
+
+use crate::{
+    util::{
+        error::{UiError, UiResult},
+        focus::FocusManager,
+        render::{max_texture_size, owned_texture},
+    },
+    widget::{draw_gui, update_gui_at_size, MouseTransform, RootPlacement, Widget},
+};
+
+/// lay out a widget (and everything it contains) in an area of some size, in
+/// physical pixels, and draw it onto a surface filled with the background.
+///
+/// the widget is updated without any events, and with its own focus manager,
+/// so nothing is drawn as focused. it keeps this layout until it's next
+/// updated. the canvas's target is restored after
+pub fn render_to_surface(
+    widget: &mut dyn Widget,
+    canvas: &mut WindowCanvas,
+    (w, h): (u32, u32),
+    background: Color,
+) -> UiResult<Surface<'static>> {
+    if w == 0 || h == 0 {
+        return Err(UiError::Layout(
+            "can't render a widget with a zero size".to_owned(),
+        ));
+    }
+    let (max_w, max_h) = max_texture_size();
+    if w > max_w || h > max_h {
+        return Err(UiError::Texture(format!(
+            "can't render a widget at {}x{}, larger than the max texture size {}x{}",
+            w, h, max_w, max_h
+        )));
+    }
+
+    let mut focus_manager = FocusManager::default();
+    update_gui_at_size(
+        widget,
+        &mut [],
+        &mut focus_manager,
+        canvas,
+        &MouseTransform::Unchanged,
+        RootPlacement::sized((w as f32, h as f32)),
+        None,
+    )?;
+
+    let creator = canvas.texture_creator();
+    let mut texture = creator
+        .create_texture_target(PixelFormatEnum::ARGB8888, w, h)
+        .map(owned_texture)?;
+
+    let mut pixels: UiResult<Vec<u8>> = Err(UiError::Sdl("texture canvas not drawn".to_owned()));
+    canvas.with_texture_canvas(&mut texture, |canvas| {
+        pixels = (|| {
+            canvas.set_draw_color(background);
+            canvas.clear();
+            draw_gui(widget, canvas, &focus_manager)?;
+            canvas
+                .read_pixels(None, PixelFormatEnum::ARGB8888)
+                .map_err(UiError::Sdl)
+        })();
+    })?;
+    let pixels = pixels?;
+
+    let mut surface = Surface::new(w, h, PixelFormatEnum::ARGB8888)?;
+    let pitch = surface.pitch() as usize;
+    let row_len = w as usize * 4;
+    surface.with_lock_mut(|buffer| {
+        for (src, dst) in pixels.chunks_exact(row_len).zip(buffer.chunks_mut(pitch)) {
+            dst[..row_len].copy_from_slice(src);
+        }
+    });
+    Ok(surface)
+}
+
+/// render_to_surface, then save it as a bmp
+pub fn save_bmp<P: AsRef<Path>>(
+    widget: &mut dyn Widget,
+    canvas: &mut WindowCanvas,
+    size: (u32, u32),
+    background: Color,
+    path: P,
+) -> UiResult<()> {
+    render_to_surface(widget, canvas, size, background)?
+        .save_bmp(path)
+        .map_err(UiError::Sdl)
+}
+
+/// render_to_surface, then save it as a png
+#[cfg(feature = "sdl2-image")]
+pub fn save_png<P: AsRef<Path>>(
+    widget: &mut dyn Widget,
+    canvas: &mut WindowCanvas,
+    size: (u32, u32),
+    background: Color,
+    path: P,
+) -> UiResult<()> {
+    use sdl2::image::SaveSurface;
+    render_to_surface(widget, canvas, size, background)?
+        .save(path)
+        .map_err(UiError::Sdl)
+}
@@ -0,0 +1,21 @@
+//! semantic ui feedback, e.g. for sounds or controller rumble. widgets whose
+//! sound style is silent give their feedback to the focus manager instead,
+//! which forwards it to a single sink set by the application. so feedback can
+//! be handled in one place, while a widget can still have its own sounds
+
+/// something happened which the user might want feedback for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiFeedback {
+    /// a widget gained focus, or the mouse moved over it
+    Focus,
+    /// a widget was pressed down, by the mouse or an activation key
+    Press,
+    /// a widget was activated (e.g. a button was released, or enter was
+    /// pressed in a text input)
+    Activate,
+    /// something was rejected (e.g. a form was submitted with invalid fields)
+    Error,
+}
+
+/// see FocusManager::set_feedback_sink
+pub type FeedbackSink = Box<dyn FnMut(UiFeedback)>;
@@ -1,9 +1,20 @@
+use std::collections::HashMap;
+
 use sdl2::{
     keyboard::{Keycode, Mod},
     render::ClippingRect,
 };
 
-use crate::widget::SDLEvent;
+use crate::{
+    util::{
+        accessibility::{AccessibilityObserver, AccessibleDescription},
+        draw_hooks::record_focus,
+        feedback::{FeedbackSink, UiFeedback},
+        hit::{HitShape, HitTest},
+        rect::FRect,
+    },
+    widget::{SDLEvent, WidgetUpdateEvent},
+};
 
 
 #[derive(Debug, PartialEq, Eq)]
@@ -13,30 +24,36 @@ pub struct FocusID {
     pub next: String,
 }
 
+/// called when the focus changes. receives the previous and current focus,
+/// respectively
+pub type FocusObserver = Box<dyn FnMut(Option<&str>, Option<&str>)>;
+
 /// a widget can be the current focus. how a widget handles what that means is
 /// up to it. only zero or one widgets should be focused at a time.
 #[derive(Default)]
-pub struct FocusManager(pub Option<String>);
-
-pub(crate) fn point_in_position_and_clipping_rect(
-    x: i32,
-    y: i32,
-    position: sdl2::rect::Rect,
-    clipping_rect: ClippingRect,
-) -> bool {
-    if position.contains_point((x, y)) {
-        // ignore mouse events out of scroll area and position
-        let point_contained_in_clipping_rect = match clipping_rect {
-            sdl2::render::ClippingRect::Some(rect) => rect.contains_point((x, y)),
-            sdl2::render::ClippingRect::Zero => false,
-            sdl2::render::ClippingRect::None => true,
-        };
+pub struct FocusManager {
+    /// the id of the focused widget. none if nothing is focused
+    pub focus: Option<String>,
+    tracking: FocusTracking,
+}
 
-        if point_contained_in_clipping_rect {
-            return true;
-        }
-    }
-    false
+/// extra state in the focus manager which isn't the focus itself
+#[derive(Default)]
+pub struct FocusTracking {
+    /// the last known position of each focusable widget, reported during update
+    rects: HashMap<String, FRect>,
+    /// the focusable widgets which reported their rect this update, in order
+    reported: Vec<String>,
+    /// the focus when the observers were last notified
+    notified_focus: Option<String>,
+    observers: Vec<FocusObserver>,
+    /// the focused widget's id and description, reported this frame
+    description: Option<(String, AccessibleDescription)>,
+    /// the description when the accessibility observers were last notified
+    announced_description: Option<AccessibleDescription>,
+    accessibility_observers: Vec<AccessibilityObserver>,
+    /// see set_feedback_sink
+    feedback_sink: Option<FeedbackSink>,
 }
 
 // closely related to WidgetUpdateEvent
@@ -45,6 +62,10 @@ pub struct DefaultFocusBehaviorArg<'sdl> {
     pub position: super::rect::FRect,
     pub clipping_rect: ClippingRect,
     pub window_id: u32,
+    /// see HitTest::expansion
+    pub hit_expansion: f32,
+    /// see HitTest::shape
+    pub hit_shape: &'sdl HitShape,
     /// a single event. the intent is that this would be inline with the
     /// existing processing loop - for consistent order of operations each
     /// element should be fully processed before moving to the next element
@@ -52,8 +73,132 @@ pub struct DefaultFocusBehaviorArg<'sdl> {
 }
 
 impl FocusManager {
+    pub fn new(focus: Option<String>) -> Self {
+        Self {
+            focus,
+            tracking: Default::default(),
+        }
+    }
+
     pub fn is_focused(&self, other: &FocusID) -> bool {
-        self.0.as_ref().map(|uid| uid == other.me.as_str()).unwrap_or(false)
+        self.focus
+            .as_ref()
+            .map(|uid| uid == other.me.as_str())
+            .unwrap_or(false)
+    }
+
+    /// the id of the focused widget, if any
+    pub fn focused(&self) -> Option<&str> {
+        self.focus.as_deref()
+    }
+
+    /// set the focus from app code. None unfocuses
+    pub fn set_focus(&mut self, focus: Option<&str>) {
+        self.focus = focus.map(|v| v.to_owned());
+    }
+
+    /// focusable widgets should call this each update so their position is
+    /// known by the focus manager
+    pub fn report_rect(&mut self, focus_id: &FocusID, position: FRect) {
+        self.tracking.reported.push(focus_id.me.clone());
+        self.tracking.rects.insert(focus_id.me.clone(), position);
+        record_focus(&focus_id.me, position);
+    }
+
+    /// the ids given to report_rect so far this update, in order
+    pub(crate) fn reported(&self) -> &[String] {
+        &self.tracking.reported
+    }
+
+    /// the last known position of a focusable widget
+    pub fn rect_of(&self, id: &str) -> Option<FRect> {
+        self.tracking.rects.get(id).copied()
+    }
+
+    /// the last known position of the focused widget
+    pub fn focused_rect(&self) -> Option<FRect> {
+        self.focused().and_then(|id| self.rect_of(id))
+    }
+
+    /// register a callback which is called when the focus changes. changes are
+    /// detected (and the observers called) at the end of update_gui, or on an
+    /// explicit call to notify_focus_observers
+    pub fn add_observer(&mut self, observer: FocusObserver) {
+        self.tracking.observers.push(observer);
+    }
+
+    /// call observers if the focus has changed since they were last notified
+    pub fn notify_focus_observers(&mut self) {
+        if self.tracking.notified_focus != self.focus {
+            let previous = std::mem::replace(&mut self.tracking.notified_focus, self.focus.clone());
+            for observer in self.tracking.observers.iter_mut() {
+                observer(previous.as_deref(), self.focus.as_deref());
+            }
+        }
+
+        let description = self.focused_description().cloned();
+        if self.tracking.announced_description != description {
+            self.tracking.announced_description = description;
+            if let Some(description) = self.tracking.announced_description.as_ref() {
+                for observer in self.tracking.accessibility_observers.iter_mut() {
+                    observer(description);
+                }
+            }
+        }
+    }
+
+    /// focusable widgets should call this at the end of each update with their
+    /// accessible description. it's only kept if the widget is focused
+    pub fn report_description(
+        &mut self,
+        focus_id: &FocusID,
+        description: Option<AccessibleDescription>,
+    ) {
+        if !self.is_focused(focus_id) {
+            return;
+        }
+        self.tracking.description = description.map(|d| (focus_id.me.clone(), d));
+    }
+
+    /// the description of the focused widget, as reported during the most
+    /// recent update
+    pub fn focused_description(&self) -> Option<&AccessibleDescription> {
+        let (id, description) = self.tracking.description.as_ref()?;
+        if self.focused() != Some(id.as_str()) {
+            return None; // stale
+        }
+        Some(description)
+    }
+
+    /// register a callback which is called with the focused widget's
+    /// description each time it changes (either the focus moved, or the
+    /// focused widget's state or value changed). intended to be forwarded to a
+    /// screen reader or text to speech
+    pub fn add_accessibility_observer(&mut self, observer: AccessibilityObserver) {
+        self.tracking.accessibility_observers.push(observer);
+    }
+
+    /// set what receives feedback (e.g. to play a sound) from widgets whose
+    /// sound style is silent. see util::feedback
+    pub fn set_feedback_sink(&mut self, sink: Option<FeedbackSink>) {
+        self.tracking.feedback_sink = sink;
+    }
+
+    /// give feedback to the sink, if one is set
+    pub fn feedback(&mut self, feedback: UiFeedback) {
+        if let Some(sink) = self.tracking.feedback_sink.as_mut() {
+            sink(feedback);
+        }
+    }
+
+    /// called at the beginning of each update
+    pub(crate) fn clear_reports(&mut self) {
+        self.tracking.description = None;
+        // forget widgets which weren't reported in the previous update (e.g.
+        // removed from the gui)
+        let tracking = &mut self.tracking;
+        tracking.rects.retain(|id, _| tracking.reported.contains(id));
+        tracking.reported.clear();
     }
 
     /// handle default behavior for how focus should change given the events:
@@ -81,10 +226,10 @@ impl FocusManager {
                 }
                 if keymod.contains(Mod::LSHIFTMOD) || keymod.contains(Mod::RSHIFTMOD) {
                     // shift tab was pressed
-                    event.focus_manager.0 = Some(my_focus_id.previous.clone());
+                    event.focus_manager.focus = Some(my_focus_id.previous.clone());
                 } else {
                     // tab was pressed
-                    event.focus_manager.0 = Some(my_focus_id.next.clone());
+                    event.focus_manager.focus = Some(my_focus_id.next.clone());
                 }
             }
             sdl2::event::Event::KeyDown {
@@ -99,23 +244,20 @@ impl FocusManager {
                 if repeat {
                     return;
                 }
-                event.focus_manager.0 = None; // unfocus
+                event.focus_manager.focus = None; // unfocus
             }
             sdl2::event::Event::MouseMotion {
                 x, y, window_id, ..
             } => {
-                if event.window_id != window_id {
-                    return; // not for me!
-                }
-                let position: Option<sdl2::rect::Rect> = event.position.into();
-                if let Some(position) = position {
-                    if point_in_position_and_clipping_rect(x, y, position, event.clipping_rect) {
-                        // even if not focused, if mouse is moved over
-                        // widget then set focus to that widget
-                        //
-                        // generally never consume mouse motion events
-                        event.focus_manager.0 = Some(my_focus_id.me.clone());
-                    }
+                let hit = HitTest::new(event.position, event.clipping_rect, event.window_id)
+                    .expanded(event.hit_expansion)
+                    .shaped(event.hit_shape.clone());
+                if hit.contains(window_id, x, y) {
+                    // even if not focused, if mouse is moved over
+                    // widget then set focus to that widget
+                    //
+                    // generally never consume mouse motion events
+                    event.focus_manager.focus = Some(my_focus_id.me.clone());
                 }
             }
             _ => {}
@@ -144,12 +286,136 @@ impl FocusManager {
                 }
                 if keymod.contains(Mod::LSHIFTMOD) || keymod.contains(Mod::RSHIFTMOD) {
                     // shift tab was pressed
-                    self.0 = Some(end_widget_focus_id.to_owned());
+                    self.focus = Some(end_widget_focus_id.to_owned());
                 } else {
                     // tab was pressed
-                    self.0 = Some(start_widget_focus_id.to_owned());
+                    self.focus = Some(start_widget_focus_id.to_owned());
+                }
+            }
+        }
+        self.notify_focus_observers();
+    }
+}
+
+/// keyboard navigation among a layout's focusable descendants, for when they're
+/// generated programmatically (e.g. rows in a list). the descendants are found
+/// as they report their rect during update, in update order, so their FocusID
+/// previous and next don't need to be authored
+///
+/// while the focus is in the group, tab and shift tab move to the next and
+/// previous descendant, and so do the arrow keys along the layout's axis. a
+/// group takes tab before its descendants, so an outer group takes precedence
+/// over inner ones
+#[derive(Default)]
+pub struct FocusGroup {
+    pub enabled: bool,
+    /// moving past the last (or before the first) goes around to the other
+    /// end. otherwise the key is left for the focused widget, which moves
+    /// focus out of the group with its FocusID previous or next
+    pub wrap: bool,
+    /// the descendants, as of the most recent update
+    ids: Vec<String>,
+    /// how many rects had been reported when the group's update started
+    reported_before: usize,
+}
+
+impl FocusGroup {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// the focusable descendants, in order, as of the most recent update
+    pub fn focus_ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// the focus id after (or before) the current focus. none if the focus
+    /// isn't in the group, or it's at an end and the group doesn't wrap
+    fn step(&self, focus_manager: &FocusManager, forward: bool) -> Option<&String> {
+        let len = self.ids.len();
+        let current = self
+            .ids
+            .iter()
+            .position(|id| Some(id.as_str()) == focus_manager.focused())?;
+        let at_end = if forward {
+            current + 1 == len
+        } else {
+            current == 0
+        };
+        if at_end && !self.wrap {
+            return None;
+        }
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.ids.get(next)
+    }
+
+    fn move_focus(
+        &self,
+        event: &mut WidgetUpdateEvent,
+        key: impl Fn(Keycode, Mod) -> Option<bool>,
+    ) {
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            if let sdl2::event::Event::KeyDown {
+                repeat,
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } = sdl_event.e
+            {
+                let forward = match key(keycode, keymod) {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let next = match self.step(event.focus_manager, forward) {
+                    Some(v) => v.clone(),
+                    None => continue,
+                };
+                sdl_event.set_consumed();
+                if repeat && keycode == Keycode::Tab {
+                    continue;
                 }
+                event.focus_manager.focus = Some(next);
+            }
+        }
+    }
+
+    /// call at the beginning of the layout's update, before the children are
+    /// updated
+    pub fn update(&mut self, event: &mut WidgetUpdateEvent) {
+        if !self.enabled {
+            return;
+        }
+        self.move_focus(event, |keycode, keymod| match keycode {
+            Keycode::Tab => Some(!keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD)),
+            _ => None,
+        });
+        self.reported_before = event.focus_manager.tracking.reported.len();
+    }
+
+    /// call at the end of the layout's update, after the children are updated.
+    /// arrow keys which the children didn't use move the focus
+    pub fn update_after(&mut self, event: &mut WidgetUpdateEvent, vertical: bool) {
+        if !self.enabled {
+            return;
+        }
+        self.ids.clear();
+        let reported = &event.focus_manager.tracking.reported;
+        for id in reported.iter().skip(self.reported_before) {
+            if !self.ids.contains(id) {
+                self.ids.push(id.clone());
             }
         }
+        self.move_focus(event, |keycode, _| match (keycode, vertical) {
+            (Keycode::Down, true) | (Keycode::Right, false) => Some(true),
+            (Keycode::Up, true) | (Keycode::Left, false) => Some(false),
+            _ => None,
+        });
     }
 }
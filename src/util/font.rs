@@ -1,5 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// each char is 10 wide
+    fn width(text: &str) -> UiResult<u32> {
+        Ok(text.chars().count() as u32 * 10)
+    }
+
+    #[test]
+    fn wrap() {
+        let text = "aa bb  cc\n\ndddddd e";
+        let lines = wrap_ranges(text, 50, width).unwrap();
+        let lines: Vec<&str> = lines.into_iter().map(|range| &text[range]).collect();
+        assert_eq!(lines, ["aa bb", "cc", "", "dddddd", "e"]);
+    }
+
+    #[test]
+    fn range_rects() {
+        let text = "aa bb cc";
+        let lines = wrap_ranges(text, 50, width).unwrap();
+        // "bb cc" spans both lines
+        let rects = line_range_rects(text, &lines, 12, 10, 3..8, width).unwrap();
+        assert_eq!(
+            rects,
+            [
+                sdl2::rect::Rect::new(30, 0, 20, 10),
+                sdl2::rect::Rect::new(0, 12, 20, 10)
+            ]
+        );
+    }
+
+    #[cfg(feature = "sdl2-ttf")]
+    #[test]
+    fn range_rects_from_font() {
+        let ttf_context = sdl2::ttf::init().unwrap();
+        let font_data = include_bytes!("../../examples/assets/TEMPSITC-REDUCED.TTF");
+        let font_manager = Cell::new(Some(FontManager::new(&ttf_context, font_data)));
+        let mut renderer = TextRenderer::new(&font_manager);
+        let font = renderer.font(20).unwrap();
+        // too narrow for both words, so "bb" is on the second line
+        let rects = MultiLineFontStyle::range_rects(&mut renderer, "aa bb", 20, 1, 3..5).unwrap();
+        assert_eq!(
+            rects,
+            [sdl2::rect::Rect::new(
+                0,
+                font.recommended_line_spacing(),
+                font.size_of("bb").unwrap().0,
+                font.height() as u32
+            )]
+        );
+    }
+}
+
 use std::{
     cell::Cell,
+    ops::Range,
     rc::{Rc, Weak},
 };
 
@@ -15,6 +70,10 @@ use sdl2::{
 #[cfg(feature = "sdl2-ttf")]
 use weak_table::WeakValueHashMap;
 
+use crate::util::error::UiResult;
+#[cfg(feature = "sdl2-ttf")]
+use crate::util::{error::UiError, render::owned_texture};
+
 /// manages a font. use this to get a font object with a certain point size
 #[cfg(feature = "sdl2-ttf")]
 pub struct FontManager<'sdl> {
@@ -39,7 +98,7 @@ impl<'sdl> FontManager<'sdl> {
 
 #[cfg(feature = "sdl2-ttf")]
 impl<'sdl> FontManager<'sdl> {
-    pub fn get(&mut self, point_size: u16) -> Result<Rc<Font<'sdl, 'sdl>>, String> {
+    pub fn get(&mut self, point_size: u16) -> UiResult<Rc<Font<'sdl, 'sdl>>> {
         if let Some(v) = self.fonts.get(&point_size) { return Ok(v) };
 
         let rwops = RWops::from_bytes(self.font_data)?;
@@ -51,7 +110,7 @@ impl<'sdl> FontManager<'sdl> {
 
 // =============================================================================
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum SingleLineTextRenderType {
     #[deprecated(note = "looks like sh**")]
     Solid(Color),
@@ -90,12 +149,12 @@ pub trait SingleLineFontStyle<'sdl> {
         text: &str,
         properties: &TextRenderProperties,
         texture_creator: &'sdl TextureCreator<WindowContext>,
-    ) -> Result<sdl2::render::Texture<'sdl>, String>;
+    ) -> UiResult<crate::util::render::OwnedTexture<'sdl>>;
 
     /// get the width, height of some text if it were to be rendered
     ///
     /// all of the doc string for render applies here as well
-    fn render_dimensions(&mut self, text: &str, point_size: u16) -> Result<(u32, u32), String>;
+    fn render_dimensions(&mut self, text: &str, point_size: u16) -> UiResult<(u32, u32)>;
 
     /// object safe clone
     fn dup(&self) -> Box<dyn SingleLineFontStyle<'sdl> + 'sdl>;
@@ -113,7 +172,99 @@ pub trait MultiLineFontStyle<'sdl> {
         point_size: u16,
         wrap_width: u32,
         texture_creator: &'sdl TextureCreator<WindowContext>,
-    ) -> Result<sdl2::render::Texture<'sdl>, String>;
+    ) -> UiResult<crate::util::render::OwnedTexture<'sdl>>;
+
+    /// as render, but the result is split into several textures if it's larger
+    /// than max_size (see util::render::max_texture_size). by default this
+    /// isn't split
+    fn render_tiled(
+        &mut self,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        wrap_width: u32,
+        max_size: (u32, u32),
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<crate::util::render::TiledTexture<'sdl>> {
+        let _ = max_size;
+        self.render(text, color, point_size, wrap_width, texture_creator)
+            .map(Into::into)
+    }
+
+    /// the rects covering a byte range of the text, relative to the top left
+    /// of what render gives (one per line the range is on). used to decorate
+    /// part of the text (e.g. spell check underlines). by default the text
+    /// isn't measured, and nothing is given
+    fn range_rects(
+        &mut self,
+        text: &str,
+        point_size: u16,
+        wrap_width: u32,
+        range: Range<usize>,
+    ) -> UiResult<Vec<sdl2::rect::Rect>> {
+        let _ = (text, point_size, wrap_width, range);
+        Ok(Vec::new())
+    }
+}
+
+/// the byte range of each line of greedily word wrapped text, excluding
+/// trailing spaces. a word wider than the wrap width is left on its own line.
+/// this approximates how sdl2-ttf wraps
+pub(crate) fn wrap_ranges(
+    text: &str,
+    wrap_width: u32,
+    mut width: impl FnMut(&str) -> UiResult<u32>,
+) -> UiResult<Vec<Range<usize>>> {
+    let trimmed = |range: Range<usize>| range.start..range.start + text[range].trim_end().len();
+    let mut lines = Vec::new();
+    let mut paragraph_start = 0;
+    for paragraph in text.split('\n') {
+        let mut line = paragraph_start..paragraph_start;
+        for word in paragraph.split_inclusive(' ') {
+            let word_end = line.end + word.len();
+            if !line.is_empty() && width(text[line.start..word_end].trim_end())? > wrap_width {
+                lines.push(trimmed(line.clone()));
+                line.start = line.end;
+            }
+            line.end = word_end;
+        }
+        lines.push(trimmed(line));
+        paragraph_start += paragraph.len() + 1;
+    }
+    Ok(lines)
+}
+
+/// implements MultiLineFontStyle::range_rects, given the text's wrapped lines
+/// (see wrap_ranges) drawn line_skip apart
+pub(crate) fn line_range_rects(
+    text: &str,
+    lines: &[Range<usize>],
+    line_skip: u32,
+    line_height: u32,
+    range: Range<usize>,
+    mut width: impl FnMut(&str) -> UiResult<u32>,
+) -> UiResult<Vec<sdl2::rect::Rect>> {
+    let mut rects = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let start = range.start.max(line.start);
+        let end = range.end.min(line.end);
+        if start >= end {
+            continue;
+        }
+        let (before, through) = match (text.get(line.start..start), text.get(line.start..end)) {
+            (Some(before), Some(through)) => (before, through),
+            _ => continue, // not on a char boundary
+        };
+        let x = if before.is_empty() { 0 } else { width(before)? };
+        let x_end = width(through)?;
+        rects.push(sdl2::rect::Rect::new(
+            x as i32,
+            (i as u32 * line_skip) as i32,
+            x_end.saturating_sub(x).max(1),
+            line_height.max(1),
+        ));
+    }
+    Ok(rects)
 }
 
 #[cfg(feature = "sdl2-ttf")]
@@ -149,12 +300,12 @@ impl<'sdl> SingleLineFontStyle<'sdl> for TextRenderer<'sdl> {
         text: &str,
         properties: &TextRenderProperties,
         texture_creator: &'sdl TextureCreator<WindowContext>,
-    ) -> Result<sdl2::render::Texture<'sdl>, String> {
+    ) -> UiResult<crate::util::render::OwnedTexture<'sdl>> {
         let surface = if text.is_empty() {
             // handle SdlError("Text has zero width")
             // create a 1x1 replacement
             let mut surface = Surface::new(1, 1, sdl2::pixels::PixelFormatEnum::ARGB8888)
-                .map_err(|e| e.to_string())?;
+                .map_err(UiError::Sdl)?;
             surface.with_lock_mut(|buffer| match properties.render_type {
                 SingleLineTextRenderType::Shaded(_, background) => {
                     buffer[3] = background.a;
@@ -171,31 +322,7 @@ impl<'sdl> SingleLineFontStyle<'sdl> for TextRenderer<'sdl> {
             });
             surface
         } else {
-            let font = match self
-                .cache
-                .take()
-                .filter(|cache| cache.font_point_size == properties.point_size)
-            {
-                Some(cache) => &self.cache.insert(cache).font,
-                None => {
-                    let mut maybe_manager = self.font_manager.take();
-                    let manager = match maybe_manager.as_mut() {
-                        Some(v) => v,
-                        // should never error, as it will always be returned to the cell
-                        None => return Err("couldn't reference font manager".to_owned()),
-                    };
-                    let maybe_r = manager.get(properties.point_size);
-                    self.font_manager.set(maybe_manager);
-                    let r = maybe_r?;
-                    &self
-                        .cache
-                        .insert(TextRendererFontCache {
-                            font: r.clone(),
-                            font_point_size: properties.point_size,
-                        })
-                        .font
-                }
-            };
+            let font = self.font(properties.point_size)?;
 
             let partial_render = font.render(text);
             
@@ -206,13 +333,13 @@ impl<'sdl> SingleLineFontStyle<'sdl> for TextRenderer<'sdl> {
                     partial_render.shaded(color, background)
                 }
                 SingleLineTextRenderType::Blended(color) => partial_render.blended(color),
-            }
-            .map_err(|e| e.to_string())?
+            }?
         };
 
         let mut texture = texture_creator
             .create_texture_from_surface(surface)
-            .map_err(|e| e.to_string())?;
+            .map(owned_texture)?;
+        crate::util::perf::count(crate::util::perf::Counter::TextureCreation);
 
         // I made this binding :)
         texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
@@ -220,34 +347,10 @@ impl<'sdl> SingleLineFontStyle<'sdl> for TextRenderer<'sdl> {
         Ok(texture)
     }
 
-    fn render_dimensions(&mut self, text: &str, point_size: u16) -> Result<(u32, u32), String> {
-        let font = match self
-            .cache
-            .take()
-            .filter(|cache| cache.font_point_size == point_size)
-        {
-            Some(cache) => &self.cache.insert(cache).font,
-            None => {
-                let mut maybe_manager = self.font_manager.take();
-                let manager = match maybe_manager.as_mut() {
-                    Some(v) => v,
-                    // should never error, as it will always be returned to the cell
-                    None => return Err("couldn't reference font manager".to_owned()),
-                };
-                let maybe_r = manager.get(point_size);
-                self.font_manager.set(maybe_manager);
-                let r = maybe_r?;
-                &self
-                    .cache
-                    .insert(TextRendererFontCache {
-                        font: r.clone(),
-                        font_point_size: point_size,
-                    })
-                    .font
-            }
-        };
+    fn render_dimensions(&mut self, text: &str, point_size: u16) -> UiResult<(u32, u32)> {
+        let font = self.font(point_size)?;
 
-        let (w, h) = font.size_of(text).map_err(|e| e.to_string())?;
+        let (w, h) = font.size_of(text)?;
         Ok((w, h))
     }
 
@@ -260,65 +363,121 @@ impl<'sdl> SingleLineFontStyle<'sdl> for TextRenderer<'sdl> {
 }
 
 #[cfg(feature = "sdl2-ttf")]
-impl<'sdl> MultiLineFontStyle<'sdl> for TextRenderer<'sdl> {
-    fn render(
+impl<'sdl> TextRenderer<'sdl> {
+    /// the font for a point size. kept for subsequent calls with the same
+    /// point size
+    pub(crate) fn font(&mut self, point_size: u16) -> UiResult<Rc<Font<'sdl, 'sdl>>> {
+        if let Some(cache) = self
+            .cache
+            .as_ref()
+            .filter(|cache| cache.font_point_size == point_size)
+        {
+            return Ok(cache.font.clone());
+        }
+        let mut maybe_manager = self.font_manager.take();
+        let manager = match maybe_manager.as_mut() {
+            Some(v) => v,
+            // should never error, as it will always be returned to the cell
+            None => return Err(UiError::Font("couldn't reference font manager".to_owned())),
+        };
+        let maybe_r = manager.get(point_size);
+        self.font_manager.set(maybe_manager);
+        let r = maybe_r?;
+        self.cache = Some(TextRendererFontCache {
+            font: r.clone(),
+            font_point_size: point_size,
+        });
+        Ok(r)
+    }
+
+    fn render_wrapped_surface(
         &mut self,
         text: &str,
         color: Color,
         point_size: u16,
         wrap_width: u32,
-        texture_creator: &'sdl TextureCreator<WindowContext>,
-    ) -> Result<sdl2::render::Texture<'sdl>, String> {
+    ) -> UiResult<Surface<'static>> {
         // closely follows SingleLineFontStyle::render implementation
-        let surface = if text.is_empty() {
+        if text.is_empty() {
             // handle SdlError("Text has zero width")
             // create a 1x1 replacement
             let mut surface = Surface::new(1, 1, sdl2::pixels::PixelFormatEnum::ARGB8888)
-                .map_err(|e| e.to_string())?;
+                .map_err(UiError::Sdl)?;
             surface.with_lock_mut(|buffer| {
                 buffer[0] = 0;
                 buffer[1] = 0;
                 buffer[2] = 0;
                 buffer[3] = 0;
             });
-            surface
-        } else {
-            let font = match self
-                .cache
-                .take()
-                .filter(|cache| cache.font_point_size == point_size)
-            {
-                Some(cache) => &self.cache.insert(cache).font,
-                None => {
-                    let mut maybe_manager = self.font_manager.take();
-                    let manager = match maybe_manager.as_mut() {
-                        Some(v) => v,
-                        // should never error, as it will always be returned to the cell
-                        None => return Err("couldn't reference font manager".to_owned()),
-                    };
-                    let maybe_r = manager.get(point_size);
-                    self.font_manager.set(maybe_manager);
-                    let r = maybe_r?;
-                    &self
-                        .cache
-                        .insert(TextRendererFontCache {
-                            font: r.clone(),
-                            font_point_size: point_size,
-                        })
-                        .font
-                }
-            };
+            return Ok(surface);
+        }
+        let font = self.font(point_size)?;
 
-            let partial_render = font.render(text);
-            
-            partial_render
-                .blended_wrapped(color, wrap_width)
-                .map_err(|e| e.to_string())?
-        };
+        font.render(text)
+            .blended_wrapped(color, wrap_width)
+            .map_err(|e| UiError::Font(e.to_string()))
+    }
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl> MultiLineFontStyle<'sdl> for TextRenderer<'sdl> {
+    fn render(
+        &mut self,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        wrap_width: u32,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<crate::util::render::OwnedTexture<'sdl>> {
+        let surface = self.render_wrapped_surface(text, color, point_size, wrap_width)?;
         let mut texture = texture_creator
             .create_texture_from_surface(surface)
-            .map_err(|e| e.to_string())?;
+            .map(owned_texture)?;
+        crate::util::perf::count(crate::util::perf::Counter::TextureCreation);
+        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+        Ok(texture)
+    }
+
+    fn render_tiled(
+        &mut self,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        wrap_width: u32,
+        max_size: (u32, u32),
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<crate::util::render::TiledTexture<'sdl>> {
+        let mut surface = self.render_wrapped_surface(text, color, point_size, wrap_width)?;
+        let mut texture = crate::util::render::TiledTexture::from_surface(
+            &mut surface,
+            texture_creator,
+            max_size,
+        )?;
         texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
         Ok(texture)
     }
+
+    fn range_rects(
+        &mut self,
+        text: &str,
+        point_size: u16,
+        wrap_width: u32,
+        range: Range<usize>,
+    ) -> UiResult<Vec<sdl2::rect::Rect>> {
+        let font = self.font(point_size)?;
+        let width = |text: &str| {
+            font.size_of(text)
+                .map(|v| v.0)
+                .map_err(|e| UiError::Font(e.to_string()))
+        };
+        let lines = wrap_ranges(text, wrap_width, width)?;
+        line_range_rects(
+            text,
+            &lines,
+            font.recommended_line_spacing().max(1) as u32,
+            font.height().max(1) as u32,
+            range,
+            width,
+        )
+    }
 }
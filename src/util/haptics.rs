@@ -0,0 +1,176 @@
+//! controller rumble, requested by widgets or the application and merged in
+//! one place. requests which overlap in time combine; each motor runs at the
+//! strongest active request. apply_rumble sends the result to the controllers
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEAK_LONG: Rumble = Rumble {
+        low: 0.2,
+        high: 0.6,
+        duration: Duration::from_millis(300),
+    };
+
+    const STRONG_SHORT: Rumble = Rumble {
+        low: 1.,
+        high: 0.,
+        duration: Duration::from_millis(100),
+    };
+
+    #[test]
+    fn merge_overlapping() {
+        let now = Instant::now();
+        assert_eq!(merged(&[], now), None);
+
+        let active = [
+            (WEAK_LONG, now + WEAK_LONG.duration),
+            (STRONG_SHORT, now + STRONG_SHORT.duration),
+        ];
+        // strongest of each motor, until the first one ends
+        assert_eq!(
+            merged(&active, now),
+            Some((u16::MAX, to_u16(0.6), Duration::from_millis(100)))
+        );
+        // the short one has ended
+        let later = now + Duration::from_millis(150);
+        assert_eq!(
+            merged(&active, later),
+            Some((to_u16(0.2), to_u16(0.6), Duration::from_millis(150)))
+        );
+        assert_eq!(merged(&active, now + Duration::from_millis(300)), None);
+    }
+}
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+use sdl2::controller::GameController;
+
+use crate::util::{
+    animation::request_redraw,
+    error::{UiError, UiResult},
+    feedback::UiFeedback,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rumble {
+    /// strength of the low frequency (strong) motor, from 0 to 1
+    pub low: f32,
+    /// strength of the high frequency (weak) motor, from 0 to 1
+    pub high: f32,
+    pub duration: Duration,
+}
+
+impl Rumble {
+    /// a light tap, e.g. a button press
+    pub const TAP: Rumble = Rumble {
+        low: 0.,
+        high: 0.4,
+        duration: Duration::from_millis(40),
+    };
+
+    /// e.g. reaching the end of a list or a slider
+    pub const BUMP: Rumble = Rumble {
+        low: 0.5,
+        high: 0.2,
+        duration: Duration::from_millis(80),
+    };
+
+    /// something was rejected
+    pub const ERROR: Rumble = Rumble {
+        low: 0.8,
+        high: 0.5,
+        duration: Duration::from_millis(200),
+    };
+
+    /// a default mapping from ui feedback, for use in a feedback sink. see
+    /// FocusManager::set_feedback_sink
+    pub fn for_feedback(feedback: UiFeedback) -> Option<Rumble> {
+        match feedback {
+            UiFeedback::Focus | UiFeedback::Activate => None,
+            UiFeedback::Press => Some(Rumble::TAP),
+            UiFeedback::Error => Some(Rumble::ERROR),
+        }
+    }
+}
+
+#[derive(Default)]
+struct HapticsState {
+    /// requests and when they end
+    active: Vec<(Rumble, Instant)>,
+    /// the merged rumble needs to be sent again
+    changed: bool,
+}
+
+// requested from anywhere during update, and applied by the application once
+// per frame. the gui is single threaded
+thread_local! {
+    static HAPTICS: RefCell<HapticsState> = RefCell::new(HapticsState::default());
+}
+
+/// request rumble, starting now
+pub fn rumble(rumble: Rumble) {
+    let end = Instant::now() + rumble.duration;
+    HAPTICS.with(|haptics| {
+        let mut haptics = haptics.borrow_mut();
+        haptics.active.push((rumble, end));
+        haptics.changed = true;
+    });
+}
+
+fn to_u16(strength: f32) -> u16 {
+    (strength.clamp(0., 1.) * u16::MAX as f32).round() as u16
+}
+
+/// the strength of each motor (low, high) from the requests which haven't
+/// ended, and how long until that changes. none if nothing is active
+fn merged(active: &[(Rumble, Instant)], now: Instant) -> Option<(u16, u16, Duration)> {
+    let mut result: Option<(f32, f32, Instant)> = None;
+    for (rumble, end) in active.iter().filter(|(_, end)| *end > now) {
+        let (low, high, next_end) = result.unwrap_or((0., 0., *end));
+        result = Some((
+            low.max(rumble.low),
+            high.max(rumble.high),
+            next_end.min(*end),
+        ));
+    }
+    result.map(|(low, high, next_end)| (to_u16(low), to_u16(high), next_end - now))
+}
+
+/// send the merged rumble to the controllers, if it changed. call once per
+/// frame (e.g. after update_gui). while requests of different lengths
+/// overlap, a redraw is requested so the change is sent when one ends
+pub fn apply_rumble(controllers: &mut [GameController]) -> UiResult<()> {
+    let now = Instant::now();
+    let send = HAPTICS.with(|haptics| {
+        let mut haptics = haptics.borrow_mut();
+        let len_before = haptics.active.len();
+        haptics.active.retain(|(_, end)| *end > now);
+        let changed = std::mem::take(&mut haptics.changed) || haptics.active.len() != len_before;
+        if !changed {
+            return None;
+        }
+        let merged = merged(&haptics.active, now);
+        if let Some((_, _, duration)) = merged {
+            if haptics.active.iter().any(|(_, end)| *end > now + duration) {
+                request_redraw();
+            }
+        }
+        Some(merged.unwrap_or((0, 0, Duration::ZERO)))
+    });
+
+    let (low, high, duration) = match send {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+    let duration_ms = duration.as_millis().min(u32::MAX as u128) as u32;
+    for controller in controllers.iter_mut() {
+        controller
+            .set_rumble(low, high, duration_ms)
+            .map_err(|e| UiError::Sdl(e.to_string()))?;
+    }
+    Ok(())
+}
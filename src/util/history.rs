@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_is_grouped() {
+        let mut history = EditHistory::default();
+        history.record("", EditKind::Typing, 0);
+        history.record("a", EditKind::Typing, 100);
+        history.record("ab", EditKind::Typing, 200);
+        assert_eq!(history.undo("abc"), Some("".to_owned()));
+        assert_eq!(history.undo(""), None);
+        assert_eq!(history.redo(""), Some("abc".to_owned()));
+        assert_eq!(history.redo("abc"), None);
+    }
+
+    #[test]
+    fn groups_split() {
+        let mut history = EditHistory::default();
+        history.record("", EditKind::Typing, 0);
+        history.record("a", EditKind::Typing, 100);
+        // different kind of edit
+        history.record("ab", EditKind::Removing, 200);
+        // too long of a pause
+        history.record("a", EditKind::Typing, 200 + EDIT_GROUP_TIMEOUT_MS);
+        assert_eq!(history.undo("ac"), Some("a".to_owned()));
+        assert_eq!(history.undo("a"), Some("ab".to_owned()));
+        assert_eq!(history.undo("ab"), Some("".to_owned()));
+    }
+
+    #[test]
+    fn edit_clears_redo() {
+        let mut history = EditHistory::default();
+        history.record("", EditKind::Other, 0);
+        assert_eq!(history.undo("a"), Some("".to_owned()));
+        history.record("", EditKind::Other, 0);
+        assert_eq!(history.redo("b"), None);
+    }
+
+    #[test]
+    fn limit() {
+        let mut history = EditHistory {
+            limit: 2,
+            ..Default::default()
+        };
+        history.record("1", EditKind::Other, 0);
+        history.record("2", EditKind::Other, 0);
+        history.record("3", EditKind::Other, 0);
+        assert_eq!(history.undo("4"), Some("3".to_owned()));
+        assert_eq!(history.undo("3"), Some("2".to_owned()));
+        assert_eq!(history.undo("2"), None);
+    }
+
+    #[test]
+    fn input_history() {
+        let mut history = InputHistory::default();
+        history.submit("first");
+        history.submit("second");
+        history.submit("second"); // not repeated
+        history.submit(""); // not recorded
+        assert_eq!(history.older("draft"), Some("second".to_owned()));
+        assert_eq!(history.older("second"), Some("first".to_owned()));
+        assert_eq!(history.older("first"), None); // at oldest
+        assert_eq!(history.newer(), Some("second".to_owned()));
+        assert_eq!(history.newer(), Some("draft".to_owned())); // back to draft
+        assert_eq!(history.newer(), None);
+    }
+}
+
+/// consecutive edits of the same kind within this duration are undone together
+pub const EDIT_GROUP_TIMEOUT_MS: u32 = 1000;
+
+/// what sort of change was made to some text. consecutive edits of the same
+/// kind (other than Other) are grouped into a single undo step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    Typing,
+    Removing,
+    /// never grouped (e.g. clearing, pasting, replacing)
+    Other,
+}
+
+/// undo and redo for some text. stores whole snapshots; intended for short
+/// text (e.g. a single line input)
+pub struct EditHistory {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    /// the kind and timestamp (ms) of the most recent edit, if it can be
+    /// grouped with
+    group: Option<(EditKind, u32)>,
+    /// the max number of undo steps kept
+    pub limit: usize,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            group: None,
+            limit: 100,
+        }
+    }
+}
+
+impl EditHistory {
+    /// call before an edit is made, with the text before the edit
+    pub fn record(&mut self, before: &str, kind: EditKind, timestamp: u32) {
+        self.redo.clear();
+        let grouped = match self.group {
+            Some((group_kind, group_timestamp)) => {
+                kind != EditKind::Other
+                    && group_kind == kind
+                    && timestamp.saturating_sub(group_timestamp) < EDIT_GROUP_TIMEOUT_MS
+            }
+            None => false,
+        };
+        self.group = Some((kind, timestamp));
+        if grouped {
+            return;
+        }
+        self.undo.push(before.to_owned());
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+
+    /// the next edit starts a new undo step
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    /// given the current text, get the text to replace it with
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.to_owned());
+        self.group = None;
+        Some(previous)
+    }
+
+    /// given the current text, get the text to replace it with
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.to_owned());
+        self.group = None;
+        Some(next)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group = None;
+    }
+}
+
+/// previously submitted entries, recalled in order (e.g. for a console or chat
+/// input)
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// the entry being shown. none if not browsing
+    position: Option<usize>,
+    /// what was typed before browsing started
+    draft: String,
+    /// the max number of entries kept
+    pub limit: usize,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            position: None,
+            draft: String::new(),
+            limit: 100,
+        }
+    }
+}
+
+impl InputHistory {
+    /// record a submitted entry. empty entries and immediate repeats are
+    /// ignored. stops browsing
+    pub fn submit(&mut self, text: &str) {
+        self.position = None;
+        if text.is_empty() || self.entries.last().map(|s| s.as_str()) == Some(text) {
+            return;
+        }
+        self.entries.push(text.to_owned());
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// an older entry. given the current text, which is kept if browsing
+    /// starts here. none if there's nothing older
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let position = match self.position {
+            Some(0) => return None,
+            Some(v) => v - 1,
+            None => {
+                let last = self.entries.len().checked_sub(1)?;
+                self.draft = current.to_owned();
+                last
+            }
+        };
+        self.position = Some(position);
+        Some(self.entries[position].clone())
+    }
+
+    /// a newer entry, or the text from before browsing started. none if not
+    /// browsing
+    pub fn newer(&mut self) -> Option<String> {
+        let position = self.position?;
+        if position + 1 < self.entries.len() {
+            self.position = Some(position + 1);
+            Some(self.entries[position + 1].clone())
+        } else {
+            self.position = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+}
@@ -0,0 +1,557 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_at(clipping_rect: ClippingRect) -> HitTest {
+        HitTest::new(
+            FRect {
+                x: 10.,
+                y: 10.,
+                w: 10.,
+                h: 10.,
+            },
+            clipping_rect,
+            1,
+        )
+    }
+
+    #[test]
+    fn position_and_window() {
+        let test = test_at(ClippingRect::None);
+        assert!(test.contains(1, 10, 10));
+        assert!(test.contains(1, 19, 19));
+        assert!(!test.contains(1, 20, 20));
+        assert!(!test.contains(1, 9, 15));
+        assert!(!test.contains(2, 15, 15)); // other window
+    }
+
+    #[test]
+    fn clipping() {
+        let test = test_at(ClippingRect::Some(Rect::new(0, 0, 15, 15)));
+        assert!(test.contains(1, 14, 14));
+        assert!(!test.contains(1, 16, 16));
+        let test = test_at(ClippingRect::Zero);
+        assert!(!test.contains(1, 15, 15));
+    }
+
+    #[test]
+    fn expanded() {
+        let test = test_at(ClippingRect::Some(Rect::new(0, 0, 22, 100))).expanded(4.);
+        assert!(test.contains(1, 6, 6));
+        assert!(test.contains(1, 21, 23));
+        assert!(!test.contains(1, 5, 15));
+        // the expansion is still clipped
+        assert!(!test.contains(1, 22, 15));
+    }
+
+    #[test]
+    fn transformed() {
+        let mut test = test_at(ClippingRect::None);
+        // widget drawn at half size, offset by 100 in x
+        test.transform = HitTransform {
+            offset: (100., 0.),
+            scale: (0.5, 0.5),
+        };
+        // window (105, 5) -> widget (10, 10)
+        assert!(test.contains(1, 105, 5));
+        assert!(!test.contains(1, 15, 15));
+    }
+
+    #[test]
+    fn hover() {
+        let test = test_at(ClippingRect::None);
+        let mut hover = HoverCache::default();
+        assert!(!hover.hovered());
+        hover.mouse_moved(1, 15, 15);
+        hover.refresh(&test);
+        assert!(hover.hovered());
+        // the widget moves away from the mouse without mouse motion
+        let moved = HitTest::new(
+            FRect {
+                x: 50.,
+                y: 50.,
+                w: 10.,
+                h: 10.,
+            },
+            ClippingRect::None,
+            1,
+        );
+        hover.refresh(&moved);
+        assert!(!hover.hovered());
+    }
+
+    #[test]
+    fn enter_and_leave() {
+        let test = test_at(ClippingRect::None);
+        let mut hover = HoverCache::default();
+        let leave = [SDLEvent::new(Event::Window {
+            timestamp: 0,
+            window_id: 1,
+            win_event: sdl2::event::WindowEvent::Leave,
+        })];
+        hover.mouse_moved(1, 15, 15);
+        hover.update(&test, &[]);
+        assert!(hover.hover_entered());
+        hover.update(&test, &[]);
+        assert!(!hover.hover_entered() && !hover.hover_left());
+        // the mouse leaving the window is also leaving the widget
+        hover.update(&test, &leave);
+        assert!(hover.hover_left());
+        assert!(!hover.hovered());
+    }
+
+    #[test]
+    fn shaped() {
+        let circle = test_at(ClippingRect::None).shaped(HitShape::Circle);
+        assert!(circle.contains(1, 15, 15));
+        assert!(circle.contains(1, 10, 14));
+        // the corners are outside of the circle
+        assert!(!circle.contains(1, 10, 10));
+        assert!(!circle.contains(1, 19, 19));
+
+        let diamond = test_at(ClippingRect::None).shaped(HitShape::Polygon(Rc::new([
+            (0.5, 0.),
+            (1., 0.5),
+            (0.5, 1.),
+            (0., 0.5),
+        ])));
+        assert!(diamond.contains(1, 15, 15));
+        assert!(diamond.contains(1, 14, 11));
+        assert!(!diamond.contains(1, 10, 10));
+        assert!(!diamond.contains(1, 18, 12));
+
+        let line = HitShape::Polygon(Rc::new([(0., 0.), (1., 1.)]));
+        assert!(!test_at(ClippingRect::None).shaped(line).contains(1, 15, 15));
+    }
+
+    #[test]
+    fn texture_points() {
+        let dst = FRect {
+            x: 10.,
+            y: 10.,
+            w: 40.,
+            h: 20.,
+        };
+        let stretch = AspectRatioFailPolicy::Stretch;
+        assert_eq!(
+            texture_point((4, 4), &stretch, dst, (10., 10.)),
+            Some((0., 0.))
+        );
+        assert_eq!(
+            texture_point((4, 4), &stretch, dst, (30., 25.)),
+            Some((2., 3.))
+        );
+        assert_eq!(texture_point((4, 4), &stretch, dst, (50., 25.)), None);
+
+        // a square texture drawn at 20x20, centered; bars on the left and right
+        let zoom_out = AspectRatioFailPolicy::ZoomOut((0.5, 0.5));
+        assert_eq!(texture_point((4, 4), &zoom_out, dst, (15., 15.)), None);
+        assert_eq!(
+            texture_point((4, 4), &zoom_out, dst, (20., 10.)),
+            Some((0., 0.))
+        );
+        assert_eq!(
+            texture_point((4, 4), &zoom_out, dst, (39., 29.)),
+            Some((3.8, 3.8))
+        );
+
+        // a square texture drawn at 40x40, centered; the top and bottom are cut off
+        let zoom_in = AspectRatioFailPolicy::ZoomIn((0.5, 0.5));
+        assert_eq!(
+            texture_point((4, 4), &zoom_in, dst, (10., 10.)),
+            Some((0., 1.))
+        );
+        assert_eq!(texture_point((4, 4), &zoom_in, dst, (10., 9.)), None);
+
+        assert_eq!(texture_point((0, 4), &stretch, dst, (20., 20.)), None);
+    }
+
+    #[test]
+    fn alpha_mask() {
+        let mask = AlphaMask {
+            width: 2,
+            height: 1,
+            opaque: vec![false, true],
+        };
+        assert!(!mask.opaque(0, 0));
+        assert!(mask.opaque(1, 0));
+        assert!(!mask.opaque(2, 0));
+        assert!(!mask.opaque(-1, 0));
+    }
+}
+
+use std::rc::Rc;
+
+use sdl2::{
+    event::Event, pixels::PixelFormatEnum, rect::Rect, render::ClippingRect, surface::Surface,
+};
+
+use crate::widget::{texture::AspectRatioFailPolicy, SDLEvent, WidgetUpdateEvent};
+
+use super::{clip, error::UiResult, rect::FRect};
+
+/// maps a point from window coordinates to a widget's coordinates, for widgets
+/// which are drawn offset or scaled (e.g. into a texture which is then drawn
+/// somewhere else). window = offset + widget * scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitTransform {
+    pub offset: (f32, f32),
+    pub scale: (f32, f32),
+}
+
+impl Default for HitTransform {
+    fn default() -> Self {
+        Self {
+            offset: (0., 0.),
+            scale: (1., 1.),
+        }
+    }
+}
+
+impl HitTransform {
+    pub fn apply(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        if self.scale.0 == 0. || self.scale.1 == 0. {
+            return None;
+        }
+        Some((
+            ((x as f32 - self.offset.0) / self.scale.0).floor() as i32,
+            ((y as f32 - self.offset.1) / self.scale.1).floor() as i32,
+        ))
+    }
+}
+
+/// the part of a widget's position which can be hit, for widgets which aren't
+/// rectangular (e.g. round buttons, or the wedges of a radial menu). layout
+/// still uses the position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum HitShape {
+    /// all of the position
+    #[default]
+    Rect,
+    /// the largest circle centered in the position
+    Circle,
+    /// the points, from 0 to 1 across the position's width and height
+    /// respectively. e.g. a diamond is [(0.5, 0.), (1., 0.5), (0.5, 1.), (0.,
+    /// 0.5)]. self intersecting polygons use the even-odd rule. shared, since
+    /// the shape is cloned into each update's hit test
+    Polygon(Rc<[(f32, f32)]>),
+}
+
+impl HitShape {
+    /// is a point within the shape, when the shape is over an area
+    pub fn contains(&self, area: FRect, (x, y): (f32, f32)) -> bool {
+        if !area.contains_point((x, y)) {
+            return false;
+        }
+        match self {
+            HitShape::Rect => true,
+            HitShape::Circle => {
+                let radius = area.w.min(area.h) / 2.;
+                let (center_x, center_y) = area.center();
+                let dx = x - center_x;
+                let dy = y - center_y;
+                dx * dx + dy * dy <= radius * radius
+            }
+            HitShape::Polygon(points) => {
+                if points.len() < 3 {
+                    return false;
+                }
+                let point_at = |(u, v): (f32, f32)| (area.x + u * area.w, area.y + v * area.h);
+                // count the edges crossed by a ray going right from the point
+                let mut inside = false;
+                let mut previous = point_at(points[points.len() - 1]);
+                for &point in points.iter() {
+                    let current = point_at(point);
+                    if (current.1 > y) != (previous.1 > y) {
+                        // div guarded by the y values being on either side
+                        let crossing_x = current.0
+                            + (y - current.1) * (previous.0 - current.0) / (previous.1 - current.1);
+                        if x < crossing_x {
+                            inside = !inside;
+                        }
+                    }
+                    previous = current;
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// is a mouse event over a widget: in the right window, within the clipping
+/// rect, and within the widget's position
+#[derive(Debug, Clone)]
+pub struct HitTest {
+    pub position: FRect,
+    /// in window coordinates (before the transform)
+    pub clipping_rect: ClippingRect,
+    pub window_id: u32,
+    pub transform: HitTransform,
+    /// the position is extended by this much on each side (in the widget's
+    /// coordinates), so small widgets are easier to hit (e.g. by touch). this
+    /// doesn't affect layout, and the clipping rect still applies
+    pub expansion: f32,
+    /// the part of the (expanded) position which can be hit
+    pub shape: HitShape,
+}
+
+impl HitTest {
+    pub fn new(position: FRect, clipping_rect: ClippingRect, window_id: u32) -> Self {
+        Self {
+            position,
+            clipping_rect,
+            window_id,
+            transform: Default::default(),
+            expansion: 0.,
+            shape: HitShape::Rect,
+        }
+    }
+
+    pub fn expanded(mut self, expansion: f32) -> Self {
+        self.expansion = expansion;
+        self
+    }
+
+    pub fn shaped(mut self, shape: HitShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn from_event(event: &WidgetUpdateEvent) -> Self {
+        Self::new(event.position, event.clipping_rect, event.window_id)
+    }
+
+    /// is a point (in window coordinates) over the widget
+    pub fn contains(&self, window_id: u32, x: i32, y: i32) -> bool {
+        if window_id != self.window_id {
+            return false; // not for me!
+        }
+        if !clip::contains_point(self.clipping_rect, (x, y)) {
+            return false;
+        }
+        let area = self.position.outset(self.expansion);
+        let position: Option<Rect> = area.into();
+        let position = match position {
+            Some(v) => v,
+            None => return false,
+        };
+        let point = match self.transform.apply(x, y) {
+            Some(v) => v,
+            None => return false,
+        };
+        if !position.contains_point(point) {
+            return false;
+        }
+        // against the center of the pixel
+        matches!(self.shape, HitShape::Rect)
+            || self
+                .shape
+                .contains(area, (point.0 as f32 + 0.5, point.1 as f32 + 0.5))
+    }
+
+    /// if the event is a mouse event over the widget, the mouse position (in
+    /// window coordinates)
+    pub fn hit(&self, e: &Event) -> Option<(i32, i32)> {
+        let (window_id, x, y) = match *e {
+            Event::MouseMotion {
+                window_id, x, y, ..
+            }
+            | Event::MouseButtonDown {
+                window_id, x, y, ..
+            }
+            | Event::MouseButtonUp {
+                window_id, x, y, ..
+            } => (window_id, x, y),
+            Event::MouseWheel {
+                window_id,
+                mouse_x,
+                mouse_y,
+                ..
+            } => (window_id, mouse_x, mouse_y),
+            _ => return None,
+        };
+        if self.contains(window_id, x, y) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn was_hit(&self, e: &Event) -> bool {
+        self.hit(e).is_some()
+    }
+}
+
+/// remembers where the mouse is, so a widget can know if it's hovered even on
+/// frames without mouse motion (e.g. it moved under the mouse). also gives
+/// when the hover starts and stops, for hover sounds or styles
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HoverCache {
+    /// window id and position of the mouse. None if it left the window
+    mouse: Option<(u32, i32, i32)>,
+    hovered: bool,
+    /// hovered as of the end of the previous update
+    hovered_previous_update: bool,
+}
+
+impl HoverCache {
+    /// call each update, with all the events (consumed or not)
+    pub fn update(&mut self, test: &HitTest, events: &[SDLEvent]) {
+        self.hovered_previous_update = self.hovered;
+        for e in events.iter() {
+            match e.e {
+                Event::MouseMotion {
+                    window_id, x, y, ..
+                } => self.mouse_moved(window_id, x, y),
+                Event::Window {
+                    window_id,
+                    win_event: sdl2::event::WindowEvent::Leave,
+                    ..
+                } if self.mouse.map(|m| m.0 == window_id).unwrap_or(false) => {
+                    self.mouse = None;
+                }
+                _ => {}
+            }
+        }
+        self.refresh(test);
+    }
+
+    pub fn mouse_moved(&mut self, window_id: u32, x: i32, y: i32) {
+        self.mouse = Some((window_id, x, y));
+    }
+
+    /// recompute hovered against a (possibly moved) widget
+    pub fn refresh(&mut self, test: &HitTest) {
+        self.hovered = self
+            .mouse
+            .map(|(window_id, x, y)| test.contains(window_id, x, y))
+            .unwrap_or(false);
+    }
+
+    pub fn hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// the mouse came over the widget since the previous update
+    pub fn hover_entered(&self) -> bool {
+        self.hovered && !self.hovered_previous_update
+    }
+
+    /// the mouse is no longer over the widget since the previous update. this
+    /// includes the widget moving away, or the mouse leaving the window
+    pub fn hover_left(&self) -> bool {
+        !self.hovered && self.hovered_previous_update
+    }
+
+    /// where the mouse is (in window coordinates), if it's over the widget
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.mouse.filter(|_| self.hovered).map(|(_, x, y)| (x, y))
+    }
+}
+
+/// which pixels of an image are opaque, for hit testing against the image's
+/// shape instead of its bounding rect (e.g. a round button). made once from
+/// the surface the texture was created from
+#[derive(Debug, Clone)]
+pub struct AlphaMask {
+    width: u32,
+    height: u32,
+    /// row major
+    opaque: Vec<bool>,
+}
+
+impl AlphaMask {
+    /// a pixel is opaque if its alpha is above the threshold
+    pub fn from_surface(surface: &Surface, threshold: u8) -> UiResult<Self> {
+        let surface = surface.convert_format(PixelFormatEnum::RGBA32)?;
+        let (width, height) = surface.size();
+        let pitch = surface.pitch() as usize;
+        let mut opaque: Vec<bool> = Vec::with_capacity(width as usize * height as usize);
+        surface.with_lock(|pixels| {
+            for row in pixels.chunks(pitch).take(height as usize) {
+                // RGBA32 is byte order, so alpha is the last of each pixel
+                for pixel in row[..width as usize * 4].chunks_exact(4) {
+                    opaque.push(pixel[3] > threshold);
+                }
+            }
+        });
+        Ok(Self {
+            width,
+            height,
+            opaque,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// is a pixel opaque. false outside of the mask
+    pub fn opaque(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return false;
+        }
+        self.opaque[y as usize * self.width as usize + x as usize]
+    }
+
+    /// is a point (in window coordinates) over an opaque pixel of the image, as
+    /// drawn by the texture widget. src and the policy are the same as the
+    /// texture's; none means the entire mask
+    pub fn opaque_at(
+        &self,
+        src: Option<Rect>,
+        aspect_ratio_fail_policy: &AspectRatioFailPolicy,
+        dst: FRect,
+        x: i32,
+        y: i32,
+    ) -> bool {
+        let src = src.unwrap_or_else(|| Rect::new(0, 0, self.width, self.height));
+        match texture_point(
+            (src.width(), src.height()),
+            aspect_ratio_fail_policy,
+            dst,
+            (x as f32, y as f32),
+        ) {
+            Some((u, v)) => self.opaque(src.x() + u.floor() as i32, src.y() + v.floor() as i32),
+            None => false,
+        }
+    }
+}
+
+/// maps a point within dst to the point of the source it shows, for a source
+/// of some size drawn with the policy (see texture_draw). none if the point
+/// isn't on the drawn source
+fn texture_point(
+    (src_w, src_h): (u32, u32),
+    aspect_ratio_fail_policy: &AspectRatioFailPolicy,
+    dst: FRect,
+    (x, y): (f32, f32),
+) -> Option<(f32, f32)> {
+    if src_w == 0 || src_h == 0 || dst.w <= 0. || dst.h <= 0. {
+        return None; // guard div
+    }
+    if !dst.contains_point((x, y)) {
+        return None;
+    }
+    let src_w = src_w as f32;
+    let src_h = src_h as f32;
+    let (scale_x, scale_y, (zoom_x, zoom_y)) = match aspect_ratio_fail_policy {
+        AspectRatioFailPolicy::Stretch => (dst.w / src_w, dst.h / src_h, (0., 0.)),
+        AspectRatioFailPolicy::ZoomOut(zoom) => {
+            let scale = (dst.w / src_w).min(dst.h / src_h);
+            (scale, scale, *zoom)
+        }
+        AspectRatioFailPolicy::ZoomIn(zoom) => {
+            let scale = (dst.w / src_w).max(dst.h / src_h);
+            (scale, scale, *zoom)
+        }
+    };
+    let drawn_x = dst.x + (dst.w - src_w * scale_x) * zoom_x;
+    let drawn_y = dst.y + (dst.h - src_h * scale_y) * zoom_y;
+    let u = (x - drawn_x) / scale_x;
+    let v = (y - drawn_y) / scale_y;
+    if u < 0. || v < 0. || u >= src_w || v >= src_h {
+        return None;
+    }
+    Some((u, v))
+}
@@ -0,0 +1,95 @@
+//! development mode for tuning styles while the app runs. style parameters
+//! (colors, border widths, point sizes, spacing, etc.) are read from a RON file
+//! which is polled for changes:
+//!
+//! ```ron
+//! (
+//!     colors: {"focus": (118, 73, 206, 255)},
+//!     lengths: {"title_min_h": 50, "border_width": 3},
+//! )
+//! ```
+//!
+//! styles can hold a reference to the HotStyle and read from it when drawing.
+//! when the file changes, all texture caches are invalidated so the new values
+//! are used immediately. sizing fields on widgets (e.g. a label's min_h) aren't
+//! read from a style, so the app should re-apply them when poll returns true
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::HashMap,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use sdl2::pixels::Color;
+use serde::Deserialize;
+
+use super::{error::UiResult, render::invalidate_texture_caches};
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleParams {
+    /// RGBA, by name
+    pub colors: HashMap<String, (u8, u8, u8, u8)>,
+    /// any length or size, by name
+    pub lengths: HashMap<String, f32>,
+}
+
+pub struct HotStyle {
+    path: PathBuf,
+    /// modification time of the file when it was last read
+    modified: Cell<Option<SystemTime>>,
+    params: RefCell<StyleParams>,
+}
+
+impl HotStyle {
+    /// the file isn't read until the first call to poll
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            modified: Default::default(),
+            params: Default::default(),
+        }
+    }
+
+    /// call once per frame (before update_gui). reloads the file if it was
+    /// modified since the last read. returns true if new values were loaded.
+    ///
+    /// a file that fails to parse is reported once, and the previous values
+    /// are kept until the file is modified again
+    pub fn poll(&self) -> UiResult<bool> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .map_err(|e| e.to_string())?;
+        if self.modified.get() == Some(modified) {
+            return Ok(false);
+        }
+        self.modified.set(Some(modified));
+
+        let contents = std::fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        let params: StyleParams = ron::from_str(&contents).map_err(|e| e.to_string())?;
+        *self.params.borrow_mut() = params;
+        invalidate_texture_caches();
+        Ok(true)
+    }
+
+    pub fn params(&self) -> Ref<'_, StyleParams> {
+        self.params.borrow()
+    }
+
+    pub fn color(&self, name: &str, default: Color) -> Color {
+        match self.params.borrow().colors.get(name) {
+            Some(&(r, g, b, a)) => Color::RGBA(r, g, b, a),
+            None => default,
+        }
+    }
+
+    pub fn length(&self, name: &str, default: f32) -> f32 {
+        self.params
+            .borrow()
+            .lengths
+            .get(name)
+            .copied()
+            .unwrap_or(default)
+    }
+}
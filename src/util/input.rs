@@ -0,0 +1,203 @@
+//! the state of the keyboard modifiers and mouse, as of the end of a frame's
+//! events. it's followed from the events given to update_gui, so widgets can
+//! check it without tracking the event stream themselves (e.g. if the left
+//! button is held, on a frame without any mouse events)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_events() {
+        let mut input = InputSnapshot::default();
+        input.apply(&Event::MouseButtonDown {
+            timestamp: 0,
+            window_id: 1,
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 10,
+            y: 20,
+        });
+        input.apply(&Event::KeyDown {
+            timestamp: 0,
+            window_id: 1,
+            keycode: None,
+            scancode: None,
+            keymod: Mod::LSHIFTMOD,
+            repeat: false,
+        });
+        assert!(input.button_down(MouseButton::Left));
+        assert!(!input.button_down(MouseButton::Right));
+        assert_eq!(input.mouse, Some((1, 10, 20)));
+        assert!(input.shift());
+        assert!(!input.ctrl());
+
+        input.apply(&Event::MouseButtonUp {
+            timestamp: 0,
+            window_id: 1,
+            which: 0,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 15,
+            y: 20,
+        });
+        input.apply(&Event::Window {
+            timestamp: 0,
+            window_id: 1,
+            win_event: WindowEvent::FocusLost,
+        });
+        assert!(!input.button_down(MouseButton::Left));
+        assert_eq!(input.mouse, Some((1, 15, 20)));
+        assert!(!input.shift());
+    }
+}
+
+use std::cell::Cell;
+
+use sdl2::{
+    event::{Event, WindowEvent},
+    keyboard::Mod,
+    mouse::MouseButton,
+};
+
+use crate::widget::SDLEvent;
+
+thread_local! {
+    static INPUT: Cell<InputSnapshot> = Cell::new(InputSnapshot::default());
+}
+
+/// the input state as of the most recent update_gui. see
+/// WidgetUpdateEvent::input
+pub fn input_snapshot() -> InputSnapshot {
+    INPUT.with(|input| input.get())
+}
+
+/// follow a frame's events. called once per update_gui, after the mouse
+/// coordinates are transformed
+pub(crate) fn update_input_snapshot(events: &[SDLEvent]) -> InputSnapshot {
+    INPUT.with(|input| {
+        let mut snapshot = input.get();
+        for sdl_event in events.iter() {
+            snapshot.apply(&sdl_event.e);
+        }
+        input.set(snapshot);
+        snapshot
+    })
+}
+
+fn button_bit(button: MouseButton) -> u8 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Middle => 1 << 1,
+        MouseButton::Right => 1 << 2,
+        MouseButton::X1 => 1 << 3,
+        MouseButton::X2 => 1 << 4,
+        MouseButton::Unknown => 0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputSnapshot {
+    /// modifier keys held, as of the most recent key event. cleared when the
+    /// window loses focus
+    pub keymod: Mod,
+    /// the window the mouse is in, and its position. none if the mouse left
+    /// the window (or hasn't been seen yet)
+    pub mouse: Option<(u32, i32, i32)>,
+    /// a bit per held mouse button
+    buttons: u8,
+}
+
+impl Default for InputSnapshot {
+    fn default() -> Self {
+        Self {
+            keymod: Mod::NOMOD,
+            mouse: None,
+            buttons: 0,
+        }
+    }
+}
+
+impl InputSnapshot {
+    pub fn button_down(&self, button: MouseButton) -> bool {
+        self.buttons & button_bit(button) != 0
+    }
+
+    pub fn shift(&self) -> bool {
+        self.keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD)
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+    }
+
+    pub fn alt(&self) -> bool {
+        self.keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+    }
+
+    /// the mouse position, if it's in some window
+    pub fn mouse_in(&self, window_id: u32) -> Option<(i32, i32)> {
+        match self.mouse {
+            Some((mouse_window_id, x, y)) if mouse_window_id == window_id => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    fn apply(&mut self, event: &Event) {
+        match event {
+            Event::KeyDown { keymod, .. } | Event::KeyUp { keymod, .. } => self.keymod = *keymod,
+            Event::MouseMotion {
+                window_id,
+                mousestate,
+                x,
+                y,
+                ..
+            } => {
+                self.mouse = Some((*window_id, *x, *y));
+                self.buttons = [
+                    MouseButton::Left,
+                    MouseButton::Middle,
+                    MouseButton::Right,
+                    MouseButton::X1,
+                    MouseButton::X2,
+                ]
+                .into_iter()
+                .filter(|button| mousestate.is_mouse_button_pressed(*button))
+                .fold(0, |buttons, button| buttons | button_bit(button));
+            }
+            Event::MouseButtonDown {
+                window_id,
+                mouse_btn,
+                x,
+                y,
+                ..
+            } => {
+                self.mouse = Some((*window_id, *x, *y));
+                self.buttons |= button_bit(*mouse_btn);
+            }
+            Event::MouseButtonUp {
+                window_id,
+                mouse_btn,
+                x,
+                y,
+                ..
+            } => {
+                self.mouse = Some((*window_id, *x, *y));
+                self.buttons &= !button_bit(*mouse_btn);
+            }
+            Event::Window {
+                window_id,
+                win_event,
+                ..
+            } => match win_event {
+                WindowEvent::Leave if self.mouse.map(|mouse| mouse.0) == Some(*window_id) => {
+                    self.mouse = None;
+                }
+                WindowEvent::FocusLost => self.keymod = Mod::NOMOD,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
@@ -1,3 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops() {
+        assert_eq!((MinLen::px(10.) + MinLen::px(5.)).0, 15.);
+        assert_eq!((MinLen::px(10.) * 2.).0, 20.);
+        assert_eq!((MaxLen::px(10.) + MaxLen::LAX).0, MaxLen::LAX.0);
+        assert_eq!((MaxLen::LAX * 0.5).0, MaxLen::LAX.0);
+        assert_eq!((MaxLen::px(10.) * 0.5).0, 5.);
+    }
+
+    #[test]
+    fn percent_of_parent() {
+        let min = MinLen::percent_of_parent(0.25);
+        let max = MaxLen::percent_of_parent(0.5);
+        assert_eq!(min.resolve(Some(200.)).map(|l| l.0), Some(50.));
+        assert_eq!(max.resolve(Some(200.)).map(|l| l.0), Some(100.));
+        // unknown parent is lax
+        assert_eq!(min.resolve(None).map(|l| l.0), Some(MinLen::LAX.0));
+        assert_eq!(max.resolve(None).map(|l| l.0), Some(MaxLen::LAX.0));
+        assert!(MinLenPolicy::Children.resolve(Some(200.)).is_none());
+
+        assert_eq!(parent_size(), None);
+        with_parent_size((10., 20.), || {
+            assert_eq!(parent_size(), Some((10., 20.)));
+            with_parent_size((1., 2.), || assert_eq!(parent_size(), Some((1., 2.))));
+            assert_eq!(parent_size(), Some((10., 20.)));
+        });
+        assert_eq!(parent_size(), None);
+    }
+}
+
+use std::{
+    cell::Cell,
+    ops::{Add, Mul},
+};
+
 /// if a minimum length can't be respected, should excess length be pushed in the
 /// positive or negative direction past the parent's boundary.
 ///
@@ -52,6 +91,16 @@ impl From<f32> for MinLen {
 }
 
 impl MinLen {
+    /// a length in logical pixels. policies apply the ui scale
+    pub fn px(len: f32) -> MinLen {
+        MinLen(len)
+    }
+
+    /// a portion of the parent's length (e.g. 0.5 is half). see parent_size
+    pub fn percent_of_parent(portion: f32) -> MinLenPolicy {
+        MinLenPolicy::PercentOfParent(portion)
+    }
+
     /// returns the strictest of two minimum lengths
     pub fn strictest(self, other: MinLen) -> MinLen {
         if self.0 > other.0 {
@@ -65,6 +114,11 @@ impl MinLen {
         MinLen(self.0 + other.0)
     }
 
+    /// apply the global ui scale. for literal lengths stated in logical pixels
+    pub fn scaled(self) -> MinLen {
+        MinLen(crate::util::scale::scaled(self.0))
+    }
+
     /// the least strict value possible
     pub const LAX: MinLen = MinLen(0.);
 }
@@ -75,6 +129,23 @@ impl Default for MinLen {
     }
 }
 
+impl Add for MinLen {
+    type Output = MinLen;
+
+    /// see combined
+    fn add(self, other: MinLen) -> MinLen {
+        self.combined(other)
+    }
+}
+
+impl Mul<f32> for MinLen {
+    type Output = MinLen;
+
+    fn mul(self, factor: f32) -> MinLen {
+        MinLen(self.0 * factor)
+    }
+}
+
 /// the maximum length of a widget. has whole number resolution
 #[derive(Debug, Clone, Copy)]
 pub struct MaxLen(pub f32);
@@ -86,6 +157,16 @@ impl From<f32> for MaxLen {
 }
 
 impl MaxLen {
+    /// a length in logical pixels. policies apply the ui scale
+    pub fn px(len: f32) -> MaxLen {
+        MaxLen(len)
+    }
+
+    /// a portion of the parent's length (e.g. 0.5 is half). see parent_size
+    pub fn percent_of_parent(portion: f32) -> MaxLenPolicy {
+        MaxLenPolicy::PercentOfParent(portion)
+    }
+
     /// returns the strictest of two maximum lengths
     pub fn strictest(self, other: MaxLen) -> MaxLen {
         if self.0 < other.0 {
@@ -104,8 +185,19 @@ impl MaxLen {
         MaxLen(v)
     }
 
+    /// apply the global ui scale. for literal lengths stated in logical pixels
+    pub fn scaled(self) -> MaxLen {
+        if self.0 == f32::MAX {
+            return self; // don't overflow lax
+        }
+        MaxLen(crate::util::scale::scaled(self.0))
+    }
+
     /// the least strict value possible
     pub const LAX: MaxLen = MaxLen(f32::MAX);
+
+    /// the strictest value possible. e.g. the start of a sum of lengths
+    pub const ZERO: MaxLen = MaxLen(0.);
 }
 
 impl Default for MaxLen {
@@ -114,6 +206,27 @@ impl Default for MaxLen {
     }
 }
 
+impl Add for MaxLen {
+    type Output = MaxLen;
+
+    /// see combined
+    fn add(self, other: MaxLen) -> MaxLen {
+        self.combined(other)
+    }
+}
+
+impl Mul<f32> for MaxLen {
+    type Output = MaxLen;
+
+    /// lax stays lax
+    fn mul(self, factor: f32) -> MaxLen {
+        if self.0 == f32::MAX {
+            return self;
+        }
+        MaxLen(self.0 * factor)
+    }
+}
+
 pub fn clamp(mut len: f32, min: MinLen, max: MaxLen) -> f32 {
     if len > max.0 {
         len = max.0;
@@ -190,6 +303,30 @@ pub enum MinLenPolicy {
     Children,
     /// min len is plainly stated, ignoring the underlying thing's dimensions
     Literal(MinLen),
+    /// a portion of the parent's length, resolved during layout. see
+    /// parent_size
+    PercentOfParent(f32),
+}
+
+impl From<MinLen> for MinLenPolicy {
+    fn from(value: MinLen) -> Self {
+        MinLenPolicy::Literal(value)
+    }
+}
+
+impl MinLenPolicy {
+    /// the stated length, with the ui scale applied. none if it's inherited
+    /// from the children. a percentage of an unknown parent is lax
+    pub fn resolve(&self, parent_len: Option<f32>) -> Option<MinLen> {
+        match self {
+            MinLenPolicy::Children => None,
+            MinLenPolicy::Literal(min_len) => Some(min_len.scaled()),
+            MinLenPolicy::PercentOfParent(portion) => Some(match parent_len {
+                Some(parent_len) => MinLen(parent_len * portion),
+                None => MinLen::LAX,
+            }),
+        }
+    }
 }
 
 
@@ -201,6 +338,60 @@ pub enum MaxLenPolicy {
     Children,
     /// max len is plainly stated, ignoring the underlying thing's dimensions
     Literal(MaxLen),
+    /// a portion of the parent's length, resolved during layout. see
+    /// parent_size
+    PercentOfParent(f32),
+}
+
+impl From<MaxLen> for MaxLenPolicy {
+    fn from(value: MaxLen) -> Self {
+        MaxLenPolicy::Literal(value)
+    }
+}
+
+impl MaxLenPolicy {
+    /// the stated length, with the ui scale applied. none if it's inherited
+    /// from the children. a percentage of an unknown parent is lax
+    pub fn resolve(&self, parent_len: Option<f32>) -> Option<MaxLen> {
+        match self {
+            MaxLenPolicy::Children => None,
+            MaxLenPolicy::Literal(max_len) => Some(max_len.scaled()),
+            MaxLenPolicy::PercentOfParent(portion) => Some(match parent_len {
+                Some(parent_len) => MaxLen(parent_len * portion),
+                None => MaxLen::LAX,
+            }),
+        }
+    }
+}
+
+// the size of the parent whose children are being sized. set by layouts
+thread_local! {
+    static PARENT_SIZE: Cell<Option<(f32, f32)>> = const { Cell::new(None) };
+}
+
+/// the size of the parent being laid out, which percentages of the parent are
+/// resolved against. this is the nearest enclosing layout (or the window at
+/// the top), while it sizes its children. none outside of that
+pub fn parent_size() -> Option<(f32, f32)> {
+    PARENT_SIZE.with(|p| p.get())
+}
+
+/// the parent's width, see parent_size
+pub fn parent_width() -> Option<f32> {
+    parent_size().map(|size| size.0)
+}
+
+/// the parent's height, see parent_size
+pub fn parent_height() -> Option<f32> {
+    parent_size().map(|size| size.1)
+}
+
+/// run something with the parent size set, e.g. while sizing children
+pub fn with_parent_size<R>(size: (f32, f32), f: impl FnOnce() -> R) -> R {
+    let previous = PARENT_SIZE.with(|p| p.replace(Some(size)));
+    let r = f();
+    PARENT_SIZE.with(|p| p.set(previous));
+    r
 }
 
 
@@ -0,0 +1,218 @@
+//! input masks, for text which follows a pattern (e.g. dates or phone
+//! numbers). the text holds what's been typed so far, including separators
+//! (e.g. "12/0"), and is shown with placeholders for the remaining slots
+//! (e.g. "12/0_/____")
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(mask: &InputMask, typed: &str) -> String {
+        let mut text = String::new();
+        mask.insert(&mut text, typed);
+        text
+    }
+
+    #[test]
+    fn parse() {
+        assert!(InputMask::new("##/##/####").is_ok());
+        assert!(InputMask::new("--").is_err()); // nothing to type
+        assert!(InputMask::new("#\\").is_err());
+        let mask = InputMask::new("\\##").unwrap();
+        assert_eq!(typed(&mask, "5"), "#5");
+    }
+
+    #[test]
+    fn separators_inserted() {
+        let mask = InputMask::new("##/##/####").unwrap();
+        assert_eq!(typed(&mask, "12"), "12");
+        assert_eq!(typed(&mask, "120"), "12/0");
+        // typing the separator is also accepted
+        assert_eq!(typed(&mask, "12/0"), "12/0");
+        assert_eq!(typed(&mask, "1a2b/"), "12/");
+        assert_eq!(typed(&mask, "1231199912"), "12/31/1999");
+        let mask = InputMask::new("(###) ###-####").unwrap();
+        assert_eq!(typed(&mask, "5551234"), "(555) 123-4");
+    }
+
+    #[test]
+    fn classes() {
+        let mask = InputMask::new("A*?").unwrap();
+        assert_eq!(typed(&mask, "1a2-"), "a2-");
+        assert_eq!(typed(&mask, "ab"), "ab");
+    }
+
+    #[test]
+    fn pop_and_complete() {
+        let mask = InputMask::new("(###) ###").unwrap();
+        let mut text = typed(&mask, "5551");
+        assert_eq!(text, "(555) 1");
+        mask.pop(&mut text);
+        assert_eq!(text, "(555");
+        assert!(!mask.is_complete(&text));
+        assert_eq!(mask.display(&text), "(555) ___");
+        mask.pop(&mut text);
+        mask.pop(&mut text);
+        mask.pop(&mut text);
+        assert_eq!(text, "");
+        mask.insert(&mut text, "123456");
+        assert!(mask.is_complete(&text));
+        assert_eq!(mask.raw(&text), "123456");
+    }
+}
+
+use crate::util::error::{UiError, UiResult};
+
+/// a position in an input mask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskSlot {
+    /// 0-9
+    Digit,
+    Letter,
+    /// a letter or digit
+    Alphanumeric,
+    Any,
+    /// a separator, inserted automatically
+    Literal(char),
+}
+
+impl MaskSlot {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_alphabetic(),
+            MaskSlot::Alphanumeric => c.is_alphanumeric(),
+            MaskSlot::Any => !c.is_control(),
+            MaskSlot::Literal(literal) => *literal == c,
+        }
+    }
+}
+
+/// restricts text to a pattern. see InputMask::new
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+    /// shown in slots which haven't been typed yet
+    pub placeholder: char,
+}
+
+impl InputMask {
+    /// in the pattern, `#` is a digit, `A` is a letter, `*` is a letter or
+    /// digit, and `?` is any character. anything else is a literal separator.
+    /// `\` makes the next character literal (e.g. `\#`)
+    pub fn new(pattern: &str) -> UiResult<Self> {
+        let mut slots = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            slots.push(match c {
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Letter,
+                '*' => MaskSlot::Alphanumeric,
+                '?' => MaskSlot::Any,
+                '\\' => match chars.next() {
+                    Some(c) => MaskSlot::Literal(c),
+                    None => {
+                        return Err(UiError::Custom(format!(
+                            "mask \"{}\" ends with an escape",
+                            pattern
+                        )))
+                    }
+                },
+                c => MaskSlot::Literal(c),
+            });
+        }
+        if !slots
+            .iter()
+            .any(|slot| !matches!(slot, MaskSlot::Literal(_)))
+        {
+            return Err(UiError::Custom(format!(
+                "mask \"{}\" has nothing to type",
+                pattern
+            )));
+        }
+        Ok(Self {
+            slots,
+            placeholder: '_',
+        })
+    }
+
+    pub fn slots(&self) -> &[MaskSlot] {
+        &self.slots
+    }
+
+    /// add typed characters to the end of the text. separators are inserted
+    /// before the next typed character. characters which don't fit are
+    /// skipped. returns if the text changed
+    pub fn insert(&self, text: &mut String, typed: &str) -> bool {
+        let mut filled = text.chars().count();
+        let mut changed = false;
+        for c in typed.chars() {
+            // a typed separator is accepted as is
+            if let Some(slot @ MaskSlot::Literal(_)) = self.slots.get(filled) {
+                if slot.accepts(c) {
+                    text.push(c);
+                    filled += 1;
+                    changed = true;
+                    continue;
+                }
+            }
+            let mut slot_index = filled;
+            while let Some(MaskSlot::Literal(_)) = self.slots.get(slot_index) {
+                slot_index += 1;
+            }
+            match self.slots.get(slot_index) {
+                Some(slot) if slot.accepts(c) => {}
+                _ => continue,
+            }
+            for slot in self.slots[filled..slot_index].iter() {
+                if let MaskSlot::Literal(literal) = slot {
+                    text.push(*literal);
+                }
+            }
+            text.push(c);
+            filled = slot_index + 1;
+            changed = true;
+        }
+        changed
+    }
+
+    /// remove the last typed character, and the separators before it
+    pub fn pop(&self, text: &mut String) {
+        text.pop();
+        while let Some(c) = text.chars().last() {
+            match self.slots.get(text.chars().count() - 1) {
+                Some(MaskSlot::Literal(literal)) if *literal == c => {
+                    text.pop();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// the text, with placeholders and separators for the remaining slots
+    pub fn display(&self, text: &str) -> String {
+        let filled = text.chars().count();
+        let mut out = text.to_owned();
+        for slot in self.slots.iter().skip(filled) {
+            out.push(match slot {
+                MaskSlot::Literal(literal) => *literal,
+                _ => self.placeholder,
+            });
+        }
+        out
+    }
+
+    /// if every slot is filled
+    pub fn is_complete(&self, text: &str) -> bool {
+        text.chars().count() >= self.slots.len()
+    }
+
+    /// the typed characters, without separators
+    pub fn raw(&self, text: &str) -> String {
+        text.chars()
+            .zip(self.slots.iter())
+            .filter(|(_, slot)| !matches!(slot, MaskSlot::Literal(_)))
+            .map(|(c, _)| c)
+            .collect()
+    }
+}
@@ -1,9 +1,51 @@
+pub mod accessibility;
+pub mod animation;
+pub mod clip;
+pub mod clipboard;
+pub mod coalesce;
+pub mod cursor;
+pub mod dirty;
+pub mod draw_hooks;
+pub mod embed;
+pub mod emoji;
+pub mod error;
+pub mod export;
+pub mod feedback;
 pub mod focus;
+pub mod hit;
+pub mod history;
+pub mod input;
 pub mod length;
+pub mod mask;
+pub mod observable;
+pub mod perf;
 pub mod rect;
 pub mod render;
 pub mod rust;
+pub mod scale;
+pub mod selection;
+pub mod settings;
+pub mod shared;
 pub(crate) mod shuffle;
+pub mod spellcheck;
+pub mod timer;
+pub mod wheel;
+pub mod word;
+
+#[cfg(feature = "declarative")]
+pub mod declarative;
+
+#[cfg(feature = "event-trace")]
+pub mod event_trace;
+
+#[cfg(feature = "controller")]
+pub mod haptics;
+
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+
+#[cfg(feature = "shaping")]
+pub mod shaping;
 
 // this module is not disabled when sdl-ttf is disabled - the traits are still
 // valid and can be implemented without sdl2-ttf
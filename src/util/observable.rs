@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn set_notifies_and_bumps_version() {
+        let observable = Observable::new(1);
+        let seen = Rc::new(Cell::new(0));
+        let seen_inner = seen.clone();
+        let id = observable.subscribe(move |v| seen_inner.set(*v));
+        assert_eq!(observable.version(), 0);
+        observable.set(2);
+        assert_eq!(observable.version(), 1);
+        assert_eq!(seen.get(), 2);
+        observable.update(|v| *v += 1);
+        assert_eq!(seen.get(), 3);
+        assert!(observable.unsubscribe(id));
+        assert!(!observable.unsubscribe(id));
+        observable.set(10);
+        assert_eq!(seen.get(), 3);
+        assert_eq!(observable.get(), 10);
+    }
+
+    #[test]
+    fn subscribe_during_notify() {
+        let observable = Rc::new(Observable::new(0));
+        let inner = observable.clone();
+        observable.subscribe(move |_| {
+            inner.subscribe(|_| {});
+        });
+        observable.set(1);
+        assert_eq!(observable.subscriber_count(), 2);
+    }
+}
+
+use std::cell::{Cell, Ref, RefCell};
+
+use crate::util::animation::request_redraw;
+
+/// identifies a subscription, so it can be removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionID(u64);
+
+type Subscriber<T> = Box<dyn FnMut(&T)>;
+
+/// a value which tells others when it changes.
+///
+/// widgets bound to an observable (via CellRefOrCell) compare its version
+/// instead of the value itself to know if their caches are still valid. each
+/// change also requests a redraw, so the application doesn't need to track
+/// what changed.
+///
+/// for use on the ui thread. see SharedState for values set from other threads
+pub struct Observable<T> {
+    value: RefCell<T>,
+    version: Cell<u64>,
+    next_id: Cell<u64>,
+    subscribers: RefCell<Vec<(SubscriptionID, Subscriber<T>)>>,
+}
+
+impl<T: Default> Default for Observable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Observable<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: RefCell::new(value),
+            version: Cell::new(0),
+            next_id: Cell::new(0),
+            subscribers: Default::default(),
+        }
+    }
+
+    /// incremented on each change
+    pub fn version(&self) -> u64 {
+        self.version.get()
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+        self.changed();
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        let old = self.value.replace(value);
+        self.changed();
+        old
+    }
+
+    /// modify the value in place
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.value.borrow_mut());
+        self.changed();
+    }
+
+    /// f is called after each change. it can read the observable, or subscribe
+    /// again, but can't change the observable or unsubscribe
+    pub fn subscribe<F: FnMut(&T) + 'static>(&self, f: F) -> SubscriptionID {
+        let id = SubscriptionID(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.subscribers.borrow_mut().push((id, Box::new(f)));
+        id
+    }
+
+    /// returns false if there was no such subscription
+    pub fn unsubscribe(&self, id: SubscriptionID) -> bool {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let len = subscribers.len();
+        subscribers.retain(|(i, _)| *i != id);
+        subscribers.len() != len
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.borrow().len()
+    }
+
+    fn changed(&self) {
+        self.version.set(self.version.get().wrapping_add(1));
+        request_redraw();
+        // taken out so subscribers can subscribe during notify
+        let mut subscribers = self.subscribers.take();
+        {
+            let value = self.value.borrow();
+            for (_, subscriber) in subscribers.iter_mut() {
+                subscriber(&value);
+            }
+        }
+        let mut added = self.subscribers.take();
+        subscribers.append(&mut added);
+        self.subscribers.replace(subscribers);
+    }
+}
+
+impl<T: Clone> Observable<T> {
+    pub fn get(&self) -> T {
+        self.value.borrow().clone()
+    }
+}
@@ -0,0 +1,90 @@
+use std::cell::Cell;
+
+/// counts of work done by the gui. only counted with the instrumentation
+/// feature; otherwise they stay zero
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfCounters {
+    /// widgets given an update event (the root, and each child a layout
+    /// updates)
+    pub widget_updates: u64,
+    /// textures created from rendered text
+    pub texture_creations: u64,
+    /// textures copied to the canvas by texture drawing
+    pub canvas_copies: u64,
+    /// lookups in text, texture, and sizing caches which were reused
+    pub cache_hits: u64,
+    /// lookups in text, texture, and sizing caches which had to be computed
+    pub cache_misses: u64,
+}
+
+impl PerfCounters {
+    /// portion (from 0 to 1) of cache lookups that were hits. none if there
+    /// were no lookups
+    pub fn cache_hit_rate(&self) -> Option<f32> {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            return None; // guard div
+        }
+        Some(self.cache_hits as f32 / total as f32)
+    }
+}
+
+// the gui is single threaded, so thread local is good enough
+thread_local! {
+    static PERF_COUNTERS: Cell<PerfCounters> = const {
+        Cell::new(PerfCounters {
+            widget_updates: 0,
+            texture_creations: 0,
+            canvas_copies: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+        })
+    };
+}
+
+/// the counts since the last reset
+pub fn perf_counters() -> PerfCounters {
+    PERF_COUNTERS.with(|c| c.get())
+}
+
+/// get the counts and reset them. for per frame counts, call once each frame
+pub fn take_perf_counters() -> PerfCounters {
+    PERF_COUNTERS.with(|c| c.replace(Default::default()))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Counter {
+    WidgetUpdate,
+    TextureCreation,
+    CanvasCopy,
+    CacheHit,
+    CacheMiss,
+}
+
+#[inline]
+pub(crate) fn count(counter: Counter) {
+    #[cfg(feature = "instrumentation")]
+    PERF_COUNTERS.with(|c| {
+        let mut counters = c.get();
+        match counter {
+            Counter::WidgetUpdate => counters.widget_updates += 1,
+            Counter::TextureCreation => counters.texture_creations += 1,
+            Counter::CanvasCopy => counters.canvas_copies += 1,
+            Counter::CacheHit => counters.cache_hits += 1,
+            Counter::CacheMiss => counters.cache_misses += 1,
+        }
+        c.set(counters);
+    });
+    #[cfg(not(feature = "instrumentation"))]
+    let _ = counter;
+}
+
+/// count a cache lookup as a hit or miss
+#[inline]
+pub(crate) fn count_cache(hit: bool) {
+    count(if hit {
+        Counter::CacheHit
+    } else {
+        Counter::CacheMiss
+    });
+}
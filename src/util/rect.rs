@@ -1,5 +1,5 @@
 /// NOT an sdl2::rect::FRect; this one has no restriction on members's values
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[derive(Default)]
 pub struct FRect {
     /// can be any value
@@ -57,6 +57,173 @@ mod tests {
         assert_eq!(rect_position_round(-1.5), -1);
         assert_eq!(rect_position_round(-2.5), -2);
     }
+
+    const A: FRect = FRect {
+        x: 0.,
+        y: 0.,
+        w: 10.,
+        h: 10.,
+    };
+
+    #[test]
+    fn intersection_and_union() {
+        let b = A.translated(5., 5.);
+        assert_eq!(
+            A.intersection(&b),
+            Some(FRect {
+                x: 5.,
+                y: 5.,
+                w: 5.,
+                h: 5.,
+            })
+        );
+        assert_eq!(
+            A.union(&b),
+            FRect {
+                x: 0.,
+                y: 0.,
+                w: 15.,
+                h: 15.,
+            }
+        );
+        // touching edges have no area in common
+        assert_eq!(A.intersection(&A.translated(10., 0.)), None);
+    }
+
+    #[test]
+    fn inset_and_contains() {
+        let inset = A.inset(2.);
+        assert_eq!(
+            inset,
+            FRect {
+                x: 2.,
+                y: 2.,
+                w: 6.,
+                h: 6.
+            }
+        );
+        assert_eq!(inset.outset(2.), A);
+        assert_eq!(A.inset(6.).w, 0.);
+        assert!(A.contains_point((0., 0.)));
+        assert!(A.contains_point((9.9, 5.)));
+        assert!(!A.contains_point((10., 5.)));
+        assert_eq!(A.center(), (5., 5.));
+    }
+
+    #[test]
+    fn clamped() {
+        let bounds = FRect {
+            x: 0.,
+            y: 0.,
+            w: 100.,
+            h: 50.,
+        };
+        let moved = A.translated(95., -3.).clamp_to(&bounds);
+        assert_eq!(
+            moved,
+            FRect {
+                x: 90.,
+                y: 0.,
+                w: 10.,
+                h: 10.
+            }
+        );
+        // too large: the top left is kept within the bounds
+        let large = FRect {
+            x: -5.,
+            y: 10.,
+            w: 200.,
+            h: 10.,
+        }
+        .clamp_to(&bounds);
+        assert_eq!((large.x, large.y), (0., 10.));
+    }
+}
+
+impl FRect {
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.w / 2., self.y + self.h / 2.)
+    }
+
+    pub fn translated(&self, dx: f32, dy: f32) -> FRect {
+        FRect {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..*self
+        }
+    }
+
+    /// includes the top left edges but not the bottom right edges, like
+    /// sdl2::rect::Rect
+    pub fn contains_point(&self, (x, y): (f32, f32)) -> bool {
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    /// none if there's no area in common
+    pub fn intersection(&self, other: &FRect) -> Option<FRect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let w = self.right().min(other.right()) - x;
+        let h = self.bottom().min(other.bottom()) - y;
+        if w <= 0. || h <= 0. {
+            return None;
+        }
+        Some(FRect { x, y, w, h })
+    }
+
+    /// the smallest rect containing both
+    pub fn union(&self, other: &FRect) -> FRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        FRect {
+            x,
+            y,
+            w: self.right().max(other.right()) - x,
+            h: self.bottom().max(other.bottom()) - y,
+        }
+    }
+
+    /// moved inward by an amount on each side. the size doesn't go below 0
+    pub fn inset(&self, amount: f32) -> FRect {
+        FRect {
+            x: self.x + amount,
+            y: self.y + amount,
+            w: (self.w - 2. * amount).max(0.),
+            h: (self.h - 2. * amount).max(0.),
+        }
+    }
+
+    /// moved outward by an amount on each side
+    pub fn outset(&self, amount: f32) -> FRect {
+        self.inset(-amount)
+    }
+
+    /// moved (not resized) to be within the bounds, e.g. keeping a popup on
+    /// screen. if it's larger than the bounds, its top left is within them
+    pub fn clamp_to(&self, bounds: &FRect) -> FRect {
+        let x = self.x.min(bounds.right() - self.w).max(bounds.x);
+        let y = self.y.min(bounds.bottom() - self.h).max(bounds.y);
+        FRect { x, y, ..*self }
+    }
+}
+
+impl From<sdl2::rect::Rect> for FRect {
+    fn from(val: sdl2::rect::Rect) -> Self {
+        FRect {
+            x: val.x() as f32,
+            y: val.y() as f32,
+            w: val.width() as f32,
+            h: val.height() as f32,
+        }
+    }
 }
 
 /// round, but if exactly between numbers, always round up.
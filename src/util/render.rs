@@ -1,7 +1,557 @@
-use sdl2::{pixels::Color, rect::Point};
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_buffer_region() {
+        // 3x3 pixels, with padding at the end of each row
+        let pitch = 3 * 4 + 2;
+        let mut buffer = vec![0u8; pitch * 3];
+        parallel_fill_buffer(&mut buffer, pitch, (1, 1, 2, 2), |x, y| {
+            Color::RGBA(x as u8, y as u8, 7, 9)
+        });
+        // outside region untouched
+        assert!(buffer[..pitch].iter().all(|b| *b == 0));
+        assert_eq!(&buffer[pitch..pitch + 4], &[0, 0, 0, 0]);
+        // bgra
+        assert_eq!(&buffer[pitch + 4..pitch + 8], &[7, 1, 1, 9]);
+        assert_eq!(&buffer[2 * pitch + 8..2 * pitch + 12], &[7, 2, 2, 9]);
+        // padding untouched
+        assert_eq!(&buffer[2 * pitch + 12..], &[0, 0]);
+    }
+
+    #[test]
+    fn tiles_cover_size() {
+        assert_eq!(tile_rects((10, 5), (16, 16)), vec![Rect::new(0, 0, 10, 5)]);
+        assert_eq!(
+            tile_rects((10, 5), (4, 3)),
+            vec![
+                Rect::new(0, 0, 4, 3),
+                Rect::new(4, 0, 4, 3),
+                Rect::new(8, 0, 2, 3),
+                Rect::new(0, 3, 4, 2),
+                Rect::new(4, 3, 4, 2),
+                Rect::new(8, 3, 2, 2),
+            ]
+        );
+        assert!(tile_rects((0, 5), (4, 4)).is_empty());
+    }
+
+    #[test]
+    fn tile_copy_scaled() {
+        let tile = Rect::new(4, 0, 4, 4);
+        // whole 8x4 texture, stretched to 16x4 at (100, 10)
+        let src = Rect::new(0, 0, 8, 4);
+        let dst = Rect::new(100, 10, 16, 4);
+        assert_eq!(
+            tile_copy_rects(tile, src, dst),
+            Some((Rect::new(0, 0, 4, 4), Rect::new(108, 10, 8, 4)))
+        );
+        // only the left half of the texture
+        let src = Rect::new(0, 0, 4, 4);
+        assert_eq!(tile_copy_rects(tile, src, dst), None);
+        // straddling the tile boundary
+        let src = Rect::new(2, 1, 4, 2);
+        let dst = Rect::new(0, 0, 4, 2);
+        assert_eq!(
+            tile_copy_rects(tile, src, dst),
+            Some((Rect::new(0, 1, 2, 2), Rect::new(2, 0, 2, 2)))
+        );
+    }
+
+    #[test]
+    fn fill_empty_region() {
+        let mut buffer = vec![0u8; 16];
+        parallel_fill_buffer(&mut buffer, 8, (0, 0, 0, 2), |_, _| Color::WHITE);
+        parallel_fill_buffer(&mut buffer, 8, (0, 0, 2, 0), |_, _| Color::WHITE);
+        assert!(buffer.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn guard_restores_state() {
+        let mut headless = crate::testing::golden::Headless::new().unwrap();
+        let canvas = &mut headless.canvas;
+        canvas.set_draw_color(Color::RGBA(1, 2, 3, 4));
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.set_clip_rect(ClippingRect::None);
+        {
+            let mut guard = CanvasStateGuard::new(canvas);
+            guard.set_draw_color(Color::RGBA(5, 6, 7, 8));
+            guard.set_blend_mode(BlendMode::Add);
+            guard.set_clip_rect(ClippingRect::Some(Rect::new(0, 0, 1, 1)));
+            assert_eq!(guard.blend_mode(), BlendMode::Add);
+        }
+        assert_eq!(canvas.draw_color(), Color::RGBA(1, 2, 3, 4));
+        assert_eq!(canvas.blend_mode(), BlendMode::None);
+        assert!(matches!(canvas.clip_rect(), ClippingRect::None));
+    }
+}
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use sdl2::{
+    pixels::Color,
+    rect::{Point, Rect},
+    render::{BlendMode, ClippingRect, ScaleMode, TextureCreator, WindowCanvas},
+    surface::Surface,
+    video::WindowContext,
+};
+
+use crate::util::{
+    error::{UiError, UiResult},
+    font::{SingleLineTextRenderType, TextRenderProperties},
+    perf::{count, count_cache, Counter},
+};
 
 // various drawing utilities
 
+thread_local! {
+    static STYLE_GENERATION: Cell<u64> = const { Cell::new(0) };
+    static MAX_TEXTURE_SIZE: Cell<Option<(u32, u32)>> = const { Cell::new(None) };
+}
+
+/// incremented each time all texture caches should be discarded. e.g. when the
+/// styles change in a way that caches can't detect (a color inside a style), or
+/// when the renderer loses its textures. caches compare this against the value
+/// they were rendered with
+pub fn style_generation() -> u64 {
+    STYLE_GENERATION.with(|g| g.get())
+}
+
+/// force texture caches to be re-rendered the next time they're drawn
+pub fn invalidate_texture_caches() {
+    STYLE_GENERATION.with(|g| g.set(g.get().wrapping_add(1)));
+}
+
+/// restores the canvas's draw color, blend mode, and clipping rect when
+/// dropped. a widget's draw can then change them without affecting whatever is
+/// drawn after it. see also ClipScope, a guard which also sets the clipping
+/// rect
+pub struct CanvasStateGuard<'a> {
+    canvas: &'a mut WindowCanvas,
+    draw_color: Color,
+    blend_mode: BlendMode,
+    clip_rect: ClippingRect,
+}
+
+impl<'a> CanvasStateGuard<'a> {
+    pub fn new(canvas: &'a mut WindowCanvas) -> Self {
+        Self {
+            draw_color: canvas.draw_color(),
+            blend_mode: canvas.blend_mode(),
+            clip_rect: canvas.clip_rect(),
+            canvas,
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for CanvasStateGuard<'a> {
+    type Target = WindowCanvas;
+
+    fn deref(&self) -> &Self::Target {
+        self.canvas
+    }
+}
+
+impl<'a> std::ops::DerefMut for CanvasStateGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.canvas
+    }
+}
+
+impl<'a> Drop for CanvasStateGuard<'a> {
+    fn drop(&mut self) {
+        self.canvas.set_draw_color(self.draw_color);
+        self.canvas.set_blend_mode(self.blend_mode);
+        self.canvas.set_clip_rect(self.clip_rect);
+    }
+}
+
+/// a texture borrowed from a TextureCreator. with the unsafe-textures feature,
+/// sdl2 textures don't carry a lifetime, and the lifetime here is unused
+#[cfg(not(feature = "unsafe-textures"))]
+pub type SdlTexture<'sdl> = sdl2::render::Texture<'sdl>;
+#[cfg(feature = "unsafe-textures")]
+pub type SdlTexture<'sdl> = sdl2::render::Texture;
+
+/// a texture owned by a widget or cache
+#[cfg(not(feature = "unsafe-textures"))]
+pub type OwnedTexture<'sdl> = sdl2::render::Texture<'sdl>;
+
+/// a texture owned by a widget or cache. with unsafe_textures, sdl2 textures
+/// are never freed on drop - this wrapper destroys the texture instead.
+///
+/// the canvas (and its TextureCreator) must outlive all widgets and caches
+/// holding textures. this is no longer checked by the compiler
+#[cfg(feature = "unsafe-textures")]
+pub struct OwnedTexture<'sdl> {
+    texture: std::mem::ManuallyDrop<sdl2::render::Texture>,
+    _creator: std::marker::PhantomData<&'sdl ()>,
+}
+
+#[cfg(feature = "unsafe-textures")]
+impl<'sdl> From<sdl2::render::Texture> for OwnedTexture<'sdl> {
+    fn from(texture: sdl2::render::Texture) -> Self {
+        Self {
+            texture: std::mem::ManuallyDrop::new(texture),
+            _creator: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "unsafe-textures")]
+impl<'sdl> std::ops::Deref for OwnedTexture<'sdl> {
+    type Target = sdl2::render::Texture;
+
+    fn deref(&self) -> &Self::Target {
+        &self.texture
+    }
+}
+
+#[cfg(feature = "unsafe-textures")]
+impl<'sdl> std::ops::DerefMut for OwnedTexture<'sdl> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.texture
+    }
+}
+
+#[cfg(feature = "unsafe-textures")]
+impl<'sdl> Drop for OwnedTexture<'sdl> {
+    fn drop(&mut self) {
+        // safety: taken once, on drop. the renderer outlives the texture (see
+        // above)
+        unsafe { std::mem::ManuallyDrop::take(&mut self.texture).destroy() };
+    }
+}
+
+/// take ownership of a texture which was just created
+#[cfg(not(feature = "unsafe-textures"))]
+pub fn owned_texture(texture: sdl2::render::Texture<'_>) -> OwnedTexture<'_> {
+    texture
+}
+
+/// take ownership of a texture which was just created
+#[cfg(feature = "unsafe-textures")]
+pub fn owned_texture<'sdl>(texture: sdl2::render::Texture) -> OwnedTexture<'sdl> {
+    texture.into()
+}
+
+/// used by max_texture_size until the renderer's limit is known. textures up to
+/// this size are supported by practically all renderers
+const DEFAULT_MAX_TEXTURE_SIZE: (u32, u32) = (4096, 4096);
+
+/// remember the renderer's max texture size, for max_texture_size. widgets
+/// which tile their textures call this on draw; applications can call it once
+/// after creating the canvas so that it's known before the first draw
+pub fn record_max_texture_size(canvas: &WindowCanvas) {
+    let info = canvas.info();
+    // 0 indicates no limit
+    let limit = |v: u32| if v == 0 { u32::MAX } else { v };
+    let size = (
+        limit(info.max_texture_width),
+        limit(info.max_texture_height),
+    );
+    MAX_TEXTURE_SIZE.with(|s| s.set(Some(size)));
+}
+
+/// the largest texture which can be created. see record_max_texture_size
+pub fn max_texture_size() -> (u32, u32) {
+    MAX_TEXTURE_SIZE.with(|s| s.get().unwrap_or(DEFAULT_MAX_TEXTURE_SIZE))
+}
+
+/// split an area into tiles no larger than max_size, row by row
+fn tile_rects(size: (u32, u32), max_size: (u32, u32)) -> Vec<Rect> {
+    let max_w = max_size.0.max(1);
+    let max_h = max_size.1.max(1);
+    let mut out = Vec::new();
+    let mut y = 0;
+    while y < size.1 {
+        let h = max_h.min(size.1 - y);
+        let mut x = 0;
+        while x < size.0 {
+            let w = max_w.min(size.0 - x);
+            out.push(Rect::new(x as i32, y as i32, w, h));
+            x += w;
+        }
+        y += h;
+    }
+    out
+}
+
+/// the part of a tile to copy (relative to the tile) and where to copy it,
+/// when copying src (in whole texture coordinates) to dst. None if the tile
+/// isn't in src. edges are rounded consistently so adjacent tiles don't leave
+/// gaps
+fn tile_copy_rects(tile: Rect, src: Rect, dst: Rect) -> Option<(Rect, Rect)> {
+    let part = tile.intersection(src)?;
+    let scale_x = dst.width() as f32 / src.width() as f32;
+    let scale_y = dst.height() as f32 / src.height() as f32;
+    let map_x = |x: i32| dst.x() + ((x - src.x()) as f32 * scale_x).round() as i32;
+    let map_y = |y: i32| dst.y() + ((y - src.y()) as f32 * scale_y).round() as i32;
+    let left = map_x(part.left());
+    let right = map_x(part.right());
+    let top = map_y(part.top());
+    let bottom = map_y(part.bottom());
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some((
+        Rect::new(
+            part.x() - tile.x(),
+            part.y() - tile.y(),
+            part.width(),
+            part.height(),
+        ),
+        Rect::new(left, top, (right - left) as u32, (bottom - top) as u32),
+    ))
+}
+
+/// content which may be larger than the renderer's max texture size, split
+/// across several textures. drawn like a single texture
+pub struct TiledTexture<'sdl> {
+    /// where each texture is within the whole
+    tiles: Vec<(Rect, OwnedTexture<'sdl>)>,
+    size: (u32, u32),
+}
+
+impl<'sdl> From<OwnedTexture<'sdl>> for TiledTexture<'sdl> {
+    fn from(texture: OwnedTexture<'sdl>) -> Self {
+        let query = texture.query();
+        Self {
+            tiles: vec![(Rect::new(0, 0, query.width, query.height), texture)],
+            size: (query.width, query.height),
+        }
+    }
+}
+
+impl<'sdl> TiledTexture<'sdl> {
+    /// create textures from a surface, split into tiles no larger than
+    /// max_size (typically max_texture_size())
+    pub fn from_surface(
+        surface: &mut Surface,
+        creator: &'sdl TextureCreator<WindowContext>,
+        max_size: (u32, u32),
+    ) -> UiResult<Self> {
+        let size = (surface.width(), surface.height());
+        if size.0 <= max_size.0 && size.1 <= max_size.1 {
+            let texture = creator
+                .create_texture_from_surface(&*surface)
+                .map(owned_texture)?;
+            count(Counter::TextureCreation);
+            return Ok(texture.into());
+        }
+
+        // copy the pixels as they are, rather than blending onto the tile
+        let blend_mode = surface.blend_mode();
+        surface.set_blend_mode(BlendMode::None)?;
+        let tiles = tile_rects(size, max_size)
+            .into_iter()
+            .map(|rect| {
+                let mut tile =
+                    Surface::new(rect.width(), rect.height(), surface.pixel_format_enum())?;
+                surface.blit(rect, &mut tile, None)?;
+                let texture = creator
+                    .create_texture_from_surface(&tile)
+                    .map(owned_texture)?;
+                count(Counter::TextureCreation);
+                Ok((rect, texture))
+            })
+            .collect::<UiResult<Vec<_>>>();
+        surface.set_blend_mode(blend_mode)?;
+        Ok(Self {
+            tiles: tiles?,
+            size,
+        })
+    }
+
+    /// width and height of the whole
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn set_blend_mode(&mut self, blend: BlendMode) {
+        self.tiles
+            .iter_mut()
+            .for_each(|(_, t)| t.set_blend_mode(blend));
+    }
+
+    pub fn set_scale_mode(&mut self, scale: ScaleMode) {
+        self.tiles
+            .iter_mut()
+            .for_each(|(_, t)| t.set_scale_mode(scale));
+    }
+
+    pub fn set_color_mod(&mut self, r: u8, g: u8, b: u8) {
+        self.tiles
+            .iter_mut()
+            .for_each(|(_, t)| t.set_color_mod(r, g, b));
+    }
+
+    /// same as canvas.copy. src is relative to the whole, and is all of it if
+    /// None
+    pub fn copy(&self, canvas: &mut WindowCanvas, src: Option<Rect>, dst: Rect) -> UiResult<()> {
+        if let [(_, texture)] = self.tiles.as_slice() {
+            return canvas.copy(texture, src, dst).map_err(UiError::Sdl);
+        }
+        if self.size.0 == 0 || self.size.1 == 0 {
+            return Ok(());
+        }
+        let src = src.unwrap_or(Rect::new(0, 0, self.size.0, self.size.1));
+        for (tile, texture) in self.tiles.iter() {
+            if let Some((tile_src, tile_dst)) = tile_copy_rects(*tile, src, dst) {
+                canvas.copy(texture, tile_src, tile_dst)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SharedTextKey {
+    text: String,
+    point_size: u16,
+    render_type: SingleLineTextRenderType,
+    /// see style_generation
+    generation: u64,
+}
+
+/// textures of rendered text, shared between widgets showing the same text in
+/// the same way (e.g. identical cells in many list rows). each is rendered
+/// once and freed once no widget holds it.
+///
+/// the font isn't part of what identifies a texture; use a separate cache for
+/// each font
+#[derive(Default)]
+pub struct SharedTextCache<'sdl> {
+    textures: RefCell<HashMap<SharedTextKey, Weak<OwnedTexture<'sdl>>>>,
+}
+
+impl<'sdl> SharedTextCache<'sdl> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// get the texture for some text. if no widget holds it, it's rendered
+    /// with the render function
+    pub fn get_or_render<F>(
+        &self,
+        text: &str,
+        properties: &TextRenderProperties,
+        render: F,
+    ) -> UiResult<Rc<OwnedTexture<'sdl>>>
+    where
+        F: FnOnce() -> UiResult<OwnedTexture<'sdl>>,
+    {
+        let key = SharedTextKey {
+            text: text.to_owned(),
+            point_size: properties.point_size,
+            render_type: properties.render_type,
+            generation: style_generation(),
+        };
+        let existing = self.textures.borrow().get(&key).and_then(|t| t.upgrade());
+        count_cache(existing.is_some());
+        if let Some(texture) = existing {
+            return Ok(texture);
+        }
+        let texture = Rc::new(render()?);
+        let mut textures = self.textures.borrow_mut();
+        // evict textures no longer held by any widget
+        textures.retain(|_, t| t.strong_count() > 0);
+        textures.insert(key, Rc::downgrade(&texture));
+        Ok(texture)
+    }
+
+    /// the number of textures currently held by widgets
+    pub fn len(&self) -> usize {
+        self.textures
+            .borrow()
+            .values()
+            .filter(|t| t.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// roughly how many pixels are computed per parallel task by parallel_fill.
+/// small enough that tasks are balanced across threads, but large enough that
+/// scheduling overhead is negligible compared to even a cheap color function
+const FILL_TILE_PIXELS: usize = 1 << 14;
+
+/// compute each pixel in a region of an ARGB8888 surface from its coordinates
+/// (relative to the surface). region is (x, y, w, h).
+///
+/// the region is split into bands of rows, which are computed in parallel if
+/// the rayon feature is enabled
+pub fn parallel_fill<F>(surface: &mut Surface, region: (u32, u32, u32, u32), f: F) -> UiResult<()>
+where
+    F: Fn(usize, usize) -> Color + Sync,
+{
+    if surface.pixel_format_enum() != sdl2::pixels::PixelFormatEnum::ARGB8888 {
+        return Err(UiError::Texture(
+            "parallel_fill requires an ARGB8888 surface".to_owned(),
+        ));
+    }
+    let (x, y, w, h) = region;
+    // clip to the surface
+    let w = w.min(surface.width().saturating_sub(x));
+    let h = h.min(surface.height().saturating_sub(y));
+    let pitch = surface.pitch() as usize;
+    surface.with_lock_mut(|buffer| parallel_fill_buffer(buffer, pitch, (x, y, w, h), f));
+    Ok(())
+}
+
+/// as parallel_fill, but for a raw ARGB8888 buffer with some row pitch (in
+/// bytes). the region must be within the buffer
+pub fn parallel_fill_buffer<F>(buffer: &mut [u8], pitch: usize, region: (u32, u32, u32, u32), f: F)
+where
+    F: Fn(usize, usize) -> Color + Sync,
+{
+    let (x, y, w, h) = region;
+    let (x, y, w, h) = (x as usize, y as usize, w as usize, h as usize);
+    if w == 0 || h == 0 {
+        return;
+    }
+    let rows_per_band = (FILL_TILE_PIXELS / w).max(1);
+    let end = ((y + h) * pitch).min(buffer.len());
+    let buffer = &mut buffer[y * pitch..end];
+
+    #[cfg(feature = "rayon")]
+    let band_iter = buffer.par_chunks_mut(pitch * rows_per_band);
+    #[cfg(not(feature = "rayon"))]
+    let band_iter = buffer.chunks_mut(pitch * rows_per_band);
+
+    band_iter.enumerate().for_each(|(band_index, band)| {
+        band.chunks_mut(pitch)
+            .enumerate()
+            .for_each(|(row_in_band, row)| {
+                let row_index = y + band_index * rows_per_band + row_in_band;
+                row[x * 4..(x + w) * 4]
+                    .chunks_exact_mut(4)
+                    .enumerate()
+                    .for_each(|(pixel_index, pixel)| {
+                        let color = f(x + pixel_index, row_index);
+                        pixel[0] = color.b;
+                        pixel[1] = color.g;
+                        pixel[2] = color.r;
+                        pixel[3] = color.a;
+                    });
+            });
+    });
+}
+
 pub fn interpolate_color(start: Color, stop: Color, progress: f32) -> Color {
     let r = (start.r as f32 + (stop.r as f32 - start.r as f32) * progress) as u8;
     let g = (start.g as f32 + (stop.g as f32 - start.g as f32) * progress) as u8;
@@ -1,5 +1,7 @@
 use std::ops::{Deref, DerefMut};
 
+use crate::util::{observable::Observable, shared::SharedState};
+
 /// give a lifetime which is a subset of the existing lifetime
 pub fn reborrow<'in_life, 'out_life, T: ?Sized>(something: &'in_life mut T) -> &'out_life mut T
 where
@@ -8,10 +10,13 @@ where
     &mut *something
 }
 
-/// references to cell or value
+/// references to cell or value, or a value which can be set from other
+/// threads, or an observable
 pub enum CellRefOrCell<'a, T> {
     Ref(&'a std::cell::Cell<T>),
     Cell(std::cell::Cell<T>),
+    Shared(SharedState<T>),
+    Observed(&'a Observable<T>),
 }
 
 // revisit. perhaps lang improvements will help? SFINAE. conflicts with From<&'a
@@ -45,20 +50,58 @@ impl<T> From<std::cell::Cell<T>> for CellRefOrCell<'_, T> {
     }
 }
 
+impl<T> From<SharedState<T>> for CellRefOrCell<'_, T> {
+    fn from(value: SharedState<T>) -> Self {
+        CellRefOrCell::Shared(value)
+    }
+}
+
+impl<'a, T> From<&'a Observable<T>> for CellRefOrCell<'a, T> {
+    fn from(value: &'a Observable<T>) -> Self {
+        CellRefOrCell::Observed(value)
+    }
+}
+
 impl<'a, T: Copy> CellRefOrCell<'a, T> {
     pub fn get(&self) -> T {
         match self {
             CellRefOrCell::Ref(cell) => cell.get(),
             CellRefOrCell::Cell(cell) => cell.get(),
+            CellRefOrCell::Shared(shared) => shared.get(),
+            CellRefOrCell::Observed(observable) => observable.get(),
         }
     }
 }
 
-impl<'a, T: Default> CellRefOrCell<'a, T> {
+impl<'a, T: Default + Clone> CellRefOrCell<'a, T> {
+    /// a shared or observed value is cloned instead, since others may still
+    /// need to see it
     pub fn take(&self) -> T {
         match self {
             CellRefOrCell::Ref(r) => r.take(),
             CellRefOrCell::Cell(b) => b.take(),
+            CellRefOrCell::Shared(shared) => shared.get(),
+            CellRefOrCell::Observed(observable) => observable.get(),
+        }
+    }
+
+    /// put back what was taken. a shared or observed value is only written if
+    /// it was modified; otherwise, putting back a clone would overwrite (and
+    /// flag as changed) whatever was set in the meantime
+    fn restore(&self, value: T, modified: bool) {
+        match self {
+            CellRefOrCell::Ref(r) => r.set(value),
+            CellRefOrCell::Cell(b) => b.set(value),
+            CellRefOrCell::Shared(shared) => {
+                if modified {
+                    shared.set(value)
+                }
+            }
+            CellRefOrCell::Observed(observable) => {
+                if modified {
+                    observable.set(value)
+                }
+            }
         }
     }
 
@@ -66,6 +109,7 @@ impl<'a, T: Default> CellRefOrCell<'a, T> {
         ScopeTake {
             source: self,
             holder: self.take(),
+            modified: false,
         }
     }
 }
@@ -75,6 +119,8 @@ impl<'a, T> CellRefOrCell<'a, T> {
         match self {
             CellRefOrCell::Ref(cell) => cell.replace(value),
             CellRefOrCell::Cell(cell) => cell.replace(value),
+            CellRefOrCell::Shared(shared) => shared.replace(value),
+            CellRefOrCell::Observed(observable) => observable.replace(value),
         }
     }
 
@@ -82,18 +128,30 @@ impl<'a, T> CellRefOrCell<'a, T> {
         match self {
             CellRefOrCell::Ref(r) => r.set(value),
             CellRefOrCell::Cell(b) => b.set(value),
+            CellRefOrCell::Shared(shared) => shared.set(value),
+            CellRefOrCell::Observed(observable) => observable.set(value),
+        }
+    }
+
+    /// if observed, the observable's version. caches can compare this instead
+    /// of the value
+    pub fn version(&self) -> Option<u64> {
+        match self {
+            CellRefOrCell::Observed(observable) => Some(observable.version()),
+            _ => None,
         }
     }
 }
 
 /// raii over ref to contents in CellRefOrCell. takes content and puts it back
 /// when dropped
-pub struct ScopeTake<'a, T: Default> {
+pub struct ScopeTake<'a, T: Default + Clone> {
     source: &'a CellRefOrCell<'a, T>,
     holder: T,
+    modified: bool,
 }
 
-impl<'a, T: Default> Deref for ScopeTake<'a, T> {
+impl<'a, T: Default + Clone> Deref for ScopeTake<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -101,14 +159,16 @@ impl<'a, T: Default> Deref for ScopeTake<'a, T> {
     }
 }
 
-impl<'a, T: Default> DerefMut for ScopeTake<'a, T> {
+impl<'a, T: Default + Clone> DerefMut for ScopeTake<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.modified = true;
         &mut self.holder
     }
 }
 
-impl<'a, T: Default> Drop for ScopeTake<'a, T> {
+impl<'a, T: Default + Clone> Drop for ScopeTake<'a, T> {
     fn drop(&mut self) {
-        self.source.set(std::mem::take(&mut self.holder));
+        self.source
+            .restore(std::mem::take(&mut self.holder), self.modified);
     }
 }
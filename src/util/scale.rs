@@ -0,0 +1,68 @@
+use std::cell::Cell;
+
+use sdl2::{render::WindowCanvas, VideoSubsystem};
+
+use crate::util::error::UiResult;
+
+/// the dpi at which a scale of 1 is used
+pub const REFERENCE_DPI: f32 = 96.;
+
+// sizing functions (min, max, etc) don't receive any context from the caller,
+// so the scale is stored here instead of being passed down with the update
+// event. the gui is single threaded, so thread local is good enough
+thread_local! {
+    static UI_SCALE: Cell<f32> = const { Cell::new(1.) };
+}
+
+/// the global ui scale factor. literal lengths given to widgets (min and max
+/// lengths, border widths, point sizes, etc) are in logical pixels, and are
+/// multiplied by this value to get physical pixels
+pub fn ui_scale() -> f32 {
+    UI_SCALE.with(|s| s.get())
+}
+
+/// set the global ui scale factor. should be set before update_gui is called,
+/// and not changed between update and draw within the same frame.
+///
+/// the scale is 1 unless set. an app will typically call
+/// `set_ui_scale(display_dpi_scale(&video, display_index)?)` at startup (and
+/// again if the window moves to a different display)
+pub fn set_ui_scale(scale: f32) {
+    debug_assert!(scale > 0.);
+    UI_SCALE.with(|s| s.set(scale));
+}
+
+/// apply the ui scale to some length
+pub fn scaled(len: f32) -> f32 {
+    len * ui_scale()
+}
+
+/// apply the ui scale to some whole number length
+pub fn scaled_u32(len: u32) -> u32 {
+    scaled(len as f32).round() as u32
+}
+
+/// the scale suggested by the display's dpi. for example, a 192 dpi display
+/// gives a scale of 2
+pub fn display_dpi_scale(video: &VideoSubsystem, display_index: i32) -> UiResult<f32> {
+    let (ddpi, _hdpi, _vdpi) = video.display_dpi(display_index)?;
+    if ddpi <= 0. {
+        return Ok(1.); // guard nonsense
+    }
+    Ok(ddpi / REFERENCE_DPI)
+}
+
+/// the ratio between the canvas's output size and the window's size. this is
+/// not 1 for windows created with allow_highdpi on some platforms (e.g. macOS
+/// retina)
+pub fn window_pixel_density(canvas: &WindowCanvas) -> f32 {
+    let (window_w, _window_h) = canvas.window().size();
+    let (output_w, _output_h) = match canvas.output_size() {
+        Ok(v) => v,
+        Err(_) => return 1.,
+    };
+    if window_w == 0 {
+        return 1.; // guard div
+    }
+    output_w as f32 / window_w as f32
+}
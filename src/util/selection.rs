@@ -0,0 +1,317 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    const NONE: SelectModifiers = SelectModifiers {
+        ctrl: false,
+        shift: false,
+    };
+    const CTRL: SelectModifiers = SelectModifiers {
+        ctrl: true,
+        shift: false,
+    };
+    const SHIFT: SelectModifiers = SelectModifiers {
+        ctrl: false,
+        shift: true,
+    };
+
+    #[test]
+    fn single() {
+        let mut s = SelectionModel::new(SelectionMode::Single, 5);
+        s.click(1, NONE);
+        s.click(3, SHIFT);
+        assert_eq!(s.selected(), vec![3]);
+        s.click(3, CTRL);
+        assert!(s.selected().is_empty());
+        s.click(10, NONE);
+        assert!(s.selected().is_empty());
+    }
+
+    #[test]
+    fn multi() {
+        let mut s = SelectionModel::new(SelectionMode::Multi, 10);
+        s.click(2, NONE);
+        s.click(5, SHIFT);
+        assert_eq!(s.selected(), vec![2, 3, 4, 5]);
+        // anchor stays at 2
+        s.click(0, SHIFT);
+        assert_eq!(s.selected(), vec![0, 1, 2]);
+        s.click(7, CTRL);
+        assert_eq!(s.selected(), vec![0, 1, 2, 7]);
+        s.click(1, CTRL);
+        assert_eq!(s.selected(), vec![0, 2, 7]);
+        // anchor moved to 1 by the ctrl click
+        s.click(3, SHIFT);
+        assert_eq!(s.selected(), vec![1, 2, 3]);
+        s.set_len(2);
+        assert_eq!(s.selected(), vec![1]);
+    }
+
+    #[test]
+    fn range_ignores_ctrl() {
+        let mut s = SelectionModel::new(SelectionMode::Range, 10);
+        s.click(4, NONE);
+        s.click(6, CTRL);
+        assert_eq!(s.selected(), vec![6]);
+        s.click(8, SHIFT);
+        assert_eq!(s.selected(), vec![6, 7, 8]);
+    }
+
+    #[test]
+    fn move_cursor() {
+        let mut s = SelectionModel::new(SelectionMode::Multi, 10);
+        s.move_to(3, NONE);
+        s.move_to(5, CTRL);
+        assert_eq!(s.selected(), vec![3]);
+        assert_eq!(s.cursor(), Some(5));
+        s.toggle_cursor();
+        assert_eq!(s.selected(), vec![3, 5]);
+        s.move_to(6, SHIFT);
+        assert_eq!(s.selected(), vec![5, 6]);
+    }
+
+    #[test]
+    fn notifications() {
+        let calls = Rc::new(Cell::new(0));
+        let calls_inner = calls.clone();
+        let mut s = SelectionModel::new(SelectionMode::Multi, 10);
+        s.add_observer(Box::new(move |_| calls_inner.set(calls_inner.get() + 1)));
+        s.click(1, NONE);
+        s.click(1, NONE); // unchanged
+        s.select_all();
+        s.clear();
+        s.clear(); // unchanged
+        assert_eq!(calls.get(), 3);
+        assert_eq!(s.version(), 3);
+    }
+}
+
+use std::collections::BTreeSet;
+
+/// how many items can be selected at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    /// zero or one item
+    #[default]
+    Single,
+    /// a contiguous range of items. shift extends from the anchor
+    Range,
+    /// any items. ctrl toggles an item, shift selects a range from the anchor
+    Multi,
+}
+
+/// the modifier keys which change what a click or keyboard move does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SelectModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl From<sdl2::keyboard::Mod> for SelectModifiers {
+    fn from(keymod: sdl2::keyboard::Mod) -> Self {
+        use sdl2::keyboard::Mod;
+        Self {
+            // cmd on mac
+            ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LGUIMOD | Mod::RGUIMOD),
+            shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        }
+    }
+}
+
+/// called when the selection changes. receives the selected indices, sorted
+pub type SelectionObserver = Box<dyn FnMut(&[usize])>;
+
+/// which items of a list (or table rows, etc.) are selected. shared by widgets
+/// and applications showing lists, so they have the same click and keyboard
+/// behavior.
+///
+/// the anchor is where a shift selection extends from. the cursor is the item
+/// most recently clicked or moved to (e.g. the one with the focus outline)
+pub struct SelectionModel {
+    pub mode: SelectionMode,
+    len: usize,
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+    cursor: Option<usize>,
+    version: u64,
+    observers: Vec<SelectionObserver>,
+}
+
+impl SelectionModel {
+    pub fn new(mode: SelectionMode, len: usize) -> Self {
+        Self {
+            mode,
+            len,
+            selected: Default::default(),
+            anchor: None,
+            cursor: None,
+            version: 0,
+            observers: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// the number of items changed. selected items past the end are removed
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.anchor = self.anchor.filter(|&i| i < len);
+        self.cursor = self.cursor.filter(|&i| i < len);
+        let removed = self.selected.split_off(&len);
+        if !removed.is_empty() {
+            self.changed();
+        }
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    /// sorted
+    pub fn selected(&self) -> Vec<usize> {
+        self.selected.iter().copied().collect()
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn anchor(&self) -> Option<usize> {
+        self.anchor
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// incremented each time the selection changes
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn add_observer(&mut self, observer: SelectionObserver) {
+        self.observers.push(observer);
+    }
+
+    /// an item was clicked. out of range indices are ignored
+    pub fn click(&mut self, index: usize, modifiers: SelectModifiers) {
+        if index >= self.len {
+            return;
+        }
+        self.cursor = Some(index);
+        match self.mode {
+            SelectionMode::Single => {
+                if modifiers.ctrl && self.is_selected(index) {
+                    self.set_selection(std::iter::empty());
+                } else {
+                    self.set_selection(std::iter::once(index));
+                }
+                self.anchor = Some(index);
+            }
+            SelectionMode::Range | SelectionMode::Multi if modifiers.shift => {
+                let anchor = *self.anchor.get_or_insert(index);
+                self.set_selection(anchor.min(index)..=anchor.max(index));
+            }
+            SelectionMode::Multi if modifiers.ctrl => {
+                self.toggle(index);
+                self.anchor = Some(index);
+            }
+            SelectionMode::Range | SelectionMode::Multi => {
+                self.set_selection(std::iter::once(index));
+                self.anchor = Some(index);
+            }
+        }
+    }
+
+    /// the cursor was moved with the keyboard (e.g. arrow keys). same as a
+    /// click, except ctrl moves the cursor without changing the selection (in
+    /// multi mode; see toggle_cursor)
+    pub fn move_to(&mut self, index: usize, modifiers: SelectModifiers) {
+        if index >= self.len {
+            return;
+        }
+        if self.mode == SelectionMode::Multi && modifiers.ctrl && !modifiers.shift {
+            self.cursor = Some(index);
+            return;
+        }
+        self.click(
+            index,
+            SelectModifiers {
+                ctrl: false,
+                shift: modifiers.shift,
+            },
+        );
+    }
+
+    /// toggle the item at the cursor (e.g. ctrl+space)
+    pub fn toggle_cursor(&mut self) {
+        if let Some(cursor) = self.cursor {
+            self.click(
+                cursor,
+                SelectModifiers {
+                    ctrl: true,
+                    shift: false,
+                },
+            );
+        }
+    }
+
+    /// toggle an item, leaving the others as they are (in single mode, the
+    /// others are deselected)
+    pub fn toggle(&mut self, index: usize) {
+        if index >= self.len {
+            return;
+        }
+        if self.is_selected(index) {
+            self.selected.remove(&index);
+        } else {
+            if self.mode != SelectionMode::Multi {
+                self.selected.clear();
+            }
+            self.selected.insert(index);
+        }
+        self.changed();
+    }
+
+    /// in single mode, only selects the cursor (if any)
+    pub fn select_all(&mut self) {
+        match self.mode {
+            SelectionMode::Single => {
+                let cursor = self.cursor;
+                self.set_selection(cursor.into_iter());
+            }
+            SelectionMode::Range | SelectionMode::Multi => {
+                self.anchor = self.anchor.or((self.len > 0).then_some(0));
+                self.set_selection(0..self.len);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.set_selection(std::iter::empty());
+    }
+
+    fn set_selection<I: Iterator<Item = usize>>(&mut self, selection: I) {
+        let selection: BTreeSet<usize> = selection.filter(|&i| i < self.len).collect();
+        if selection != self.selected {
+            self.selected = selection;
+            self.changed();
+        }
+    }
+
+    fn changed(&mut self) {
+        self.version = self.version.wrapping_add(1);
+        let selected = self.selected();
+        for observer in self.observers.iter_mut() {
+            observer(&selected);
+        }
+    }
+}
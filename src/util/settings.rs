@@ -0,0 +1,358 @@
+//! a store of named settings which widgets are bound to directly. each entry
+//! is an Observable, so a checkbox, text input, keybind, etc. reads and writes
+//! the setting itself and no glue is needed per control:
+//!
+//! ```ignore
+//! let mut settings = Settings::default();
+//! settings.add_bool("vsync", true);
+//! settings.add_binding("jump", Some(Binding::Key(Keycode::Space)));
+//! let vsync = CheckBox::new(settings.bool("vsync")?, ...);
+//! ```
+//!
+//! with the settings feature, the store is saved to and loaded from a RON or
+//! JSON file:
+//!
+//! ```ron
+//! {"jump": "key:Space", "volume": 0.8, "vsync": true}
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_round_trip() {
+        let mut settings = Settings::default();
+        settings.add_bool("vsync", true);
+        settings.add_float("volume", 0.5);
+        settings.add_index("difficulty", 1);
+        settings.add_text("name", "player");
+        let values = settings.values();
+
+        let mut other = Settings::default();
+        other.add_bool("vsync", false);
+        other.add_float("volume", 1.);
+        other.add_index("difficulty", 0);
+        other.add_text("name", "");
+        other.apply(values).unwrap();
+        assert!(other.bool("vsync").unwrap().get());
+        assert_eq!(other.float("volume").unwrap().get(), 0.5);
+        assert_eq!(other.index("difficulty").unwrap().get(), 1);
+        assert_eq!(other.text("name").unwrap().get(), "player");
+    }
+
+    #[test]
+    fn apply_skips_unknown_and_mismatched() {
+        let mut settings = Settings::default();
+        settings.add_bool("vsync", true);
+        settings.add_float("volume", 0.5);
+        let mut values = BTreeMap::new();
+        values.insert("removed".to_owned(), SettingValue::Int(3));
+        values.insert("vsync".to_owned(), SettingValue::Text("yes".to_owned()));
+        // a whole number is accepted for a float
+        values.insert("volume".to_owned(), SettingValue::Int(1));
+        assert!(settings.apply(values).is_err());
+        assert!(settings.bool("vsync").unwrap().get());
+        assert_eq!(settings.float("volume").unwrap().get(), 1.);
+        assert!(settings.bool("volume").is_err());
+    }
+}
+
+use std::collections::BTreeMap;
+
+use sdl2::{controller::Button, keyboard::Keycode};
+
+#[cfg(feature = "settings")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{
+        error::{UiError, UiResult},
+        observable::Observable,
+    },
+    widget::keybind::Binding,
+};
+
+/// a setting's value, as it's written to disk
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "settings", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "settings", serde(untagged))]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+enum SettingEntry {
+    Bool(Observable<bool>),
+    Int(Observable<i64>),
+    Float(Observable<f32>),
+    /// a choice from a list (e.g. a dropdown or carousel)
+    Index(Observable<usize>),
+    Text(Observable<String>),
+    Binding(Observable<Option<Binding>>),
+}
+
+fn binding_to_text(binding: Option<Binding>) -> String {
+    match binding {
+        Some(Binding::Key(keycode)) => format!("key:{}", keycode.name()),
+        Some(Binding::ControllerButton(button)) => format!("button:{}", button.string()),
+        None => String::new(),
+    }
+}
+
+fn binding_from_text(text: &str) -> Option<Option<Binding>> {
+    if text.is_empty() {
+        return Some(None);
+    }
+    if let Some(name) = text.strip_prefix("key:") {
+        return Keycode::from_name(name).map(|keycode| Some(Binding::Key(keycode)));
+    }
+    if let Some(name) = text.strip_prefix("button:") {
+        return Button::from_string(name).map(|button| Some(Binding::ControllerButton(button)));
+    }
+    None
+}
+
+impl SettingEntry {
+    fn value(&self) -> SettingValue {
+        match self {
+            SettingEntry::Bool(v) => SettingValue::Bool(v.get()),
+            SettingEntry::Int(v) => SettingValue::Int(v.get()),
+            SettingEntry::Float(v) => SettingValue::Float(v.get() as f64),
+            SettingEntry::Index(v) => SettingValue::Int(v.get() as i64),
+            SettingEntry::Text(v) => SettingValue::Text(v.get()),
+            SettingEntry::Binding(v) => SettingValue::Text(binding_to_text(v.get())),
+        }
+    }
+
+    /// returns false if the value doesn't suit this entry
+    fn apply(&self, value: SettingValue) -> bool {
+        match (self, value) {
+            (SettingEntry::Bool(v), SettingValue::Bool(value)) => v.set(value),
+            (SettingEntry::Int(v), SettingValue::Int(value)) => v.set(value),
+            (SettingEntry::Float(v), SettingValue::Float(value)) => v.set(value as f32),
+            (SettingEntry::Float(v), SettingValue::Int(value)) => v.set(value as f32),
+            (SettingEntry::Index(v), SettingValue::Int(value)) => match value.try_into() {
+                Ok(value) => v.set(value),
+                Err(_) => return false,
+            },
+            (SettingEntry::Text(v), SettingValue::Text(value)) => v.set(value),
+            (SettingEntry::Binding(v), SettingValue::Text(value)) => {
+                match binding_from_text(&value) {
+                    Some(value) => v.set(value),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn version(&self) -> u64 {
+        match self {
+            SettingEntry::Bool(v) => v.version(),
+            SettingEntry::Int(v) => v.version(),
+            SettingEntry::Float(v) => v.version(),
+            SettingEntry::Index(v) => v.version(),
+            SettingEntry::Text(v) => v.version(),
+            SettingEntry::Binding(v) => v.version(),
+        }
+    }
+}
+
+/// file formats for saving and loading settings
+#[cfg(feature = "settings")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFormat {
+    Ron,
+    Json,
+}
+
+#[cfg(feature = "settings")]
+impl SettingsFormat {
+    /// json if the path ends with .json, otherwise ron
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => SettingsFormat::Json,
+            _ => SettingsFormat::Ron,
+        }
+    }
+}
+
+/// named settings. entries are added up front (with their defaults), then
+/// widgets are bound to them by name
+#[derive(Default)]
+pub struct Settings {
+    entries: BTreeMap<String, SettingEntry>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_bool(&mut self, name: impl Into<String>, default: bool) {
+        self.entries
+            .insert(name.into(), SettingEntry::Bool(Observable::new(default)));
+    }
+
+    pub fn add_int(&mut self, name: impl Into<String>, default: i64) {
+        self.entries
+            .insert(name.into(), SettingEntry::Int(Observable::new(default)));
+    }
+
+    pub fn add_float(&mut self, name: impl Into<String>, default: f32) {
+        self.entries
+            .insert(name.into(), SettingEntry::Float(Observable::new(default)));
+    }
+
+    /// a choice from a list (e.g. a dropdown or carousel)
+    pub fn add_index(&mut self, name: impl Into<String>, default: usize) {
+        self.entries
+            .insert(name.into(), SettingEntry::Index(Observable::new(default)));
+    }
+
+    pub fn add_text(&mut self, name: impl Into<String>, default: impl Into<String>) {
+        self.entries.insert(
+            name.into(),
+            SettingEntry::Text(Observable::new(default.into())),
+        );
+    }
+
+    /// for a KeyCaptureButton
+    pub fn add_binding(&mut self, name: impl Into<String>, default: Option<Binding>) {
+        self.entries
+            .insert(name.into(), SettingEntry::Binding(Observable::new(default)));
+    }
+
+    fn entry<'a, T>(
+        &'a self,
+        name: &str,
+        kind: &str,
+        f: impl Fn(&'a SettingEntry) -> Option<&'a Observable<T>>,
+    ) -> UiResult<&'a Observable<T>> {
+        self.entries
+            .get(name)
+            .and_then(f)
+            .ok_or_else(|| UiError::Custom(format!("no {} setting named \"{}\"", kind, name)))
+    }
+
+    pub fn bool(&self, name: &str) -> UiResult<&Observable<bool>> {
+        self.entry(name, "bool", |e| match e {
+            SettingEntry::Bool(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    pub fn int(&self, name: &str) -> UiResult<&Observable<i64>> {
+        self.entry(name, "int", |e| match e {
+            SettingEntry::Int(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    pub fn float(&self, name: &str) -> UiResult<&Observable<f32>> {
+        self.entry(name, "float", |e| match e {
+            SettingEntry::Float(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    pub fn index(&self, name: &str) -> UiResult<&Observable<usize>> {
+        self.entry(name, "index", |e| match e {
+            SettingEntry::Index(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    pub fn text(&self, name: &str) -> UiResult<&Observable<String>> {
+        self.entry(name, "text", |e| match e {
+            SettingEntry::Text(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    pub fn binding(&self, name: &str) -> UiResult<&Observable<Option<Binding>>> {
+        self.entry(name, "binding", |e| match e {
+            SettingEntry::Binding(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// changes each time any setting changes. the application can compare this
+    /// to decide when to save
+    pub fn version(&self) -> u64 {
+        self.entries.values().map(|e| e.version()).sum()
+    }
+
+    /// the current value of each setting
+    pub fn values(&self) -> BTreeMap<String, SettingValue> {
+        self.entries
+            .iter()
+            .map(|(name, e)| (name.clone(), e.value()))
+            .collect()
+    }
+
+    /// set settings from values (e.g. loaded from disk). values for unknown
+    /// settings are ignored, so old files still load. values of the wrong type
+    /// are skipped and reported after the rest are applied
+    pub fn apply(&self, values: BTreeMap<String, SettingValue>) -> UiResult<()> {
+        let mut mismatched: Vec<String> = Vec::new();
+        for (name, value) in values {
+            if let Some(entry) = self.entries.get(&name) {
+                if !entry.apply(value) {
+                    mismatched.push(name);
+                }
+            }
+        }
+        if mismatched.is_empty() {
+            Ok(())
+        } else {
+            Err(UiError::Custom(format!(
+                "invalid values for settings: {}",
+                mismatched.join(", ")
+            )))
+        }
+    }
+
+    #[cfg(feature = "settings")]
+    pub fn to_string(&self, format: SettingsFormat) -> UiResult<String> {
+        let values = self.values();
+        match format {
+            SettingsFormat::Ron => ron::ser::to_string_pretty(&values, Default::default())
+                .map_err(|e| UiError::Custom(e.to_string())),
+            SettingsFormat::Json => {
+                serde_json::to_string_pretty(&values).map_err(|e| UiError::Custom(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(feature = "settings")]
+    pub fn load_str(&self, contents: &str, format: SettingsFormat) -> UiResult<()> {
+        let values: BTreeMap<String, SettingValue> = match format {
+            SettingsFormat::Ron => ron::from_str(contents).map_err(|e| e.to_string())?,
+            SettingsFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string())?,
+        };
+        self.apply(values)
+    }
+
+    /// the format is chosen from the extension (see SettingsFormat::from_path)
+    #[cfg(feature = "settings")]
+    pub fn save(&self, path: &std::path::Path) -> UiResult<()> {
+        let contents = self.to_string(SettingsFormat::from_path(path))?;
+        std::fs::write(path, contents).map_err(|e| UiError::Custom(e.to_string()))
+    }
+
+    /// a missing file isn't an error; the defaults are kept
+    #[cfg(feature = "settings")]
+    pub fn load(&self, path: &std::path::Path) -> UiResult<()> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(UiError::Custom(e.to_string())),
+        };
+        self.load_str(&contents, SettingsFormat::from_path(path))
+    }
+}
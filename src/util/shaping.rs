@@ -0,0 +1,259 @@
+//! text rendering with proper shaping, for scripts which sdl2-ttf renders
+//! incorrectly (ligatures, arabic joining, devanagari conjuncts, etc.).
+//!
+//! the text is shaped by rustybuzz (a port of harfbuzz) and the resulting
+//! glyphs are rasterized by ab_glyph and blitted to a surface. it implements
+//! the same font traits as TextRenderer, so it's chosen per label by giving it
+//! as the label's font interface. it doesn't depend on sdl2-ttf.
+//!
+//! each line is shaped as a single run in the direction guessed from its
+//! text; mixed direction text isn't reordered
+
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    render::TextureCreator,
+    surface::Surface,
+    video::WindowContext,
+};
+
+use ab_glyph::Font;
+
+use super::error::{UiError, UiResult};
+use super::font::{
+    line_range_rects, wrap_ranges, MultiLineFontStyle, SingleLineFontStyle,
+    SingleLineTextRenderType, TextRenderProperties,
+};
+use super::render::{owned_texture, OwnedTexture};
+
+struct ShapedGlyph {
+    id: u16,
+    /// position of the glyph's origin, relative to the line's start and
+    /// baseline
+    x: f32,
+    y: f32,
+}
+
+struct ShapedLine {
+    glyphs: Vec<ShapedGlyph>,
+    width: f32,
+}
+
+/// renders text from a font file's data. cheap to clone
+#[derive(Clone)]
+pub struct ShapedTextRenderer<'sdl> {
+    face: rustybuzz::Face<'sdl>,
+    font: ab_glyph::FontRef<'sdl>,
+}
+
+impl<'sdl> ShapedTextRenderer<'sdl> {
+    /// font_data is the contents of a ttf or otf file read to the end
+    pub fn new(font_data: &'sdl [u8]) -> UiResult<Self> {
+        let face = rustybuzz::Face::from_slice(font_data, 0)
+            .ok_or_else(|| "couldn't parse font for shaping".to_owned())?;
+        let font = ab_glyph::FontRef::try_from_slice(font_data)
+            .map_err(|e| UiError::Font(e.to_string()))?;
+        Ok(Self { face, font })
+    }
+
+    /// pixels per font unit. as sdl2-ttf, the point size is the em size in
+    /// pixels
+    fn px_per_unit(&self, point_size: u16) -> f32 {
+        let units_per_em = self.face.units_per_em() as f32;
+        if units_per_em <= 0. {
+            return 0.; // guard div
+        }
+        point_size as f32 / units_per_em
+    }
+
+    /// distance from the top of a line to its baseline, and the line's height
+    fn line_metrics(&self, point_size: u16) -> (f32, u32) {
+        let s = self.px_per_unit(point_size);
+        let ascent = self.face.ascender() as f32 * s;
+        let descent = self.face.descender() as f32 * s; // negative
+        (ascent, (ascent - descent).ceil().max(1.) as u32)
+    }
+
+    fn shape(&self, text: &str, point_size: u16) -> ShapedLine {
+        let s = self.px_per_unit(point_size);
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(&self.face, &[], buffer);
+
+        let mut pen = 0.;
+        let glyphs = output
+            .glyph_infos()
+            .iter()
+            .zip(output.glyph_positions())
+            .map(|(info, position)| {
+                let glyph = ShapedGlyph {
+                    id: info.glyph_id as u16,
+                    x: pen + position.x_offset as f32 * s,
+                    y: -position.y_offset as f32 * s,
+                };
+                pen += position.x_advance as f32 * s;
+                glyph
+            })
+            .collect();
+        ShapedLine { glyphs, width: pen }
+    }
+
+    /// rasterize lines, one below the other
+    fn render_lines(
+        &self,
+        lines: &[ShapedLine],
+        point_size: u16,
+        render_type: SingleLineTextRenderType,
+    ) -> UiResult<Surface<'static>> {
+        let (ascent, line_height) = self.line_metrics(point_size);
+        let width = lines
+            .iter()
+            .map(|line| line.width.ceil() as u32)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let height = line_height * lines.len().max(1) as u32;
+
+        // overlapping glyphs (e.g. marks) keep the higher coverage
+        let mut coverage = vec![0f32; width as usize * height as usize];
+        if let Some(scale) = self.font.pt_to_px_scale(point_size as f32) {
+            for (i, line) in lines.iter().enumerate() {
+                let baseline = ascent + (i as u32 * line_height) as f32;
+                for glyph in line.glyphs.iter() {
+                    let outlined = match self.font.outline_glyph(
+                        ab_glyph::GlyphId(glyph.id).with_scale_and_position(
+                            scale,
+                            ab_glyph::point(glyph.x, baseline + glyph.y),
+                        ),
+                    ) {
+                        Some(v) => v,
+                        None => continue, // e.g. a space
+                    };
+                    let bounds = outlined.px_bounds();
+                    outlined.draw(|x, y, c| {
+                        let x = bounds.min.x as i32 + x as i32;
+                        let y = bounds.min.y as i32 + y as i32;
+                        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                            return;
+                        }
+                        let cell = &mut coverage[y as usize * width as usize + x as usize];
+                        *cell = cell.max(c);
+                    });
+                }
+            }
+        }
+
+        let mut surface =
+            Surface::new(width, height, PixelFormatEnum::ARGB8888).map_err(UiError::Sdl)?;
+        let pitch = surface.pitch() as usize;
+        surface.with_lock_mut(|buffer| {
+            for y in 0..height as usize {
+                for x in 0..width as usize {
+                    let c = coverage[y * width as usize + x].clamp(0., 1.);
+                    let color = coverage_color(render_type, c);
+                    let i = y * pitch + x * 4;
+                    buffer[i] = color.b;
+                    buffer[i + 1] = color.g;
+                    buffer[i + 2] = color.r;
+                    buffer[i + 3] = color.a;
+                }
+            }
+        });
+        Ok(surface)
+    }
+
+    fn texture_from_surface(
+        surface: Surface,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        let mut texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map(owned_texture)?;
+        crate::util::perf::count(crate::util::perf::Counter::TextureCreation);
+        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+        Ok(texture)
+    }
+}
+
+/// the color of a pixel given how much of it is covered by a glyph
+fn coverage_color(render_type: SingleLineTextRenderType, coverage: f32) -> Color {
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * coverage).round() as u8;
+    match render_type {
+        #[allow(deprecated)]
+        SingleLineTextRenderType::Solid(color) => {
+            if coverage >= 0.5 {
+                color
+            } else {
+                Color::RGBA(0, 0, 0, 0)
+            }
+        }
+        SingleLineTextRenderType::Shaded(color, background) => Color::RGBA(
+            lerp(background.r, color.r),
+            lerp(background.g, color.g),
+            lerp(background.b, color.b),
+            lerp(background.a, color.a),
+        ),
+        SingleLineTextRenderType::Blended(color) => Color::RGBA(
+            color.r,
+            color.g,
+            color.b,
+            (color.a as f32 * coverage).round() as u8,
+        ),
+    }
+}
+
+impl<'sdl> SingleLineFontStyle<'sdl> for ShapedTextRenderer<'sdl> {
+    fn render(
+        &mut self,
+        text: &str,
+        properties: &TextRenderProperties,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        let line = self.shape(text, properties.point_size);
+        let surface = self.render_lines(&[line], properties.point_size, properties.render_type)?;
+        Self::texture_from_surface(surface, texture_creator)
+    }
+
+    fn render_dimensions(&mut self, text: &str, point_size: u16) -> UiResult<(u32, u32)> {
+        let (_, line_height) = self.line_metrics(point_size);
+        let width = self.shape(text, point_size).width.ceil() as u32;
+        Ok((width, line_height))
+    }
+
+    fn dup(&self) -> Box<dyn SingleLineFontStyle<'sdl> + 'sdl> {
+        Box::new(self.clone())
+    }
+}
+
+impl<'sdl> MultiLineFontStyle<'sdl> for ShapedTextRenderer<'sdl> {
+    fn render(
+        &mut self,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        wrap_width: u32,
+        texture_creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<OwnedTexture<'sdl>> {
+        let width = |text: &str| Ok(self.shape(text, point_size).width.ceil() as u32);
+        let lines: Vec<ShapedLine> = wrap_ranges(text, wrap_width, width)?
+            .into_iter()
+            .map(|range| self.shape(&text[range], point_size))
+            .collect();
+        let surface =
+            self.render_lines(&lines, point_size, SingleLineTextRenderType::Blended(color))?;
+        Self::texture_from_surface(surface, texture_creator)
+    }
+
+    fn range_rects(
+        &mut self,
+        text: &str,
+        point_size: u16,
+        wrap_width: u32,
+        range: std::ops::Range<usize>,
+    ) -> UiResult<Vec<sdl2::rect::Rect>> {
+        let (_, line_height) = self.line_metrics(point_size);
+        let width = |text: &str| Ok(self.shape(text, point_size).width.ceil() as u32);
+        let lines = wrap_ranges(text, wrap_width, width)?;
+        line_range_rects(text, &lines, line_height, line_height, range, width)
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_from_other_thread() {
+        let state = SharedState::new(0u32);
+        assert!(!state.take_changed());
+        let other = state.clone();
+        std::thread::spawn(move || other.set(5)).join().unwrap();
+        assert_eq!(state.get(), 5);
+        assert!(state.take_changed());
+        assert!(!state.take_changed());
+    }
+
+    #[test]
+    fn take_leaves_default() {
+        let state = SharedState::new("hello".to_owned());
+        assert_eq!(state.take(), "hello");
+        assert_eq!(state.get(), "");
+        state.update(|s| s.push('a'));
+        assert_eq!(state.replace("b".to_owned()), "a");
+        assert!(state.take_changed());
+    }
+}
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+/// a value which can be changed from any thread (e.g. a download's status, or
+/// a game simulation's score), and shown by widgets on the ui thread. the
+/// thread-safe counterpart to a Cell.
+///
+/// clones refer to the same value. widgets accept it via CellRefOrCell
+///
+/// changes from other threads don't wake the ui. the application should check
+/// take_changed (e.g. each time it would wait for events), and redraw if true
+pub struct SharedState<T> {
+    value: Arc<RwLock<T>>,
+    changed: Arc<AtomicBool>,
+}
+
+impl<T> Clone for SharedState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            changed: self.changed.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for SharedState<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> SharedState<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Arc::new(RwLock::new(value)),
+            changed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // a panic while holding the lock doesn't leave the value in an invalid
+    // state (it's only ever replaced or modified by the caller), so poisoning
+    // is ignored
+
+    fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.value.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.value.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.write() = value;
+        self.changed.store(true, Ordering::Release);
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        let old = std::mem::replace(&mut *self.write(), value);
+        self.changed.store(true, Ordering::Release);
+        old
+    }
+
+    /// modify the value in place
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.write());
+        self.changed.store(true, Ordering::Release);
+    }
+
+    /// call f with a reference to the value. the lock is held during the call
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, f: F) -> R {
+        f(&self.read())
+    }
+
+    /// has the value been set since the last call. this is shared between all
+    /// clones; it should only be checked by one place (typically the
+    /// application's main loop)
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Acquire)
+    }
+}
+
+impl<T: Clone> SharedState<T> {
+    pub fn get(&self) -> T {
+        self.read().clone()
+    }
+}
+
+impl<T: Default> SharedState<T> {
+    /// take the value, leaving the default in its place. this doesn't count as
+    /// a change
+    pub fn take(&self) -> T {
+        std::mem::take(&mut *self.write())
+    }
+}
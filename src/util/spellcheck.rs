@@ -0,0 +1,187 @@
+//! spell check decorations. the application decides what's misspelled (e.g.
+//! with a dictionary); text widgets underline those ranges with a squiggle and
+//! report which one is under the caret or mouse, e.g. for a suggestion popup
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_at_index() {
+        let ranges = [0..3, 5..9];
+        assert_eq!(range_at(&ranges, 0), Some(0..3));
+        // the caret right after a word is still on it
+        assert_eq!(range_at(&ranges, 3), Some(0..3));
+        assert_eq!(range_at(&ranges, 4), None);
+        assert_eq!(range_at(&ranges, 9), Some(5..9));
+        assert_eq!(range_at(&ranges, 10), None);
+    }
+
+    #[test]
+    fn checked_once() {
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let calls_dup = calls.clone();
+        let mut spell_check = SpellCheck::new(Box::new(move |text: &str| {
+            calls_dup.set(calls_dup.get() + 1);
+            // empty, out of bounds and non char boundary ranges are dropped
+            vec![3..text.len(), 0..2, 3..6, 6..9, 2..2]
+        }));
+        assert_eq!(spell_check.misspelled("teh cät"), &[0..2, 3..8]);
+        assert_eq!(spell_check.misspelled("teh cät"), &[0..2, 3..8]);
+        assert_eq!(calls.get(), 1);
+        spell_check.misspelled("teh");
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn located_once() {
+        let mut spell_check = SpellCheck::new(Box::new(|_: &str| std::iter::once(0..3).collect()));
+        let mut calls = 0;
+        let mut locate = |range: Range<usize>| {
+            calls += 1;
+            Ok(vec![Rect::new(
+                range.start as i32,
+                0,
+                range.len() as u32,
+                10,
+            )])
+        };
+        let located = spell_check.located("teh", (12, 0, 0), &mut locate).unwrap();
+        assert_eq!(located, &[(0..3, Rect::new(0, 0, 3, 10))]);
+        spell_check.located("teh", (12, 0, 0), &mut locate).unwrap();
+        // relocated when the layout changes
+        spell_check.located("teh", (14, 0, 0), &mut locate).unwrap();
+        assert_eq!(calls, 2);
+    }
+}
+
+use std::ops::Range;
+
+use sdl2::{
+    pixels::Color,
+    rect::{Point, Rect},
+    render::Canvas,
+    video::Window,
+};
+
+use crate::util::error::{UiError, UiResult};
+
+/// decides which parts of some text are misspelled
+pub trait SpellChecker {
+    /// byte ranges of the text to underline
+    fn check(&mut self, text: &str) -> Vec<Range<usize>>;
+}
+
+impl<F: FnMut(&str) -> Vec<Range<usize>>> SpellChecker for F {
+    fn check(&mut self, text: &str) -> Vec<Range<usize>> {
+        self(text)
+    }
+}
+
+/// what the rects of misspelled ranges depend on: point size, wrap width and
+/// style generation
+type Layout = (u16, u32, u64);
+
+/// a misspelled range, and a rect covering (part of) it
+type LocatedRange = (Range<usize>, Rect);
+
+/// a text widget's spell checking. the checker is only run when the text
+/// changes
+pub struct SpellCheck<'state> {
+    pub checker: Box<dyn SpellChecker + 'state>,
+    /// color of the squiggle
+    pub color: Color,
+    /// the text which was checked, and its misspelled ranges
+    checked: Option<(String, Vec<Range<usize>>)>,
+    /// the layout the misspelled ranges were located in, and the rects
+    /// covering each
+    located: Option<(Layout, Vec<LocatedRange>)>,
+}
+
+impl<'state> SpellCheck<'state> {
+    pub fn new(checker: Box<dyn SpellChecker + 'state>) -> Self {
+        Self {
+            checker,
+            color: Color::RGB(230, 50, 50),
+            checked: None,
+            located: None,
+        }
+    }
+
+    /// the misspelled ranges of the text, in order. ranges which are empty,
+    /// out of bounds or not on char boundaries are ignored
+    pub fn misspelled(&mut self, text: &str) -> &[Range<usize>] {
+        let checked = match self.checked.take().filter(|checked| checked.0 == text) {
+            Some(v) => v,
+            None => {
+                let mut ranges: Vec<Range<usize>> = self
+                    .checker
+                    .check(text)
+                    .into_iter()
+                    .filter(|range| !range.is_empty() && text.get(range.clone()).is_some())
+                    .collect();
+                ranges.sort_by_key(|range| range.start);
+                self.located = None;
+                (text.to_owned(), ranges)
+            }
+        };
+        &self.checked.insert(checked).1
+    }
+
+    /// the misspelled ranges of the text, with the rects covering each
+    /// (relative to the rendered text). layout is what the rects depend on
+    /// (point size, wrap width and style generation). locate is only called
+    /// when the text or layout changes
+    pub(crate) fn located(
+        &mut self,
+        text: &str,
+        layout: Layout,
+        mut locate: impl FnMut(Range<usize>) -> UiResult<Vec<Rect>>,
+    ) -> UiResult<&[LocatedRange]> {
+        self.misspelled(text);
+        let located = match self.located.take().filter(|located| located.0 == layout) {
+            Some(v) => v,
+            None => {
+                let mut rects = Vec::new();
+                for range in self.checked.iter().flat_map(|checked| checked.1.iter()) {
+                    for rect in locate(range.clone())? {
+                        rects.push((range.clone(), rect));
+                    }
+                }
+                (layout, rects)
+            }
+        };
+        Ok(&self.located.insert(located).1)
+    }
+}
+
+/// the range containing an index. a range's end is included, so a caret just
+/// after a word is on it
+pub fn range_at(ranges: &[Range<usize>], index: usize) -> Option<Range<usize>> {
+    ranges
+        .iter()
+        .find(|range| range.start <= index && index <= range.end)
+        .cloned()
+}
+
+/// draw a squiggly underline from x to x_end, with its bottom at y
+pub fn draw_squiggle(
+    canvas: &mut Canvas<Window>,
+    color: Color,
+    x: i32,
+    x_end: i32,
+    y: i32,
+) -> UiResult<()> {
+    let amplitude = (crate::util::scale::scaled(2.).round() as i32).max(1);
+    let mut points: Vec<Point> = Vec::new();
+    let mut point_x = x;
+    let mut up = false;
+    while point_x < x_end {
+        points.push(Point::new(point_x, if up { y - amplitude } else { y }));
+        point_x += amplitude;
+        up = !up;
+    }
+    points.push(Point::new(x_end, if up { y - amplitude } else { y }));
+    canvas.set_draw_color(color);
+    canvas.draw_lines(points.as_slice()).map_err(UiError::Sdl)
+}
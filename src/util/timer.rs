@@ -0,0 +1,328 @@
+//! callbacks which run after a delay, once or repeatedly (e.g. tooltip delays,
+//! caret blinking, debouncing, toast timeouts). the queue is owned by the
+//! application and referenced by whatever schedules on it. due callbacks are
+//! run by update_gui_with_timers, before the widgets are updated
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter() -> (Rc<Cell<u32>>, TimerCallback<'static>) {
+        let count = Rc::new(Cell::new(0));
+        let count_dup = count.clone();
+        (
+            count,
+            Box::new(move || {
+                count_dup.set(count_dup.get() + 1);
+                Ok(())
+            }),
+        )
+    }
+
+    #[test]
+    fn once_and_repeating() {
+        let timers = TimerQueue::new();
+        let start = Instant::now();
+        let (once, callback) = counter();
+        let once_id = timers.once(Duration::from_millis(100), callback);
+        let (repeating, callback) = counter();
+        timers.repeating(Duration::from_millis(40), callback);
+
+        timers.process(start).unwrap();
+        assert_eq!((once.get(), repeating.get()), (0, 0));
+        timers.process(start + Duration::from_millis(50)).unwrap();
+        assert_eq!((once.get(), repeating.get()), (0, 1));
+        // fell behind; runs once
+        timers.process(start + Duration::from_millis(500)).unwrap();
+        assert_eq!((once.get(), repeating.get()), (1, 2));
+        assert!(!timers.is_scheduled(once_id));
+        assert_eq!(timers.len(), 1);
+    }
+
+    #[test]
+    fn cancel_and_restart() {
+        let timers = TimerQueue::new();
+        let (count, callback) = counter();
+        let id = timers.once(Duration::from_millis(100), callback);
+        assert!(timers.cancel(id));
+        assert!(!timers.cancel(id));
+        timers
+            .process(Instant::now() + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(count.get(), 0);
+
+        let (count, callback) = counter();
+        let id = timers.once(Duration::from_millis(100), callback);
+        set_frame_time(Instant::now() + Duration::from_millis(80));
+        assert!(timers.restart(id));
+        timers
+            .process(frame_time() + Duration::from_millis(50))
+            .unwrap();
+        assert_eq!(count.get(), 0);
+        timers
+            .process(frame_time() + Duration::from_millis(100))
+            .unwrap();
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn cancelled_by_earlier_callback() {
+        let timers = Rc::new(TimerQueue::new());
+        let (count, callback) = counter();
+        let second = Rc::new(Cell::new(None));
+        let timers_dup = timers.clone();
+        let second_dup = second.clone();
+        timers.once(
+            Duration::ZERO,
+            Box::new(move || {
+                if let Some(id) = second_dup.get() {
+                    timers_dup.cancel(id);
+                }
+                Ok(())
+            }),
+        );
+        second.set(Some(timers.once(Duration::from_millis(1), callback)));
+        timers
+            .process(Instant::now() + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(count.get(), 0);
+        assert!(timers.is_empty());
+    }
+
+    #[test]
+    fn debounce() {
+        let timers = TimerQueue::new();
+        let (count, callback) = counter();
+        let mut debounce = Debounce::new(&timers, Duration::from_millis(100), callback);
+        let start = Instant::now();
+        set_frame_time(start);
+        debounce.changed();
+        set_frame_time(start + Duration::from_millis(80));
+        debounce.changed();
+        timers.process(start + Duration::from_millis(150)).unwrap();
+        assert_eq!(count.get(), 0);
+        assert!(debounce.pending());
+        timers.process(start + Duration::from_millis(200)).unwrap();
+        assert_eq!(count.get(), 1);
+        assert!(!debounce.pending());
+    }
+}
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::util::error::UiResult;
+
+// set once per frame, so everything in the frame sees the same time
+thread_local! {
+    static FRAME_TIME: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// when the current frame's update started. before the first frame, the
+/// current time
+pub fn frame_time() -> Instant {
+    FRAME_TIME.with(|t| t.get()).unwrap_or_else(Instant::now)
+}
+
+pub(crate) fn set_frame_time(time: Instant) {
+    FRAME_TIME.with(|t| t.set(Some(time)));
+}
+
+pub type TimerCallback<'a> = Box<dyn FnMut() -> UiResult<()> + 'a>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+struct Timer<'a> {
+    id: TimerId,
+    due: Instant,
+    /// the interval, if repeating
+    repeat: Option<Duration>,
+    /// the delay it was scheduled with, for restart
+    delay: Duration,
+    callback: TimerCallback<'a>,
+}
+
+/// scheduled callbacks. a callback can schedule or cancel timers (including
+/// its own)
+#[derive(Default)]
+pub struct TimerQueue<'a> {
+    timers: RefCell<Vec<Timer<'a>>>,
+    /// timers taken out of the queue to be run, which haven't finished yet
+    processing: RefCell<Vec<TimerId>>,
+    next_id: Cell<u64>,
+}
+
+impl<'a> TimerQueue<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn schedule(
+        &self,
+        delay: Duration,
+        repeat: Option<Duration>,
+        callback: TimerCallback<'a>,
+    ) -> TimerId {
+        let id = TimerId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.timers.borrow_mut().push(Timer {
+            id,
+            due: frame_time() + delay,
+            repeat,
+            delay,
+            callback,
+        });
+        id
+    }
+
+    /// run the callback once, after a delay
+    pub fn once(&self, delay: Duration, callback: TimerCallback<'a>) -> TimerId {
+        self.schedule(delay, None, callback)
+    }
+
+    /// run the callback every interval, until cancelled
+    pub fn repeating(&self, interval: Duration, callback: TimerCallback<'a>) -> TimerId {
+        self.schedule(interval, Some(interval), callback)
+    }
+
+    /// start a timer's delay over (e.g. to debounce: restart on each change,
+    /// so the callback runs once changes stop). returns false if it isn't
+    /// scheduled, or is currently running
+    pub fn restart(&self, id: TimerId) -> bool {
+        match self.timers.borrow_mut().iter_mut().find(|t| t.id == id) {
+            Some(timer) => {
+                timer.due = frame_time() + timer.delay;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// returns false if it wasn't scheduled
+    pub fn cancel(&self, id: TimerId) -> bool {
+        let mut timers = self.timers.borrow_mut();
+        let len = timers.len();
+        timers.retain(|t| t.id != id);
+        if timers.len() != len {
+            return true;
+        }
+        let mut processing = self.processing.borrow_mut();
+        let len = processing.len();
+        processing.retain(|processing_id| *processing_id != id);
+        processing.len() != len
+    }
+
+    pub fn is_scheduled(&self, id: TimerId) -> bool {
+        self.timers.borrow().iter().any(|t| t.id == id) || self.processing.borrow().contains(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.borrow().len() + self.processing.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// time until the next timer is due (zero if overdue). an application
+    /// which waits for events should wait at most this long
+    pub fn until_next(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.timers
+            .borrow()
+            .iter()
+            .map(|t| t.due.saturating_duration_since(now))
+            .min()
+    }
+
+    /// run the callbacks of timers which are due, in the order they're due. a
+    /// repeating timer which fell several intervals behind runs once. every
+    /// due callback runs; the first error is given after
+    pub fn process(&self, now: Instant) -> UiResult<()> {
+        let mut due: Vec<Timer<'a>> = {
+            let mut timers = self.timers.borrow_mut();
+            let (due, pending) = std::mem::take(&mut *timers)
+                .into_iter()
+                .partition(|t| t.due <= now);
+            *timers = pending;
+            due
+        };
+        due.sort_by_key(|t| t.due);
+        *self.processing.borrow_mut() = due.iter().map(|t| t.id).collect();
+
+        let mut result = Ok(());
+        for mut timer in due {
+            if !self.processing.borrow().contains(&timer.id) {
+                continue; // cancelled by an earlier callback
+            }
+            let r = (timer.callback)();
+            if result.is_ok() {
+                result = r;
+            }
+            let mut processing = self.processing.borrow_mut();
+            let len = processing.len();
+            processing.retain(|id| *id != timer.id);
+            if processing.len() == len {
+                continue; // cancelled by its own callback
+            }
+            if let Some(interval) = timer.repeat {
+                timer.due += interval;
+                if timer.due <= now {
+                    timer.due = now + interval;
+                }
+                self.timers.borrow_mut().push(timer);
+            }
+        }
+        result
+    }
+}
+
+/// runs a callback once a value has stopped changing for a while (e.g. a
+/// search query, once typing stops)
+pub struct Debounce<'t, 'a> {
+    /// how long the value must be unchanged
+    pub delay: Duration,
+    timers: &'t TimerQueue<'a>,
+    timer: Option<TimerId>,
+    callback: Rc<RefCell<TimerCallback<'a>>>,
+}
+
+impl<'t, 'a> Debounce<'t, 'a> {
+    pub fn new(timers: &'t TimerQueue<'a>, delay: Duration, callback: TimerCallback<'a>) -> Self {
+        Self {
+            delay,
+            timers,
+            timer: None,
+            callback: Rc::new(RefCell::new(callback)),
+        }
+    }
+
+    /// the value changed. the callback runs after the delay, unless it changes
+    /// again first
+    pub fn changed(&mut self) {
+        self.cancel();
+        let callback = self.callback.clone();
+        self.timer = Some(
+            self.timers
+                .once(self.delay, Box::new(move || (callback.borrow_mut())())),
+        );
+    }
+
+    /// if the callback is waiting to run
+    pub fn pending(&self) -> bool {
+        self.timer
+            .map(|timer| self.timers.is_scheduled(timer))
+            .unwrap_or(false)
+    }
+
+    /// don't run the callback for the most recent change
+    pub fn cancel(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            self.timers.cancel(timer);
+        }
+    }
+}
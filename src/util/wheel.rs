@@ -0,0 +1,111 @@
+//! the mouse wheel changing a value (e.g. a number, or cycling through a list
+//! of choices). the wheel is only used while the mouse is directly over the
+//! control, and it's then kept from any scroller containing the control. this
+//! way, scrolling a list past a control doesn't change the control's value
+//! unless the wheel is used while over it
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_steps() {
+        let mut remainder = 0.;
+        assert_eq!(accumulate(1., &mut remainder), 1);
+        assert_eq!(accumulate(-2., &mut remainder), -2);
+        // e.g. a trackpad gives fractions of a notch
+        assert_eq!(accumulate(0.4, &mut remainder), 0);
+        assert_eq!(accumulate(0.4, &mut remainder), 0);
+        assert_eq!(accumulate(0.4, &mut remainder), 1);
+        assert!((remainder - 0.2).abs() < 1e-5);
+        // changing direction drops what was left over
+        assert_eq!(accumulate(-0.5, &mut remainder), 0);
+        assert!((remainder + 0.5).abs() < 1e-5);
+    }
+}
+
+use sdl2::{keyboard::Mod, mouse::MouseWheelDirection};
+
+use crate::widget::WidgetUpdateEvent;
+
+use super::hit::HitTest;
+
+/// add a wheel amount (in notches) to what was left over from before, giving
+/// the whole number of steps
+fn accumulate(amount: f32, remainder: &mut f32) -> i32 {
+    if amount * *remainder < 0. {
+        *remainder = 0.;
+    }
+    let total = amount + *remainder;
+    *remainder = total.fract();
+    total.trunc() as i32
+}
+
+/// turns the mouse wheel into steps of a value. held by the value widget
+pub struct WheelSteps {
+    /// steps per notch of the wheel
+    pub steps_per_notch: f32,
+    /// while held, each notch is instead this many times as many steps
+    pub fast_modifier: Mod,
+    pub fast_multiplier: f32,
+    /// if the control must also be focused for the wheel to change it.
+    /// otherwise hovering is enough
+    pub require_focus: bool,
+    /// fraction of a step left over, from precise wheels (e.g. trackpads)
+    remainder: f32,
+}
+
+impl Default for WheelSteps {
+    fn default() -> Self {
+        Self {
+            steps_per_notch: 1.,
+            fast_modifier: Mod::LSHIFTMOD | Mod::RSHIFTMOD,
+            fast_multiplier: 10.,
+            require_focus: false,
+            remainder: 0.,
+        }
+    }
+}
+
+impl WheelSteps {
+    /// consume the wheel events over the control, giving the number of steps
+    /// to change the value by (positive for wheel up). the value widget
+    /// multiplies this by its own step
+    pub fn update(&mut self, event: &mut WidgetUpdateEvent, hit: &HitTest, focused: bool) -> i32 {
+        if self.require_focus && !focused {
+            self.remainder = 0.;
+            return 0;
+        }
+        let multiplier = if event.input.keymod.intersects(self.fast_modifier) {
+            self.steps_per_notch * self.fast_multiplier
+        } else {
+            self.steps_per_notch
+        };
+        let mut steps = 0;
+        for e in event.events.iter_mut().filter(|e| e.available()) {
+            let (precise_y, direction) = match e.e {
+                sdl2::event::Event::MouseWheel {
+                    precise_y,
+                    direction,
+                    ..
+                } => (precise_y, direction),
+                _ => continue,
+            };
+            if !hit.was_hit(&e.e) {
+                continue;
+            }
+            let direction_multiplier = match direction {
+                MouseWheelDirection::Flipped => -1.,
+                _ => 1.,
+            };
+            // a scroller also looks at events consumed by widgets (for nested
+            // scrollers). this one is the control's alone
+            e.set_consumed_by_layout();
+            steps += accumulate(
+                precise_y * direction_multiplier * multiplier,
+                &mut self.remainder,
+            );
+        }
+        steps
+    }
+}
@@ -0,0 +1,157 @@
+//! word boundaries in text, for moving the caret or deleting by word and for
+//! selecting a word by double clicking
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving() {
+        let words = WordBoundaries::default();
+        let text = "hello,  wörld! ok";
+        // from the end of a word to the start of it
+        assert_eq!(words.previous(text, 5), 0);
+        // punctuation is its own word, and whitespace is skipped
+        assert_eq!(words.previous(text, 8), 5);
+        assert_eq!(words.previous(text, text.len()), 16);
+        assert_eq!(words.previous(text, 0), 0);
+        assert_eq!(words.next(text, 0), 5);
+        assert_eq!(words.next(text, 5), 6);
+        assert_eq!(words.next(text, 6), 14);
+        assert_eq!(words.next(text, text.len()), text.len());
+    }
+
+    #[test]
+    fn whitespace_only() {
+        let words = WordBoundaries {
+            classify: whitespace_separated,
+        };
+        let text = "a.b c";
+        assert_eq!(words.previous(text, 3), 0);
+        assert_eq!(words.next(text, 0), 3);
+    }
+
+    #[test]
+    fn word_at_index() {
+        let words = WordBoundaries::default();
+        let text = "ab, cd";
+        assert_eq!(words.word_at(text, 0), 0..2);
+        assert_eq!(words.word_at(text, 2), 2..3);
+        assert_eq!(words.word_at(text, 3), 3..4);
+        // the end is in the last word
+        assert_eq!(words.word_at(text, text.len()), 4..6);
+        assert_eq!(words.word_at("", 0), 0..0);
+    }
+}
+
+use std::ops::Range;
+
+/// what kind of character, for finding word boundaries. a word is a run of
+/// characters of the same class, other than whitespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Whitespace,
+    Punctuation,
+    Word,
+}
+
+/// words are separated by whitespace, and punctuation is a separate word
+pub fn whitespace_and_punctuation(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// words are separated by whitespace only
+pub fn whitespace_separated(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else {
+        CharClass::Word
+    }
+}
+
+/// finds the boundaries of words. indices are byte indices in the text, on
+/// char boundaries
+#[derive(Debug, Clone, Copy)]
+pub struct WordBoundaries {
+    pub classify: fn(char) -> CharClass,
+}
+
+impl Default for WordBoundaries {
+    fn default() -> Self {
+        Self {
+            classify: whitespace_and_punctuation,
+        }
+    }
+}
+
+impl WordBoundaries {
+    /// the start of the word before an index, skipping whitespace. e.g. for
+    /// ctrl+left or ctrl+backspace
+    pub fn previous(&self, text: &str, index: usize) -> usize {
+        let mut chars = text[..index]
+            .char_indices()
+            .rev()
+            .skip_while(|(_, c)| (self.classify)(*c) == CharClass::Whitespace)
+            .peekable();
+        let class = match chars.peek() {
+            Some((_, c)) => (self.classify)(*c),
+            None => return 0,
+        };
+        chars
+            .take_while(|(_, c)| (self.classify)(*c) == class)
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// the end of the word after an index, skipping whitespace. e.g. for
+    /// ctrl+right or ctrl+delete
+    pub fn next(&self, text: &str, index: usize) -> usize {
+        let mut chars = text[index..]
+            .char_indices()
+            .map(|(i, c)| (index + i, c))
+            .skip_while(|(_, c)| (self.classify)(*c) == CharClass::Whitespace)
+            .peekable();
+        let class = match chars.peek() {
+            Some((_, c)) => (self.classify)(*c),
+            None => return text.len(),
+        };
+        chars
+            .find(|(_, c)| (self.classify)(*c) != class)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len())
+    }
+
+    /// the run of characters of the same class containing an index (the
+    /// character after it, or before it at the end of the text). e.g. for
+    /// selecting a word by double clicking
+    pub fn word_at(&self, text: &str, index: usize) -> Range<usize> {
+        let c = match text[index..].chars().next() {
+            Some(c) => c,
+            None => match text[..index].chars().next_back() {
+                Some(c) => return self.word_at(text, index - c.len_utf8()),
+                None => return index..index,
+            },
+        };
+        let class = (self.classify)(c);
+        let start = text[..index]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| (self.classify)(*c) == class)
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(index);
+        let end = text[index..]
+            .char_indices()
+            .find(|(_, c)| (self.classify)(*c) != class)
+            .map(|(i, _)| index + i)
+            .unwrap_or(text.len());
+        start..end
+    }
+}
@@ -0,0 +1,149 @@
+use sdl2::event::Event;
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    hit::{AlphaMask, HitTest},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+};
+
+use super::{texture::AspectRatioFailPolicy, SDLEvent, Widget, WidgetUpdateEvent};
+
+/// the contained only registers the mouse where the image is opaque (e.g. a
+/// round button, or an irregularly shaped texture). over transparent parts,
+/// mouse events pass through to the widgets behind.
+///
+/// the mask and policy should match how the image is drawn: for a texture
+/// widget its texture_src and aspect_ratio_fail_policy, and for a button with
+/// texture variants the default policy
+pub struct AlphaHit<'sdl> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub mask: &'sdl AlphaMask,
+    /// none means the entire mask
+    pub mask_src: Option<sdl2::rect::Rect>,
+    pub aspect_ratio_fail_policy: AspectRatioFailPolicy,
+}
+
+impl<'sdl> AlphaHit<'sdl> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, mask: &'sdl AlphaMask) -> Self {
+        Self {
+            contained,
+            mask,
+            mask_src: None,
+            aspect_ratio_fail_policy: Default::default(),
+        }
+    }
+
+    /// the event with its window changed, so no widget's hit test matches it
+    fn missed(e: &Event) -> Option<Event> {
+        let mut e = e.clone();
+        match &mut e {
+            Event::MouseMotion { window_id, .. }
+            | Event::MouseButtonDown { window_id, .. }
+            | Event::MouseButtonUp { window_id, .. }
+            | Event::MouseWheel { window_id, .. } => *window_id = 0,
+            _ => return None,
+        }
+        Some(e)
+    }
+}
+
+impl<'sdl> Widget for AlphaHit<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let position = event.position;
+        let hit_test = HitTest::from_event(&event);
+        // the events given to the contained, and the index of the event each
+        // came from
+        let mut forwarded: Vec<SDLEvent> = Vec::new();
+        let mut origin: Vec<usize> = Vec::new();
+        for (i, sdl_event) in event.events.iter_mut().enumerate() {
+            if sdl_event.consumed() {
+                continue;
+            }
+            let transparent = hit_test.hit(&sdl_event.e).is_some_and(|(x, y)| {
+                !self.mask.opaque_at(
+                    self.mask_src,
+                    &self.aspect_ratio_fail_policy,
+                    position,
+                    x,
+                    y,
+                )
+            });
+            let missed = if transparent {
+                Self::missed(&sdl_event.e)
+            } else {
+                None
+            };
+            forwarded.push(SDLEvent::new(missed.unwrap_or_else(|| sdl_event.e.clone())));
+            origin.push(i);
+        }
+
+        let mut sub_event = event.sub_event(position);
+        sub_event.events = forwarded.as_mut_slice();
+        let r = self.contained.update(sub_event);
+
+        // whatever the contained consumed is consumed outside as well
+        for (forwarded_event, i) in forwarded.iter().zip(origin) {
+            if forwarded_event.consumed() {
+                event.events[i].consumed_status = forwarded_event.consumed_status;
+            }
+        }
+        r
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}
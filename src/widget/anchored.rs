@@ -0,0 +1,129 @@
+use sdl2::render::{ClippingRect, WindowCanvas};
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MinLen},
+    rect::FRect,
+    render::CanvasStateGuard,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// where in the window an anchored widget is placed. each component is from 0
+/// to 1 (inclusive), where 0 aligns in the negative direction (left, top) and 1
+/// aligns in the positive direction (right, bottom)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Anchor {
+    pub const TOP_LEFT: Anchor = Anchor { x: 0., y: 0. };
+    pub const TOP: Anchor = Anchor { x: 0.5, y: 0. };
+    pub const TOP_RIGHT: Anchor = Anchor { x: 1., y: 0. };
+    pub const LEFT: Anchor = Anchor { x: 0., y: 0.5 };
+    pub const CENTER: Anchor = Anchor { x: 0.5, y: 0.5 };
+    pub const RIGHT: Anchor = Anchor { x: 1., y: 0.5 };
+    pub const BOTTOM_LEFT: Anchor = Anchor { x: 0., y: 1. };
+    pub const BOTTOM: Anchor = Anchor { x: 0.5, y: 1. };
+    pub const BOTTOM_RIGHT: Anchor = Anchor { x: 1., y: 1. };
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::TOP_LEFT
+    }
+}
+
+/// places the contained widget relative to the window instead of where the
+/// parent layout puts it (e.g. a floating action button in the bottom right).
+/// within the parent layout, it takes up no space.
+///
+/// the contained widget is drawn in the overlay pass (see draw_gui), so it's
+/// on top of everything else. it's sized as if the window (less the margin) is
+/// its parent. it doesn't move with scrolling.
+///
+/// like other overlays, it's only guaranteed to get mouse events first if it's
+/// updated before whatever it covers
+pub struct Anchored<'sdl> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub anchor: Anchor,
+    /// space kept between the contained and the window's edges. in logical
+    /// pixels (scaled by the ui scale)
+    pub margin: f32,
+    /// applied after anchoring, in logical pixels (scaled by the ui scale).
+    /// for an absolute position, use Anchor::TOP_LEFT with no margin
+    pub offset: (f32, f32),
+}
+
+impl<'sdl> Anchored<'sdl> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, anchor: Anchor) -> Self {
+        Self {
+            contained,
+            anchor,
+            margin: 0.,
+            offset: (0., 0.),
+        }
+    }
+}
+
+impl<'sdl> Widget for Anchored<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        Ok((MinLen::LAX, MinLen::LAX))
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        Ok((MaxLen(0.), MaxLen(0.)))
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let margin = crate::util::scale::scaled(self.margin);
+        let offset = (
+            crate::util::scale::scaled(self.offset.0),
+            crate::util::scale::scaled(self.offset.1),
+        );
+        let window = event.window_area;
+        let available = window.inset(margin);
+
+        let size = super::place(
+            self.contained.as_mut(),
+            available,
+            event.aspect_ratio_priority,
+        )?;
+        let position = FRect {
+            x: available.x + (available.w - size.w) * self.anchor.x + offset.0,
+            y: available.y + (available.h - size.h) * self.anchor.y + offset.1,
+            w: size.w,
+            h: size.h,
+        };
+
+        // the overlay pass has no clipping rect
+        let mut sub_event = event.sub_event(position);
+        sub_event.clipping_rect = ClippingRect::None;
+        self.contained.update(sub_event)
+    }
+
+    // not forwarded. the contained's position doesn't depend on the parent
+    fn update_adjust_position(&mut self, _pos_delta: (i32, i32)) {}
+
+    fn draw(&mut self, _canvas: &mut WindowCanvas, _focus_manager: &FocusManager) -> UiResult<()> {
+        Ok(()) // drawn in the overlay pass
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        self.contained.draw(&mut canvas, focus_manager)?;
+        canvas.set_clip_rect(ClippingRect::None);
+        self.contained.draw_overlay(&mut canvas, focus_manager)
+    }
+}
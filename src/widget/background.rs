@@ -9,8 +9,6 @@ use sdl2::{
     pixels::Color, rect::Rect, render::TextureCreator, surface::Surface, video::WindowContext,
 };
 
-#[cfg(feature = "rayon")]
-use rayon::prelude::*;
 
 #[derive(Default)]
 pub enum BackgroundSizingPolicy {
@@ -27,6 +25,8 @@ pub struct SolidColorBackground<'sdl> {
     pub color: Color,
     pub contained: &'sdl mut dyn Widget,
     pub sizing_policy: BackgroundSizingPolicy,
+    /// clip the contained to the background's position
+    pub clip_children: ClipChildren,
     /// state stored from update for draw
     background_draw_pos: FRect,
 }
@@ -41,17 +41,1258 @@ impl<'sdl> SolidColorBackground<'sdl> {
             color,
             contained,
             sizing_policy,
+            clip_children: Default::default(),
             background_draw_pos: Default::default(),
         }
     }
 }
 
 impl<'sdl> Widget for SolidColorBackground<'sdl> {
-    fn update(&mut self, mut event: WidgetUpdateEvent) -> Result<(), String> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut *self.contained as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.background_draw_pos = event.position;
+        self.clip_children.update(&mut event);
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => {
+                // exactly passes sizing information to parent in this
+                // case, no need to place again
+                self.contained.update(event)
+            }
+            BackgroundSizingPolicy::Custom(_) => {
+                // whatever the sizing of the parent, properly place the
+                // contained within it
+                let position_for_contained =
+                    place(self.contained, event.position, event.aspect_ratio_priority)?;
+                self.contained
+                    .update(event.sub_event(position_for_contained))
+            }
+        }
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.background_draw_pos.x += pos_delta.0 as f32;
+        self.background_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        canvas.set_draw_color(self.color);
+        let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
+        if let Some(pos) = pos {
+            canvas.fill_rect(pos)?;
+        }
+        let r = self
+            .contained
+            .draw(&mut self.clip_children.scope(&mut canvas), focus_manager);
+        r
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.min_w.scaled(), custom.min_h.scaled()))
+            }
+        }
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_w_fail_policy,
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_h_fail_policy,
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.max_w.scaled(), custom.max_h.scaled()))
+            }
+        }
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_w_fail_policy,
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_h_fail_policy,
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_portion(),
+            BackgroundSizingPolicy::Custom(custom) => (custom.preferred_w, custom.preferred_h),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::width_from_height(
+                    *ratio, pref_h,
+                )))
+            }
+        }
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::height_from_width(
+                    *ratio, pref_w,
+                )))
+            }
+        }
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => {
+                self.contained.preferred_link_allowed_exceed_portion()
+            }
+            BackgroundSizingPolicy::Custom(custom) => custom.preferred_link_allowed_exceed_portion,
+        }
+    }
+}
+
+/// a point along a gradient. the position is from 0 to 1 inclusive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    pub position: f32,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(position: f32, color: Color) -> Self {
+        Self { position, color }
+    }
+}
+
+/// color at some point t (0 to 1) along stops, which are sorted by position
+fn gradient_color(stops: &[GradientStop], t: f32) -> Color {
+    let first = match stops.first() {
+        Some(v) => v,
+        None => return Color::RGBA(0, 0, 0, 0),
+    };
+    if t <= first.position {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if t <= b.position {
+            let span = b.position - a.position;
+            let f = if span <= 0. { 1. } else { (t - a.position) / span };
+            let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+            return Color::RGBA(
+                lerp(a.color.r, b.color.r),
+                lerp(a.color.g, b.color.g),
+                lerp(a.color.b, b.color.b),
+                lerp(a.color.a, b.color.a),
+            );
+        }
+    }
+    stops[stops.len() - 1].color
+}
+
+struct LinearGradientBackgroundCache<'sdl> {
+    pub width: u32,
+    pub height: u32,
+    pub angle: f32,
+    pub stops: Vec<GradientStop>,
+    /// tiled if larger than the max texture size (e.g. a very large window)
+    pub texture: TiledTexture<'sdl>,
+    /// see style_generation
+    pub generation: u64,
+}
+
+/// a background which smoothly transitions between colors. rendered once and
+/// cached, until the size or gradient changes
+pub struct LinearGradientBackground<'sdl> {
+    /// the position of each stop along the gradient. must be sorted by position
+    pub stops: Vec<GradientStop>,
+    /// direction of the gradient, in degrees. 0 goes from left to right, and 90
+    /// goes from top to bottom
+    pub angle: f32,
+    pub contained: &'sdl mut dyn Widget,
+    pub sizing_policy: BackgroundSizingPolicy,
+    /// clip the contained to the background's position
+    pub clip_children: ClipChildren,
+    /// state stored from update for draw
+    background_draw_pos: FRect,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    cache: Option<LinearGradientBackgroundCache<'sdl>>,
+}
+
+impl<'sdl> LinearGradientBackground<'sdl> {
+    pub fn new(
+        stops: Vec<GradientStop>,
+        angle: f32,
+        contained: &'sdl mut dyn Widget,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            stops,
+            angle,
+            contained,
+            sizing_policy: Default::default(),
+            clip_children: Default::default(),
+            background_draw_pos: Default::default(),
+            creator,
+            cache: None,
+        }
+    }
+}
+
+impl<'sdl> Widget for LinearGradientBackground<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut *self.contained as &mut dyn Widget]
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
+
+        if let Some(position) = pos {
+            record_max_texture_size(&canvas);
+            let generation = style_generation();
+            let width = position.width();
+            let height = position.height();
+            let cache = match self.cache.take().filter(|cache| {
+                cache.width == width
+                    && cache.height == height
+                    && cache.angle == self.angle
+                    && cache.stops == self.stops
+                    && cache.generation == generation
+            }) {
+                Some(cache) => cache,
+                None => {
+                    let mut surface = Surface::new(
+                        width,
+                        height,
+                        sdl2::pixels::PixelFormatEnum::ARGB8888,
+                    )?;
+                    let (sin, cos) = self.angle.to_radians().sin_cos();
+                    // half the length of the gradient line, such that the
+                    // corners are at the ends
+                    let half = ((width as f32 * cos).abs() + (height as f32 * sin).abs()) / 2.;
+                    let stops = &self.stops;
+                    parallel_fill(&mut surface, (0, 0, width, height), |x, y| {
+                        let dx = x as f32 + 0.5 - width as f32 / 2.;
+                        let dy = y as f32 + 0.5 - height as f32 / 2.;
+                        let t = if half <= 0. {
+                            0.
+                        } else {
+                            (dx * cos + dy * sin + half) / (2. * half)
+                        };
+                        gradient_color(stops, t)
+                    })?;
+                    let mut texture =
+                        TiledTexture::from_surface(&mut surface, self.creator, max_texture_size())?;
+                    texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+                    LinearGradientBackgroundCache {
+                        width,
+                        height,
+                        angle: self.angle,
+                        stops: self.stops.clone(),
+                        texture,
+                        generation,
+                    }
+                }
+            };
+            cache.texture.copy(&mut canvas, None, position)?;
+            self.cache = Some(cache);
+        }
+
+        let r = self
+            .contained
+            .draw(&mut self.clip_children.scope(&mut canvas), focus_manager);
+        r
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.background_draw_pos = event.position;
+        self.clip_children.update(&mut event);
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.update(event),
+            BackgroundSizingPolicy::Custom(_) => {
+                let position_for_contained =
+                    place(self.contained, event.position, event.aspect_ratio_priority)?;
+                self.contained
+                    .update(event.sub_event(position_for_contained))
+            }
+        }
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.background_draw_pos.x += pos_delta.0 as f32;
+        self.background_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.min_w.scaled(), custom.min_h.scaled()))
+            }
+        }
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_w_fail_policy,
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_h_fail_policy,
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.max_w.scaled(), custom.max_h.scaled()))
+            }
+        }
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_w_fail_policy,
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_h_fail_policy,
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_portion(),
+            BackgroundSizingPolicy::Custom(custom) => (custom.preferred_w, custom.preferred_h),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::width_from_height(
+                    *ratio, pref_h,
+                )))
+            }
+        }
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::height_from_width(
+                    *ratio, pref_w,
+                )))
+            }
+        }
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => {
+                self.contained.preferred_link_allowed_exceed_portion()
+            }
+            BackgroundSizingPolicy::Custom(custom) => custom.preferred_link_allowed_exceed_portion,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TexturedBackgroundMode {
+    /// the texture covers the entire background, ignoring its aspect ratio
+    #[default]
+    Stretch,
+    /// the texture is repeated from the top left, at its own size (scaled by
+    /// the ui scale)
+    Tile,
+}
+
+/// a background drawn from a texture
+pub struct TexturedBackground<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    pub mode: TexturedBackgroundMode,
+    pub contained: &'sdl mut dyn Widget,
+    pub sizing_policy: BackgroundSizingPolicy,
+    /// clip the contained to the background's position
+    pub clip_children: ClipChildren,
+    color_mod: (u8, u8, u8),
+    /// state stored from update for draw
+    background_draw_pos: FRect,
+}
+
+impl<'sdl> TexturedBackground<'sdl> {
+    pub fn new(
+        texture: OwnedTexture<'sdl>,
+        mode: TexturedBackgroundMode,
+        contained: &'sdl mut dyn Widget,
+    ) -> Self {
+        Self {
+            texture,
+            mode,
+            contained,
+            sizing_policy: Default::default(),
+            clip_children: Default::default(),
+            color_mod: (0xFF, 0xFF, 0xFF),
+            background_draw_pos: Default::default(),
+        }
+    }
+
+    pub fn set_color_mod(&mut self, color_mod: (u8, u8, u8)) {
+        self.color_mod = color_mod;
+        self.texture
+            .set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+    }
+
+    pub fn get_color_mod(&self) -> (u8, u8, u8) {
+        self.color_mod
+    }
+}
+
+impl<'sdl> Widget for TexturedBackground<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut *self.contained as &mut dyn Widget]
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
+
+        if let Some(position) = pos {
+            match self.mode {
+                TexturedBackgroundMode::Stretch => {
+                    canvas.copy(&self.texture, None, position)?;
+                }
+                TexturedBackgroundMode::Tile => {
+                    let query = self.texture.query();
+                    let tile_w = crate::util::scale::scaled(query.width as f32).round() as u32;
+                    let tile_h = crate::util::scale::scaled(query.height as f32).round() as u32;
+                    if tile_w == 0 || tile_h == 0 {
+                        return self
+                            .contained
+                            .draw(&mut self.clip_children.scope(canvas), focus_manager);
+                    }
+                    let mut y = 0u32;
+                    while y < position.height() {
+                        // the last tile in each direction is cut off
+                        let dst_h = tile_h.min(position.height() - y);
+                        let src_h = (query.height as u64 * dst_h as u64 / tile_h as u64) as u32;
+                        let mut x = 0u32;
+                        while x < position.width() {
+                            let dst_w = tile_w.min(position.width() - x);
+                            let src_w =
+                                (query.width as u64 * dst_w as u64 / tile_w as u64) as u32;
+                            canvas.copy(
+                                &self.texture,
+                                Rect::new(0, 0, src_w.max(1), src_h.max(1)),
+                                Rect::new(
+                                    position.x() + x as i32,
+                                    position.y() + y as i32,
+                                    dst_w,
+                                    dst_h,
+                                ),
+                            )?;
+                            x += tile_w;
+                        }
+                        y += tile_h;
+                    }
+                }
+            }
+        }
+
+        self.contained
+            .draw(&mut self.clip_children.scope(canvas), focus_manager)
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.background_draw_pos = event.position;
+        self.clip_children.update(&mut event);
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.update(event),
+            BackgroundSizingPolicy::Custom(_) => {
+                let position_for_contained =
+                    place(self.contained, event.position, event.aspect_ratio_priority)?;
+                self.contained
+                    .update(event.sub_event(position_for_contained))
+            }
+        }
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.background_draw_pos.x += pos_delta.0 as f32;
+        self.background_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.min_w.scaled(), custom.min_h.scaled()))
+            }
+        }
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_w_fail_policy,
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.min_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.min_h_fail_policy,
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max(),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.max_w.scaled(), custom.max_h.scaled()))
+            }
+        }
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_w_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_w_fail_policy,
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.max_h_fail_policy(),
+            BackgroundSizingPolicy::Custom(custom) => custom.max_h_fail_policy,
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_portion(),
+            BackgroundSizingPolicy::Custom(custom) => (custom.preferred_w, custom.preferred_h),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::width_from_height(
+                    *ratio, pref_h,
+                )))
+            }
+        }
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        match &mut self.sizing_policy {
+            BackgroundSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
+            BackgroundSizingPolicy::Custom(custom) => {
+                let ratio = match &custom.aspect_ratio {
+                    None => return None,
+                    Some(v) => v,
+                };
+
+                Some(Ok(AspectRatioPreferredDirection::height_from_width(
+                    *ratio, pref_w,
+                )))
+            }
+        }
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match &self.sizing_policy {
+            BackgroundSizingPolicy::Children => {
+                self.contained.preferred_link_allowed_exceed_portion()
+            }
+            BackgroundSizingPolicy::Custom(custom) => custom.preferred_link_allowed_exceed_portion,
+        }
+    }
+}
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{
+    mpsc::{channel, Receiver, TryRecvError},
+    Arc,
+};
+use std::time::Instant;
+
+use crate::layout::clipper::ClipChildren;
+use crate::util::animation::request_redraw;
+use crate::util::error::{UiError, UiResult};
+use crate::util::focus::FocusManager;
+use crate::util::length::{
+    AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy,
+    PreferredPortion,
+};
+use crate::util::rect::FRect;
+use crate::util::render::{
+    max_texture_size, owned_texture, parallel_fill, parallel_fill_buffer, record_max_texture_size,
+    style_generation, CanvasStateGuard, OwnedTexture, TiledTexture,
+};
+
+use super::{place, Widget, WidgetUpdateEvent};
+use super::debug::CustomSizingControl;
+
+pub trait SoftwareRenderBackgroundStyle: Send + Sync {
+    /// retrieve color at coordinate to draw a static texture
+    fn get(&self, x: usize, y: usize) -> Color;
+
+    /// samples every n points in the x and y coordinates - tunable performance
+    fn scale_factor(&self) -> u32;
+}
+
+#[cfg(feature = "noise")]
+pub struct Smooth {
+    noise: Fbm<OpenSimplex>,
+    scale_factor: u32,
+}
+
+#[cfg(feature = "noise")]
+impl Smooth {
+    /// fast. suitable for whole background size rendering within 1 frame (with
+    /// parallel feature enabled)
+    pub fn fast(random_seed: u32) -> Self {
+        Self {
+            scale_factor: 3,
+            noise: Fbm::<OpenSimplex>::new(random_seed)
+                .set_frequency(0.05)
+                .set_octaves(3),
+        }
+    }
+
+    pub fn slow(random_seed: u32) -> Self {
+        Self {
+            noise: Fbm::<OpenSimplex>::new(random_seed)
+                .set_frequency(0.05)
+                .set_octaves(5),
+            scale_factor: 1,
+        }
+    }
+}
+
+#[cfg(feature = "noise")]
+impl SoftwareRenderBackgroundStyle for Smooth {
+    fn get(&self, x: usize, y: usize) -> Color {
+        let arg: [f64; 2] = [x as f64, y as f64];
+        let noise_value = ((((self.noise.get(arg) + 1.0) / 2.) * 0xFF as f64).round()) as u8;
+        Color::RGB(noise_value, noise_value, noise_value)
+    }
+
+    fn scale_factor(&self) -> u32 {
+        self.scale_factor
+    }
+}
+
+#[cfg(feature = "noise")]
+pub struct Wood {
+    // perhaps there is a better way of using templates for this?
+    wood_fn: Turbulence<
+        RotatePoint<
+            TranslatePoint<
+                Turbulence<
+                    Add<f64, Cylinders, ScaleBias<f64, ScalePoint<BasicMulti<Perlin>>, 2>, 2>,
+                    Perlin,
+                >,
+            >,
+        >,
+        Perlin,
+    >,
+    wood_gradient: ColorGradient,
+    scale_factor: u32,
+}
+
+#[cfg(feature = "noise")]
+impl Wood {
+    pub fn new(random_seed: u32) -> Self {
+        // modified from: https://github.com/Razaekel/noise-rs/
+        // (same license)
+        let base_wood = Cylinders::new().set_frequency(16.0);
+        let wood_grain_noise = BasicMulti::<Perlin>::new(0)
+            .set_frequency(48.0)
+            .set_persistence(0.5)
+            .set_lacunarity(2.20703125)
+            .set_octaves(2);
+        let scaled_base_wood_grain = ScalePoint::new(wood_grain_noise).set_z_scale(0.25);
+        let wood_grain = ScaleBias::new(scaled_base_wood_grain)
+            .set_scale(0.25)
+            .set_bias(0.125);
+        let combined_wood = Add::new(base_wood, wood_grain);
+        let perturbed_wood = Turbulence::<_, Perlin>::new(combined_wood)
+            .set_seed(random_seed)
+            .set_frequency(4.0)
+            .set_power(1.0 / 256.0)
+            .set_roughness(4);
+        let translated_wood = TranslatePoint::new(perturbed_wood).set_y_translation(1.48);
+        let rotated_wood = RotatePoint::new(translated_wood).set_angles(84.0, 0.0, 0.0, 0.0);
+        let final_wood = Turbulence::<_, Perlin>::new(rotated_wood)
+            .set_seed(random_seed.checked_add(1).unwrap_or(0))
+            .set_frequency(2.0)
+            .set_power(1.0 / 64.0)
+            .set_roughness(4);
+        let wood_gradient = ColorGradient::new()
+            .clear_gradient()
+            .add_gradient_point(-1.000, [189, 94, 4, 255])
+            .add_gradient_point(0.500, [144, 48, 6, 255])
+            .add_gradient_point(1.0, [60, 10, 8, 255]);
+        Self {
+            wood_fn: final_wood,
+            wood_gradient,
+            scale_factor: 3,
+        }
+    }
+}
+
+#[cfg(feature = "noise")]
+impl SoftwareRenderBackgroundStyle for Wood {
+    fn get(&self, x: usize, y: usize) -> Color {
+        let arg: [f64; 2] = [x as f64, y as f64];
+        let arg = [arg[0] / 500., arg[1] / 500.];
+        let val = self.wood_fn.get(arg);
+        let val = self.wood_gradient.get_color(val);
+        Color::RGBA(val[0], val[1], val[2], val[3])
+    }
+
+    fn scale_factor(&self) -> u32 {
+        self.scale_factor
+    }
+}
+
+// =============================================================================
+
+/// based on width and height, if larger than cached then creates new surface and texture
+struct SoftwareRenderBackgroundCache<'sdl> {
+    pub texture: OwnedTexture<'sdl>,
+    pub surface: sdl2::surface::Surface<'sdl>, // reuse previous computation - only expanded size is calculated
+    /// see style_generation
+    pub generation: u64,
+    /// consecutive draws for which the cache was oversized. see
+    /// CacheShrinkPolicy
+    pub oversized_draws: u32,
+}
+
+impl<'sdl> SoftwareRenderBackgroundCache<'sdl> {
+    /// keep only the top left of the cache. no recomputation is needed
+    fn crop(
+        self,
+        width: u32,
+        height: u32,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<Self> {
+        let width = width.clamp(1, self.surface.width());
+        let height = height.clamp(1, self.surface.height());
+        let mut surface = Surface::new(width, height, sdl2::pixels::PixelFormatEnum::ARGB8888)?;
+        self.surface
+            .blit(Rect::new(0, 0, width, height), &mut surface, None)?;
+        let mut texture = creator
+            .create_texture_from_surface(&surface)
+            .map(owned_texture)?;
+        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+        Ok(Self {
+            texture,
+            surface,
+            generation: self.generation,
+            oversized_draws: 0,
+        })
+    }
+
+    /// from a render done on a worker thread
+    fn from_frame(
+        frame: AnimatedFrame,
+        generation: u64,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> UiResult<Self> {
+        let (width, height, buffer) = frame;
+        let mut surface = Surface::new(width, height, sdl2::pixels::PixelFormatEnum::ARGB8888)?;
+        let pitch = surface.pitch() as usize;
+        let row_len = width as usize * 4;
+        surface.with_lock_mut(|pixels| {
+            for (row, src) in buffer.chunks_exact(row_len).enumerate() {
+                pixels[row * pitch..row * pitch + row_len].copy_from_slice(src);
+            }
+        });
+        let mut texture = creator
+            .create_texture_from_surface(&surface)
+            .map(owned_texture)?;
+        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+        Ok(Self {
+            texture,
+            surface,
+            generation,
+            oversized_draws: 0,
+        })
+    }
+}
+
+fn render_software_frame<Style: SoftwareRenderBackgroundStyle>(
+    style: &Style,
+    width: u32,
+    height: u32,
+) -> AnimatedFrame {
+    let scale_factor = style.scale_factor() as usize;
+    let row_stride = width as usize * 4;
+    let mut buffer = vec![0u8; row_stride * height as usize];
+    parallel_fill_buffer(&mut buffer, row_stride, (0, 0, width, height), |x, y| {
+        style.get(x * scale_factor, y * scale_factor)
+    });
+    (width, height, buffer)
+}
+
+/// starts a render of some width and height on a worker thread
+type BackgroundRenderFn = Box<dyn Fn(u32, u32) -> Receiver<AnimatedFrame>>;
+
+/// the background's cache grows as needed, but by default doesn't shrink (e.g.
+/// after a window is maximized then restored)
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CacheShrinkPolicy {
+    #[default]
+    Never,
+    /// shrink the cache once it's been larger than needed (in either
+    /// dimension) by some factor for some number of consecutive draws
+    WhenOversized { factor: f32, draws: u32 },
+}
+
+/// a cache which can be used by multiple backgrounds with the same style. see
+/// SoftwareRenderBackground::sharing
+type SharedSoftwareRenderBackgroundCache<'sdl> =
+    Rc<RefCell<Option<SoftwareRenderBackgroundCache<'sdl>>>>;
+
+/// suitable for background coloring. for example, multiple widgets can be
+/// composed in a stacked layout.
+/// 
+/// CAREFUL! Should not drop each frame, as it will recompute.
+pub struct SoftwareRenderBackground<'sdl, Style: SoftwareRenderBackgroundStyle> {
+    style: Style,
+    pub contained: &'sdl mut dyn Widget,
+
+    /// indicates the sizing information which will be used to position this
+    /// background and widget together. following this, the background then
+    /// places the contained using the sizing information provided by the
+    /// contained (two distinct steps)
+    pub sizing_policy: BackgroundSizingPolicy,
+    /// clip the contained to the background's position
+    pub clip_children: ClipChildren,
+
+    color_mod: (u8, u8, u8),
+
+    pub shrink_policy: CacheShrinkPolicy,
+
+    /// state stored for draw from update
+    background_draw_pos: crate::util::rect::FRect,
+
+    /// size of the cache used by the most recent draw
+    last_needed: Option<(u32, u32)>,
+
+    /// set by render_in_background
+    background_render: Option<BackgroundRenderFn>,
+    /// render in progress, and the style generation it was started with
+    pending: Option<(Receiver<AnimatedFrame>, u64)>,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    cache: SharedSoftwareRenderBackgroundCache<'sdl>,
+}
+
+impl<'sdl, Style: SoftwareRenderBackgroundStyle> SoftwareRenderBackground<'sdl, Style> {
+    pub fn new(
+        contained: &'sdl mut dyn Widget,
+        style: Style,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            style,
+            contained,
+            sizing_policy: Default::default(),
+            clip_children: Default::default(),
+            creator,
+            color_mod: (0xFF, 0xFF, 0xFF),
+            shrink_policy: Default::default(),
+            background_draw_pos: Default::default(),
+            last_needed: None,
+            background_render: None,
+            pending: None,
+            cache: Default::default(),
+        }
+    }
+
+    /// compute the texture on a worker thread instead of during draw, for
+    /// styles which are too slow to render within a frame (e.g. at full
+    /// window size). while a render is in progress, the previous texture is
+    /// drawn (stretched if the size changed).
+    ///
+    /// not carried over by sharing
+    pub fn render_in_background(&mut self)
+    where
+        Style: Clone + 'static,
+    {
+        let style = Arc::new(self.style.clone());
+        self.background_render = Some(Box::new(move |width, height| {
+            let (sender, receiver) = channel();
+            let style = style.clone();
+            std::thread::spawn(move || {
+                // receiver may have been dropped; that's fine
+                let _ = sender.send(render_software_frame(&*style, width, height));
+            });
+            receiver
+        }));
+    }
+
+    /// with render_in_background, start a render if the cache isn't suitable
+    /// and draw what's available instead. returns false if the cache is
+    /// suitable, and should be drawn as usual
+    fn draw_pending(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        position: Rect,
+        needed: (u32, u32),
+        generation: u64,
+    ) -> UiResult<bool> {
+        let background_render = match &self.background_render {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+        let cache_rc = self.cache.clone();
+        let mut cache_slot = cache_rc.borrow_mut();
+
+        if let Some((receiver, render_generation)) = &self.pending {
+            match receiver.try_recv() {
+                Ok(frame) => {
+                    if *render_generation == generation {
+                        *cache_slot = Some(SoftwareRenderBackgroundCache::from_frame(
+                            frame,
+                            generation,
+                            self.creator,
+                        )?);
+                    }
+                    self.pending = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
+                    self.pending = None;
+                    return Err(UiError::Custom(
+                        "software background render thread panicked".to_owned(),
+                    ));
+                }
+            }
+        }
+
+        let cache = cache_slot
+            .as_mut()
+            .filter(|cache| cache.generation == generation);
+        let (cache_width, cache_height) = cache
+            .as_ref()
+            .map(|cache| (cache.surface.width(), cache.surface.height()))
+            .unwrap_or((0, 0));
+        if cache_width >= needed.0 && cache_height >= needed.1 {
+            return Ok(false);
+        }
+
+        if self.pending.is_none() {
+            // grows like the cache does when rendered during draw
+            let size = (needed.0.max(cache_width), needed.1.max(cache_height));
+            self.pending = Some((background_render(size.0, size.1), generation));
+        }
+        if let Some(cache) = cache {
+            cache
+                .texture
+                .set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+            let src = Rect::new(
+                0,
+                0,
+                needed.0.min(cache_width).max(1),
+                needed.1.min(cache_height).max(1),
+            );
+            canvas.copy(&cache.texture, src, position)?;
+        }
+        // check for the render next frame
+        request_redraw();
+        Ok(true)
+    }
+
+    /// another background with the same style, which shares this one's cache.
+    /// whatever was already rendered by either is reused by both
+    pub fn sharing(&self, contained: &'sdl mut dyn Widget) -> Self
+    where
+        Style: Clone,
+    {
+        Self {
+            style: self.style.clone(),
+            contained,
+            sizing_policy: Default::default(),
+            clip_children: Default::default(),
+            creator: self.creator,
+            color_mod: self.color_mod,
+            shrink_policy: self.shrink_policy,
+            background_draw_pos: Default::default(),
+            last_needed: None,
+            background_render: None,
+            pending: None,
+            cache: self.cache.clone(),
+        }
+    }
+
+    pub fn set_color_mod(&mut self, color_mod: (u8, u8, u8)) {
+        // applied to the (possibly shared) texture at each draw
+        self.color_mod = color_mod;
+    }
+
+    pub fn get_color_mod(&self) -> (u8, u8, u8) {
+        self.color_mod
+    }
+
+    /// shrink the cache down to what was needed by the most recent draw
+    pub fn trim(&mut self) -> UiResult<()> {
+        let (width, height) = match self.last_needed {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let mut cache = self.cache.borrow_mut();
+        if let Some(c) = cache.take() {
+            *cache = Some(c.crop(width, height, self.creator)?);
+        }
+        Ok(())
+    }
+}
+
+impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackground<'sdl, Style> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut *self.contained as &mut dyn Widget]
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
+
+        if let Some(position) = pos {
+            let scale_factor = self.style.scale_factor();
+            let needed_width = position.width() / scale_factor;
+            let needed_height = position.height() / scale_factor;
+            self.last_needed = Some((needed_width, needed_height));
+
+            let generation = style_generation();
+            if self.draw_pending(canvas, position, (needed_width, needed_height), generation)? {
+                return self
+                    .contained
+                    .draw(&mut self.clip_children.scope(canvas), focus_manager);
+            }
+            let cache_rc = self.cache.clone();
+            let mut cache_slot = cache_rc.borrow_mut();
+            let (mut texture, surface, oversized_draws) = match cache_slot
+                .take()
+                .filter(|cache| cache.generation == generation)
+            {
+                Some(mut cache) => {
+                    if cache.surface.width() >= needed_width
+                        && cache.surface.height() >= needed_height
+                    {
+                        // large enough to use cache
+                        if let CacheShrinkPolicy::WhenOversized { factor, draws } =
+                            self.shrink_policy
+                        {
+                            let oversized = cache.surface.width() as f32
+                                > needed_width as f32 * factor
+                                || cache.surface.height() as f32 > needed_height as f32 * factor;
+                            cache.oversized_draws = if oversized {
+                                cache.oversized_draws.saturating_add(1)
+                            } else {
+                                0
+                            };
+                            if cache.oversized_draws >= draws {
+                                cache = cache.crop(needed_width, needed_height, self.creator)?;
+                            }
+                        }
+                        (cache.texture, cache.surface, cache.oversized_draws)
+                    } else {
+                        let old_width = cache.surface.width();
+                        let old_height = cache.surface.height();
+                        let new_width = (position.width() / scale_factor).max(old_width);
+                        let new_height = (position.height() / scale_factor).max(old_height);
+                        // must expand texture in the cache
+                        let mut surface = Surface::new(
+                            new_width,
+                            new_height,
+                            sdl2::pixels::PixelFormatEnum::ARGB8888,
+                        )?;
+
+                        // reuse what was already computed
+                        cache.surface.blit(None, &mut surface, None)?;
+
+                        let style = &self.style;
+                        let get = |x: usize, y: usize| {
+                            style.get(x * scale_factor as usize, y * scale_factor as usize)
+                        };
+                        // the expanded height
+                        parallel_fill(
+                            &mut surface,
+                            (0, old_height, new_width, new_height - old_height),
+                            get,
+                        )?;
+                        // the expanded width (corner already done)
+                        parallel_fill(
+                            &mut surface,
+                            (old_width, 0, new_width - old_width, old_height),
+                            get,
+                        )?;
+
+                        let mut surface_copy = Surface::new(
+                            new_width,
+                            new_height,
+                            sdl2::pixels::PixelFormatEnum::ARGB8888,
+                        )?;
+
+                        surface.blit(None, &mut surface_copy, None)?;
+
+                        let mut texture = self
+                            .creator
+                            .create_texture_from_surface(surface)
+                            .map(owned_texture)?;
+                        texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+                        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+                        (texture, surface_copy, 0)
+                    }
+                }
+                None => {
+                    // create texture from scratch
+                    let mut surface = Surface::new(
+                        position.width() / scale_factor,
+                        position.height() / scale_factor,
+                        sdl2::pixels::PixelFormatEnum::ARGB8888,
+                    )?;
+
+                    let style = &self.style;
+                    parallel_fill(
+                        &mut surface,
+                        (0, 0, needed_width, needed_height),
+                        |x, y| style.get(x * scale_factor as usize, y * scale_factor as usize),
+                    )?;
+
+                    let mut surface_copy = Surface::new(
+                        position.width() / scale_factor,
+                        position.height() / scale_factor,
+                        sdl2::pixels::PixelFormatEnum::ARGB8888,
+                    )?;
+
+                    surface.blit(None, &mut surface_copy, None)?;
+
+                    let mut texture = self
+                        .creator
+                        .create_texture_from_surface(surface)
+                        .map(owned_texture)?;
+                    texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+                    texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+                    (texture, surface_copy, 0)
+                }
+            };
+
+            texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+            canvas.copy(
+                &texture,
+                Rect::new(0, 0, needed_width, needed_height),
+                position,
+            )?;
+
+            *cache_slot = Some(SoftwareRenderBackgroundCache {
+                texture,
+                surface,
+                generation,
+                oversized_draws,
+            });
+        }
+
+        self.contained
+            .draw(&mut self.clip_children.scope(canvas), focus_manager)
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.background_draw_pos = event.position;
+        self.clip_children.update(&mut event);
         match &self.sizing_policy {
             BackgroundSizingPolicy::Children => {
-                // exactly passes sizing information to parent in this
+                // scroller exactly passes sizing information to parent in this
                 // case, no need to place again
                 self.contained.update(event)
             }
@@ -69,26 +1310,16 @@ impl<'sdl> Widget for SolidColorBackground<'sdl> {
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
         self.background_draw_pos.x += pos_delta.0 as f32;
         self.background_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
         self.contained.update_adjust_position(pos_delta);
     }
 
-    fn draw(
-        &mut self,
-        canvas: &mut sdl2::render::WindowCanvas,
-        focus_manager: &FocusManager,
-    ) -> Result<(), String> {
-        canvas.set_draw_color(self.color);
-        let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
-        if let Some(pos) = pos {
-            canvas.fill_rect(pos)?;
-        }
-        self.contained.draw(canvas, focus_manager)
-    }
-
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
         match &self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.min(),
-            BackgroundSizingPolicy::Custom(custom) => Ok((custom.min_w, custom.min_h)),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.min_w.scaled(), custom.min_h.scaled()))
+            }
         }
     }
 
@@ -106,10 +1337,12 @@ impl<'sdl> Widget for SolidColorBackground<'sdl> {
         }
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
         match &self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.max(),
-            BackgroundSizingPolicy::Custom(custom) => Ok((custom.max_w, custom.max_h)),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.max_w.scaled(), custom.max_h.scaled()))
+            }
         }
     }
 
@@ -134,7 +1367,7 @@ impl<'sdl> Widget for SolidColorBackground<'sdl> {
         }
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
             BackgroundSizingPolicy::Custom(custom) => {
@@ -150,7 +1383,7 @@ impl<'sdl> Widget for SolidColorBackground<'sdl> {
         }
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
             BackgroundSizingPolicy::Custom(custom) => {
@@ -176,57 +1409,45 @@ impl<'sdl> Widget for SolidColorBackground<'sdl> {
     }
 }
 
-use crate::util::focus::FocusManager;
-use crate::util::length::{
-    AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy,
-    PreferredPortion,
-};
-use crate::util::rect::FRect;
-
-use super::{place, Widget, WidgetUpdateEvent};
-use super::debug::CustomSizingControl;
+// =============================================================================
 
-pub trait SoftwareRenderBackgroundStyle: Send + Sync {
-    /// retrieve color at coordinate to draw a static texture
-    fn get(&self, x: usize, y: usize) -> Color;
+pub trait AnimatedSoftwareRenderBackgroundStyle: Send + Sync {
+    /// retrieve color at coordinate, at some time (seconds since the background
+    /// was created)
+    fn get(&self, x: usize, y: usize, t: f32) -> Color;
 
-    /// samples every n points in the x and y coordinates - tunable performance
+    /// samples every n points in the x and y coordinates - tunable performance.
+    /// animated styles should generally be cheaper than static ones, since
+    /// they're rendered repeatedly
     fn scale_factor(&self) -> u32;
 }
 
+/// smooth noise which slowly changes over time, like drifting clouds
 #[cfg(feature = "noise")]
-pub struct Smooth {
+pub struct Drifting {
     noise: Fbm<OpenSimplex>,
+    /// how quickly the noise changes, in noise units per second
+    pub speed: f64,
     scale_factor: u32,
 }
 
 #[cfg(feature = "noise")]
-impl Smooth {
-    /// fast. suitable for whole background size rendering within 1 frame (with
-    /// parallel feature enabled)
-    pub fn fast(random_seed: u32) -> Self {
+impl Drifting {
+    pub fn new(random_seed: u32) -> Self {
         Self {
-            scale_factor: 3,
             noise: Fbm::<OpenSimplex>::new(random_seed)
-                .set_frequency(0.05)
+                .set_frequency(0.02)
                 .set_octaves(3),
-        }
-    }
-
-    pub fn slow(random_seed: u32) -> Self {
-        Self {
-            noise: Fbm::<OpenSimplex>::new(random_seed)
-                .set_frequency(0.05)
-                .set_octaves(5),
-            scale_factor: 1,
+            speed: 0.5,
+            scale_factor: 4,
         }
     }
 }
 
 #[cfg(feature = "noise")]
-impl SoftwareRenderBackgroundStyle for Smooth {
-    fn get(&self, x: usize, y: usize) -> Color {
-        let arg: [f64; 2] = [x as f64, y as f64];
+impl AnimatedSoftwareRenderBackgroundStyle for Drifting {
+    fn get(&self, x: usize, y: usize, t: f32) -> Color {
+        let arg: [f64; 3] = [x as f64, y as f64, t as f64 * self.speed];
         let noise_value = ((((self.noise.get(arg) + 1.0) / 2.) * 0xFF as f64).round()) as u8;
         Color::RGB(noise_value, noise_value, noise_value)
     }
@@ -236,132 +1457,98 @@ impl SoftwareRenderBackgroundStyle for Smooth {
     }
 }
 
-#[cfg(feature = "noise")]
-pub struct Wood {
-    // perhaps there is a better way of using templates for this?
-    wood_fn: Turbulence<
-        RotatePoint<
-            TranslatePoint<
-                Turbulence<
-                    Add<f64, Cylinders, ScaleBias<f64, ScalePoint<BasicMulti<Perlin>>, 2>, 2>,
-                    Perlin,
-                >,
-            >,
-        >,
-        Perlin,
-    >,
-    wood_gradient: ColorGradient,
-    scale_factor: u32,
-}
-
-#[cfg(feature = "noise")]
-impl Wood {
-    pub fn new(random_seed: u32) -> Self {
-        // modified from: https://github.com/Razaekel/noise-rs/
-        // (same license)
-        let base_wood = Cylinders::new().set_frequency(16.0);
-        let wood_grain_noise = BasicMulti::<Perlin>::new(0)
-            .set_frequency(48.0)
-            .set_persistence(0.5)
-            .set_lacunarity(2.20703125)
-            .set_octaves(2);
-        let scaled_base_wood_grain = ScalePoint::new(wood_grain_noise).set_z_scale(0.25);
-        let wood_grain = ScaleBias::new(scaled_base_wood_grain)
-            .set_scale(0.25)
-            .set_bias(0.125);
-        let combined_wood = Add::new(base_wood, wood_grain);
-        let perturbed_wood = Turbulence::<_, Perlin>::new(combined_wood)
-            .set_seed(random_seed)
-            .set_frequency(4.0)
-            .set_power(1.0 / 256.0)
-            .set_roughness(4);
-        let translated_wood = TranslatePoint::new(perturbed_wood).set_y_translation(1.48);
-        let rotated_wood = RotatePoint::new(translated_wood).set_angles(84.0, 0.0, 0.0, 0.0);
-        let final_wood = Turbulence::<_, Perlin>::new(rotated_wood)
-            .set_seed(random_seed.checked_add(1).unwrap_or(0))
-            .set_frequency(2.0)
-            .set_power(1.0 / 64.0)
-            .set_roughness(4);
-        let wood_gradient = ColorGradient::new()
-            .clear_gradient()
-            .add_gradient_point(-1.000, [189, 94, 4, 255])
-            .add_gradient_point(0.500, [144, 48, 6, 255])
-            .add_gradient_point(1.0, [60, 10, 8, 255]);
-        Self {
-            wood_fn: final_wood,
-            wood_gradient,
-            scale_factor: 3,
-        }
-    }
-}
-
-#[cfg(feature = "noise")]
-impl SoftwareRenderBackgroundStyle for Wood {
-    fn get(&self, x: usize, y: usize) -> Color {
-        let arg: [f64; 2] = [x as f64, y as f64];
-        let arg = [arg[0] / 500., arg[1] / 500.];
-        let val = self.wood_fn.get(arg);
-        let val = self.wood_gradient.get_color(val);
-        Color::RGBA(val[0], val[1], val[2], val[3])
-    }
-
-    fn scale_factor(&self) -> u32 {
-        self.scale_factor
-    }
+/// result of rendering a frame on a worker thread: width, height, and ARGB8888
+/// pixels
+type AnimatedFrame = (u32, u32, Vec<u8>);
+
+fn render_animated_frame<Style: AnimatedSoftwareRenderBackgroundStyle>(
+    style: &Style,
+    width: u32,
+    height: u32,
+    t: f32,
+) -> AnimatedFrame {
+    let scale_factor = style.scale_factor() as usize;
+    let row_stride = width as usize * 4;
+    let mut buffer = vec![0u8; row_stride * height as usize];
+    parallel_fill_buffer(&mut buffer, row_stride, (0, 0, width, height), |x, y| {
+        style.get(x * scale_factor, y * scale_factor, t)
+    });
+    (width, height, buffer)
 }
 
-// =============================================================================
-
-/// based on width and height, if larger than cached then creates new surface and texture
-struct SoftwareRenderBackgroundCache<'sdl> {
-    pub texture: sdl2::render::Texture<'sdl>,
-    pub surface: sdl2::surface::Surface<'sdl>, // reuse previous computation - only expanded size is calculated
+struct AnimatedBackgroundTexture<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    width: u32,
+    height: u32,
+    /// see style_generation
+    generation: u64,
 }
 
-/// suitable for background coloring. for example, multiple widgets can be
-/// composed in a stacked layout.
-/// 
-/// CAREFUL! Should not drop each frame, as it will recompute.
-pub struct SoftwareRenderBackground<'sdl, Style: SoftwareRenderBackgroundStyle> {
-    style: Style,
+/// like SoftwareRenderBackground, but the style also receives a time. the
+/// texture is re-rendered every n frames, on a worker thread. the previous
+/// frame is shown until the next one is ready, so the ui thread never waits on
+/// the render
+///
+/// while drawn, this continuously requests redraws
+pub struct AnimatedSoftwareRenderBackground<
+    'sdl,
+    Style: AnimatedSoftwareRenderBackgroundStyle + 'static,
+> {
+    style: Arc<Style>,
     pub contained: &'sdl mut dyn Widget,
 
-    /// indicates the sizing information which will be used to position this
-    /// background and widget together. following this, the background then
-    /// places the contained using the sizing information provided by the
-    /// contained (two distinct steps)
+    /// see SoftwareRenderBackground
     pub sizing_policy: BackgroundSizingPolicy,
+    /// clip the contained to the background's position
+    pub clip_children: ClipChildren,
+
+    /// a new render is started every this many frames (at most one render is
+    /// in progress at a time)
+    pub frames_per_render: u32,
+    frames_since_render: u32,
 
     color_mod: (u8, u8, u8),
 
+    /// time zero for the style
+    start: Instant,
+
     /// state stored for draw from update
     background_draw_pos: crate::util::rect::FRect,
 
     creator: &'sdl TextureCreator<WindowContext>,
-    cache: Option<SoftwareRenderBackgroundCache<'sdl>>,
+    /// render in progress
+    pending: Option<Receiver<AnimatedFrame>>,
+    texture: Option<AnimatedBackgroundTexture<'sdl>>,
 }
 
-impl<'sdl, Style: SoftwareRenderBackgroundStyle> SoftwareRenderBackground<'sdl, Style> {
+impl<'sdl, Style: AnimatedSoftwareRenderBackgroundStyle + 'static>
+    AnimatedSoftwareRenderBackground<'sdl, Style>
+{
     pub fn new(
         contained: &'sdl mut dyn Widget,
         style: Style,
         creator: &'sdl TextureCreator<WindowContext>,
     ) -> Self {
         Self {
-            style,
+            style: Arc::new(style),
             contained,
             sizing_policy: Default::default(),
-            creator,
+            clip_children: Default::default(),
+            frames_per_render: 2,
+            frames_since_render: 0,
             color_mod: (0xFF, 0xFF, 0xFF),
+            start: Instant::now(),
             background_draw_pos: Default::default(),
-            cache: Default::default(),
+            creator,
+            pending: None,
+            texture: None,
         }
     }
 
     pub fn set_color_mod(&mut self, color_mod: (u8, u8, u8)) {
         self.color_mod = color_mod;
-        if let Some(cache) = &mut self.cache {
-            cache
+        if let Some(texture) = &mut self.texture {
+            texture
                 .texture
                 .set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
         }
@@ -370,197 +1557,119 @@ impl<'sdl, Style: SoftwareRenderBackgroundStyle> SoftwareRenderBackground<'sdl,
     pub fn get_color_mod(&self) -> (u8, u8, u8) {
         self.color_mod
     }
+
+    /// if a render has finished, upload it to the texture
+    fn receive_frame(&mut self, generation: u64) -> UiResult<()> {
+        let frame = match &self.pending {
+            None => return Ok(()),
+            Some(receiver) => match receiver.try_recv() {
+                Ok(frame) => frame,
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => {
+                    self.pending = None;
+                    return Err(UiError::Custom(
+                        "animated background render thread panicked".to_owned(),
+                    ));
+                }
+            },
+        };
+        self.pending = None;
+        let (width, height, buffer) = frame;
+
+        let mut texture = match self.texture.take().filter(|texture| {
+            texture.width == width && texture.height == height && texture.generation == generation
+        }) {
+            Some(texture) => texture,
+            None => {
+                let mut texture = self
+                    .creator
+                    .create_texture_streaming(
+                        sdl2::pixels::PixelFormatEnum::ARGB8888,
+                        width,
+                        height,
+                    )
+                    .map(owned_texture)?;
+                texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
+                texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
+                AnimatedBackgroundTexture {
+                    texture,
+                    width,
+                    height,
+                    generation,
+                }
+            }
+        };
+        texture.texture.update(None, &buffer, width as usize * 4)?;
+        self.texture = Some(texture);
+        Ok(())
+    }
 }
 
-impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackground<'sdl, Style> {
+impl<'sdl, Style: AnimatedSoftwareRenderBackgroundStyle + 'static> Widget
+    for AnimatedSoftwareRenderBackground<'sdl, Style>
+{
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut *self.contained as &mut dyn Widget]
+    }
+
     fn draw(
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
         let pos: Option<sdl2::rect::Rect> = self.background_draw_pos.into();
 
         if let Some(position) = pos {
-            let scale_factor = self.style.scale_factor();
-
-            let (texture, surface) = match self.cache.take() {
-                Some(cache) => {
-                    if cache.surface.width() >= position.width() / scale_factor
-                        && cache.surface.height() >= position.height() / scale_factor
-                    {
-                        // large enough to use cache
-                        (cache.texture, cache.surface)
-                    } else {
-                        let old_width = cache.surface.width();
-                        let old_height = cache.surface.height();
-                        let new_width = (position.width() / scale_factor).max(old_width);
-                        let new_height = (position.height() / scale_factor).max(old_height);
-                        // must expand texture in the cache
-                        let mut surface = Surface::new(
-                            new_width,
-                            new_height,
-                            sdl2::pixels::PixelFormatEnum::ARGB8888,
-                        )?;
-
-                        // reuse what was already computed
-                        cache.surface.blit(None, &mut surface, None)?;
-
-                        let row_stride = new_width as usize * 4;
-                        surface.with_lock_mut(|buffer| {
-                            // draw the expanded height
-                            if new_height > cache.surface.height() {
-                                #[cfg(feature = "rayon")]
-                                let row_iter = buffer.par_chunks_exact_mut(row_stride);
-                                #[cfg(not(feature = "rayon"))]
-                                let row_iter = buffer.chunks_exact_mut(row_stride);
-
-                                let row_iter = row_iter.skip(old_height as usize);
-                                row_iter.enumerate().for_each(|(row_index, row)| {
-                                    let row_index = row_index + old_height as usize;
-                                    let pixel_iter = row.chunks_exact_mut(4);
-
-                                    pixel_iter.enumerate().for_each(|(pixel_index, pixel)| {
-                                        let x = pixel_index;
-                                        let y = row_index;
-                                        let color = self.style.get(
-                                            x * scale_factor as usize,
-                                            y * scale_factor as usize,
-                                        );
-                                        pixel[0] = color.b;
-                                        pixel[1] = color.g;
-                                        pixel[2] = color.r;
-                                        pixel[3] = color.a;
-                                    });
-                                });
-                            }
-
-                            // draw the expanded width + corner
-                            if new_width > cache.surface.width() {
-                                #[cfg(feature = "rayon")]
-                                let row_iter = buffer.par_chunks_exact_mut(row_stride);
-                                #[cfg(not(feature = "rayon"))]
-                                let row_iter = buffer.chunks_exact_mut(row_stride);
-
-                                row_iter.enumerate().for_each(|(row_index, row)| {
-                                    let pixel_iter = row.chunks_exact_mut(4);
-
-                                    let pixel_iter = pixel_iter.skip(old_width as usize);
-                                    pixel_iter.enumerate().for_each(|(pixel_index, pixel)| {
-                                        let x = pixel_index + old_width as usize;
-                                        let y = row_index;
-                                        let color = self.style.get(
-                                            x * scale_factor as usize,
-                                            y * scale_factor as usize,
-                                        );
-                                        pixel[0] = color.b;
-                                        pixel[1] = color.g;
-                                        pixel[2] = color.r;
-                                        pixel[3] = color.a;
-                                    });
-                                });
-                            }
-                        });
-
-                        let mut surface_copy = Surface::new(
-                            new_width,
-                            new_height,
-                            sdl2::pixels::PixelFormatEnum::ARGB8888,
-                        )?;
-
-                        surface.blit(None, &mut surface_copy, None)?;
-
-                        let mut texture = self
-                            .creator
-                            .create_texture_from_surface(surface)
-                            .map_err(|e| e.to_string())?;
-                        texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
-                        texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
-                        (texture, surface_copy)
-                    }
-                }
-                None => {
-                    // create texture from scratch
-                    let mut surface = Surface::new(
-                        position.width() / scale_factor,
-                        position.height() / scale_factor,
-                        sdl2::pixels::PixelFormatEnum::ARGB8888,
-                    )?;
-
-                    surface.with_lock_mut(|buffer| {
-                        let width = (position.width() / scale_factor) as usize;
-                        let row_stride = width * 4;
-
-                        // let start = Instant::now();
-
-                        #[cfg(feature = "rayon")]
-                        let row_iter = buffer.par_chunks_exact_mut(row_stride);
-                        #[cfg(not(feature = "rayon"))]
-                        let row_iter = buffer.chunks_exact_mut(row_stride);
-
-                        row_iter.enumerate().for_each(|(row_index, row)| {
-                            let pixel_iter = row.chunks_exact_mut(4);
-                            pixel_iter.enumerate().for_each(|(pixel_index, pixel)| {
-                                let x = pixel_index;
-                                let y = row_index;
-                                let color = self
-                                    .style
-                                    .get(x * scale_factor as usize, y * scale_factor as usize);
-                                pixel[0] = color.b;
-                                pixel[1] = color.g;
-                                pixel[2] = color.r;
-                                pixel[3] = color.a;
-                            });
-                        });
-
-                        // println!("{}", start.elapsed().as_millis());
-                    });
-
-                    let mut surface_copy = Surface::new(
-                        position.width() / scale_factor,
-                        position.height() / scale_factor,
-                        sdl2::pixels::PixelFormatEnum::ARGB8888,
-                    )?;
-
-                    surface.blit(None, &mut surface_copy, None)?;
-
-                    let mut texture = self
-                        .creator
-                        .create_texture_from_surface(surface)
-                        .map_err(|e| e.to_string())?;
-                    texture.set_color_mod(self.color_mod.0, self.color_mod.1, self.color_mod.2);
-                    texture.set_scale_mode(sdl2::render::ScaleMode::Linear);
-                    (texture, surface_copy)
+            let generation = style_generation();
+            self.receive_frame(generation)?;
+
+            let scale_factor = self.style.scale_factor().max(1);
+            let width = (position.width() / scale_factor).max(1);
+            let height = (position.height() / scale_factor).max(1);
+
+            let texture_stale = match &self.texture {
+                Some(texture) => {
+                    texture.width != width
+                        || texture.height != height
+                        || texture.generation != generation
                 }
+                None => true,
             };
 
-            canvas.copy(
-                &texture,
-                Rect::new(
-                    0,
-                    0,
-                    position.width() / scale_factor,
-                    position.height() / scale_factor,
-                ),
-                position,
-            )?;
+            self.frames_since_render = self.frames_since_render.saturating_add(1);
+            if self.pending.is_none()
+                && (texture_stale || self.frames_since_render >= self.frames_per_render)
+            {
+                self.frames_since_render = 0;
+                let (sender, receiver) = channel();
+                let style = self.style.clone();
+                let t = self.start.elapsed().as_secs_f32();
+                std::thread::spawn(move || {
+                    // receiver may have been dropped; that's fine
+                    let _ = sender.send(render_animated_frame(&*style, width, height, t));
+                });
+                self.pending = Some(receiver);
+            }
 
-            self.cache = Some(SoftwareRenderBackgroundCache { texture, surface });
+            // the most recent frame, stretched if the size has since changed
+            if let Some(texture) = &self.texture {
+                if texture.generation == generation {
+                    canvas.copy(&texture.texture, None, position)?;
+                }
+            }
+            request_redraw();
         }
 
-        self.contained.draw(canvas, focus_manager)
+        self.contained
+            .draw(&mut self.clip_children.scope(canvas), focus_manager)
     }
 
-    fn update(&mut self, mut event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.background_draw_pos = event.position;
+        self.clip_children.update(&mut event);
         match &self.sizing_policy {
-            BackgroundSizingPolicy::Children => {
-                // scroller exactly passes sizing information to parent in this
-                // case, no need to place again
-                self.contained.update(event)
-            }
+            BackgroundSizingPolicy::Children => self.contained.update(event),
             BackgroundSizingPolicy::Custom(_) => {
-                // whatever the sizing of the parent, properly place the
-                // contained within it
                 let position_for_contained =
                     place(self.contained, event.position, event.aspect_ratio_priority)?;
                 self.contained
@@ -572,13 +1681,16 @@ impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackgr
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
         self.background_draw_pos.x += pos_delta.0 as f32;
         self.background_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
         self.contained.update_adjust_position(pos_delta);
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
         match &self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.min(),
-            BackgroundSizingPolicy::Custom(custom) => Ok((custom.min_w, custom.min_h)),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.min_w.scaled(), custom.min_h.scaled()))
+            }
         }
     }
 
@@ -596,10 +1708,12 @@ impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackgr
         }
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
         match &self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.max(),
-            BackgroundSizingPolicy::Custom(custom) => Ok((custom.max_w, custom.max_h)),
+            BackgroundSizingPolicy::Custom(custom) => {
+                Ok((custom.max_w.scaled(), custom.max_h.scaled()))
+            }
         }
     }
 
@@ -624,7 +1738,7 @@ impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackgr
         }
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.preferred_width_from_height(pref_h),
             BackgroundSizingPolicy::Custom(custom) => {
@@ -640,7 +1754,7 @@ impl<'sdl, Style: SoftwareRenderBackgroundStyle> Widget for SoftwareRenderBackgr
         }
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         match &mut self.sizing_policy {
             BackgroundSizingPolicy::Children => self.contained.preferred_height_from_width(pref_w),
             BackgroundSizingPolicy::Custom(custom) => {
@@ -0,0 +1,339 @@
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    render::{Canvas, TextureCreator},
+    video::{Window, WindowContext},
+};
+
+use crate::util::{
+    error::{UiError, UiResult},
+    focus::FocusManager,
+    font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+    rust::CellRefOrCell,
+    scale::{scaled_u32, ui_scale},
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// what a badge looks like
+pub trait BadgeStyle {
+    /// the size of the badge showing some count, in physical pixels
+    fn size(&mut self, count: u32) -> UiResult<(u32, u32)>;
+
+    /// draw the badge on the provided texture canvas, which has the size given
+    /// by size. the texture will be redrawn only if the count, ui scale or
+    /// style generation changes
+    fn draw(&mut self, count: u32, canvas: &mut Canvas<Window>) -> UiResult<()>;
+}
+
+/// fill a rect with round ends (a circle, if it's square)
+fn fill_pill(canvas: &mut Canvas<Window>, color: Color) -> UiResult<()> {
+    let (w, h) = canvas.output_size()?;
+    let r = w.min(h) as f32 / 2.;
+    canvas.set_draw_color(color);
+    for y in 0..h {
+        // distance from the center of the row to the center of the ends
+        let dy = (y as f32 + 0.5 - h as f32 / 2.).abs();
+        let inset = if dy >= r {
+            r
+        } else {
+            r - (r * r - dy * dy).sqrt()
+        };
+        let inset = inset.round() as i32;
+        if inset * 2 >= w as i32 {
+            continue;
+        }
+        canvas.draw_line((inset, y as i32), (w as i32 - 1 - inset, y as i32))?;
+    }
+    Ok(())
+}
+
+/// a default provided badge style. a dot, regardless of the count
+pub struct Dot {
+    pub color: Color,
+    /// in logical pixels (scaled by the ui scale)
+    pub diameter: u32,
+}
+
+impl Default for Dot {
+    fn default() -> Self {
+        Self {
+            color: Color::RGB(220, 50, 50),
+            diameter: 10,
+        }
+    }
+}
+
+impl BadgeStyle for Dot {
+    fn size(&mut self, _count: u32) -> UiResult<(u32, u32)> {
+        let diameter = scaled_u32(self.diameter).max(1);
+        Ok((diameter, diameter))
+    }
+
+    fn draw(&mut self, _count: u32, canvas: &mut Canvas<Window>) -> UiResult<()> {
+        fill_pill(canvas, self.color)
+    }
+}
+
+/// a default provided badge style. the count, on a pill
+pub struct Counter<'sdl> {
+    pub font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+    /// in logical pixels (scaled by the ui scale)
+    pub point_size: u16,
+    pub text_color: Color,
+    pub background: Color,
+    /// space around the text, in logical pixels
+    pub padding: u32,
+    /// counts above this are shown as this with a plus (e.g. "99+")
+    pub max: u32,
+    creator: &'sdl TextureCreator<WindowContext>,
+}
+
+impl<'sdl> Counter<'sdl> {
+    pub fn new(
+        font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            font_interface,
+            point_size: 12,
+            text_color: Color::WHITE,
+            background: Color::RGB(220, 50, 50),
+            padding: 3,
+            max: 99,
+            creator,
+        }
+    }
+
+    fn text(&self, count: u32) -> String {
+        if count > self.max {
+            format!("{}+", self.max)
+        } else {
+            count.to_string()
+        }
+    }
+
+    fn scaled_point_size(&self) -> u16 {
+        crate::util::scale::scaled(self.point_size as f32)
+            .round()
+            .clamp(1., u16::MAX as f32) as u16
+    }
+}
+
+impl<'sdl> BadgeStyle for Counter<'sdl> {
+    fn size(&mut self, count: u32) -> UiResult<(u32, u32)> {
+        let text = self.text(count);
+        let (w, h) = self
+            .font_interface
+            .render_dimensions(&text, self.scaled_point_size())?;
+        let padding = scaled_u32(self.padding);
+        let h = h + padding * 2;
+        // never narrower than a circle
+        Ok(((w + padding * 2).max(h), h))
+    }
+
+    fn draw(&mut self, count: u32, canvas: &mut Canvas<Window>) -> UiResult<()> {
+        fill_pill(canvas, self.background)?;
+        let text = self.text(count);
+        let texture = self.font_interface.render(
+            &text,
+            &TextRenderProperties {
+                point_size: self.scaled_point_size(),
+                render_type: SingleLineTextRenderType::Blended(self.text_color),
+            },
+            self.creator,
+        )?;
+        let (w, h) = canvas.output_size()?;
+        let query = texture.query();
+        canvas
+            .copy(
+                &texture,
+                None,
+                sdl2::rect::Rect::new(
+                    (w as i32 - query.width as i32) / 2,
+                    (h as i32 - query.height as i32) / 2,
+                    query.width,
+                    query.height,
+                ),
+            )
+            .map_err(UiError::Sdl)
+    }
+}
+
+/// which corner of the contained widget the badge is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// draws a badge (e.g. a notification count) centered on a corner of the
+/// contained widget. it's drawn after the contained, and takes up no space, so
+/// it can extend past the contained's bounds. sizing is that of the contained
+pub struct Badge<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    /// the badge is hidden while this is 0
+    pub count: CellRefOrCell<'state, u32>,
+    pub corner: BadgeCorner,
+    /// moves the badge from the corner, in logical pixels (scaled by the ui
+    /// scale). positive is right and down
+    pub offset: (f32, f32),
+
+    style: Box<dyn BadgeStyle + 'sdl>,
+
+    /// state stored for draw from update
+    draw_pos: crate::util::rect::FRect,
+
+    /// texture is re-rendered only when the count, ui scale, or style
+    /// generation changes
+    texture: Option<OwnedTexture<'sdl>>,
+    /// the count, ui scale and style generation used to render the texture
+    texture_key: (u32, f32, u64),
+    creator: &'sdl TextureCreator<WindowContext>,
+}
+
+impl<'sdl, 'state> Badge<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        count: impl Into<CellRefOrCell<'state, u32>>,
+        style: Box<dyn BadgeStyle + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            contained,
+            count: count.into(),
+            corner: Default::default(),
+            offset: (0., 0.),
+            style,
+            draw_pos: Default::default(),
+            texture: None,
+            texture_key: (0, 0., 0),
+            creator,
+        }
+    }
+
+    /// the badge texture for the current count, re-rendered if needed
+    fn texture(&mut self, canvas: &mut Canvas<Window>) -> UiResult<&OwnedTexture<'sdl>> {
+        let key = (self.count.get(), ui_scale(), style_generation());
+        let cache = self.texture.take().filter(|_| self.texture_key == key);
+        let texture = match cache {
+            Some(v) => v,
+            None => {
+                let (w, h) = self.style.size(key.0)?;
+                let mut texture = self
+                    .creator
+                    .create_texture_target(PixelFormatEnum::ARGB8888, w.max(1), h.max(1))
+                    .map(owned_texture)?;
+                texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+
+                let mut e_out: Option<UiError> = None;
+                canvas.with_texture_canvas(&mut texture, |canvas| {
+                    canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                    canvas.clear();
+                    if let Err(e) = self.style.draw(key.0, canvas) {
+                        e_out = Some(e);
+                    }
+                })?;
+                if let Some(e) = e_out {
+                    return Err(e);
+                }
+                self.texture_key = key;
+                texture
+            }
+        };
+        Ok(self.texture.insert(texture))
+    }
+}
+
+impl<'sdl, 'state> Widget for Badge<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        self.contained.draw(&mut canvas, focus_manager)?;
+        if self.count.get() == 0 {
+            return Ok(());
+        }
+
+        let pos = self.draw_pos;
+        let (corner_x, corner_y) = match self.corner {
+            BadgeCorner::TopLeft => (pos.x, pos.y),
+            BadgeCorner::TopRight => (pos.x + pos.w, pos.y),
+            BadgeCorner::BottomLeft => (pos.x, pos.y + pos.h),
+            BadgeCorner::BottomRight => (pos.x + pos.w, pos.y + pos.h),
+        };
+        let offset_x = crate::util::scale::scaled(self.offset.0);
+        let offset_y = crate::util::scale::scaled(self.offset.1);
+
+        let texture = self.texture(&mut canvas)?;
+        let query = texture.query();
+        let dst = sdl2::rect::Rect::new(
+            (corner_x + offset_x - query.width as f32 / 2.).round() as i32,
+            (corner_y + offset_y - query.height as f32 / 2.).round() as i32,
+            query.width,
+            query.height,
+        );
+        canvas.copy(texture, None, dst).map_err(UiError::Sdl)
+    }
+}
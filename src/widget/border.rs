@@ -1,31 +1,92 @@
 use sdl2::{
     pixels::{Color, PixelFormatEnum},
-    render::{Canvas, Texture, TextureCreator},
+    render::{Canvas, TextureCreator},
     video::{Window, WindowContext},
 };
 
+use crate::layout::clipper::ClipChildren;
 use crate::util::{
+    error::{UiError, UiResult},
     focus::FocusManager,
     length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
     render::{
         bottom_right_center_seeking_rect_points, center_seeking_rect_points, interpolate_color,
-        up_left_center_seeking_rect_points,
+        owned_texture, style_generation, up_left_center_seeking_rect_points, CanvasStateGuard,
+        OwnedTexture,
     },
+    scale::{scaled_u32, ui_scale},
 };
 
 use super::{Widget, WidgetUpdateEvent};
 
+/// the width of each edge of a border. an edge with a width of zero isn't
+/// drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BorderEdges {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl BorderEdges {
+    /// the same width all the way around
+    pub fn uniform(width: u32) -> Self {
+        Self {
+            top: width,
+            right: width,
+            bottom: width,
+            left: width,
+        }
+    }
+
+    /// only the bottom edge, e.g. an underline for a text input
+    pub fn bottom(width: u32) -> Self {
+        Self {
+            bottom: width,
+            ..Default::default()
+        }
+    }
+
+    /// apply the ui scale to each edge
+    pub fn scaled(&self) -> Self {
+        Self {
+            top: scaled_u32(self.top),
+            right: scaled_u32(self.right),
+            bottom: scaled_u32(self.bottom),
+            left: scaled_u32(self.left),
+        }
+    }
+
+    /// left and right together
+    pub fn horizontal(&self) -> u32 {
+        self.left + self.right
+    }
+
+    /// top and bottom together
+    pub fn vertical(&self) -> u32 {
+        self.top + self.bottom
+    }
+}
+
 /// interface indicating what type of border the widget should use
 pub trait BorderStyle {
-    /// what is the width of this border (equal all the way around)
+    /// what is the width of this border (equal all the way around). in logical
+    /// pixels (scaled by the ui scale)
     fn width(&self) -> u32;
 
+    /// the width of each edge, in logical pixels. the contained widget is
+    /// inset by these. by default, width on every edge
+    fn edges(&self) -> BorderEdges {
+        BorderEdges::uniform(self.width())
+    }
+
     /// draw the border on the provided texture canvas. the texture will be
     /// redrawn only if the target dimensions change.
     ///
     /// the texture canvas can have a width or height of down to 1 (regardless
     /// of specified border width)
-    fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), String>;
+    fn draw(&self, canvas: &mut Canvas<Window>) -> UiResult<()>;
 }
 
 /// a default provided border style
@@ -54,15 +115,16 @@ impl BorderStyle for Bevel {
         self.width
     }
 
-    fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+    fn draw(&self, canvas: &mut Canvas<Window>) -> UiResult<()> {
         let size = canvas.output_size()?;
+        let width = scaled_u32(self.width);
         let smallest_parent_len = size.0.min(size.1);
-        let actual_width = self.width.min((smallest_parent_len + 1) / 2);
+        let actual_width = width.min((smallest_parent_len + 1) / 2);
         for i in 0i32..actual_width as i32 {
-            let progress = if self.width < 2 {
+            let progress = if width < 2 {
                 0.
             } else {
-                i as f32 / (self.width - 1) as f32
+                i as f32 / (width - 1) as f32
             };
             let lighter_color = interpolate_color(
                 self.top_left_outer_color,
@@ -108,15 +170,16 @@ impl BorderStyle for Gradient {
         self.width
     }
 
-    fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+    fn draw(&self, canvas: &mut Canvas<Window>) -> UiResult<()> {
         let size = canvas.output_size()?;
+        let width = scaled_u32(self.width);
         let smallest_parent_len = size.0.min(size.1);
-        let actual_width = self.width.min((smallest_parent_len + 1) / 2);
+        let actual_width = width.min((smallest_parent_len + 1) / 2);
         for i in 0i32..actual_width as i32 {
-            let progress = if self.width < 2 {
+            let progress = if width < 2 {
                 0.
             } else {
-                i as f32 / (self.width - 1) as f32
+                i as f32 / (width - 1) as f32
             };
 
             let color = interpolate_color(self.outer_color, self.inner_color, progress);
@@ -147,11 +210,11 @@ impl BorderStyle for Line {
         1
     }
 
-    fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+    fn draw(&self, canvas: &mut Canvas<Window>) -> UiResult<()> {
         let size = canvas.output_size()?;
         canvas.set_draw_color(self.color);
         let points = center_seeking_rect_points(0, size);
-        canvas.draw_lines(points.as_ref())
+        canvas.draw_lines(points.as_ref()).map_err(UiError::Sdl)
     }
 }
 
@@ -165,7 +228,53 @@ impl BorderStyle for Empty {
         self.width
     }
 
-    fn draw(&self, _canvas: &mut Canvas<Window>) -> Result<(), String> {
+    fn draw(&self, _canvas: &mut Canvas<Window>) -> UiResult<()> {
+        Ok(())
+    }
+}
+
+/// a default provided border style. a solid color on each edge with a non
+/// zero width, e.g. BorderEdges::bottom(1) for an underline
+pub struct Sides {
+    pub color: Color,
+    pub edges: BorderEdges,
+}
+
+impl Default for Sides {
+    fn default() -> Self {
+        Self {
+            color: Color::RGB(200, 200, 200),
+            edges: BorderEdges::bottom(1),
+        }
+    }
+}
+
+impl BorderStyle for Sides {
+    fn width(&self) -> u32 {
+        let e = self.edges;
+        e.top.max(e.right).max(e.bottom).max(e.left)
+    }
+
+    fn edges(&self) -> BorderEdges {
+        self.edges
+    }
+
+    fn draw(&self, canvas: &mut Canvas<Window>) -> UiResult<()> {
+        let (w, h) = canvas.output_size()?;
+        let e = self.edges.scaled();
+        let rects = [
+            (0, 0, w, e.top),
+            (0, h as i32 - e.bottom as i32, w, e.bottom),
+            (0, 0, e.left, h),
+            (w as i32 - e.right as i32, 0, e.right, h),
+        ];
+        canvas.set_draw_color(self.color);
+        for (x, y, w, h) in rects {
+            if w == 0 || h == 0 {
+                continue;
+            }
+            canvas.fill_rect(sdl2::rect::Rect::new(x, y, w, h))?;
+        }
         Ok(())
     }
 }
@@ -176,11 +285,18 @@ pub struct Border<'sdl> {
 
     /// store state for draw from update
     border_draw_pos: crate::util::rect::FRect,
+    /// clip the contained to the border's position
+    pub clip_children: ClipChildren,
 
     style: Box<dyn BorderStyle>,
 
-    /// texture is re-rendered only when the width or height changes
-    texture: Option<Texture<'sdl>>,
+    /// texture is re-rendered only when the width, height, ui scale, or style
+    /// generation changes
+    texture: Option<OwnedTexture<'sdl>>,
+    /// the ui scale used to render the texture
+    texture_scale: f32,
+    /// the style generation used to render the texture
+    texture_generation: u64,
     creator: &'sdl TextureCreator<WindowContext>,
 }
 
@@ -193,50 +309,50 @@ impl<'sdl> Border<'sdl> {
         Self {
             contained: contains,
             border_draw_pos: Default::default(),
+            clip_children: Default::default(),
             creator,
             texture: Default::default(),
+            texture_scale: ui_scale(),
+            texture_generation: style_generation(),
             style,
         }
     }
+
+    /// the border's edges in physical pixels
+    fn scaled_edges(&self) -> BorderEdges {
+        self.style.edges().scaled()
+    }
 }
 
 impl<'sdl> Widget for Border<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
     fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
         self.contained.preferred_portion()
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
-        let sub_amount = self.style.width() * 2; // * 2 for each side
-        let sub_amount = sub_amount as f32;
-        // subtract border width from the pref input before passing to the
-        // contained widget. then, add it back after getting the result
-        let (amount_subtracted, pref_h) = if sub_amount >= pref_h {
-            // atypical case (guard against subtract into negative range)
-            (pref_h, 0.)
-        } else {
-            // typical case
-            (sub_amount, pref_h - sub_amount)
-        };
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        let edges = self.scaled_edges();
+        // subtract the top and bottom edges from the pref input before passing
+        // to the contained widget. then, add the left and right edges to the
+        // result. guard against subtract into negative range
+        let pref_h = (pref_h - edges.vertical() as f32).max(0.);
         self.contained
             .preferred_width_from_height(pref_h)
-            .map(|some| some.map(|ok| ok + amount_subtracted))
-    }
-
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
-        let sub_amount = self.style.width() * 2; // * 2 for each side
-        let sub_amount = sub_amount as f32;
-        // subtract border width from the pref input before passing to the
-        // contained widget. then, add it back after getting the result
-        let (amount_subtracted, pref_w) = if sub_amount >= pref_w {
-            // atypical case (guard against subtract into negative range)
-            (pref_w, 0.)
-        } else {
-            // typical case
-            (sub_amount, pref_w - sub_amount)
-        };
+            .map(|some| some.map(|ok| ok + edges.horizontal() as f32))
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        let edges = self.scaled_edges();
+        // subtract the left and right edges from the pref input before passing
+        // to the contained widget. then, add the top and bottom edges to the
+        // result. guard against subtract into negative range
+        let pref_w = (pref_w - edges.horizontal() as f32).max(0.);
         self.contained
             .preferred_height_from_width(pref_w)
-            .map(|some| some.map(|ok| ok + amount_subtracted))
+            .map(|some| some.map(|ok| ok + edges.vertical() as f32))
     }
 
     fn preferred_link_allowed_exceed_portion(&self) -> bool {
@@ -259,26 +375,33 @@ impl<'sdl> Widget for Border<'sdl> {
         self.contained.max_h_fail_policy()
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        let baseline = MinLen((self.style.width() * 2) as f32);
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let edges = self.scaled_edges();
         let m = self.contained.min()?;
-        Ok((m.0.combined(baseline), m.1.combined(baseline)))
+        Ok((
+            m.0.combined(MinLen(edges.horizontal() as f32)),
+            m.1.combined(MinLen(edges.vertical() as f32)),
+        ))
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        let baseline = MaxLen((self.style.width() * 2) as f32);
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let edges = self.scaled_edges();
         let m = self.contained.max()?;
-        Ok((m.0.combined(baseline), m.1.combined(baseline)))
+        Ok((
+            m.0.combined(MaxLen(edges.horizontal() as f32)),
+            m.1.combined(MaxLen(edges.vertical() as f32)),
+        ))
     }
 
-    fn update(&mut self, mut event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.border_draw_pos = event.position;
-        let style_width = self.style.width() as f32;
+        self.clip_children.update(&mut event);
+        let edges = self.scaled_edges();
         let position_for_child = crate::util::rect::FRect {
-            x: event.position.x + style_width,
-            y: event.position.y + style_width,
-            w: event.position.w - style_width * 2.,
-            h: event.position.h - style_width * 2., // deliberately allow negative
+            x: event.position.x + edges.left as f32,
+            y: event.position.y + edges.top as f32,
+            w: event.position.w - edges.horizontal() as f32,
+            h: event.position.h - edges.vertical() as f32, // deliberately allow negative
         };
         self.contained.update(event.sub_event(position_for_child))
     }
@@ -286,6 +409,7 @@ impl<'sdl> Widget for Border<'sdl> {
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
         self.border_draw_pos.x += pos_delta.0 as f32;
         self.border_draw_pos.y += pos_delta.1 as f32;
+        self.clip_children.update_adjust_position(pos_delta);
         self.contained.update_adjust_position(pos_delta);
     }
 
@@ -293,17 +417,23 @@ impl<'sdl> Widget for Border<'sdl> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
-        self.contained.draw(canvas, focus_manager)?;
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        self.contained
+            .draw(&mut self.clip_children.scope(&mut canvas), focus_manager)?;
 
         let maybe_pos: Option<sdl2::rect::Rect> = self.border_draw_pos.into();
 
         if let Some(pos) = maybe_pos {
             // draw border if non empty position
 
+            let scale = ui_scale();
+            let generation = style_generation();
+            let scale_unchanged =
+                self.texture_scale == scale && self.texture_generation == generation;
             let cache = self.texture.take().filter(|texture| {
                 let q = texture.query();
-                q.width == pos.width() && q.height == pos.height()
+                scale_unchanged && q.width == pos.width() && q.height == pos.height()
             });
 
             let texture = match cache {
@@ -314,28 +444,28 @@ impl<'sdl> Widget for Border<'sdl> {
                     let mut texture = self
                         .creator
                         .create_texture_target(PixelFormatEnum::ARGB8888, pos.width(), pos.height())
-                        .map_err(|e| e.to_string())?;
+                        .map(owned_texture)?;
                     // the border is drawn over top of the contained texture. but the
                     // transparent part in the middle should still show through
                     texture.set_blend_mode(sdl2::render::BlendMode::Blend);
 
-                    let mut e_out: Option<String> = None;
+                    let mut e_out: Option<UiError> = None;
 
-                    canvas
-                        .with_texture_canvas(&mut texture, |canvas| {
-                            canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                            canvas.clear(); // required to prevent flickering
+                    canvas.with_texture_canvas(&mut texture, |canvas| {
+                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                        canvas.clear(); // required to prevent flickering
 
-                            if let Err(e) = self.style.draw(canvas) {
-                                e_out = Some(e);
-                            }
-                        })
-                        .map_err(|e| e.to_string())?;
+                        if let Err(e) = self.style.draw(canvas) {
+                            e_out = Some(e);
+                        }
+                    })?;
 
                     if let Some(e) = e_out {
                         return Err(e);
                     }
 
+                    self.texture_scale = scale;
+                    self.texture_generation = generation;
                     texture
                 }
             };
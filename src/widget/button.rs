@@ -1,20 +1,49 @@
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::SystemCursor;
 use sdl2::render::TextureCreator;
 use sdl2::video::WindowContext;
 
+use crate::util::accessibility::{AccessibleDescription, AccessibleRole, AccessibleState};
+use crate::util::cursor::SystemCursorCache;
+#[cfg(feature = "sdl2-ttf")]
+use crate::util::error::UiError;
+use crate::util::error::UiResult;
 use crate::util::focus::{FocusID, FocusManager};
+use crate::util::hit::{HitShape, HoverCache};
 use crate::util::length::{MaxLen, MinLen};
+use crate::util::render::CanvasStateGuard;
+use crate::util::rust::CellRefOrCell;
 
-use super::checkbox::{FocusPressWidgetSoundStyle, TextureVariantSizeCache, TextureVariantStyle};
+use super::checkbox::{
+    copy_with_opacity, default_activation_keys, ActivationContext, FocusPressWidgetSoundStyle,
+    LongPress, TextureVariantSizeCache, TextureVariantStyle, VariantTransition,
+};
 use super::{Widget, WidgetUpdateEvent};
 
 #[cfg(feature = "sdl2-ttf")]
-use super::single_line_label::SingleLineLabel;
+use super::{single_line_label::SingleLineLabel, texture::AspectRatioFailPolicy};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum ButtonTextureVariant {
     Idle,
     Focused,
     FocusedPressed,
+    /// for a latching button (see Button::latch) which is on
+    IdleOn,
+    FocusedOn,
+    FocusedPressedOn,
+}
+
+impl ButtonTextureVariant {
+    /// if a latching button is on
+    pub fn on(&self) -> bool {
+        matches!(
+            self,
+            ButtonTextureVariant::IdleOn
+                | ButtonTextureVariant::FocusedOn
+                | ButtonTextureVariant::FocusedPressedOn
+        )
+    }
 }
 
 // a button style which contains a label and draws a focus border with lines on
@@ -30,6 +59,10 @@ pub trait ButtonStyle<TVariant>: TextureVariantStyle<TVariant> {
     fn as_mut_widget(&mut self) -> &mut dyn Widget;
     fn as_widget(&self) -> &dyn Widget;
     fn as_mut_texture_variant_style(&mut self) -> &mut dyn TextureVariantStyle<TVariant>;
+    /// what the button is called, for screen readers
+    fn accessible_label(&self) -> String {
+        String::new()
+    }
 }
 
 #[cfg(feature = "sdl2-ttf")]
@@ -47,6 +80,11 @@ impl<'sdl, 'state> ButtonStyle<ButtonTextureVariant> for LabelButtonStyle<'sdl,
     ) -> &mut dyn TextureVariantStyle<ButtonTextureVariant> {
         self
     }
+
+    fn accessible_label(&self) -> String {
+        let text = self.label.text.scope_take();
+        text.clone()
+    }
 }
 
 #[cfg(feature = "sdl2-ttf")]
@@ -55,98 +93,446 @@ impl<'sdl, 'state> TextureVariantStyle<ButtonTextureVariant> for LabelButtonStyl
         &mut self,
         variant: ButtonTextureVariant,
         canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
-    ) -> Result<(), String> {
-        let size = canvas.output_size().map_err(|e| e.to_string())?;
-
-        let amount_inward = 5i32;
-
-        if size.0 <= amount_inward as u32 || size.1 <= amount_inward as u32 {
+    ) -> UiResult<()> {
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
+        if !draw_button_corners(variant, size, canvas)? {
             return Ok(()); // too small to draw properly
         }
 
-        let color = match variant {
-            ButtonTextureVariant::Idle => sdl2::pixels::Color::RGB(50, 50, 50),
-            ButtonTextureVariant::Focused => sdl2::pixels::Color::RGB(118, 73, 206),
-            ButtonTextureVariant::FocusedPressed => sdl2::pixels::Color::RGB(200, 200, 200),
-        };
-
-        canvas.set_draw_color(color);
-
-        let top_left_points = [
-            sdl2::rect::Point::new(amount_inward, 0),
-            sdl2::rect::Point::new(0, 0),
-            sdl2::rect::Point::new(0, amount_inward),
-        ];
-
-        let bottom_left_points = [
-            sdl2::rect::Point::new(amount_inward, size.1 as i32 - 1),
-            sdl2::rect::Point::new(0, size.1 as i32 - 1),
-            sdl2::rect::Point::new(0, size.1 as i32 - 1 - amount_inward),
-        ];
-
-        let top_right_points = [
-            sdl2::rect::Point::new(size.0 as i32 - 1 - amount_inward, 0),
-            sdl2::rect::Point::new(size.0 as i32 - 1, 0),
-            sdl2::rect::Point::new(size.0 as i32 - 1, amount_inward),
-        ];
-
-        let bottom_right_points = [
-            sdl2::rect::Point::new(size.0 as i32 - 1 - amount_inward, size.1 as i32 - 1),
-            sdl2::rect::Point::new(size.0 as i32 - 1, size.1 as i32 - 1),
-            sdl2::rect::Point::new(size.0 as i32 - 1, size.1 as i32 - 1 - amount_inward),
-        ];
-
-        let all_points = [
-            top_left_points,
-            top_right_points,
-            bottom_left_points,
-            bottom_right_points,
-        ];
-
-        for points in all_points {
-            canvas.draw_lines(points.as_ref())?;
-        }
-
         // draw foreground
-        let mut event = WidgetUpdateEvent {
-            position: crate::util::rect::FRect {
+        draw_label_at(
+            &mut self.label,
+            crate::util::rect::FRect {
                 x: 0.,
                 y: 0.,
                 w: size.0 as f32,
                 h: size.1 as f32,
             },
-            aspect_ratio_priority: Default::default(),
-            events: Default::default(),
-            focus_manager: &mut FocusManager::default(), // dummy
-            clipping_rect: sdl2::render::ClippingRect::None,
-            // does not matter, as the window_id is used to filter relevant
-            // events and no events are being passed in
-            window_id: u32::MAX,
-        };
+            canvas,
+        )
+    }
 
-        match self.label.update(event.dup()) {
-            Ok(()) => (),
-            Err(e) => return Err(e),
-        };
+    fn draw_long_press(
+        &mut self,
+        progress: f32,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        position: sdl2::rect::Rect,
+    ) -> UiResult<()> {
+        super::checkbox::draw_long_press_bar(
+            progress,
+            sdl2::pixels::Color::RGB(118, 73, 206),
+            canvas,
+            position,
+        )
+    }
+}
 
-        match self.label.draw(canvas, &FocusManager::default()) {
-            Ok(()) => (),
-            Err(e) => return Err(e),
-        };
+/// draws the corner lines used by the provided button styles. returns false if
+/// the size is too small to draw properly
+#[cfg(feature = "sdl2-ttf")]
+fn draw_button_corners(
+    variant: ButtonTextureVariant,
+    size: (u32, u32),
+    canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+) -> UiResult<bool> {
+    let amount_inward = 5i32;
+
+    if size.0 <= amount_inward as u32 || size.1 <= amount_inward as u32 {
+        return Ok(false);
+    }
+
+    if variant.on() {
+        canvas.set_draw_color(sdl2::pixels::Color::RGB(45, 30, 80));
+        canvas.fill_rect(None)?;
+    }
+
+    let color = match variant {
+        ButtonTextureVariant::Idle | ButtonTextureVariant::IdleOn => {
+            sdl2::pixels::Color::RGB(50, 50, 50)
+        }
+        ButtonTextureVariant::Focused | ButtonTextureVariant::FocusedOn => {
+            sdl2::pixels::Color::RGB(118, 73, 206)
+        }
+        ButtonTextureVariant::FocusedPressed | ButtonTextureVariant::FocusedPressedOn => {
+            sdl2::pixels::Color::RGB(200, 200, 200)
+        }
+    };
+
+    canvas.set_draw_color(color);
+
+    let top_left_points = [
+        sdl2::rect::Point::new(amount_inward, 0),
+        sdl2::rect::Point::new(0, 0),
+        sdl2::rect::Point::new(0, amount_inward),
+    ];
+
+    let bottom_left_points = [
+        sdl2::rect::Point::new(amount_inward, size.1 as i32 - 1),
+        sdl2::rect::Point::new(0, size.1 as i32 - 1),
+        sdl2::rect::Point::new(0, size.1 as i32 - 1 - amount_inward),
+    ];
+
+    let top_right_points = [
+        sdl2::rect::Point::new(size.0 as i32 - 1 - amount_inward, 0),
+        sdl2::rect::Point::new(size.0 as i32 - 1, 0),
+        sdl2::rect::Point::new(size.0 as i32 - 1, amount_inward),
+    ];
+
+    let bottom_right_points = [
+        sdl2::rect::Point::new(size.0 as i32 - 1 - amount_inward, size.1 as i32 - 1),
+        sdl2::rect::Point::new(size.0 as i32 - 1, size.1 as i32 - 1),
+        sdl2::rect::Point::new(size.0 as i32 - 1, size.1 as i32 - 1 - amount_inward),
+    ];
+
+    let all_points = [
+        top_left_points,
+        top_right_points,
+        bottom_left_points,
+        bottom_right_points,
+    ];
+
+    for points in all_points {
+        canvas.draw_lines(points.as_ref())?;
+    }
+    Ok(true)
+}
+
+/// update then draw a label at some position on a texture canvas
+#[cfg(feature = "sdl2-ttf")]
+fn draw_label_at(
+    label: &mut SingleLineLabel,
+    position: crate::util::rect::FRect,
+    canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+) -> UiResult<()> {
+    let mut event = WidgetUpdateEvent {
+        position,
+        aspect_ratio_priority: Default::default(),
+        events: Default::default(),
+        focus_manager: &mut FocusManager::default(), // dummy
+        clipping_rect: sdl2::render::ClippingRect::None,
+        // does not matter, as the window_id is used to filter relevant
+        // events and no events are being passed in
+        window_id: u32::MAX,
+        window_area: position,
+        input: Default::default(),
+    };
+
+    label.update(event.dup())?;
+    label.draw(canvas, &FocusManager::default())
+}
+
+/// where the icon is placed relative to the label
+#[derive(Clone, Copy, Default)]
+pub enum IconPlacement {
+    #[default]
+    Left,
+    Right,
+    /// above the label. the icon is given the same height as the label
+    Top,
+}
+
+/// a button style with a texture icon beside a label. the icon keeps its
+/// aspect ratio and matches the label's height
+#[cfg(feature = "sdl2-ttf")]
+pub struct IconLabelButtonStyle<'sdl, 'state> {
+    pub icon: &'sdl crate::util::render::SdlTexture<'sdl>,
+    pub label: SingleLineLabel<'sdl, 'state>,
+    /// space between the icon and the label, in logical pixels (scaled by the
+    /// ui scale)
+    pub gap: f32,
+    pub placement: IconPlacement,
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl, 'state> IconLabelButtonStyle<'sdl, 'state> {
+    pub fn new(
+        icon: &'sdl crate::util::render::SdlTexture<'sdl>,
+        label: SingleLineLabel<'sdl, 'state>,
+    ) -> Self {
+        Self {
+            icon,
+            label,
+            gap: 5.,
+            placement: Default::default(),
+        }
+    }
+
+    /// width / height of the icon
+    fn icon_ratio(&self) -> f32 {
+        let q = self.icon.query();
+        if q.height == 0 {
+            return 1.; // guard div
+        }
+        q.width as f32 / q.height as f32
+    }
+
+    fn scaled_gap(&self) -> f32 {
+        crate::util::scale::scaled(self.gap)
+    }
+}
+
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl, 'state> ButtonStyle<ButtonTextureVariant> for IconLabelButtonStyle<'sdl, 'state> {
+    fn as_mut_widget(&mut self) -> &mut dyn Widget {
+        self
+    }
+
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+
+    fn as_mut_texture_variant_style(
+        &mut self,
+    ) -> &mut dyn TextureVariantStyle<ButtonTextureVariant> {
+        self
+    }
+
+    fn accessible_label(&self) -> String {
+        let text = self.label.text.scope_take();
+        text.clone()
+    }
+}
+
+/// gives the sizing of the icon and label together
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl, 'state> Widget for IconLabelButtonStyle<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut self.label as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let (min_w, min_h) = self.label.min()?;
+        let r = self.icon_ratio();
+        let gap = self.scaled_gap();
+        Ok(match self.placement {
+            IconPlacement::Left | IconPlacement::Right => {
+                (MinLen(min_w.0 + gap + r * min_h.0), min_h)
+            }
+            IconPlacement::Top => (
+                MinLen(min_w.0.max(r * min_h.0)),
+                MinLen(min_h.0 * 2. + gap),
+            ),
+        })
+    }
+
+    fn min_w_fail_policy(&self) -> crate::util::length::MinLenFailPolicy {
+        self.label.min_w_fail_policy()
+    }
 
+    fn min_h_fail_policy(&self) -> crate::util::length::MinLenFailPolicy {
+        self.label.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let (max_w, max_h) = self.label.max()?;
+        if max_h.0 == MaxLen::LAX.0 {
+            return Ok((MaxLen::LAX, MaxLen::LAX));
+        }
+        let r = self.icon_ratio();
+        let gap = self.scaled_gap();
+        Ok(match self.placement {
+            IconPlacement::Left | IconPlacement::Right => {
+                (max_w.combined(MaxLen(gap + r * max_h.0)), max_h)
+            }
+            IconPlacement::Top => (
+                MaxLen(max_w.0.max(r * max_h.0)),
+                MaxLen(max_h.0 * 2. + gap),
+            ),
+        })
+    }
+
+    fn max_w_fail_policy(&self) -> crate::util::length::MaxLenFailPolicy {
+        self.label.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> crate::util::length::MaxLenFailPolicy {
+        self.label.max_h_fail_policy()
+    }
+
+    fn preferred_portion(
+        &self,
+    ) -> (
+        crate::util::length::PreferredPortion,
+        crate::util::length::PreferredPortion,
+    ) {
+        self.label.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        let r = self.icon_ratio();
+        let gap = self.scaled_gap();
+        match self.placement {
+            IconPlacement::Left | IconPlacement::Right => self
+                .label
+                .preferred_width_from_height(pref_h)
+                .map(|w| w.map(|w| w + gap + r * pref_h)),
+            IconPlacement::Top => {
+                let label_h = ((pref_h - gap) / 2.).max(0.);
+                self.label
+                    .preferred_width_from_height(label_h)
+                    .map(|w| w.map(|w| w.max(r * label_h)))
+            }
+        }
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        let r = self.icon_ratio();
+        let gap = self.scaled_gap();
+        match self.placement {
+            IconPlacement::Left | IconPlacement::Right => {
+                // the label's width is proportional to its height
+                let label_ratio = match self.label.preferred_width_from_height(1.)? {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                };
+                let ratio = label_ratio + r;
+                if ratio <= 0. {
+                    return None; // guard div
+                }
+                Some(Ok(((pref_w - gap) / ratio).max(0.)))
+            }
+            IconPlacement::Top => self
+                .label
+                .preferred_height_from_width(pref_w)
+                .map(|h| h.map(|h| h * 2. + gap)),
+        }
+    }
+
+    fn draw(
+        &mut self,
+        _canvas: &mut sdl2::render::WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        // only used for sizing. drawn via TextureVariantStyle
         Ok(())
     }
 }
 
+#[cfg(feature = "sdl2-ttf")]
+impl<'sdl, 'state> TextureVariantStyle<ButtonTextureVariant>
+    for IconLabelButtonStyle<'sdl, 'state>
+{
+    fn draw(
+        &mut self,
+        variant: ButtonTextureVariant,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+    ) -> UiResult<()> {
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
+        if !draw_button_corners(variant, size, canvas)? {
+            return Ok(()); // too small to draw properly
+        }
+
+        let (w, h) = (size.0 as f32, size.1 as f32);
+        let r = self.icon_ratio();
+        let gap = self.scaled_gap();
+
+        let (icon_pos, label_pos) = match self.placement {
+            IconPlacement::Left | IconPlacement::Right => {
+                let mut label_h = h;
+                let mut label_w = match self.label.preferred_width_from_height(label_h) {
+                    Some(v) => v?,
+                    None => (w - gap - r * label_h).max(0.),
+                };
+                // shrink if the content doesn't fit
+                let content_w = label_w + gap + r * label_h;
+                if content_w > w && content_w > gap {
+                    let factor = ((w - gap) / (content_w - gap)).max(0.);
+                    label_h *= factor;
+                    label_w *= factor;
+                }
+                let icon_w = r * label_h;
+                // center the content
+                let x = (w - (label_w + gap + icon_w)) / 2.;
+                let y = (h - label_h) / 2.;
+                let (icon_x, label_x) = match self.placement {
+                    IconPlacement::Left => (x, x + icon_w + gap),
+                    _ => (x + label_w + gap, x),
+                };
+                (
+                    crate::util::rect::FRect {
+                        x: icon_x,
+                        y,
+                        w: icon_w,
+                        h: label_h,
+                    },
+                    crate::util::rect::FRect {
+                        x: label_x,
+                        y,
+                        w: label_w,
+                        h: label_h,
+                    },
+                )
+            }
+            IconPlacement::Top => {
+                let label_h = ((h - gap) / 2.).max(0.);
+                (
+                    crate::util::rect::FRect {
+                        x: 0.,
+                        y: 0.,
+                        w,
+                        h: label_h,
+                    },
+                    crate::util::rect::FRect {
+                        x: 0.,
+                        y: label_h + gap,
+                        w,
+                        h: label_h,
+                    },
+                )
+            }
+        };
+
+        super::texture::texture_draw(
+            self.icon,
+            &AspectRatioFailPolicy::default(),
+            canvas,
+            None,
+            icon_pos,
+        )?;
+        draw_label_at(&mut self.label, label_pos, canvas)
+    }
+
+    fn draw_long_press(
+        &mut self,
+        progress: f32,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        position: sdl2::rect::Rect,
+    ) -> UiResult<()> {
+        super::checkbox::draw_long_press_bar(
+            progress,
+            sdl2::pixels::Color::RGB(118, 73, 206),
+            canvas,
+            position,
+        )
+    }
+}
+
+/// called when the button is activated
+pub type ButtonFn<'state> = Box<dyn FnMut(&ActivationContext) -> UiResult<()> + 'state>;
+
 pub struct Button<'sdl, 'state> {
-    pub functionality: Box<dyn FnMut() -> Result<(), String> + 'state>,
+    pub functionality: ButtonFn<'state>,
     pub focus_id: FocusID,
     /// internal state for drawing
     pressed: bool,
-    /// hovered is only used if no focus manager is available
-    hovered: bool,
     /// internal state for sound
     focused_previous_frame: bool,
+    /// internal state for activating via keyboard
+    activation_key_held: bool,
+    /// keys which activate the button while it is focused
+    pub activation_keys: Vec<Keycode>,
+    /// the button can be pressed this far outside of where it's drawn, in
+    /// logical pixels. see HitTest::expansion
+    pub hit_expansion: f32,
+    /// the part of the button which can be pressed. see ShapedButtonStyle for
+    /// drawing a matching shape
+    pub hit_shape: HitShape,
+    /// the mouse cursor shown while the button is hovered. None leaves the
+    /// cursor as is
+    pub hover_cursor: Option<SystemCursor>,
+    /// internal state for drawing, sound, and the hover cursor
+    hover: HoverCache,
+    cursor_cache: SystemCursorCache,
 
     /// how does the button look
     style: Box<dyn ButtonStyle<ButtonTextureVariant> + 'sdl>,
@@ -156,15 +542,46 @@ pub struct Button<'sdl, 'state> {
     /// state stored for draw from update
     draw_pos: crate::util::rect::FRect,
 
+    /// crossfade between variants. disabled by default
+    pub transition: VariantTransition<ButtonTextureVariant>,
+    /// if set, holding the press calls on_long_press instead of the
+    /// functionality
+    pub long_press: Option<LongPress<'state>>,
+    /// if set, the button latches: activation toggles this before calling the
+    /// functionality, and the style is given the on variants while it's true.
+    /// e.g. bold or italic in a toolbar
+    pub latch: Option<CellRefOrCell<'state, bool>>,
+
     creator: &'sdl TextureCreator<WindowContext>,
     idle: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
     focused: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
     focus_pressed: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
+    idle_on: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
+    focused_on: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
+    focus_pressed_on: TextureVariantSizeCache<'sdl, ButtonTextureVariant>,
 }
 
 impl<'sdl, 'state> Button<'sdl, 'state> {
     pub fn new(
-        functionality: Box<dyn FnMut() -> Result<(), String> + 'state>,
+        mut functionality: Box<dyn FnMut() -> UiResult<()> + 'state>,
+        focus_id: FocusID,
+        style: Box<dyn ButtonStyle<ButtonTextureVariant> + 'sdl>,
+        sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self::new_with_context(
+            Box::new(move |_| functionality()),
+            focus_id,
+            style,
+            sounds,
+            creator,
+        )
+    }
+
+    /// the functionality is told how the button was activated (e.g. for
+    /// shift-click or double click)
+    pub fn new_with_context(
+        functionality: ButtonFn<'state>,
         focus_id: FocusID,
         style: Box<dyn ButtonStyle<ButtonTextureVariant> + 'sdl>,
         sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
@@ -174,21 +591,37 @@ impl<'sdl, 'state> Button<'sdl, 'state> {
             functionality,
             focus_id,
             pressed: false,
-            hovered: false,
             focused_previous_frame: false,
+            activation_key_held: false,
+            activation_keys: default_activation_keys(),
+            hit_expansion: 0.,
+            hit_shape: HitShape::Rect,
+            hover_cursor: Some(SystemCursor::Hand),
+            hover: Default::default(),
+            cursor_cache: Default::default(),
             style,
             sounds,
             creator,
+            transition: Default::default(),
+            long_press: None,
+            latch: None,
             idle: Default::default(),
             focused: Default::default(),
             focus_pressed: Default::default(),
+            idle_on: Default::default(),
+            focused_on: Default::default(),
+            focus_pressed_on: Default::default(),
             draw_pos: Default::default(),
         }
     }
 }
 
 impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.style.as_mut_widget()]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
         self.style.as_mut_widget().min()
     }
 
@@ -200,7 +633,7 @@ impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
         self.style.as_widget().min_h_fail_policy()
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
         self.style.as_mut_widget().max()
     }
 
@@ -221,14 +654,14 @@ impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
         self.style.as_widget().preferred_portion()
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         self.style
             .as_mut_widget()
             .preferred_width_from_height(pref_h)
     }
 
     /// implementors should use this to enforce an aspect ratio
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         self.style
             .as_mut_widget()
             .preferred_height_from_width(pref_w)
@@ -240,18 +673,48 @@ impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
             .preferred_link_allowed_exceed_portion()
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn accessible_description(&self) -> Option<AccessibleDescription> {
+        Some(AccessibleDescription {
+            role: AccessibleRole::Button,
+            label: self.style.accessible_label(),
+            value: None,
+            state: AccessibleState {
+                checked: self.latch.as_ref().map(|latch| latch.get()),
+                pressed: self.pressed,
+            },
+        })
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
-        let fun: &mut dyn FnMut() -> Result<(), String> = &mut self.functionality;
+        let latch = &self.latch;
+        let functionality = &mut self.functionality;
+        let fun = &mut |context: &ActivationContext| {
+            if let Some(latch) = latch {
+                latch.set(!latch.get());
+            }
+            functionality(context)
+        };
         super::checkbox::focus_press_update_implementation(
-            &mut self.hovered,
+            &mut self.hover,
             &mut self.pressed,
             &mut self.focused_previous_frame,
+            &mut self.activation_key_held,
+            &self.activation_keys,
             &self.focus_id,
-            event,
+            crate::util::scale::scaled(self.hit_expansion),
+            &self.hit_shape,
+            event.dup(),
             fun,
             self.sounds.as_mut(),
-        )
+            self.long_press.as_mut(),
+        )?;
+        self.cursor_cache
+            .set_if(self.hover_cursor.filter(|_| self.hover.hovered()));
+        event
+            .focus_manager
+            .report_description(&self.focus_id, self.accessible_description());
+        Ok(())
     }
 
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
@@ -263,7 +726,8 @@ impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         let position: sdl2::rect::Rect = match self.draw_pos.into() {
             Some(v) => v,
             // the rest of this is just for drawing or being clicked, both
@@ -274,31 +738,49 @@ impl<'sdl, 'state> Widget for Button<'sdl, 'state> {
         let focused = focus_manager.is_focused(&self.focus_id);
         let pressed = self.pressed;
 
-        let variant = if focused || self.hovered {
-            if pressed {
-                ButtonTextureVariant::FocusedPressed
-            } else {
-                ButtonTextureVariant::Focused
-            }
-        } else {
-            ButtonTextureVariant::Idle
-        };
-
-        let cache = match variant {
-            ButtonTextureVariant::Idle => &mut self.idle,
-            ButtonTextureVariant::Focused => &mut self.focused,
-            ButtonTextureVariant::FocusedPressed => &mut self.focus_pressed,
+        let on = self
+            .latch
+            .as_ref()
+            .map(|latch| latch.get())
+            .unwrap_or(false);
+
+        let variant = match (focused || self.hover.hovered(), pressed, on) {
+            (true, true, false) => ButtonTextureVariant::FocusedPressed,
+            (true, true, true) => ButtonTextureVariant::FocusedPressedOn,
+            (true, false, false) => ButtonTextureVariant::Focused,
+            (true, false, true) => ButtonTextureVariant::FocusedOn,
+            (false, _, false) => ButtonTextureVariant::Idle,
+            (false, _, true) => ButtonTextureVariant::IdleOn,
         };
 
-        let txt = cache.render(
-            self.style.as_mut_texture_variant_style(),
-            variant,
-            (position.width(), position.height()),
-            self.creator,
-            canvas,
-        )?;
+        for (variant, opacity) in self.transition.layers(variant).into_iter().flatten() {
+            let cache = match variant {
+                ButtonTextureVariant::Idle => &mut self.idle,
+                ButtonTextureVariant::Focused => &mut self.focused,
+                ButtonTextureVariant::FocusedPressed => &mut self.focus_pressed,
+                ButtonTextureVariant::IdleOn => &mut self.idle_on,
+                ButtonTextureVariant::FocusedOn => &mut self.focused_on,
+                ButtonTextureVariant::FocusedPressedOn => &mut self.focus_pressed_on,
+            };
+
+            let txt = cache.render(
+                self.style.as_mut_texture_variant_style(),
+                variant,
+                (position.width(), position.height()),
+                self.creator,
+                &mut canvas,
+            )?;
+
+            copy_with_opacity(&mut canvas, txt, opacity, position)?;
+        }
 
-        canvas.copy(txt, None, Some(position))?;
+        if let Some(progress) = self.long_press.as_ref().and_then(|l| l.progress()) {
+            self.style.as_mut_texture_variant_style().draw_long_press(
+                progress,
+                &mut canvas,
+                position,
+            )?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,169 @@
+use crate::util::{
+    dirty::DirtyFlag,
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    perf::count_cache,
+    render::style_generation,
+    scale::ui_scale,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// what the cached values were computed with
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CacheKey {
+    generation: u64,
+    ui_scale: f32,
+    /// fonts or styles can change measurements
+    style_generation: u64,
+}
+
+#[derive(Default)]
+struct SizingCache {
+    key: Option<CacheKey>,
+    min: Option<(MinLen, MinLen)>,
+    max: Option<(MaxLen, MaxLen)>,
+    /// the most recent input and output
+    width_from_height: Option<(f32, Option<f32>)>,
+    height_from_width: Option<(f32, Option<f32>)>,
+}
+
+/// remembers the contained widget's min, max, and preferred lengths, instead
+/// of asking for them each frame. for deep trees, or widgets which are costly
+/// to measure.
+///
+/// the contained's sizing must only depend on its content, which is signaled
+/// to have changed via the dirty flag (e.g. after setting a label's text). the
+/// cache is also discarded if the ui scale or style generation changes. errors
+/// aren't cached
+pub struct CachedSizing<'sdl> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub dirty: DirtyFlag,
+    cache: SizingCache,
+}
+
+impl<'sdl> CachedSizing<'sdl> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, dirty: DirtyFlag) -> Self {
+        Self {
+            contained,
+            dirty,
+            cache: Default::default(),
+        }
+    }
+
+    /// discard the cache if it's stale
+    fn check(&mut self) {
+        let key = CacheKey {
+            generation: self.dirty.generation(),
+            ui_scale: ui_scale(),
+            style_generation: style_generation(),
+        };
+        if self.cache.key != Some(key) {
+            self.cache = SizingCache {
+                key: Some(key),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+impl<'sdl> Widget for CachedSizing<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.check();
+        count_cache(self.cache.min.is_some());
+        if let Some(min) = self.cache.min {
+            return Ok(min);
+        }
+        let min = self.contained.min()?;
+        self.cache.min = Some(min);
+        Ok(min)
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.check();
+        count_cache(self.cache.max.is_some());
+        if let Some(max) = self.cache.max {
+            return Ok(max);
+        }
+        let max = self.contained.max()?;
+        self.cache.max = Some(max);
+        Ok(max)
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.check();
+        if let Some((h, w)) = self.cache.width_from_height {
+            if h == pref_h {
+                return w.map(Ok);
+            }
+        }
+        let w = self.contained.preferred_width_from_height(pref_h);
+        match w {
+            Some(Err(_)) => {}
+            Some(Ok(w)) => self.cache.width_from_height = Some((pref_h, Some(w))),
+            None => self.cache.width_from_height = Some((pref_h, None)),
+        }
+        w
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.check();
+        if let Some((w, h)) = self.cache.height_from_width {
+            if w == pref_w {
+                return h.map(Ok);
+            }
+        }
+        let h = self.contained.preferred_height_from_width(pref_w);
+        match h {
+            Some(Err(_)) => {}
+            Some(Ok(h)) => self.cache.height_from_width = Some((pref_w, Some(h))),
+            None => self.cache.height_from_width = Some((pref_w, None)),
+        }
+        h
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+}
@@ -0,0 +1,172 @@
+use sdl2::{
+    pixels::Color,
+    render::{Canvas, TextureCreator},
+    video::{Window, WindowContext},
+};
+
+use crate::util::{
+    error::{UiError, UiResult},
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rect::FRect,
+    render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+    rust::CellRefOrCell,
+};
+
+use super::{debug::CustomSizingControl, Widget, WidgetUpdateEvent};
+
+/// draws into a texture of the given size (width, height). the texture starts
+/// out transparent
+pub type CustomCanvasDraw<'sdl> =
+    Box<dyn Fn(&mut Canvas<Window>, (u32, u32)) -> UiResult<()> + 'sdl>;
+
+struct CustomCanvasCache<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    width: u32,
+    height: u32,
+    version: u64,
+    /// see style_generation
+    generation: u64,
+}
+
+/// draws with a user provided function. the result is kept in a texture, and
+/// the function is only called again when the size or version changes
+pub struct CustomCanvas<'sdl, 'state> {
+    pub draw_fn: CustomCanvasDraw<'sdl>,
+    /// change this to re-draw (e.g. increment when whatever is drawn changes)
+    pub version: CellRefOrCell<'state, u64>,
+    pub sizing: CustomSizingControl,
+
+    /// state stored for draw from update
+    draw_pos: FRect,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    cache: Option<CustomCanvasCache<'sdl>>,
+}
+
+impl<'sdl, 'state> CustomCanvas<'sdl, 'state> {
+    pub fn new(
+        draw_fn: CustomCanvasDraw<'sdl>,
+        version: CellRefOrCell<'state, u64>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            draw_fn,
+            version,
+            sizing: Default::default(),
+            draw_pos: Default::default(),
+            creator,
+            cache: None,
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for CustomCanvas<'sdl, 'state> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.sizing.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.sizing.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.sizing.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.sizing.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.sizing.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.sizing.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.sizing.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.sizing.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.sizing.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.sizing.preferred_link_allowed_exceed_portion()
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let pos: Option<sdl2::rect::Rect> = self.draw_pos.into();
+        let pos = match pos {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let size = (pos.width(), pos.height());
+        let version = self.version.get();
+        let generation = style_generation();
+
+        let cache = match self.cache.take().filter(|cache| {
+            (cache.width, cache.height) == size
+                && cache.version == version
+                && cache.generation == generation
+        }) {
+            Some(cache) => cache,
+            None => {
+                let mut texture = self
+                    .creator
+                    .create_texture_target(None, size.0, size.1)
+                    .map(owned_texture)?;
+                texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+
+                let mut e_out: Option<UiError> = None;
+                let draw_fn = &self.draw_fn;
+                canvas.with_texture_canvas(&mut texture, |canvas| {
+                    canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                    canvas.clear();
+                    if let Err(e) = draw_fn(canvas, size) {
+                        e_out = Some(e);
+                    }
+                })?;
+
+                if let Some(e) = e_out {
+                    return Err(e);
+                }
+
+                CustomCanvasCache {
+                    texture,
+                    width: size.0,
+                    height: size.1,
+                    version,
+                    generation,
+                }
+            }
+        };
+
+        canvas.copy(&cache.texture, None, pos)?;
+        self.cache = Some(cache);
+        Ok(())
+    }
+}
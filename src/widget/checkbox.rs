@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::time::{Duration, Instant};
 
 use sdl2::{
     keyboard::{Keycode, Mod},
@@ -10,17 +10,22 @@ use sdl2::{
 };
 
 use crate::util::{
-    focus::{
-        point_in_position_and_clipping_rect, DefaultFocusBehaviorArg, FocusID, FocusManager
-    },
+    accessibility::{AccessibleDescription, AccessibleRole, AccessibleState},
+    animation::{request_redraw, Easing},
+    error::{UiError, UiResult},
+    feedback::UiFeedback,
+    focus::{DefaultFocusBehaviorArg, FocusID, FocusManager},
+    hit::{HitShape, HitTest, HoverCache},
     length::{MaxLen, MinLen},
+    render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+    rust::CellRefOrCell,
 };
 
 use super::{Widget, WidgetUpdateEvent};
 
 /// a different texture is rendered for each of the displayed states that a
 /// checkbox can have
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum CheckBoxTextureVariant {
     Idle,
     Focused,
@@ -33,7 +38,7 @@ pub enum CheckBoxTextureVariant {
 }
 
 impl CheckBoxTextureVariant {
-    fn focused(&self) -> bool {
+    pub fn focused(&self) -> bool {
         match self {
             CheckBoxTextureVariant::Focused
             | CheckBoxTextureVariant::FocusedPressed
@@ -43,7 +48,7 @@ impl CheckBoxTextureVariant {
         }
     }
 
-    fn pressed(&self) -> bool {
+    pub fn pressed(&self) -> bool {
         match self {
             CheckBoxTextureVariant::FocusedPressed
             | CheckBoxTextureVariant::FocusedPressedChecked
@@ -52,7 +57,7 @@ impl CheckBoxTextureVariant {
         }
     }
 
-    fn checked(&self) -> bool {
+    pub fn checked(&self) -> bool {
         match self {
             CheckBoxTextureVariant::FocusChecked
             | CheckBoxTextureVariant::FocusedPressedChecked
@@ -66,7 +71,41 @@ impl CheckBoxTextureVariant {
 /// indicates how a size cache should be drawn for a given variant
 pub trait TextureVariantStyle<TVariant> {
     /// The texture will be redrawn only if the target dimensions change.
-    fn draw(&mut self, variant: TVariant, canvas: &mut Canvas<Window>) -> Result<(), String>;
+    fn draw(&mut self, variant: TVariant, canvas: &mut Canvas<Window>) -> UiResult<()>;
+
+    /// drawn over the widget each frame while a long press is being held (see
+    /// LongPress). progress is from 0 to 1. draws nothing by default
+    fn draw_long_press(
+        &mut self,
+        _progress: f32,
+        _canvas: &mut Canvas<Window>,
+        _position: sdl2::rect::Rect,
+    ) -> UiResult<()> {
+        Ok(())
+    }
+}
+
+/// a bar along the bottom of the position, filling from left to right
+pub fn draw_long_press_bar(
+    progress: f32,
+    color: Color,
+    canvas: &mut Canvas<Window>,
+    position: sdl2::rect::Rect,
+) -> UiResult<()> {
+    let height = crate::util::scale::scaled_u32(3).min(position.height());
+    let width = (position.width() as f32 * progress.clamp(0., 1.)).round() as u32;
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+    canvas.set_draw_color(color);
+    canvas
+        .fill_rect(sdl2::rect::Rect::new(
+            position.x(),
+            position.bottom() - height as i32,
+            width,
+            height,
+        ))
+        .map_err(UiError::Sdl)
 }
 
 /// a default provided check box style
@@ -78,8 +117,8 @@ impl TextureVariantStyle<CheckBoxTextureVariant> for DefaultCheckBoxStyle {
         &mut self,
         variant: CheckBoxTextureVariant,
         canvas: &mut Canvas<Window>,
-    ) -> Result<(), String> {
-        let size = canvas.output_size().map_err(|e| e.to_string())?;
+    ) -> UiResult<()> {
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
 
         let amount_inward = 5i32;
 
@@ -189,11 +228,22 @@ impl TextureVariantStyle<CheckBoxTextureVariant> for DefaultCheckBoxStyle {
 
         Ok(())
     }
+
+    fn draw_long_press(
+        &mut self,
+        progress: f32,
+        canvas: &mut Canvas<Window>,
+        position: sdl2::rect::Rect,
+    ) -> UiResult<()> {
+        draw_long_press_bar(progress, Color::RGB(118, 73, 206), canvas, position)
+    }
 }
 
 /// A cache for managing and reusing textures based on some style variant and size.
 pub(crate) struct TextureVariantSizeCache<'sdl, TVariant> {
-    pub cache: Option<sdl2::render::Texture<'sdl>>,
+    pub cache: Option<OwnedTexture<'sdl>>,
+    /// the style generation that the cache was rendered with
+    generation: u64,
     _marker: std::marker::PhantomData<TVariant>,
 }
 
@@ -201,6 +251,7 @@ impl<'sdl, TVariant> Default for TextureVariantSizeCache<'sdl, TVariant> {
     fn default() -> Self {
         Self {
             cache: None,
+            generation: style_generation(),
             _marker: std::marker::PhantomData,
         }
     }
@@ -218,10 +269,11 @@ impl<'sdl, TVariant> TextureVariantSizeCache<'sdl, TVariant> {
         size: (u32, u32),
         creator: &'sdl TextureCreator<WindowContext>,
         canvas: &mut Canvas<Window>,
-    ) -> Result<&'_ Texture<'sdl>, String> {
+    ) -> UiResult<&'_ mut OwnedTexture<'sdl>> {
+        let generation = style_generation();
         let cache = match self.cache.take().filter(|cache| {
             let q = cache.query();
-            (q.width, q.height) == size
+            (q.width, q.height) == size && self.generation == generation
         }) {
             Some(cache) => cache, // reuse cache
             None => {
@@ -229,22 +281,21 @@ impl<'sdl, TVariant> TextureVariantSizeCache<'sdl, TVariant> {
                 // either way, needs re-render
                 let mut texture = creator
                     .create_texture_target(PixelFormatEnum::ARGB8888, size.0, size.1)
-                    .map_err(|e| e.to_string())?;
+                    .map(owned_texture)?;
                 texture.set_blend_mode(sdl2::render::BlendMode::Blend);
 
-                let mut e_out: Option<String> = None;
-                canvas
-                    .with_texture_canvas(&mut texture, |canvas| {
-                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                        canvas.clear(); // required to prevent flickering
+                let mut e_out: Option<UiError> = None;
+                canvas.with_texture_canvas(&mut texture, |canvas| {
+                    canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                    canvas.clear(); // required to prevent flickering
 
-                        e_out = style.draw(variant, canvas).err();
-                    })
-                    .map_err(|e| e.to_string())?;
+                    e_out = style.draw(variant, canvas).err();
+                })?;
 
                 if let Some(e) = e_out {
                     return Err(e);
                 }
+                self.generation = generation;
                 texture
             }
         };
@@ -253,6 +304,151 @@ impl<'sdl, TVariant> TextureVariantSizeCache<'sdl, TVariant> {
     }
 }
 
+/// crossfades between the textures of a widget's variants when the displayed
+/// variant changes, instead of snapping between them
+pub struct VariantTransition<TVariant> {
+    /// how long the crossfade takes. zero disables the transition
+    pub duration: Duration,
+    pub easing: Easing,
+    /// the variant which was most recently drawn
+    shown: Option<TVariant>,
+    /// the variant being faded out, and when that started
+    fading: Option<(TVariant, Instant)>,
+}
+
+impl<TVariant> Default for VariantTransition<TVariant> {
+    fn default() -> Self {
+        Self {
+            duration: Duration::ZERO,
+            easing: Default::default(),
+            shown: None,
+            fading: None,
+        }
+    }
+}
+
+impl<TVariant: Copy + PartialEq> VariantTransition<TVariant> {
+    /// call each draw with the variant that should be displayed. if a
+    /// crossfade is in progress, gives the variant being faded out and the
+    /// opacity (0-1) of the displayed variant
+    pub(crate) fn step(&mut self, variant: TVariant) -> Option<(TVariant, f32)> {
+        let previous = self.shown.replace(variant);
+        if self.duration.is_zero() {
+            self.fading = None;
+            return None;
+        }
+        if let Some(previous) = previous {
+            if previous != variant {
+                self.fading = Some((previous, Instant::now()));
+            }
+        }
+        let (from, start) = self.fading?;
+        let progress = start.elapsed().as_secs_f32() / self.duration.as_secs_f32();
+        if progress >= 1. {
+            self.fading = None;
+            return None;
+        }
+        request_redraw(); // still animating
+        Some((from, self.easing.apply(progress)))
+    }
+
+    /// the layers to draw, in order, with their opacity. the outgoing variant
+    /// stays opaque underneath while the displayed one fades in over it; fading
+    /// both would let what's behind the widget show through midway
+    pub(crate) fn layers(&mut self, variant: TVariant) -> [Option<(TVariant, f32)>; 2] {
+        match self.step(variant) {
+            None => [None, Some((variant, 1.))],
+            Some((from, opacity)) => [Some((from, 1.)), Some((variant, opacity))],
+        }
+    }
+}
+
+/// a press held for long enough fires an alternate callback instead of the
+/// normal activation (for a button or checkbox)
+pub struct LongPress<'state> {
+    /// how long the press must be held
+    pub duration: Duration,
+    pub on_long_press: Box<dyn FnMut() -> UiResult<()> + 'state>,
+    /// when the current press started
+    started: Option<Instant>,
+    /// on_long_press was called for the current press
+    fired: bool,
+}
+
+impl<'state> LongPress<'state> {
+    pub fn new(
+        duration: Duration,
+        on_long_press: Box<dyn FnMut() -> UiResult<()> + 'state>,
+    ) -> Self {
+        Self {
+            duration,
+            on_long_press,
+            started: None,
+            fired: false,
+        }
+    }
+
+    /// from 0 to 1, while a press is held and hasn't fired yet
+    pub fn progress(&self) -> Option<f32> {
+        if self.fired {
+            return None;
+        }
+        let started = self.started?;
+        if self.duration.is_zero() {
+            return Some(1.);
+        }
+        Some((started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.))
+    }
+
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+        self.fired = false;
+    }
+
+    fn cancel(&mut self) {
+        self.started = None;
+        self.fired = false;
+    }
+
+    /// the press was released. returns true if the long press fired, in which
+    /// case the normal activation should not happen
+    fn release(&mut self) -> bool {
+        let fired = self.fired;
+        self.cancel();
+        fired
+    }
+
+    /// call each update
+    fn step(&mut self) -> UiResult<()> {
+        let started = match self.started {
+            Some(v) if !self.fired => v,
+            _ => return Ok(()),
+        };
+        if started.elapsed() >= self.duration {
+            self.fired = true;
+            return (self.on_long_press)();
+        }
+        request_redraw(); // still waiting, and the progress is changing
+        Ok(())
+    }
+}
+
+/// copy a texture with some opacity (0-1)
+pub(crate) fn copy_with_opacity(
+    canvas: &mut Canvas<Window>,
+    texture: &mut Texture,
+    opacity: f32,
+    dst: sdl2::rect::Rect,
+) -> UiResult<()> {
+    if opacity >= 1. {
+        return canvas.copy(texture, None, Some(dst)).map_err(UiError::Sdl);
+    }
+    texture.set_alpha_mod((opacity.clamp(0., 1.) * 255.).round() as u8);
+    let r = canvas.copy(texture, None, Some(dst));
+    texture.set_alpha_mod(0xFF);
+    r.map_err(UiError::Sdl)
+}
+
 /// for which sound should be played, for a widget that is focusable and
 /// press-able (like a checkbox or a button)
 pub enum FocusPressWidgetSoundVariant {
@@ -263,7 +459,31 @@ pub enum FocusPressWidgetSoundVariant {
 }
 
 pub trait FocusPressWidgetSoundStyle {
-    fn play_sound(&mut self, which: FocusPressWidgetSoundVariant) -> Result<(), String>;
+    fn play_sound(&mut self, which: FocusPressWidgetSoundVariant) -> UiResult<()>;
+
+    /// a silent style leaves the feedback to the focus manager's feedback
+    /// sink. see util::feedback
+    fn is_silent(&self) -> bool {
+        false
+    }
+}
+
+/// play a widget's sound, or give the feedback to the focus manager if the
+/// widget's style is silent
+fn play_focus_press_sound(
+    sounds: &mut dyn FocusPressWidgetSoundStyle,
+    focus_manager: &mut FocusManager,
+    which: FocusPressWidgetSoundVariant,
+) -> UiResult<()> {
+    if !sounds.is_silent() {
+        return sounds.play_sound(which);
+    }
+    focus_manager.feedback(match which {
+        FocusPressWidgetSoundVariant::Focus => UiFeedback::Focus,
+        FocusPressWidgetSoundVariant::Press => UiFeedback::Press,
+        FocusPressWidgetSoundVariant::Release => UiFeedback::Activate,
+    });
+    Ok(())
 }
 
 /// a style which does not play any sounds and is not reliant on sdl2-mixer being enabled
@@ -271,16 +491,20 @@ pub trait FocusPressWidgetSoundStyle {
 pub struct EmptyFocusPressWidgetSoundStyle {}
 
 impl FocusPressWidgetSoundStyle for EmptyFocusPressWidgetSoundStyle {
-    fn play_sound(&mut self, _which: FocusPressWidgetSoundVariant) -> Result<(), String> {
+    fn play_sound(&mut self, _which: FocusPressWidgetSoundVariant) -> UiResult<()> {
         // nothing
         Ok(())
     }
+
+    fn is_silent(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(feature = "sdl2-mixer")]
 #[derive(Clone, Copy)]
 pub struct DefaultFocusPressWidgetSoundStyle<'sdl> {
-    pub sound_manager: &'sdl Cell<Option<crate::util::audio::SoundManager>>,
+    pub sound_manager: &'sdl std::cell::Cell<Option<crate::util::audio::SoundManager>>,
     pub focus_sound_path: Option<&'sdl std::path::Path>,
     pub press_sound_path: Option<&'sdl std::path::Path>,
     pub release_sound_path: Option<&'sdl std::path::Path>,
@@ -288,7 +512,7 @@ pub struct DefaultFocusPressWidgetSoundStyle<'sdl> {
 
 #[cfg(feature = "sdl2-mixer")]
 impl<'sdl> FocusPressWidgetSoundStyle for DefaultFocusPressWidgetSoundStyle<'sdl> {
-    fn play_sound(&mut self, which: FocusPressWidgetSoundVariant) -> Result<(), String> {
+    fn play_sound(&mut self, which: FocusPressWidgetSoundVariant) -> UiResult<()> {
         let maybe_sound_path: Option<&std::path::Path> = match which {
             FocusPressWidgetSoundVariant::Focus => self.focus_sound_path,
             FocusPressWidgetSoundVariant::Press => self.press_sound_path,
@@ -303,7 +527,11 @@ impl<'sdl> FocusPressWidgetSoundStyle for DefaultFocusPressWidgetSoundStyle<'sdl
         let manager = match maybe_manager.as_mut() {
             Some(v) => v,
             // should never error, as it will always be returned to the cell
-            None => return Err("couldn't reference sound manager".to_owned()),
+            None => {
+                return Err(UiError::Custom(
+                    "couldn't reference sound manager".to_owned(),
+                ))
+            }
         };
         let maybe_r = manager.get(sound_path);
         self.sound_manager.set(maybe_manager);
@@ -314,18 +542,34 @@ impl<'sdl> FocusPressWidgetSoundStyle for DefaultFocusPressWidgetSoundStyle<'sdl
     }
 }
 
+pub type CheckBoxToggleFn<'state> =
+    Box<dyn FnMut(bool, &ActivationContext) -> UiResult<()> + 'state>;
+
 pub struct CheckBox<'sdl, 'state> {
-    pub checked: &'state Cell<bool>,
+    pub checked: CellRefOrCell<'state, bool>,
     pub focus_id: FocusID,
     /// internal state for drawing
     pressed: bool,
-    /// hovered is only used if no focus manager is available
-    hovered: bool,
+    /// internal state for drawing and sound
+    hover: HoverCache,
 
     /// internal state for sound
     focused_previous_frame: bool,
-
+    /// internal state for activating via keyboard
+    activation_key_held: bool,
+    /// keys which toggle the checkbox while it is focused
+    pub activation_keys: Vec<Keycode>,
+    /// what the checkbox is called, for screen readers
+    pub accessible_label: String,
+
+    /// width and height, in logical pixels (scaled by the ui scale)
     pub size: f32,
+    /// the checkbox can be pressed this far outside of where it's drawn, in
+    /// logical pixels. see HitTest::expansion
+    pub hit_expansion: f32,
+    /// the part of the checkbox which can be pressed. see ShapedCheckBoxStyle
+    /// for drawing a matching shape
+    pub hit_shape: HitShape,
     creator: &'sdl TextureCreator<WindowContext>,
 
     /// state stored for draw from update
@@ -336,6 +580,13 @@ pub struct CheckBox<'sdl, 'state> {
     /// what sounds should be played when the checkbox is interacted with
     sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
 
+    /// crossfade between variants. disabled by default
+    pub transition: VariantTransition<CheckBoxTextureVariant>,
+    /// if set, holding the press calls on_long_press instead of toggling
+    pub long_press: Option<LongPress<'state>>,
+    /// called with the new value after the checkbox is toggled by the user
+    pub on_toggle: Option<CheckBoxToggleFn<'state>>,
+
     idle: TextureVariantSizeCache<'sdl, CheckBoxTextureVariant>,
     focused: TextureVariantSizeCache<'sdl, CheckBoxTextureVariant>,
     focused_pressed: TextureVariantSizeCache<'sdl, CheckBoxTextureVariant>,
@@ -347,23 +598,31 @@ pub struct CheckBox<'sdl, 'state> {
 
 impl<'sdl, 'state> CheckBox<'sdl, 'state> {
     pub fn new(
-        checked: &'state Cell<bool>,
+        checked: impl Into<CellRefOrCell<'state, bool>>,
         focus_id: FocusID,
         style: Box<dyn TextureVariantStyle<CheckBoxTextureVariant> + 'sdl>,
         sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
         creator: &'sdl TextureCreator<WindowContext>,
     ) -> Self {
         Self {
-            checked,
+            checked: checked.into(),
             focus_id,
             pressed: false,
-            hovered: false,
+            hover: Default::default(),
             focused_previous_frame: false,
+            activation_key_held: false,
+            activation_keys: default_activation_keys(),
+            accessible_label: String::new(),
             style,
             sounds,
             size: 30.,
+            hit_expansion: 0.,
+            hit_shape: HitShape::Rect,
             creator,
             draw_pos: Default::default(),
+            transition: Default::default(),
+            long_press: None,
+            on_toggle: None,
             idle: Default::default(),
             idle_checked: Default::default(),
             checked_pressed: Default::default(),
@@ -375,41 +634,87 @@ impl<'sdl, 'state> CheckBox<'sdl, 'state> {
     }
 }
 
+/// the keys which activate a focused button or checkbox by default, matching
+/// platform conventions
+pub fn default_activation_keys() -> Vec<Keycode> {
+    vec![Keycode::Return, Keycode::KpEnter, Keycode::Space]
+}
+
+/// what activated a button or checkbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationSource {
+    Mouse,
+    Keyboard,
+}
+
+/// given to callbacks when a button or checkbox is activated
+#[derive(Debug, Clone, Copy)]
+pub struct ActivationContext {
+    pub source: ActivationSource,
+    /// where the mouse was released, in window coordinates. None if from the
+    /// keyboard
+    pub position: Option<(i32, i32)>,
+    /// modifier keys held at the time (e.g. for shift-click)
+    pub keymod: Mod,
+    /// 2 for a double click, etc. always 1 from the keyboard
+    pub clicks: u8,
+}
+
+/// modifier keys currently held. mouse events don't carry them
+fn current_keymod() -> Mod {
+    // safety: only reads sdl's keyboard state. events were received, so sdl
+    // is initialized
+    let state = unsafe { sdl2::sys::SDL_GetModState() };
+    Mod::from_bits_truncate(state as u16)
+}
+
 /// update implementation for something which can be focused and pressed
+///
+/// activation_key_held persists between frames; it indicates that one of the
+/// activation keys was pressed down while focused and hasn't been released yet
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn focus_press_update_implementation<T>(
-    hovered: &mut bool,
+    hover: &mut HoverCache,
     pressed: &mut bool,
     focused_previous_frame: &mut bool,
+    activation_key_held: &mut bool,
+    activation_keys: &[Keycode],
     focus_id: &FocusID,
+    hit_expansion: f32,
+    hit_shape: &HitShape,
     mut event: WidgetUpdateEvent,
     functionality: &mut T,
     sounds: &mut dyn FocusPressWidgetSoundStyle,
-) -> Result<(), String>
+    mut long_press: Option<&mut LongPress>,
+) -> UiResult<()>
 where
-    T: FnMut() -> Result<(), String> + ?Sized,
+    T: FnMut(&ActivationContext) -> UiResult<()> + ?Sized,
 {
+    event.focus_manager.report_rect(focus_id, event.position);
     let has_focus_at_beginning = event.focus_manager.is_focused(focus_id);
 
     // detect if focus was sent to this widget for any reason by something else
     // since the last time it was updated
     if has_focus_at_beginning && !*focused_previous_frame {
-        sounds.play_sound(FocusPressWidgetSoundVariant::Focus)?;
+        play_focus_press_sound(
+            sounds,
+            event.focus_manager,
+            FocusPressWidgetSoundVariant::Focus,
+        )?;
     }
 
-    // used to detect rising edge, for when the focus or hover is gained on the
-    // widget. at that point, play sound
-    //
-    // this part got a bit messy, but here's how it's used:
-    // - not touched in key events, as key events are only applicable when the
-    //   widget is already focused (can't be rising edge if already positive)
-    // - any mouse stuff over the widget - set to true
-    // - if mouse moved and was previously false, play sound and set to true
-    let mut focus_sound_state = *hovered || has_focus_at_beginning;
-
     // value updated each frame
-    *hovered = false;
     *pressed = false;
 
+    let hit = HitTest::from_event(&event)
+        .expanded(hit_expansion)
+        .shaped(hit_shape.clone());
+    hover.update(&hit, event.events);
+
+    // the hover starting plays a sound, unless the widget was already focused.
+    // the sound is decided by the mouse events below; pressing or releasing
+    // over the widget plays its own sound instead
+    let mut hover_sound_pending = hover.hover_entered() && !has_focus_at_beginning;
     for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
         FocusManager::default_widget_focus_behavior(
             focus_id,
@@ -419,6 +724,8 @@ where
                 event: sdl_event,
                 clipping_rect: event.clipping_rect,
                 window_id: event.window_id,
+                hit_expansion,
+                hit_shape,
             },
         );
         if sdl_event.consumed() {
@@ -443,43 +750,62 @@ where
                         continue;
                     }
                     if keymod.contains(Mod::LSHIFTMOD) || keymod.contains(Mod::RSHIFTMOD) {
-                        event.focus_manager.0 = Some(focus_id.previous.clone());
+                        event.focus_manager.focus = Some(focus_id.previous.clone());
                     } else {
-                        event.focus_manager.0 = Some(focus_id.next.clone());
+                        event.focus_manager.focus = Some(focus_id.next.clone());
                     }
                 }
             }
             sdl2::event::Event::KeyDown {
                 repeat,
-                keycode: Some(Keycode::Return),
+                keycode: Some(keycode),
                 ..
-            } => {
-                // enter key pressed down. only if currently focused
-                if event.focus_manager.is_focused(&focus_id) {
+            } if activation_keys.contains(&keycode) => {
+                // activation key pressed down. only if currently focused
+                if event.focus_manager.is_focused(focus_id) {
                     sdl_event.set_consumed();
                     if repeat {
                         continue;
                     }
-                    *pressed = true;
-                    sounds.play_sound(FocusPressWidgetSoundVariant::Press)?;
+                    *activation_key_held = true;
+                    if let Some(long_press) = long_press.as_mut() {
+                        long_press.start();
+                    }
+                    play_focus_press_sound(
+                        sounds,
+                        event.focus_manager,
+                        FocusPressWidgetSoundVariant::Press,
+                    )?;
                 }
             }
             sdl2::event::Event::KeyUp {
                 repeat,
-                keycode: Some(Keycode::Return),
+                keycode: Some(keycode),
+                keymod,
                 ..
-            } => {
-                // enter key released. only if currently focused.
-                if event.focus_manager.is_focused(focus_id) {
+            } if activation_keys.contains(&keycode) => {
+                // activation key released. only if currently focused, and the
+                // key was pressed down while focused
+                if event.focus_manager.is_focused(focus_id) && *activation_key_held {
                     sdl_event.set_consumed(); // consume before trying functionality
                     if repeat {
                         continue;
                     }
-                    sounds.play_sound(FocusPressWidgetSoundVariant::Release)?;
-                    match functionality() {
-                        Ok(()) => (),
-                        Err(e) => return Err(e),
-                    };
+                    *activation_key_held = false;
+                    play_focus_press_sound(
+                        sounds,
+                        event.focus_manager,
+                        FocusPressWidgetSoundVariant::Release,
+                    )?;
+                    if long_press.as_mut().map(|l| l.release()).unwrap_or(false) {
+                        continue; // long press happened instead
+                    }
+                    functionality(&ActivationContext {
+                        source: ActivationSource::Keyboard,
+                        position: None,
+                        keymod,
+                        clicks: 1,
+                    })?;
                 }
             }
             // mouse:
@@ -494,32 +820,29 @@ where
                 window_id,
                 ..
             } => {
-                if window_id != event.window_id {
-                    continue; // not for me!
-                }
-                let position: Option<sdl2::rect::Rect> = event.position.into();
-                if let Some(position) = position {
-                    if point_in_position_and_clipping_rect(x, y, position, event.clipping_rect) {
-                        *hovered = true;
-                        if !mousestate.left() {
-                            if !focus_sound_state {
-                                focus_sound_state = true;
-                                sounds.play_sound(FocusPressWidgetSoundVariant::Focus)?;
-                            }
-                            continue;
-                        }
-                        if !focus_sound_state {
-                            focus_sound_state = true;
-                            sounds.play_sound(FocusPressWidgetSoundVariant::Press)?;
-                        }
-
-                        // the mouse was moved over the widget AND the left
-                        // button is pressed
-                        //
-                        // generally never consume mouse motion events
-                        *pressed = true;
-                        event.focus_manager.0 = Some(focus_id.me.clone());
+                if hit.contains(window_id, x, y) {
+                    if hover_sound_pending {
+                        hover_sound_pending = false;
+                        let variant = if mousestate.left() {
+                            FocusPressWidgetSoundVariant::Press
+                        } else {
+                            FocusPressWidgetSoundVariant::Focus
+                        };
+                        play_focus_press_sound(sounds, event.focus_manager, variant)?;
+                    }
+                    if !mousestate.left() {
+                        continue;
                     }
+
+                    // the mouse was moved over the widget AND the left
+                    // button is pressed
+                    //
+                    // generally never consume mouse motion events
+                    *pressed = true;
+                    event.focus_manager.focus = Some(focus_id.me.clone());
+                } else if let Some(long_press) = long_press.as_mut() {
+                    // dragged off of the widget
+                    long_press.cancel();
                 }
             }
             sdl2::event::Event::MouseButtonDown {
@@ -528,86 +851,148 @@ where
                 y,
                 window_id,
                 ..
-            } => {
-                if window_id != event.window_id {
-                    continue; // not for me!
-                }
-                let position: Option<sdl2::rect::Rect> = event.position.into();
-                if let Some(position) = position {
-                    if point_in_position_and_clipping_rect(x, y, position, event.clipping_rect) {
-                        sounds.play_sound(FocusPressWidgetSoundVariant::Press)?;
-                        // the left mouse button was pressed on this widget
-                        *pressed = true;
-                        *hovered = true;
-                        focus_sound_state = true;
-                        sdl_event.set_consumed();
-                        event.focus_manager.0 = Some(focus_id.me.clone());
-                    }
+            } if hit.contains(window_id, x, y) => {
+                play_focus_press_sound(
+                    sounds,
+                    event.focus_manager,
+                    FocusPressWidgetSoundVariant::Press,
+                )?;
+                if let Some(long_press) = long_press.as_mut() {
+                    long_press.start();
                 }
+                // the left mouse button was pressed on this widget
+                *pressed = true;
+                hover_sound_pending = false;
+                sdl_event.set_consumed();
+                event.focus_manager.focus = Some(focus_id.me.clone());
             }
             sdl2::event::Event::MouseButtonUp {
                 mouse_btn: MouseButton::Left,
+                clicks,
                 x,
                 y,
                 window_id,
                 ..
             } => {
-                if window_id != event.window_id {
-                    continue; // not for me!
-                }
                 // ok even if not focused (button click works even if no
                 // focus manager is used at all)
-                let position: Option<sdl2::rect::Rect> = event.position.into();
-                if let Some(position) = position {
-                    if point_in_position_and_clipping_rect(x, y, position, event.clipping_rect) {
-                        *pressed = false;
-                        *hovered = true;
-                        focus_sound_state = true;
-                        sdl_event.set_consumed();
-                        event.focus_manager.0 = Some(focus_id.me.clone());
-                        sounds.play_sound(FocusPressWidgetSoundVariant::Release)?;
-                        match functionality() {
-                            Ok(()) => (),
-                            Err(e) => return Err(e),
-                        };
+                if hit.contains(window_id, x, y) {
+                    *pressed = false;
+                    hover_sound_pending = false;
+                    sdl_event.set_consumed();
+                    event.focus_manager.focus = Some(focus_id.me.clone());
+                    play_focus_press_sound(
+                        sounds,
+                        event.focus_manager,
+                        FocusPressWidgetSoundVariant::Release,
+                    )?;
+                    if long_press.as_mut().map(|l| l.release()).unwrap_or(false) {
+                        continue; // long press happened instead
                     }
+                    functionality(&ActivationContext {
+                        source: ActivationSource::Mouse,
+                        position: Some((x, y)),
+                        keymod: current_keymod(),
+                        clicks,
+                    })?;
+                } else if let Some(long_press) = long_press.as_mut() {
+                    // released elsewhere
+                    long_press.cancel();
                 }
             }
             _ => {}
         }
     }
 
+    if hover_sound_pending {
+        // e.g. the widget moved under the mouse
+        play_focus_press_sound(
+            sounds,
+            event.focus_manager,
+            FocusPressWidgetSoundVariant::Focus,
+        )?;
+    }
+
     *focused_previous_frame = event
         .focus_manager.is_focused(focus_id);
 
+    if !*focused_previous_frame {
+        // focus was lost while the key was held. the release will be ignored
+        if *activation_key_held {
+            if let Some(long_press) = long_press.as_mut() {
+                long_press.cancel();
+            }
+        }
+        *activation_key_held = false;
+    }
+
+    if *activation_key_held {
+        // show as pressed for as long as the key is held down
+        *pressed = true;
+    }
+
+    if let Some(long_press) = long_press {
+        long_press.step()?;
+        if long_press.progress().is_some() {
+            // show as pressed for as long as the press is held
+            *pressed = true;
+        }
+    }
+
     Ok(())
 }
 
 impl<'sdl, 'state> Widget for CheckBox<'sdl, 'state> {
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        Ok((MinLen(self.size), MinLen(self.size)))
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let size = MinLen(self.size).scaled();
+        Ok((size, size))
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        Ok((MaxLen(self.size), MaxLen(self.size)))
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let size = MaxLen(self.size).scaled();
+        Ok((size, size))
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn accessible_description(&self) -> Option<AccessibleDescription> {
+        Some(AccessibleDescription {
+            role: AccessibleRole::CheckBox,
+            label: self.accessible_label.clone(),
+            value: None,
+            state: AccessibleState {
+                checked: Some(self.checked.get()),
+                pressed: self.pressed,
+            },
+        })
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
         focus_press_update_implementation(
-            &mut self.hovered,
+            &mut self.hover,
             &mut self.pressed,
             &mut self.focused_previous_frame,
+            &mut self.activation_key_held,
+            &self.activation_keys,
             &self.focus_id,
-            event,
-            &mut || {
+            crate::util::scale::scaled(self.hit_expansion),
+            &self.hit_shape,
+            event.dup(),
+            &mut |context: &ActivationContext| {
                 let v = self.checked.get();
                 let v = !v;
                 self.checked.set(v);
-                Ok(())
+                match self.on_toggle.as_mut() {
+                    Some(on_toggle) => on_toggle(v, context),
+                    None => Ok(()),
+                }
             },
             self.sounds.as_mut(),
-        )
+            self.long_press.as_mut(),
+        )?;
+        event
+            .focus_manager
+            .report_description(&self.focus_id, self.accessible_description());
+        Ok(())
     }
 
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
@@ -619,7 +1004,8 @@ impl<'sdl, 'state> Widget for CheckBox<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         let position: sdl2::rect::Rect = match self.draw_pos.into() {
             Some(v) => v,
             // the rest of this is just for drawing or being clicked, both
@@ -629,7 +1015,7 @@ impl<'sdl, 'state> Widget for CheckBox<'sdl, 'state> {
 
         let focused = focus_manager.is_focused(&self.focus_id);
         let checked = self.checked.get();
-        let variant = if focused || self.hovered {
+        let variant = if focused || self.hover.hovered() {
             if self.pressed {
                 if checked {
                     CheckBoxTextureVariant::FocusedPressedChecked
@@ -651,25 +1037,34 @@ impl<'sdl, 'state> Widget for CheckBox<'sdl, 'state> {
             CheckBoxTextureVariant::Idle
         };
 
-        let cache = match variant {
-            CheckBoxTextureVariant::Idle => &mut self.idle,
-            CheckBoxTextureVariant::Focused => &mut self.focused,
-            CheckBoxTextureVariant::FocusedPressed => &mut self.focused_pressed,
-            CheckBoxTextureVariant::FocusChecked => &mut self.focused_checked,
-            CheckBoxTextureVariant::FocusedPressedChecked => &mut self.focused_checked_pressed,
-            CheckBoxTextureVariant::Checked => &mut self.idle_checked,
-            CheckBoxTextureVariant::CheckedPressed => &mut self.checked_pressed,
-        };
-
-        let txt = cache.render(
-            self.style.as_mut(),
-            variant,
-            (position.width(), position.height()),
-            self.creator,
-            canvas,
-        )?;
+        for (variant, opacity) in self.transition.layers(variant).into_iter().flatten() {
+            let cache = match variant {
+                CheckBoxTextureVariant::Idle => &mut self.idle,
+                CheckBoxTextureVariant::Focused => &mut self.focused,
+                CheckBoxTextureVariant::FocusedPressed => &mut self.focused_pressed,
+                CheckBoxTextureVariant::FocusChecked => &mut self.focused_checked,
+                CheckBoxTextureVariant::FocusedPressedChecked => {
+                    &mut self.focused_checked_pressed
+                }
+                CheckBoxTextureVariant::Checked => &mut self.idle_checked,
+                CheckBoxTextureVariant::CheckedPressed => &mut self.checked_pressed,
+            };
+
+            let txt = cache.render(
+                self.style.as_mut(),
+                variant,
+                (position.width(), position.height()),
+                self.creator,
+                &mut canvas,
+            )?;
+
+            copy_with_opacity(&mut canvas, txt, opacity, position)?;
+        }
 
-        canvas.copy(txt, None, Some(position))?;
+        if let Some(progress) = self.long_press.as_ref().and_then(|l| l.progress()) {
+            self.style
+                .draw_long_press(progress, &mut canvas, position)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,317 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+};
+
+use sdl2::{
+    mouse::MouseWheelDirection, pixels::Color, rect::Rect, render::TextureCreator,
+    video::WindowContext,
+};
+
+use crate::{
+    layout::clipper::ClipScope,
+    util::{
+        error::UiResult,
+        focus::FocusManager,
+        font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties},
+        hit::HitTest,
+        perf::count_cache,
+        rect::FRect,
+        render::{style_generation, OwnedTexture},
+        scale::scaled,
+    },
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// a line of text in a console
+#[derive(Debug, Clone)]
+pub struct ConsoleLine {
+    pub text: String,
+    pub color: Color,
+    /// shown before the text, if set (formatted by the application)
+    pub timestamp: Option<String>,
+}
+
+impl ConsoleLine {
+    fn display_text(&self) -> String {
+        match &self.timestamp {
+            Some(timestamp) => format!("[{}] {}", timestamp, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+struct ConsoleBufferInner {
+    lines: VecDeque<ConsoleLine>,
+    /// each line is given an id, in order. this is the id of the front line
+    first_id: u64,
+    max_lines: usize,
+}
+
+/// the lines shown by a TextConsole. the oldest lines are discarded once there
+/// are more than max_lines
+pub struct ConsoleBuffer {
+    inner: RefCell<ConsoleBufferInner>,
+}
+
+impl ConsoleBuffer {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            inner: RefCell::new(ConsoleBufferInner {
+                lines: VecDeque::new(),
+                first_id: 0,
+                max_lines,
+            }),
+        }
+    }
+
+    pub fn push(&self, text: impl Into<String>, color: Color) {
+        self.push_line(ConsoleLine {
+            text: text.into(),
+            color,
+            timestamp: None,
+        });
+    }
+
+    pub fn push_line(&self, line: ConsoleLine) {
+        let mut inner = self.inner.borrow_mut();
+        inner.lines.push_back(line);
+        inner.trim();
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.first_id += inner.lines.len() as u64;
+        inner.lines.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.borrow().lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn max_lines(&self) -> usize {
+        self.inner.borrow().max_lines
+    }
+
+    pub fn set_max_lines(&self, max_lines: usize) {
+        let mut inner = self.inner.borrow_mut();
+        inner.max_lines = max_lines;
+        inner.trim();
+    }
+
+    /// a copy of the lines, oldest first
+    pub fn lines(&self) -> Vec<ConsoleLine> {
+        self.inner.borrow().lines.iter().cloned().collect()
+    }
+
+    /// the id of the first line, and the number of lines
+    fn id_range(&self) -> (u64, u64) {
+        let inner = self.inner.borrow();
+        (inner.first_id, inner.lines.len() as u64)
+    }
+
+    fn line(&self, id: u64) -> Option<ConsoleLine> {
+        let inner = self.inner.borrow();
+        let index = id.checked_sub(inner.first_id)?;
+        inner.lines.get(index as usize).cloned()
+    }
+}
+
+impl ConsoleBufferInner {
+    fn trim(&mut self) {
+        while self.lines.len() > self.max_lines {
+            self.lines.pop_front();
+            self.first_id += 1;
+        }
+    }
+}
+
+struct ConsoleLineCache<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    point_size: u16,
+    /// see style_generation
+    generation: u64,
+}
+
+/// a scrolling list of lines (e.g. a log or chat).
+///
+/// each line is rendered to its own texture, and only the visible lines are
+/// rendered; appending doesn't re-render the existing lines. while scrolled to
+/// the bottom, new lines keep it at the bottom. scrolling up stops this until
+/// scrolled back down
+pub struct TextConsole<'sdl, 'state> {
+    pub buffer: &'state ConsoleBuffer,
+    /// height of each line, in logical pixels (scaled by the ui scale)
+    pub line_height: f32,
+    /// lines moved per mouse wheel step
+    pub wheel_lines: f32,
+    /// keep the newest line in view
+    follow: bool,
+    /// the line id at the top of the view. fractional while partially
+    /// scrolled
+    top: f32,
+
+    font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+    creator: &'sdl TextureCreator<WindowContext>,
+    /// line id to texture
+    cache: HashMap<u64, ConsoleLineCache<'sdl>>,
+
+    /// state stored for draw from update
+    draw_pos: FRect,
+}
+
+impl<'sdl, 'state> TextConsole<'sdl, 'state> {
+    pub fn new(
+        buffer: &'state ConsoleBuffer,
+        font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            buffer,
+            line_height: 20.,
+            wheel_lines: 3.,
+            follow: true,
+            top: 0.,
+            font_interface,
+            creator,
+            cache: Default::default(),
+            draw_pos: Default::default(),
+        }
+    }
+
+    /// if the view is kept at the newest line
+    pub fn following(&self) -> bool {
+        self.follow
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.follow = true;
+    }
+
+    /// number of lines which fit in the view
+    fn visible_lines(&self, position: FRect) -> f32 {
+        let line_height = scaled(self.line_height);
+        if line_height <= 0. {
+            return 0.;
+        }
+        (position.h / line_height).max(0.)
+    }
+
+    /// keep the top within the lines, and at the bottom if following
+    fn clamp_top(&mut self, position: FRect) {
+        let (first_id, len) = self.buffer.id_range();
+        let min_top = first_id as f32;
+        let max_top = (first_id as f32 + len as f32 - self.visible_lines(position)).max(min_top);
+        if self.follow {
+            self.top = max_top;
+        }
+        self.top = self.top.clamp(min_top, max_top);
+        if self.top >= max_top {
+            // scrolled back to the bottom
+            self.follow = true;
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for TextConsole<'sdl, 'state> {
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        let hit = HitTest::from_event(&event);
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            if let sdl2::event::Event::MouseWheel {
+                precise_y,
+                direction,
+                ..
+            } = sdl_event.e
+            {
+                if !hit.was_hit(&sdl_event.e) {
+                    continue;
+                }
+                let direction_multiplier: f32 = match direction {
+                    MouseWheelDirection::Flipped => -1.,
+                    _ => 1.,
+                };
+                // sdl's positive y is up, towards older lines
+                let delta = precise_y * direction_multiplier * self.wheel_lines;
+                if delta > 0. {
+                    self.follow = false;
+                }
+                self.top -= delta;
+                sdl_event.set_consumed();
+            }
+        }
+        self.clamp_top(event.position);
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let position: Option<Rect> = self.draw_pos.into();
+        let position = match position {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let line_height = scaled(self.line_height);
+        if line_height < 1. {
+            return Ok(());
+        }
+        let point_size: u16 = (line_height.round() as u32).try_into().unwrap_or(u16::MAX);
+        let generation = style_generation();
+
+        let (first_id, len) = self.buffer.id_range();
+        let first_visible = (self.top.floor() as u64).max(first_id);
+        let last_visible = (first_visible + self.visible_lines(self.draw_pos).ceil() as u64 + 1)
+            .min(first_id + len);
+
+        // only keep textures for what's visible
+        self.cache
+            .retain(|id, _| *id >= first_visible && *id < last_visible);
+
+        let mut canvas = ClipScope::intersect(canvas, Some(position));
+        for id in first_visible..last_visible {
+            let line = match self.buffer.line(id) {
+                Some(v) => v,
+                None => break,
+            };
+            let cache = self
+                .cache
+                .remove(&id)
+                .filter(|cache| cache.point_size == point_size && cache.generation == generation);
+            count_cache(cache.is_some());
+            let cache = match cache {
+                Some(v) => v,
+                None => ConsoleLineCache {
+                    texture: self.font_interface.render(
+                        &line.display_text(),
+                        &TextRenderProperties {
+                            point_size,
+                            render_type: SingleLineTextRenderType::Blended(line.color),
+                        },
+                        self.creator,
+                    )?,
+                    point_size,
+                    generation,
+                },
+            };
+            let query = cache.texture.query();
+            let y = self.draw_pos.y + (id as f32 - self.top) * line_height;
+            let dst = Rect::new(position.x(), y.round() as i32, query.width, query.height);
+            canvas.copy(&cache.texture, None, dst)?;
+            self.cache.insert(id, cache);
+        }
+        Ok(())
+    }
+}
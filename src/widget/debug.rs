@@ -1,16 +1,21 @@
 use sdl2::{
+    keyboard::{Keycode, Mod},
     mouse::MouseButton,
     pixels::Color,
     rect::{Point, Rect},
 };
 
 use crate::util::{
+    error::{UiError, UiResult},
     focus::FocusManager,
     length::{
         AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy,
         PreferredPortion,
     },
+    hit::{HitTest, HoverCache},
     rect::FRect,
+    render::CanvasStateGuard,
+    scale::ui_scale,
 };
 
 use super::{Widget, WidgetUpdateEvent};
@@ -32,9 +37,21 @@ pub struct Debug {
     pub min_h_fail_policy: MinLenFailPolicy,
     pub max_h_fail_policy: MaxLenFailPolicy,
     pub preferred_link_allowed_exceed_portion: bool,
+    /// tweak the sizing at runtime, for layout iteration. while the mouse is
+    /// over the widget:
+    ///  - arrow keys change the preferred portion by 1%
+    ///  - with shift, the min by 1 logical pixel
+    ///  - with ctrl, the max by 1 logical pixel
+    ///  - with alt, steps are 10 times larger
+    ///  - page up and page down change the aspect ratio, and delete removes it
+    ///
+    /// right and up increase. the new values are printed after each change
+    pub editable: bool,
 
     /// internal state. set during update. used during draw
     clicked_this_frame: bool,
+    /// for editable
+    hover: HoverCache,
     /// state stored for draw from update
     draw_pos: FRect,
 }
@@ -43,13 +60,120 @@ pub struct Debug {
 /// sizing info
 pub type CustomSizingControl = Debug;
 
+impl Debug {
+    /// exactly some size, in logical pixels
+    pub fn fixed(w: f32, h: f32) -> Self {
+        Self {
+            min_w: w.into(),
+            min_h: h.into(),
+            max_w: w.into(),
+            max_h: h.into(),
+            ..Default::default()
+        }
+    }
+
+    /// a percentage of the parent's size along each axis (e.g. 50 for half)
+    pub fn percentage(w: f32, h: f32) -> Self {
+        Self {
+            preferred_w: (w / 100.).into(),
+            preferred_h: (h / 100.).into(),
+            ..Default::default()
+        }
+    }
+
+    /// keep an aspect ratio (width / height), as large as fits in the parent
+    pub fn aspect(ratio: f32) -> Self {
+        Self {
+            aspect_ratio: Some(ratio),
+            ..Default::default()
+        }
+    }
+
+    /// the sizing values, e.g. to paste back into code after editing
+    pub fn describe(&self) -> String {
+        let max = |max: MaxLen| {
+            if max.0 == MaxLen::LAX.0 {
+                "lax".to_owned()
+            } else {
+                max.0.to_string()
+            }
+        };
+        format!(
+            "min ({}, {}), max ({}, {}), preferred ({}, {}), aspect ratio {:?}",
+            self.min_w.0,
+            self.min_h.0,
+            max(self.max_w),
+            max(self.max_h),
+            self.preferred_w.0,
+            self.preferred_h.0,
+            self.aspect_ratio
+        )
+    }
+
+    /// apply an editing key. true if it was used. see editable
+    fn edit(&mut self, keycode: Keycode, keymod: Mod) -> bool {
+        let (dx, dy) = match keycode {
+            Keycode::Right => (1., 0.),
+            Keycode::Left => (-1., 0.),
+            Keycode::Up => (0., 1.),
+            Keycode::Down => (0., -1.),
+            Keycode::PageUp | Keycode::PageDown => {
+                let current = self.aspect_ratio.unwrap_or(if self.draw_pos.h > 0. {
+                    self.draw_pos.w / self.draw_pos.h
+                } else {
+                    1. // guard div
+                });
+                self.aspect_ratio = Some(if keycode == Keycode::PageUp {
+                    current * 1.05
+                } else {
+                    current / 1.05
+                });
+                return true;
+            }
+            Keycode::Delete => {
+                self.aspect_ratio = None;
+                return true;
+            }
+            _ => return false,
+        };
+        let step = if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) {
+            10.
+        } else {
+            1.
+        };
+        let (dx, dy) = (dx * step, dy * step);
+        if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+            self.min_w.0 = (self.min_w.0 + dx).max(0.);
+            self.min_h.0 = (self.min_h.0 + dy).max(0.);
+        } else if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+            // a lax max starts from the current size
+            let scale = ui_scale();
+            let nudge = |max: &mut MaxLen, current: f32, delta: f32| {
+                if delta == 0. {
+                    return;
+                }
+                if max.0 == MaxLen::LAX.0 {
+                    max.0 = current / scale;
+                }
+                max.0 = (max.0 + delta).max(0.);
+            };
+            nudge(&mut self.max_w, self.draw_pos.w, dx);
+            nudge(&mut self.max_h, self.draw_pos.h, dy);
+        } else {
+            self.preferred_w.0 = (self.preferred_w.0 + dx / 100.).max(0.);
+            self.preferred_h.0 = (self.preferred_h.0 + dy / 100.).max(0.);
+        }
+        true
+    }
+}
+
 
 /// use as a placeholder if some texture is missing, etc.
 pub fn debug_rect_outline(
     color: sdl2::pixels::Color,
     position: Rect,
     canvas: &mut sdl2::render::WindowCanvas,
-) -> Result<(), String> {
+) -> UiResult<()> {
     // debug is super simple. simply re-render every frame
     canvas.set_draw_color(Color::RGB(50, 50, 50));
     canvas.fill_rect(position)?;
@@ -64,7 +188,7 @@ pub fn debug_rect_outline(
         Point::new(position.x, position.y),
         Point::new(position.x + position.w - 1, position.y + position.h - 1),
     ];
-    canvas.draw_lines(points.as_ref())
+    canvas.draw_lines(points.as_ref()).map_err(UiError::Sdl)
 }
 
 impl Widget for Debug {
@@ -72,8 +196,8 @@ impl Widget for Debug {
         self.preferred_link_allowed_exceed_portion
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        Ok((self.min_w, self.min_h))
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        Ok((self.min_w.scaled(), self.min_h.scaled()))
     }
 
     fn min_w_fail_policy(&self) -> MinLenFailPolicy {
@@ -84,8 +208,8 @@ impl Widget for Debug {
         self.min_h_fail_policy
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        Ok((self.max_w, self.max_h))
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        Ok((self.max_w.scaled(), self.max_h.scaled()))
     }
 
     fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
@@ -100,7 +224,7 @@ impl Widget for Debug {
         (self.preferred_w, self.preferred_h)
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         let ratio = match &self.aspect_ratio {
             None => return None,
             Some(v) => v,
@@ -111,7 +235,7 @@ impl Widget for Debug {
         )))
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         let ratio = match &self.aspect_ratio {
             None => return None,
             Some(v) => v,
@@ -122,41 +246,35 @@ impl Widget for Debug {
         )))
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
         self.clicked_this_frame = false; // reset each frame
         self.draw_pos = event.position;
 
-        let pos: Option<sdl2::rect::Rect> = event.position.into();
-        let pos = match pos {
-            Some(v) => v,
-            None => return Ok(()), // only functionality is being clicked
-        };
-
+        let hit = HitTest::from_event(&event);
+        if self.editable {
+            self.hover.update(&hit, event.events);
+        }
         for e in event.events.iter_mut().filter(|e| e.available()) {
-            if let sdl2::event::Event::MouseButtonUp {
-                    x,
-                    y,
+            match e.e {
+                sdl2::event::Event::MouseButtonUp {
                     mouse_btn: MouseButton::Left,
-                    window_id,
                     ..
-                } = e.e {
-                if event.window_id != window_id {
-                    continue; // not for me!
+                } if hit.was_hit(&e.e) => {
+                    e.set_consumed();
+                    self.clicked_this_frame = true;
                 }
-                if pos.contains_point((x, y)) {
-                    // ignore mouse events out of scroll area
-                    let point_contained_in_clipping_rect = match event.clipping_rect {
-                        sdl2::render::ClippingRect::Some(rect) => rect.contains_point((x, y)),
-                        sdl2::render::ClippingRect::Zero => false,
-                        sdl2::render::ClippingRect::None => true,
-                    };
-                    if !point_contained_in_clipping_rect {
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(keycode),
+                    keymod,
+                    ..
+                } if self.editable && self.hover.hovered() => {
+                    if !self.edit(keycode, keymod) {
                         continue;
                     }
-
                     e.set_consumed();
-                    self.clicked_this_frame = true;
+                    println!("debug rect sizing: {}", self.describe());
                 }
+                _ => {}
             }
         }
 
@@ -172,7 +290,8 @@ impl Widget for Debug {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         _focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         // as always, snap to integer grid before rendering / using,
         // plus checks that draw area is non-zero
         let pos: Option<sdl2::rect::Rect> = self.draw_pos.into();
@@ -182,11 +301,14 @@ impl Widget for Debug {
         };
 
         let mut color_to_use = Color::RED;
+        if self.editable && self.hover.hovered() {
+            color_to_use = Color::YELLOW;
+        }
         if self.clicked_this_frame {
             color_to_use = Color::GREEN;
             println!("debug rect at {:?} was clicked!", pos);
         }
 
-        debug_rect_outline(color_to_use, pos, canvas)
+        debug_rect_outline(color_to_use, pos, &mut canvas)
     }
 }
@@ -0,0 +1,165 @@
+use sdl2::event::Event;
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+};
+
+use super::{SDLEvent, Widget, WidgetUpdateEvent};
+
+/// what an event filter does with an event
+pub enum EventFilterAction {
+    /// the contained sees the event as is
+    Pass,
+    /// the contained doesn't see the event. it's still available to widgets
+    /// outside of the filter
+    Hide,
+    /// the contained doesn't see the event, and it's marked as consumed
+    Consume,
+    /// the contained sees this event instead. if the contained consumes it,
+    /// the original event is consumed
+    Replace(Event),
+}
+
+/// inspects each available event, before the contained widget is updated
+pub type EventFilterFn<'sdl> = Box<dyn FnMut(&Event) -> EventFilterAction + 'sdl>;
+
+/// is an event from the user (keyboard, mouse, touch, controller, etc.)
+pub fn is_input(e: &Event) -> bool {
+    matches!(
+        e,
+        Event::KeyDown { .. }
+            | Event::KeyUp { .. }
+            | Event::TextEditing { .. }
+            | Event::TextInput { .. }
+            | Event::MouseMotion { .. }
+            | Event::MouseButtonDown { .. }
+            | Event::MouseButtonUp { .. }
+            | Event::MouseWheel { .. }
+            | Event::JoyAxisMotion { .. }
+            | Event::JoyBallMotion { .. }
+            | Event::JoyHatMotion { .. }
+            | Event::JoyButtonDown { .. }
+            | Event::JoyButtonUp { .. }
+            | Event::ControllerAxisMotion { .. }
+            | Event::ControllerButtonDown { .. }
+            | Event::ControllerButtonUp { .. }
+            | Event::FingerDown { .. }
+            | Event::FingerUp { .. }
+            | Event::FingerMotion { .. }
+            | Event::DropFile { .. }
+            | Event::DropText { .. }
+    )
+}
+
+/// controls which events reach the contained widget (e.g. blocking input
+/// while loading, remapping keys, or logging the events for some part of the
+/// gui).
+///
+/// events already consumed aren't given to the filter
+pub struct EventFilter<'sdl> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub filter: EventFilterFn<'sdl>,
+}
+
+impl<'sdl> EventFilter<'sdl> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, filter: EventFilterFn<'sdl>) -> Self {
+        Self { contained, filter }
+    }
+}
+
+impl<'sdl> Widget for EventFilter<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        // the events given to the contained, and the index of the event each
+        // came from
+        let mut forwarded: Vec<SDLEvent> = Vec::new();
+        let mut origin: Vec<usize> = Vec::new();
+        for (i, sdl_event) in event.events.iter_mut().enumerate() {
+            if sdl_event.consumed() {
+                continue;
+            }
+            match (self.filter)(&sdl_event.e) {
+                EventFilterAction::Pass => {
+                    forwarded.push(SDLEvent::new(sdl_event.e.clone()));
+                    origin.push(i);
+                }
+                EventFilterAction::Hide => {}
+                EventFilterAction::Consume => sdl_event.set_consumed(),
+                EventFilterAction::Replace(e) => {
+                    forwarded.push(SDLEvent::new(e));
+                    origin.push(i);
+                }
+            }
+        }
+
+        let position = event.position;
+        let mut sub_event = event.sub_event(position);
+        sub_event.events = forwarded.as_mut_slice();
+        let r = self.contained.update(sub_event);
+
+        // whatever the contained consumed is consumed outside as well
+        for (forwarded_event, i) in forwarded.iter().zip(origin) {
+            if forwarded_event.consumed() {
+                event.events[i].consumed_status = forwarded_event.consumed_status;
+            }
+        }
+        r
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}
@@ -0,0 +1,163 @@
+use sdl2::keyboard::{Keycode, Mod};
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rust::CellRefOrCell,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// constrains tab navigation to the focusable widgets inside of it, while
+/// active. intended for modal dialogs and side panels.
+///
+/// while active:
+///  - tab and shift tab cycle through focus_ids in order, wrapping around at
+///    the ends. this takes precedence over the contained widgets' FocusID
+///    previous and next
+///  - if the focus is outside of the scope, tab brings it back inside
+///
+/// when activated, the current focus is remembered and the first focus id is
+/// focused. when deactivated, the remembered focus is restored.
+///
+/// this does not prevent widgets outside of the scope from receiving events
+/// (e.g. mouse hover giving focus); that's up to how the gui is composed
+pub struct FocusScope<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    /// the focusable widgets inside this scope, in tab order
+    pub focus_ids: Vec<String>,
+    pub active: CellRefOrCell<'state, bool>,
+
+    /// internal state. detects rising and falling edge of active
+    was_active: bool,
+    /// the focus before this scope was activated
+    previous_focus: Option<String>,
+}
+
+impl<'sdl, 'state> FocusScope<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        focus_ids: Vec<String>,
+        active: CellRefOrCell<'state, bool>,
+    ) -> Self {
+        Self {
+            contained,
+            focus_ids,
+            active,
+            was_active: false,
+            previous_focus: None,
+        }
+    }
+
+    fn scoped_tab_behavior(&self, event: &mut WidgetUpdateEvent) {
+        if self.focus_ids.is_empty() {
+            return;
+        }
+        let len = self.focus_ids.len();
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            if let sdl2::event::Event::KeyDown {
+                repeat,
+                keycode: Some(Keycode::Tab),
+                keymod,
+                ..
+            } = sdl_event.e
+            {
+                sdl_event.set_consumed();
+                if repeat {
+                    continue;
+                }
+                // None if nothing is focused or the focus is outside of this
+                // scope. either way, it's brought back in
+                let current = event
+                    .focus_manager
+                    .focus
+                    .as_ref()
+                    .and_then(|id| self.focus_ids.iter().position(|v| v == id));
+                let shift = keymod.contains(Mod::LSHIFTMOD) || keymod.contains(Mod::RSHIFTMOD);
+                let next = match (current, shift) {
+                    (None, false) => 0,
+                    (None, true) => len - 1,
+                    (Some(i), false) => (i + 1) % len,
+                    (Some(i), true) => (i + len - 1) % len,
+                };
+                event.focus_manager.focus = Some(self.focus_ids[next].clone());
+            }
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for FocusScope<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let active = self.active.get();
+        if active && !self.was_active {
+            self.previous_focus = event.focus_manager.focus.take();
+            event.focus_manager.focus = self.focus_ids.first().cloned();
+        } else if !active && self.was_active {
+            event.focus_manager.focus = self.previous_focus.take();
+        }
+        self.was_active = active;
+
+        if active {
+            // before the contained widgets, so they don't use the tab
+            self.scoped_tab_behavior(&mut event);
+        }
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}
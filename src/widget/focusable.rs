@@ -0,0 +1,209 @@
+use sdl2::{keyboard::Keycode, pixels::Color};
+
+use crate::util::{
+    accessibility::{AccessibleDescription, AccessibleRole, AccessibleState},
+    error::UiResult,
+    focus::{FocusID, FocusManager},
+    hit::{HitShape, HoverCache},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rect::FRect,
+    render::CanvasStateGuard,
+    scale::{scaled, scaled_u32},
+};
+
+use super::{
+    button::ButtonFn,
+    checkbox::{
+        default_activation_keys, focus_press_update_implementation, FocusPressWidgetSoundStyle,
+    },
+    Widget, WidgetUpdateEvent,
+};
+
+/// an outline drawn around a Focusable while it's focused
+#[derive(Debug, Clone, Copy)]
+pub struct FocusIndicator {
+    pub color: Color,
+    /// in logical pixels (scaled by the ui scale)
+    pub width: u32,
+}
+
+impl Default for FocusIndicator {
+    fn default() -> Self {
+        Self {
+            color: Color::RGB(118, 73, 206),
+            width: 2,
+        }
+    }
+}
+
+/// makes a widget which doesn't handle input (e.g. a card or list row)
+/// focusable. it takes part in keyboard navigation like a button: it's
+/// activated by a click or an activation key while focused, and draws an
+/// indicator while focused. sizing is that of the contained
+pub struct Focusable<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub focus_id: FocusID,
+    /// called when activated
+    pub functionality: ButtonFn<'state>,
+    /// keys which activate the widget while it is focused
+    pub activation_keys: Vec<Keycode>,
+    /// the widget can be clicked this far outside of where it's drawn, in
+    /// logical pixels. see HitTest::expansion
+    pub hit_expansion: f32,
+    /// the part of the widget which can be clicked. see HitTest::shape
+    pub hit_shape: HitShape,
+    /// none doesn't draw anything, e.g. if the contained shows focus itself
+    pub indicator: Option<FocusIndicator>,
+    /// what the widget is called, for screen readers
+    pub accessible_label: String,
+    sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
+
+    /// internal state for the press
+    pressed: bool,
+    hover: HoverCache,
+    /// internal state for sound
+    focused_previous_frame: bool,
+    /// internal state for activating via keyboard
+    activation_key_held: bool,
+
+    /// state stored for draw from update
+    draw_pos: FRect,
+}
+
+impl<'sdl, 'state> Focusable<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        focus_id: FocusID,
+        functionality: ButtonFn<'state>,
+        sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
+    ) -> Self {
+        Self {
+            contained,
+            focus_id,
+            functionality,
+            activation_keys: default_activation_keys(),
+            hit_expansion: 0.,
+            hit_shape: HitShape::Rect,
+            indicator: Some(Default::default()),
+            accessible_label: String::new(),
+            sounds,
+            pressed: false,
+            hover: Default::default(),
+            focused_previous_frame: false,
+            activation_key_held: false,
+            draw_pos: Default::default(),
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for Focusable<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+
+    fn accessible_description(&self) -> Option<AccessibleDescription> {
+        Some(AccessibleDescription {
+            role: AccessibleRole::Button,
+            label: self.accessible_label.clone(),
+            value: None,
+            state: AccessibleState {
+                checked: None,
+                pressed: self.pressed,
+            },
+        })
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        focus_press_update_implementation(
+            &mut self.hover,
+            &mut self.pressed,
+            &mut self.focused_previous_frame,
+            &mut self.activation_key_held,
+            &self.activation_keys,
+            &self.focus_id,
+            scaled(self.hit_expansion),
+            &self.hit_shape,
+            event.dup(),
+            self.functionality.as_mut(),
+            self.sounds.as_mut(),
+            None,
+        )?;
+        event
+            .focus_manager
+            .report_description(&self.focus_id, self.accessible_description());
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        self.contained.draw(&mut canvas, focus_manager)?;
+        let indicator = match self.indicator {
+            Some(v) if focus_manager.is_focused(&self.focus_id) => v,
+            _ => return Ok(()),
+        };
+        let pos: sdl2::rect::Rect = match self.draw_pos.into() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        canvas.set_draw_color(indicator.color);
+        let width = scaled_u32(indicator.width).min(pos.width().min(pos.height()).div_ceil(2));
+        for i in 0..width {
+            let w = pos.width() - i * 2;
+            let h = pos.height() - i * 2;
+            let inset = sdl2::rect::Rect::new(pos.x() + i as i32, pos.y() + i as i32, w, h);
+            canvas.draw_rect(inset)?;
+        }
+        Ok(())
+    }
+}
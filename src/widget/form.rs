@@ -0,0 +1,213 @@
+use std::cell::{Cell, RefCell};
+
+use crate::util::{
+    error::UiResult,
+    feedback::UiFeedback,
+    focus::{FocusID, FocusManager},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// checks a field's value (typically read from the same state the field's
+/// widget is bound to). gives an error describing the problem if invalid (e.g.
+/// UiError::Custom); its message is what's shown for the field
+pub type FieldValidator<'state> = Box<dyn FnMut() -> UiResult<()> + 'state>;
+
+/// called when a valid form is submitted
+pub type FormSubmitFn<'state> = Box<dyn FnMut() -> UiResult<()> + 'state>;
+
+struct FormField<'state> {
+    /// the focus id of the field's widget
+    focus_id: String,
+    validate: FieldValidator<'state>,
+    /// from the most recent validation
+    error: Option<String>,
+}
+
+/// the fields of a form and their validation state. referenced by the Form
+/// widget and by whatever submits the form (e.g. a button's callback)
+#[derive(Default)]
+pub struct FormState<'state> {
+    fields: RefCell<Vec<FormField<'state>>>,
+    submit_requested: Cell<bool>,
+}
+
+impl<'state> FormState<'state> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// fields are ordered; the first invalid field is focused on submit. the
+    /// validator can't access this form state
+    pub fn add_field(&self, focus_id: &FocusID, validate: FieldValidator<'state>) {
+        self.fields.borrow_mut().push(FormField {
+            focus_id: focus_id.me.clone(),
+            validate,
+            error: None,
+        });
+    }
+
+    /// run all validators. returns if all fields are valid
+    pub fn validate(&self) -> bool {
+        let mut fields = self.fields.borrow_mut();
+        for field in fields.iter_mut() {
+            field.error = (field.validate)().err().map(|e| e.message().to_owned());
+        }
+        fields.iter().all(|field| field.error.is_none())
+    }
+
+    /// from the most recent validation. the submit button can be styled
+    /// according to this
+    pub fn is_valid(&self) -> bool {
+        self.fields
+            .borrow()
+            .iter()
+            .all(|field| field.error.is_none())
+    }
+
+    /// the message for a field, from the most recent validation
+    pub fn error(&self, focus_id: &str) -> Option<String> {
+        self.fields
+            .borrow()
+            .iter()
+            .find(|field| field.focus_id == focus_id)
+            .and_then(|field| field.error.clone())
+    }
+
+    /// focus id and message of each invalid field, in order
+    pub fn errors(&self) -> Vec<(String, String)> {
+        self.fields
+            .borrow()
+            .iter()
+            .filter_map(|field| {
+                field
+                    .error
+                    .as_ref()
+                    .map(|error| (field.focus_id.clone(), error.clone()))
+            })
+            .collect()
+    }
+
+    pub fn first_invalid(&self) -> Option<String> {
+        self.fields
+            .borrow()
+            .iter()
+            .find(|field| field.error.is_some())
+            .map(|field| field.focus_id.clone())
+    }
+
+    /// submit the form during the Form widget's next update (or the current
+    /// one, if called from within the form's contained, e.g. by a button)
+    pub fn request_submit(&self) {
+        self.submit_requested.set(true);
+    }
+}
+
+/// groups input widgets (text inputs, checkboxes, etc.) which are submitted
+/// together.
+///
+/// on submit, all fields are validated. if valid, on_submit is called.
+/// otherwise, the first invalid field is focused
+pub struct Form<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub state: &'state FormState<'state>,
+    pub on_submit: FormSubmitFn<'state>,
+    /// validate each update, so that is_valid and the field errors are kept
+    /// up to date while editing. otherwise, only validated on submit
+    pub validate_on_update: bool,
+}
+
+impl<'sdl, 'state> Form<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        state: &'state FormState<'state>,
+        on_submit: FormSubmitFn<'state>,
+    ) -> Self {
+        Self {
+            contained,
+            state,
+            on_submit,
+            validate_on_update: true,
+        }
+    }
+
+    fn submit(&mut self, focus_manager: &mut FocusManager) -> UiResult<()> {
+        if self.state.validate() {
+            return (self.on_submit)();
+        }
+        focus_manager.feedback(UiFeedback::Error);
+        if let Some(focus_id) = self.state.first_invalid() {
+            focus_manager.set_focus(Some(&focus_id));
+        }
+        Ok(())
+    }
+}
+
+impl<'sdl, 'state> Widget for Form<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.contained.update(event.dup())?;
+        if self.state.submit_requested.take() {
+            self.submit(event.focus_manager)?;
+        } else if self.validate_on_update {
+            self.state.validate();
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}
@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::util::timer::set_frame_time;
+
+    use super::*;
+
+    #[test]
+    fn widest_label_of_previous_frame() {
+        let sync = FormColumnSync::new();
+        let start = Instant::now();
+        set_frame_time(start);
+        assert_eq!(sync.report(30.), 30.);
+        // wider than the rows so far
+        assert_eq!(sync.report(50.), 50.);
+        assert_eq!(sync.report(40.), 50.);
+
+        set_frame_time(start + Duration::from_millis(16));
+        assert_eq!(sync.report(30.), 50.);
+        assert_eq!(sync.report(20.), 50.);
+
+        // the wide label was removed
+        set_frame_time(start + Duration::from_millis(32));
+        assert_eq!(sync.report(20.), 30.);
+        assert_eq!(sync.width(), 30.);
+    }
+}
+
+use std::{cell::Cell, time::Instant};
+
+use crate::util::{
+    animation::request_redraw,
+    error::UiResult,
+    focus::FocusManager,
+    length::{
+        clamp, AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy,
+        PreferredPortion,
+    },
+    rect::FRect,
+    scale::scaled,
+    timer::frame_time,
+};
+
+use super::{place, Widget, WidgetUpdateEvent};
+
+/// shared by form rows so their label columns line up. the column is as wide
+/// as the widest label
+#[derive(Default)]
+pub struct FormColumnSync {
+    /// the width of the column for this frame
+    width: Cell<f32>,
+    /// the widest label reported so far this frame
+    widest: Cell<f32>,
+    /// the frame which is being reported, see frame_time
+    frame: Cell<Option<Instant>>,
+}
+
+impl FormColumnSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the width of the label column, as of the most recent update
+    pub fn width(&self) -> f32 {
+        self.width.get()
+    }
+
+    /// a row reports the width its label wants, and gets the width of the
+    /// column. the column starts each frame as wide as the widest label of the
+    /// previous frame, so it can shrink
+    fn report(&self, label_width: f32) -> f32 {
+        let now = frame_time();
+        if self.frame.get() != Some(now) {
+            if self.frame.get().is_some() {
+                self.width.set(self.widest.get());
+            }
+            self.frame.set(Some(now));
+            self.widest.set(0.);
+        }
+        self.widest.set(self.widest.get().max(label_width));
+        if self.widest.get() > self.width.get() {
+            // rows already updated this frame used a narrower column
+            self.width.set(self.widest.get());
+            request_redraw();
+        }
+        self.width.get()
+    }
+}
+
+/// a label (right aligned in a column) beside a control, e.g. a row of a
+/// settings form. rows which share a FormColumnSync line up their controls.
+///
+/// the label is sized by its width at the height of the row; the control is
+/// placed in the remaining width
+pub struct FormRow<'sdl, 'state> {
+    pub label: Box<dyn Widget + 'sdl>,
+    pub control: Box<dyn Widget + 'sdl>,
+    /// if not set, the label column is only as wide as this row's label
+    pub sync: Option<&'state FormColumnSync>,
+    /// space between the label column and the control, in logical pixels
+    /// (scaled by the ui scale)
+    pub gap: f32,
+}
+
+impl<'sdl, 'state> FormRow<'sdl, 'state> {
+    pub fn new(
+        label: Box<dyn Widget + 'sdl>,
+        control: Box<dyn Widget + 'sdl>,
+        sync: Option<&'state FormColumnSync>,
+    ) -> Self {
+        Self {
+            label,
+            control,
+            sync,
+            gap: 8.,
+        }
+    }
+
+    /// the width the label wants, at the height of the row
+    fn label_width(&mut self, row_h: f32) -> UiResult<f32> {
+        let (min_w, min_h) = self.label.min()?;
+        let (max_w, max_h) = self.label.max()?;
+        let h = clamp(row_h, min_h, max_h);
+        let w = match self.label.preferred_width_from_height(h) {
+            Some(w) => w?,
+            None => min_w.0,
+        };
+        Ok(clamp(w, min_w, max_w))
+    }
+}
+
+impl<'sdl, 'state> Widget for FormRow<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            self.label.as_mut() as &mut dyn Widget,
+            self.control.as_mut() as &mut dyn Widget,
+        ]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let (label_w, label_h) = self.label.min()?;
+        let (control_w, control_h) = self.control.min()?;
+        let column = match self.sync {
+            Some(sync) => label_w.strictest(MinLen(sync.width())),
+            None => label_w,
+        };
+        Ok((
+            column
+                .combined(MinLen(scaled(self.gap)))
+                .combined(control_w),
+            label_h.strictest(control_h),
+        ))
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.control.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let (_, control_h) = self.control.max()?;
+        Ok((MaxLen::LAX, control_h))
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.control.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.control.preferred_portion()
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let position = event.position;
+        let label_w = self.label_width(position.h)?;
+        let column = match self.sync {
+            Some(sync) => sync.report(label_w),
+            None => label_w,
+        }
+        .min(position.w);
+        let label_w = label_w.min(column);
+
+        let label_cell = FRect {
+            x: position.x + column - label_w,
+            y: position.y,
+            w: label_w,
+            h: position.h,
+        };
+        let label_pos = place(
+            self.label.as_mut(),
+            label_cell,
+            AspectRatioPreferredDirection::WidthFromHeight,
+        )?;
+        self.label.update(event.sub_event(label_pos))?;
+
+        let control_x = column + scaled(self.gap);
+        let control_cell = FRect {
+            x: position.x + control_x,
+            y: position.y,
+            w: (position.w - control_x).max(0.),
+            h: position.h,
+        };
+        let control_pos = place(
+            self.control.as_mut(),
+            control_cell,
+            event.aspect_ratio_priority,
+        )?;
+        self.control.update(event.sub_event(control_pos))
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.label.update_adjust_position(pos_delta);
+        self.control.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.label.draw(canvas, focus_manager)?;
+        self.control.draw(canvas, focus_manager)
+    }
+}
@@ -0,0 +1,149 @@
+use sdl2::pixels::Color;
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    hit::{HitTest, HoverCache},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rect::FRect,
+    render::CanvasStateGuard,
+    rust::CellRefOrCell,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// fills the contained widget's position with a color depending on its state,
+/// then draws the contained on top. e.g. for list rows or menu items. lighter
+/// than the background widgets; sizing is that of the contained.
+///
+/// if several states apply, the color is the first set of: selected, focused,
+/// hovered, normal. a state without a color is skipped
+pub struct Highlight<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub normal: Option<Color>,
+    /// the mouse is over the contained
+    pub hovered: Option<Color>,
+    /// the focus is on the contained, or something inside it
+    pub focused: Option<Color>,
+    pub selected: Option<Color>,
+    /// set by the application (e.g. the chosen row of a list)
+    pub is_selected: CellRefOrCell<'state, bool>,
+
+    hover: HoverCache,
+    /// if the focus was within the contained, as of the most recent update
+    focus_within: bool,
+    /// state stored for draw from update
+    draw_pos: FRect,
+}
+
+impl<'sdl, 'state> Highlight<'sdl, 'state> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>) -> Self {
+        Self {
+            contained,
+            normal: None,
+            hovered: None,
+            focused: None,
+            selected: None,
+            is_selected: CellRefOrCell::Cell(std::cell::Cell::new(false)),
+            hover: Default::default(),
+            focus_within: false,
+            draw_pos: Default::default(),
+        }
+    }
+
+    /// the color which is drawn, given the current state
+    pub fn color(&self) -> Option<Color> {
+        [
+            (self.is_selected.get(), self.selected),
+            (self.focus_within, self.focused),
+            (self.hover.hovered(), self.hovered),
+            (true, self.normal),
+        ]
+        .into_iter()
+        .filter(|(applies, _)| *applies)
+        .find_map(|(_, color)| color)
+    }
+}
+
+impl<'sdl, 'state> Widget for Highlight<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        self.hover
+            .update(&HitTest::from_event(&event), event.events);
+
+        // the descendants report their focus ids during their update
+        let reported_before = event.focus_manager.reported().len();
+        self.contained.update(event.dup())?;
+        let focus_manager = &*event.focus_manager;
+        self.focus_within = focus_manager.reported()[reported_before..]
+            .iter()
+            .any(|id| Some(id.as_str()) == focus_manager.focused());
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        if let Some(color) = self.color() {
+            let pos: Option<sdl2::rect::Rect> = self.draw_pos.into();
+            if let Some(pos) = pos {
+                canvas.set_draw_color(color);
+                canvas.fill_rect(pos)?;
+            }
+        }
+        self.contained.draw(&mut canvas, focus_manager)
+    }
+}
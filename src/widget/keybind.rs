@@ -0,0 +1,217 @@
+use sdl2::{
+    controller::Button as ControllerButton, keyboard::Keycode, render::TextureCreator,
+    video::WindowContext,
+};
+
+use crate::util::{
+    error::UiResult,
+    focus::{FocusID, FocusManager},
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rust::CellRefOrCell,
+    shared::SharedState,
+};
+
+use super::{
+    button::{Button, ButtonStyle, ButtonTextureVariant},
+    checkbox::FocusPressWidgetSoundStyle,
+    Widget, WidgetUpdateEvent,
+};
+
+/// an input which an action is bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(Keycode),
+    ControllerButton(ControllerButton),
+}
+
+impl Binding {
+    /// a name to show to the user
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(keycode) => keycode.name(),
+            Binding::ControllerButton(button) => button.string(),
+        }
+    }
+}
+
+/// a button which, when activated, waits for the next key or controller
+/// button press and writes it into the binding. for a settings screen's
+/// controls.
+///
+/// the button shows the bound input's name, or the waiting text while
+/// capturing. a cancel key stops capturing without changing the binding. a
+/// mouse press anywhere also cancels
+pub struct KeyCaptureButton<'sdl, 'state> {
+    pub button: Button<'sdl, 'state>,
+    pub binding: CellRefOrCell<'state, Option<Binding>>,
+    /// shown while waiting for a press
+    pub waiting_text: String,
+    /// shown while nothing is bound
+    pub unbound_text: String,
+    /// keys which stop capturing, instead of being bound
+    pub cancel_keys: Vec<Keycode>,
+
+    /// what the button shows. given to the style
+    text: SharedState<String>,
+    /// set by the button's functionality
+    capturing: SharedState<bool>,
+}
+
+impl<'sdl, 'state> KeyCaptureButton<'sdl, 'state> {
+    /// the style is created from the text the button should show (e.g. a
+    /// LabelButtonStyle, whose label's text is that state)
+    pub fn new(
+        binding: impl Into<CellRefOrCell<'state, Option<Binding>>>,
+        focus_id: FocusID,
+        style: impl FnOnce(SharedState<String>) -> Box<dyn ButtonStyle<ButtonTextureVariant> + 'sdl>,
+        sounds: Box<dyn FocusPressWidgetSoundStyle + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        let text = SharedState::<String>::default();
+        let capturing = SharedState::new(false);
+        let capturing_dup = capturing.clone();
+        let button = Button::new(
+            Box::new(move || {
+                capturing_dup.set(true);
+                Ok(())
+            }),
+            focus_id,
+            style(text.clone()),
+            sounds,
+            creator,
+        );
+        let key_capture = Self {
+            button,
+            binding: binding.into(),
+            waiting_text: "press a key...".to_owned(),
+            unbound_text: "none".to_owned(),
+            cancel_keys: vec![Keycode::Escape],
+            text,
+            capturing,
+        };
+        key_capture.update_text();
+        key_capture
+    }
+
+    pub fn capturing(&self) -> bool {
+        self.capturing.get()
+    }
+
+    /// stop waiting for a press, without changing the binding
+    pub fn cancel(&self) {
+        self.capturing.set(false);
+    }
+
+    fn update_text(&self) {
+        let text = if self.capturing() {
+            self.waiting_text.clone()
+        } else {
+            match self.binding.get() {
+                Some(binding) => binding.name(),
+                None => self.unbound_text.clone(),
+            }
+        };
+        if self.text.with(|current| *current != text) {
+            self.text.set(text);
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for KeyCaptureButton<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut self.button as &mut dyn Widget]
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        if self.capturing() {
+            // before the button, so the press isn't also used to navigate or
+            // activate
+            for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+                match sdl_event.e {
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(keycode),
+                        repeat,
+                        ..
+                    } => {
+                        sdl_event.set_consumed();
+                        if repeat {
+                            continue;
+                        }
+                        if !self.cancel_keys.contains(&keycode) {
+                            self.binding.set(Some(Binding::Key(keycode)));
+                        }
+                        self.cancel();
+                        break;
+                    }
+                    sdl2::event::Event::ControllerButtonDown { button, .. } => {
+                        sdl_event.set_consumed();
+                        self.binding.set(Some(Binding::ControllerButton(button)));
+                        self.cancel();
+                        break;
+                    }
+                    sdl2::event::Event::MouseButtonDown { .. } => {
+                        // not consumed; the press is still used
+                        self.cancel();
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.button.update(event.dup())?;
+        self.update_text();
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.button.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.button.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.button.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.button.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.button.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.button.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.button.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.button.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.button.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.button.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.button.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.button.preferred_link_allowed_exceed_portion()
+    }
+}
@@ -0,0 +1,807 @@
+use std::collections::HashMap;
+
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    pixels::Color,
+    render::{TextureCreator, WindowCanvas},
+    video::WindowContext,
+};
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties},
+    hit::HitTest,
+    length::{MaxLen, MinLen},
+    rect::FRect,
+    render::{style_generation, CanvasStateGuard},
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+pub type MenuAction<'sdl> = Box<dyn FnMut() -> UiResult<()> + 'sdl>;
+
+pub enum MenuItem<'sdl> {
+    Action {
+        label: String,
+        /// shown right aligned, e.g. "Ctrl+S". display only; the app handles
+        /// the key itself
+        accelerator: Option<String>,
+        enabled: bool,
+        action: MenuAction<'sdl>,
+    },
+    Submenu {
+        label: String,
+        enabled: bool,
+        items: Vec<MenuItem<'sdl>>,
+    },
+    Separator,
+}
+
+impl<'sdl> MenuItem<'sdl> {
+    pub fn action(label: &str, action: MenuAction<'sdl>) -> Self {
+        MenuItem::Action {
+            label: label.to_owned(),
+            accelerator: None,
+            enabled: true,
+            action,
+        }
+    }
+
+    pub fn submenu(label: &str, items: Vec<MenuItem<'sdl>>) -> Self {
+        MenuItem::Submenu {
+            label: label.to_owned(),
+            enabled: true,
+            items,
+        }
+    }
+
+    /// can be highlighted and activated
+    fn selectable(&self) -> bool {
+        match self {
+            MenuItem::Action { enabled, .. } | MenuItem::Submenu { enabled, .. } => *enabled,
+            MenuItem::Separator => false,
+        }
+    }
+}
+
+/// a top level entry in the menu bar
+pub struct Menu<'sdl> {
+    pub label: String,
+    pub items: Vec<MenuItem<'sdl>>,
+}
+
+pub struct MenuStyle {
+    /// in logical pixels (scaled by the ui scale)
+    pub point_size: u16,
+    /// space around text, in logical pixels
+    pub padding: f32,
+    pub text: Color,
+    pub disabled_text: Color,
+    pub background: Color,
+    pub highlight: Color,
+    pub border: Color,
+}
+
+impl MenuStyle {
+    pub(crate) fn scaled_point_size(&self) -> u16 {
+        crate::util::scale::scaled(self.point_size as f32)
+            .round()
+            .clamp(1., u16::MAX as f32) as u16
+    }
+
+    pub(crate) fn scaled_padding(&self) -> f32 {
+        crate::util::scale::scaled(self.padding)
+    }
+
+    /// the height of an item
+    pub(crate) fn line_height(&self) -> f32 {
+        self.scaled_point_size() as f32 + 2. * self.scaled_padding()
+    }
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        Self {
+            point_size: 16,
+            padding: 6.,
+            text: Color::WHITE,
+            disabled_text: Color::RGB(120, 120, 120),
+            background: Color::RGB(40, 40, 40),
+            highlight: Color::RGB(118, 73, 206),
+            border: Color::RGB(90, 90, 90),
+        }
+    }
+}
+
+/// an open drop down (or submenu), computed during update
+pub(crate) struct PopupLayout {
+    pub(crate) position: FRect,
+    /// one per item
+    pub(crate) items: Vec<FRect>,
+}
+
+impl PopupLayout {
+    /// the index of the item under a point
+    pub(crate) fn item_under(&self, x: i32, y: i32) -> Option<usize> {
+        self.items.iter().position(|rect| {
+            let r: Option<sdl2::rect::Rect> = (*rect).into();
+            r.map(|r| r.contains_point((x, y))).unwrap_or(false)
+        })
+    }
+
+    pub(crate) fn contains(&self, x: i32, y: i32) -> bool {
+        let r: Option<sdl2::rect::Rect> = self.position.into();
+        r.map(|r| r.contains_point((x, y))).unwrap_or(false)
+    }
+
+    pub(crate) fn adjust_position(&mut self, dx: f32, dy: f32) {
+        self.position.x += dx;
+        self.position.y += dy;
+        for item in self.items.iter_mut() {
+            item.x += dx;
+            item.y += dy;
+        }
+    }
+}
+
+/// renders the text of menus, cached
+pub(crate) struct MenuText<'sdl> {
+    font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+    creator: &'sdl TextureCreator<WindowContext>,
+    /// rendered text, by text and color
+    cache: HashMap<(String, Color), crate::util::render::OwnedTexture<'sdl>>,
+    /// the cache was rendered with this point size and generation
+    cache_key: (u16, u64),
+}
+
+impl<'sdl> MenuText<'sdl> {
+    pub(crate) fn new(
+        font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            font_interface,
+            creator,
+            cache: HashMap::new(),
+            cache_key: (0, 0),
+        }
+    }
+
+    pub(crate) fn size(&mut self, text: &str, point_size: u16) -> UiResult<(f32, f32)> {
+        let (w, h) = self.font_interface.render_dimensions(text, point_size)?;
+        Ok((w as f32, h as f32))
+    }
+
+    /// draw text vertically centered. returns its width
+    pub(crate) fn draw(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        text: &str,
+        color: Color,
+        point_size: u16,
+        x: f32,
+        center_y: f32,
+    ) -> UiResult<f32> {
+        if text.is_empty() {
+            return Ok(0.);
+        }
+        let key = (point_size, style_generation());
+        if self.cache_key != key {
+            self.cache.clear();
+            self.cache_key = key;
+        }
+        let cache_key = (text.to_owned(), color);
+        if !self.cache.contains_key(&cache_key) {
+            let texture = self.font_interface.render(
+                text,
+                &TextRenderProperties {
+                    point_size,
+                    render_type: SingleLineTextRenderType::Blended(color),
+                },
+                self.creator,
+            )?;
+            self.cache.insert(cache_key.clone(), texture);
+        }
+        let texture = &self.cache[&cache_key];
+        let query = texture.query();
+        canvas.copy(
+            texture,
+            None,
+            sdl2::rect::Rect::new(
+                x.round() as i32,
+                (center_y - query.height as f32 / 2.).round() as i32,
+                query.width,
+                query.height,
+            ),
+        )?;
+        Ok(query.width as f32)
+    }
+}
+
+/// a desktop style menu bar. each top level entry opens a drop down menu,
+/// which can contain nested submenus.
+///
+/// the drop downs are drawn in the overlay pass (see draw_gui). the menu bar
+/// should be updated before the widgets its drop downs cover (e.g. placed
+/// first in a vertical layout)
+///
+/// mouse: click an entry to open it, then hover or click. keyboard: F10 opens
+/// the first menu, arrows navigate, return or space activates, escape closes
+pub struct MenuBar<'sdl> {
+    pub menus: Vec<Menu<'sdl>>,
+    pub style: MenuStyle,
+
+    /// the open menus. the first element is the index of the open top level
+    /// menu. each following element is the index of an open submenu within the
+    /// previous level. empty if closed
+    open: Vec<usize>,
+    /// the highlighted item within the deepest open level
+    highlighted: Option<usize>,
+
+    /// state stored for draw from update
+    bar_position: FRect,
+    entries: Vec<FRect>,
+    popups: Vec<PopupLayout>,
+
+    text: MenuText<'sdl>,
+}
+
+impl<'sdl> MenuBar<'sdl> {
+    pub fn new(
+        menus: Vec<Menu<'sdl>>,
+        font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            menus,
+            style: Default::default(),
+            open: Vec::new(),
+            highlighted: None,
+            bar_position: Default::default(),
+            entries: Vec::new(),
+            popups: Vec::new(),
+            text: MenuText::new(font_interface, creator),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    pub fn close(&mut self) {
+        self.open.clear();
+        self.highlighted = None;
+    }
+
+    fn point_size(&self) -> u16 {
+        self.style.scaled_point_size()
+    }
+
+    fn padding(&self) -> f32 {
+        self.style.scaled_padding()
+    }
+
+    fn line_height(&self) -> f32 {
+        self.style.line_height()
+    }
+
+    /// the items shown at some level of the open menus
+    fn items_at(&self, level: usize) -> Option<&Vec<MenuItem<'sdl>>> {
+        let mut items = &self.menus.get(*self.open.first()?)?.items;
+        for &index in self.open.iter().skip(1).take(level) {
+            items = match items.get(index)? {
+                MenuItem::Submenu { items, .. } => items,
+                _ => return None,
+            };
+        }
+        Some(items)
+    }
+
+    fn item_at_mut(&mut self, level: usize, index: usize) -> Option<&mut MenuItem<'sdl>> {
+        let mut items = &mut self.menus.get_mut(*self.open.first()?)?.items;
+        for &index in self.open.iter().skip(1).take(level) {
+            items = match items.get_mut(index)? {
+                MenuItem::Submenu { items, .. } => items,
+                _ => return None,
+            };
+        }
+        items.get_mut(index)
+    }
+
+    fn deepest_level(&self) -> Option<usize> {
+        self.open.len().checked_sub(1)
+    }
+
+    /// open a top level menu, closing anything else
+    fn open_menu(&mut self, index: usize) {
+        self.open.clear();
+        self.open.push(index);
+        self.highlighted = None;
+    }
+
+    /// move the highlight within the deepest level, skipping what can't be
+    /// selected. direction is 1 or -1
+    fn move_highlight(&mut self, direction: isize) {
+        let level = match self.deepest_level() {
+            Some(v) => v,
+            None => return,
+        };
+        let selectable: Vec<bool> = match self.items_at(level) {
+            Some(items) => items.iter().map(|item| item.selectable()).collect(),
+            None => return,
+        };
+        let len = selectable.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let mut index = match self.highlighted {
+            Some(v) => v as isize,
+            None if direction > 0 => -1,
+            None => len,
+        };
+        for _ in 0..len {
+            index = (index + direction).rem_euclid(len);
+            if selectable[index as usize] {
+                self.highlighted = Some(index as usize);
+                return;
+            }
+        }
+    }
+
+    /// open the highlighted submenu, or run the highlighted action
+    fn activate(&mut self, level: usize, index: usize) -> UiResult<()> {
+        let item = match self.item_at_mut(level, index) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        match item {
+            MenuItem::Action {
+                enabled: true,
+                action,
+                ..
+            } => {
+                let r = action();
+                self.close();
+                r
+            }
+            MenuItem::Submenu { enabled: true, .. } => {
+                self.open.truncate(level + 1);
+                self.open.push(index);
+                self.highlighted = None;
+                self.move_highlight(1);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn text_size(&mut self, text: &str) -> UiResult<(f32, f32)> {
+        let point_size = self.point_size();
+        self.text.size(text, point_size)
+    }
+
+    fn compute_layout(&mut self, position: FRect) -> UiResult<()> {
+        let padding = self.padding();
+        let line_height = self.line_height();
+
+        self.entries.clear();
+        let mut x = position.x;
+        for i in 0..self.menus.len() {
+            let label = self.menus[i].label.clone();
+            let (w, _) = self.text_size(&label)?;
+            let w = w + 2. * padding;
+            self.entries.push(FRect {
+                x,
+                y: position.y,
+                w,
+                h: position.h,
+            });
+            x += w;
+        }
+
+        self.popups.clear();
+        for level in 0..self.open.len() {
+            // where the popup is attached
+            let anchor = if level == 0 {
+                let entry = match self.entries.get(self.open[0]) {
+                    Some(v) => *v,
+                    None => break,
+                };
+                (entry.x, entry.y + entry.h)
+            } else {
+                let parent = &self.popups[level - 1];
+                let item = match parent.items.get(self.open[level]) {
+                    Some(v) => *v,
+                    None => break,
+                };
+                (parent.position.x + parent.position.w, item.y)
+            };
+
+            // measure
+            let mut entries: Vec<(String, Option<String>, bool)> = Vec::new();
+            match self.items_at(level) {
+                Some(items) => {
+                    for item in items.iter() {
+                        match item {
+                            MenuItem::Action {
+                                label, accelerator, ..
+                            } => entries.push((label.clone(), accelerator.clone(), false)),
+                            MenuItem::Submenu { label, .. } => {
+                                entries.push((label.clone(), None, true))
+                            }
+                            MenuItem::Separator => entries.push((String::new(), None, false)),
+                        }
+                    }
+                }
+                None => break,
+            }
+            let mut width: f32 = 0.;
+            for (label, accelerator, submenu) in entries.iter() {
+                let mut w = self.text_size(label)?.0 + 2. * padding;
+                if let Some(accelerator) = accelerator {
+                    w += self.text_size(accelerator)?.0 + 4. * padding;
+                }
+                if *submenu {
+                    w += self.text_size(">")?.0 + 2. * padding;
+                }
+                width = width.max(w);
+            }
+
+            let mut items = Vec::new();
+            let mut y = anchor.1;
+            let item_layout_source = self.items_at(level);
+            for item in item_layout_source.into_iter().flatten() {
+                let h = match item {
+                    MenuItem::Separator => padding,
+                    _ => line_height,
+                };
+                items.push(FRect {
+                    x: anchor.0,
+                    y,
+                    w: width,
+                    h,
+                });
+                y += h;
+            }
+            self.popups.push(PopupLayout {
+                position: FRect {
+                    x: anchor.0,
+                    y: anchor.1,
+                    w: width,
+                    h: y - anchor.1,
+                },
+                items,
+            });
+        }
+        Ok(())
+    }
+
+    /// the popup level and item under a point
+    fn item_under(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        // deepest first; they may overlap
+        for (level, popup) in self.popups.iter().enumerate().rev() {
+            if let Some(index) = popup.item_under(x, y) {
+                return Some((level, index));
+            }
+        }
+        None
+    }
+
+    fn in_popup(&self, x: i32, y: i32) -> bool {
+        self.popups.iter().any(|popup| popup.contains(x, y))
+    }
+
+    fn entry_under(&self, x: i32, y: i32) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            let r: Option<sdl2::rect::Rect> = (*entry).into();
+            r.map(|r| r.contains_point((x, y))).unwrap_or(false)
+        })
+    }
+
+    /// hovering over an item highlights it, and opens it if it's a submenu
+    fn hover_item(&mut self, level: usize, index: usize) {
+        let selectable = self
+            .items_at(level)
+            .and_then(|items| items.get(index))
+            .map(|item| (item.selectable(), matches!(item, MenuItem::Submenu { .. })));
+        match selectable {
+            Some((true, true)) if self.open.get(level + 1) != Some(&index) => {
+                self.open.truncate(level + 1);
+                self.open.push(index);
+                self.highlighted = None;
+            }
+            Some((true, false)) => {
+                self.open.truncate(level + 1);
+                self.highlighted = Some(index);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_text(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        text: &str,
+        color: Color,
+        x: f32,
+        center_y: f32,
+    ) -> UiResult<f32> {
+        let point_size = self.point_size();
+        self.text.draw(canvas, text, color, point_size, x, center_y)
+    }
+}
+
+impl<'sdl> Widget for MenuBar<'sdl> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        Ok((MinLen::LAX, MinLen(self.line_height())))
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        Ok((MaxLen::LAX, MaxLen(self.line_height())))
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.bar_position = event.position;
+        self.compute_layout(event.position)?;
+        let hit = HitTest::from_event(&event);
+
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            match sdl_event.e {
+                sdl2::event::Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    window_id,
+                    ..
+                } => {
+                    if window_id != event.window_id {
+                        continue; // not for me!
+                    }
+                    if hit.contains(window_id, x, y) {
+                        sdl_event.set_consumed();
+                        match self.entry_under(x, y) {
+                            Some(index) if self.open.first() != Some(&index) => {
+                                self.open_menu(index)
+                            }
+                            _ => self.close(),
+                        }
+                    } else if self.is_open() {
+                        // clicks on the popups are handled on release.
+                        // clicking elsewhere closes the menus
+                        sdl_event.set_consumed();
+                        if !self.in_popup(x, y) {
+                            self.close();
+                        }
+                    }
+                }
+                sdl2::event::Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    window_id,
+                    ..
+                } => {
+                    if window_id != event.window_id || !self.is_open() {
+                        continue;
+                    }
+                    if let Some((level, index)) = self.item_under(x, y) {
+                        sdl_event.set_consumed();
+                        self.activate(level, index)?;
+                        self.compute_layout(event.position)?;
+                    } else if self.in_popup(x, y) {
+                        sdl_event.set_consumed();
+                    }
+                }
+                sdl2::event::Event::MouseMotion {
+                    x, y, window_id, ..
+                } => {
+                    if window_id != event.window_id || !self.is_open() {
+                        continue;
+                    }
+                    if let Some(index) = self.entry_under(x, y) {
+                        if self.open.first() != Some(&index) {
+                            self.open_menu(index);
+                            self.compute_layout(event.position)?;
+                        }
+                    } else if let Some((level, index)) = self.item_under(x, y) {
+                        self.hover_item(level, index);
+                        self.compute_layout(event.position)?;
+                    }
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(Keycode::F10),
+                    ..
+                } => {
+                    sdl_event.set_consumed();
+                    if self.is_open() {
+                        self.close();
+                    } else if !self.menus.is_empty() {
+                        self.open_menu(0);
+                        self.move_highlight(1);
+                    }
+                    self.compute_layout(event.position)?;
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if self.is_open() => {
+                    let level = self.open.len() - 1;
+                    match keycode {
+                        Keycode::Down => self.move_highlight(1),
+                        Keycode::Up => self.move_highlight(-1),
+                        Keycode::Right => {
+                            let is_submenu = self.highlighted.and_then(|index| {
+                                self.items_at(level).and_then(|items| items.get(index))
+                            });
+                            if let Some(MenuItem::Submenu { .. }) = is_submenu {
+                                let index = self.highlighted.unwrap_or(0);
+                                self.activate(level, index)?;
+                            } else if !self.menus.is_empty() {
+                                let next = (self.open[0] + 1) % self.menus.len();
+                                self.open_menu(next);
+                                self.move_highlight(1);
+                            }
+                        }
+                        Keycode::Left => {
+                            if level > 0 {
+                                let parent = self.open.pop();
+                                self.highlighted = parent;
+                            } else if !self.menus.is_empty() {
+                                let len = self.menus.len();
+                                let previous = (self.open[0] + len - 1) % len;
+                                self.open_menu(previous);
+                                self.move_highlight(1);
+                            }
+                        }
+                        Keycode::Return | Keycode::KpEnter | Keycode::Space => {
+                            if let Some(index) = self.highlighted {
+                                self.activate(level, index)?;
+                            }
+                        }
+                        Keycode::Escape => {
+                            if level > 0 {
+                                let parent = self.open.pop();
+                                self.highlighted = parent;
+                            } else {
+                                self.close();
+                            }
+                        }
+                        _ => continue, // not for me
+                    }
+                    sdl_event.set_consumed();
+                    self.compute_layout(event.position)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        let (dx, dy) = (pos_delta.0 as f32, pos_delta.1 as f32);
+        self.bar_position.x += dx;
+        self.bar_position.y += dy;
+        for entry in self.entries.iter_mut() {
+            entry.x += dx;
+            entry.y += dy;
+        }
+        for popup in self.popups.iter_mut() {
+            popup.adjust_position(dx, dy);
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas, _focus_manager: &FocusManager) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let bar: Option<sdl2::rect::Rect> = self.bar_position.into();
+        if let Some(bar) = bar {
+            canvas.set_draw_color(self.style.background);
+            canvas.fill_rect(bar)?;
+        }
+        let padding = self.padding();
+        for i in 0..self.entries.len() {
+            let entry = self.entries[i];
+            if self.open.first() == Some(&i) {
+                let r: Option<sdl2::rect::Rect> = entry.into();
+                if let Some(r) = r {
+                    canvas.set_draw_color(self.style.highlight);
+                    canvas.fill_rect(r)?;
+                }
+            }
+            let label = self.menus[i].label.clone();
+            let color = self.style.text;
+            self.draw_text(
+                &mut canvas,
+                &label,
+                color,
+                entry.x + padding,
+                entry.y + entry.h / 2.,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let padding = self.padding();
+        for level in 0..self.popups.len() {
+            let popup_position = self.popups[level].position;
+            let r: Option<sdl2::rect::Rect> = popup_position.into();
+            let r = match r {
+                Some(v) => v,
+                None => continue,
+            };
+            canvas.set_draw_color(self.style.background);
+            canvas.fill_rect(r)?;
+            canvas.set_draw_color(self.style.border);
+            canvas.draw_rect(r)?;
+
+            // label, accelerator, is submenu, is separator, enabled
+            let mut entries: Vec<(String, Option<String>, bool, bool, bool)> = Vec::new();
+            for item in self.items_at(level).into_iter().flatten() {
+                entries.push(match item {
+                    MenuItem::Action {
+                        label,
+                        accelerator,
+                        enabled,
+                        ..
+                    } => (label.clone(), accelerator.clone(), false, false, *enabled),
+                    MenuItem::Submenu { label, enabled, .. } => {
+                        (label.clone(), None, true, false, *enabled)
+                    }
+                    MenuItem::Separator => (String::new(), None, false, true, false),
+                });
+            }
+
+            for (index, (label, accelerator, submenu, separator, enabled)) in
+                entries.into_iter().enumerate()
+            {
+                let rect = match self.popups[level].items.get(index) {
+                    Some(v) => *v,
+                    None => break,
+                };
+                let center_y = rect.y + rect.h / 2.;
+                if separator {
+                    canvas.set_draw_color(self.style.border);
+                    canvas.draw_line(
+                        ((rect.x + padding) as i32, center_y as i32),
+                        ((rect.x + rect.w - padding) as i32, center_y as i32),
+                    )?;
+                    continue;
+                }
+
+                let on_path = self.open.get(level + 1) == Some(&index);
+                let highlighted = level + 1 == self.open.len() && self.highlighted == Some(index);
+                if on_path || highlighted {
+                    let r: Option<sdl2::rect::Rect> = rect.into();
+                    if let Some(r) = r {
+                        canvas.set_draw_color(self.style.highlight);
+                        canvas.fill_rect(r)?;
+                    }
+                }
+
+                let color = if enabled {
+                    self.style.text
+                } else {
+                    self.style.disabled_text
+                };
+                self.draw_text(&mut canvas, &label, color, rect.x + padding, center_y)?;
+                let right = rect.x + rect.w - padding;
+                if let Some(accelerator) = accelerator {
+                    let w = self.text_size(&accelerator)?.0;
+                    self.draw_text(&mut canvas, &accelerator, color, right - w, center_y)?;
+                }
+                if submenu {
+                    let w = self.text_size(">")?.0;
+                    self.draw_text(&mut canvas, ">", color, right - w, center_y)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+use std::cell::Cell;
+
+use sdl2::{
+    mouse::MouseButton,
+    pixels::Color,
+    render::{ClippingRect, TextureCreator},
+    video::WindowContext,
+};
+
+use crate::{
+    layout::scroller::ScrollView,
+    util::{
+        error::{UiError, UiResult},
+        focus::FocusManager,
+        hit::HitTest,
+        length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+        rect::FRect,
+        render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+    },
+};
+
+use super::{debug::CustomSizingControl, SDLEvent, Widget, WidgetUpdateEvent};
+
+struct MinimapCache<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    width: u32,
+    height: u32,
+    /// see style_generation
+    generation: u64,
+}
+
+/// a scaled down view of a scroller's content, with an indicator showing the
+/// scroller's visible area. clicking or dragging on the minimap scrolls to
+/// that point
+///
+/// the content drawn is a separate widget tree from the scroller's content
+/// (widgets can't be in two places at once). typically it's a second instance
+/// built from the same state. it receives no events and has its own focus
+/// manager, so it's display only
+pub struct Minimap<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    /// the scroller's scroll position
+    pub scroll_x: &'state Cell<i32>,
+    pub scroll_y: &'state Cell<i32>,
+    /// written by the scroller. see Scroller::view
+    pub view: &'state Cell<ScrollView>,
+    /// true restricts jumps to keep the content in frame (as the scroller's
+    /// restrict_scroll)
+    pub restrict_scroll: bool,
+    pub indicator_color: Color,
+    pub sizing: CustomSizingControl,
+
+    /// set from update for draw. the minimap's position
+    draw_pos: FRect,
+    /// set from update for draw. how much the content is scaled down
+    scale: f32,
+    /// set from update for draw. where the content is within the minimap
+    content_offset: (f32, f32),
+    /// a click happened in the minimap, and the mouse is still held
+    dragging: bool,
+    /// isolates the contained from the real focus
+    focus_manager: FocusManager,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    cache: Option<MinimapCache<'sdl>>,
+}
+
+impl<'sdl, 'state> Minimap<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        scroll_x: &'state Cell<i32>,
+        scroll_y: &'state Cell<i32>,
+        view: &'state Cell<ScrollView>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            contained,
+            scroll_x,
+            scroll_y,
+            view,
+            restrict_scroll: true,
+            indicator_color: Color::RGB(118, 73, 206),
+            sizing: Default::default(),
+            draw_pos: Default::default(),
+            scale: 1.,
+            content_offset: (0., 0.),
+            dragging: false,
+            focus_manager: Default::default(),
+            creator,
+            cache: None,
+        }
+    }
+
+    /// scroll so the viewport is centered on a point within the minimap
+    fn jump_to(&self, x: i32, y: i32) {
+        if self.scale <= 0. {
+            return;
+        }
+        let view = self.view.get();
+        // position within the content
+        let content_x = (x as f32 - self.draw_pos.x - self.content_offset.0) / self.scale;
+        let content_y = (y as f32 - self.draw_pos.y - self.content_offset.1) / self.scale;
+        // the scroll that puts that point at the center of the viewport
+        let mut scroll_x = (view.viewport.w / 2. - content_x).round() as i32;
+        let mut scroll_y = (view.viewport.h / 2. - content_y).round() as i32;
+        scroll_x += (view.viewport.x - view.content.x) as i32;
+        scroll_y += (view.viewport.y - view.content.y) as i32;
+        if self.restrict_scroll {
+            scroll_x = view.x_extent().clamp(scroll_x);
+            scroll_y = view.y_extent().clamp(scroll_y);
+        }
+        self.scroll_x.set(scroll_x);
+        self.scroll_y.set(scroll_y);
+    }
+}
+
+impl<'sdl, 'state> Widget for Minimap<'sdl, 'state> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.sizing.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.sizing.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.sizing.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.sizing.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.sizing.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.sizing.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.sizing.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.sizing.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.sizing.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.sizing.preferred_link_allowed_exceed_portion()
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        let view = self.view.get();
+
+        // fit the content within the minimap
+        self.scale = if view.content.w <= 0. || view.content.h <= 0. {
+            0.
+        } else {
+            (event.position.w / view.content.w).min(event.position.h / view.content.h)
+        };
+        self.content_offset = (
+            (event.position.w - view.content.w * self.scale) / 2.,
+            (event.position.h - view.content.h * self.scale) / 2.,
+        );
+
+        // the contained is laid out at full size, relative to the minimap's
+        // texture. it's scaled at draw
+        let mut no_events: [SDLEvent; 0] = [];
+        let contained_position = FRect {
+            x: if self.scale > 0. { self.content_offset.0 / self.scale } else { 0. },
+            y: if self.scale > 0. { self.content_offset.1 / self.scale } else { 0. },
+            w: view.content.w,
+            h: view.content.h,
+        };
+        let contained_event = WidgetUpdateEvent {
+            focus_manager: &mut self.focus_manager,
+            position: contained_position,
+            clipping_rect: ClippingRect::None,
+            window_id: event.window_id,
+            aspect_ratio_priority: event.aspect_ratio_priority,
+            events: &mut no_events,
+            window_area: contained_position,
+            input: event.input,
+        };
+        self.contained.update(contained_event)?;
+
+        let hit = HitTest::from_event(&event);
+        for e in event.events.iter_mut().filter(|e| e.available()) {
+            match e.e {
+                sdl2::event::Event::MouseButtonDown {
+                    x,
+                    y,
+                    mouse_btn: MouseButton::Left,
+                    window_id,
+                    ..
+                } => {
+                    if !hit.contains(window_id, x, y) {
+                        continue;
+                    }
+                    e.set_consumed();
+                    self.dragging = true;
+                    self.jump_to(x, y);
+                }
+                sdl2::event::Event::MouseMotion {
+                    x,
+                    y,
+                    mousestate,
+                    window_id,
+                    ..
+                } => {
+                    if !mousestate.left() {
+                        self.dragging = false;
+                    }
+                    if !self.dragging || event.window_id != window_id {
+                        continue;
+                    }
+                    e.set_consumed();
+                    self.jump_to(x, y);
+                }
+                sdl2::event::Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                } if self.dragging => {
+                    self.dragging = false;
+                    e.set_consumed();
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        // the contained is relative to the texture; it doesn't move
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let pos: Option<sdl2::rect::Rect> = self.draw_pos.into();
+        let pos = match pos {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        if self.scale <= 0. {
+            return Ok(());
+        }
+
+        let generation = style_generation();
+        let mut cache = match self.cache.take().filter(|cache| {
+            cache.width == pos.width()
+                && cache.height == pos.height()
+                && cache.generation == generation
+        }) {
+            Some(cache) => cache,
+            None => {
+                let mut texture = self
+                    .creator
+                    .create_texture_target(None, pos.width(), pos.height())
+                    .map(owned_texture)?;
+                texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+                MinimapCache {
+                    texture,
+                    width: pos.width(),
+                    height: pos.height(),
+                    generation,
+                }
+            }
+        };
+
+        // content may change at any time; re-render each frame
+        let mut e_out: Option<UiError> = None;
+        let scale = self.scale;
+        let contained = &mut self.contained;
+        let focus_manager = &self.focus_manager;
+        canvas.with_texture_canvas(&mut cache.texture, |canvas| {
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+            canvas.clear();
+            canvas.set_clip_rect(ClippingRect::None);
+            if let Err(e) = canvas.set_scale(scale, scale) {
+                e_out = Some(UiError::Sdl(e));
+                return;
+            }
+            if let Err(e) = contained.draw(canvas, focus_manager) {
+                e_out = Some(e);
+            }
+            if let Err(e) = canvas.set_scale(1., 1.) {
+                e_out.get_or_insert(UiError::Sdl(e));
+            }
+        })?;
+
+        if let Some(e) = e_out {
+            return Err(e);
+        }
+
+        canvas.copy(&cache.texture, None, pos)?;
+        self.cache = Some(cache);
+
+        // visible area indicator
+        let view = self.view.get();
+        let visible_x = view.viewport.x - (view.content.x + self.scroll_x.get() as f32);
+        let visible_y = view.viewport.y - (view.content.y + self.scroll_y.get() as f32);
+        let indicator = FRect {
+            x: self.draw_pos.x + self.content_offset.0 + visible_x * scale,
+            y: self.draw_pos.y + self.content_offset.1 + visible_y * scale,
+            w: view.viewport.w * scale,
+            h: view.viewport.h * scale,
+        };
+        let indicator: Option<sdl2::rect::Rect> = indicator.into();
+        if let Some(indicator) = indicator {
+            canvas.set_draw_color(self.indicator_color);
+            canvas.draw_rect(indicator)?;
+        }
+        Ok(())
+    }
+}
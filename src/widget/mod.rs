@@ -1,8 +1,11 @@
 pub mod debug;
 pub mod strut;
 pub mod texture;
+pub mod alpha_hit;
 
+pub mod badge;
 pub mod border;
+pub mod padding;
 
 pub mod multi_line_label;
 pub mod single_line_label;
@@ -10,19 +13,58 @@ pub mod single_line_text_input;
 
 pub mod background;
 pub mod checkbox;
+pub mod highlight;
 
 pub mod button;
+pub mod keybind;
 
-use sdl2::render::{ClippingRect, WindowCanvas};
+pub mod cached_sizing;
+pub mod canvas;
+
+pub mod event_filter;
+pub mod focus_scope;
+pub mod focusable;
+pub mod shaped;
+pub mod form;
+pub mod form_row;
+
+pub mod minimap;
+pub mod sparkline;
+
+pub mod console;
+pub mod menu;
+pub mod toolbar;
+
+pub mod anchored;
+
+pub mod navigation;
+pub mod swapper;
+pub mod tagged;
+pub mod visibility;
+
+use std::time::{Duration, Instant};
+
+use sdl2::{
+    event::WindowEvent,
+    render::{ClippingRect, WindowCanvas},
+};
 
 use crate::util::{
+    accessibility::AccessibleDescription,
+    animation::request_redraw,
+    draw_hooks::{record_layout, recording_layout, DrawHook, LaidOut},
+    error::UiResult,
     focus::FocusManager,
+    input::{update_input_snapshot, InputSnapshot},
+    render::invalidate_texture_caches,
     length::{
         clamp, AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy,
         PreferredPortion,
     },
+    perf::{count, Counter},
     rect::FRect,
     rust::reborrow,
+    timer::{set_frame_time, TimerQueue},
 };
 
 /// two purposes:
@@ -53,6 +95,9 @@ pub enum ConsumedStatus {
 pub struct SDLEvent {
     pub e: sdl2::event::Event,
     consumed_status: ConsumedStatus,
+    /// each time the event was consumed
+    #[cfg(feature = "event-trace")]
+    trace: Vec<crate::util::event_trace::EventConsumption>,
 }
 
 impl SDLEvent {
@@ -71,24 +116,47 @@ impl SDLEvent {
         self.consumed_status
     }
 
+    #[cfg_attr(feature = "event-trace", track_caller)]
     pub fn set_consumed(&mut self) {
         // shouldn't be consumed twice
         debug_assert!(matches!(self.consumed_status, ConsumedStatus::None));
         self.consumed_status = ConsumedStatus::ConsumedByWidget;
+        self.record_consumption();
     }
 
+    #[cfg_attr(feature = "event-trace", track_caller)]
     pub fn set_consumed_by_layout(&mut self) {
         debug_assert!(match self.consumed_status {
             ConsumedStatus::ConsumedByLayout => false,
             _ => true,
         });
         self.consumed_status = ConsumedStatus::ConsumedByLayout;
+        self.record_consumption();
+    }
+
+    #[cfg_attr(feature = "event-trace", track_caller)]
+    #[inline]
+    fn record_consumption(&mut self) {
+        #[cfg(feature = "event-trace")]
+        self.trace.push(crate::util::event_trace::EventConsumption {
+            status: self.consumed_status,
+            location: std::panic::Location::caller(),
+            time: Instant::now(),
+        });
+    }
+
+    /// each time the event was consumed, in order. see util::event_trace
+    #[cfg(feature = "event-trace")]
+    pub fn trace(&self) -> &[crate::util::event_trace::EventConsumption] {
+        &self.trace
     }
 
     pub fn new(e: sdl2::event::Event) -> Self {
         Self {
             e,
             consumed_status: ConsumedStatus::None,
+            #[cfg(feature = "event-trace")]
+            trace: Vec::new(),
         }
     }
 }
@@ -118,12 +186,19 @@ pub struct WidgetUpdateEvent<'sdl> {
     pub aspect_ratio_priority: AspectRatioPreferredDirection,
     /// handle all events from sdl. contains events in order of occurrence
     pub events: &'sdl mut [SDLEvent],
+    /// the area that the entire gui is laid out in (the window, in layout
+    /// coordinates). for widgets placed independently of their parent
+    pub window_area: FRect,
+    /// modifier keys and mouse state, as of the end of this frame's events
+    pub input: InputSnapshot,
 }
 
 impl<'sdl> WidgetUpdateEvent<'sdl> {
     /// create a new event, same as self, but with a different position.
     /// intended to be passed to a layout's children
     pub fn sub_event(&mut self, position: FRect) -> WidgetUpdateEvent<'_> {
+        count(Counter::WidgetUpdate);
+        record_layout(position, self.clipping_rect);
         WidgetUpdateEvent {
             // do a re-borrow. create a mutable borrow of the mutable borrow
             // output lifetime is elided - it's the re-borrowed lifetime
@@ -133,6 +208,8 @@ impl<'sdl> WidgetUpdateEvent<'sdl> {
             window_id: self.window_id,
             aspect_ratio_priority: self.aspect_ratio_priority,
             events: reborrow(self.events),
+            window_area: self.window_area,
+            input: self.input,
         }
     }
 
@@ -144,7 +221,7 @@ impl<'sdl> WidgetUpdateEvent<'sdl> {
 pub trait Widget {
     /// the widget will never have a width or height smaller than this width or
     /// height, respectively.
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
         Ok((MinLen::LAX, MinLen::LAX))
     }
 
@@ -158,7 +235,7 @@ pub trait Widget {
     /// the widget will never have a width or height greater than this width or
     /// height, respectively, unless it would conflict with the minimum width or
     /// height, respectively.
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
         Ok((MaxLen::LAX, MaxLen::LAX))
     }
 
@@ -176,13 +253,13 @@ pub trait Widget {
 
     /// implementors should use this to request an aspect ratio (additionally,
     /// the min and max should have the same ratio)
-    fn preferred_width_from_height(&mut self, _pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, _pref_h: f32) -> Option<UiResult<f32>> {
         None
     }
 
     /// implementors should use this to request an aspect ratio (additionally,
     /// the min and max should have the same ratio)
-    fn preferred_height_from_width(&mut self, _pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, _pref_w: f32) -> Option<UiResult<f32>> {
         None
     }
 
@@ -198,8 +275,39 @@ pub trait Widget {
         false
     }
 
+    /// how this widget describes itself to assistive technology (e.g. a
+    /// screen reader). focusable widgets should give this to the focus manager
+    /// at the end of each update, via FocusManager::report_description
+    fn accessible_description(&self) -> Option<AccessibleDescription> {
+        None
+    }
+
+    /// the widgets directly contained by this one. used for generically walking
+    /// the widget tree (e.g. inspectors, testing)
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        Vec::new()
+    }
+
+    /// application data attached to this widget. see Tagged
+    fn user_data(&self) -> Option<tagged::UserData<'_>> {
+        None
+    }
+
+    /// called when the widget becomes part of a gui, via attach. a widget
+    /// might allocate resources here
+    fn on_attach(&mut self) {}
+
+    /// called when the widget is removed from a gui, via detach. a widget
+    /// might free resources here
+    fn on_detach(&mut self) {}
+
+    /// called by update_gui, before update, for each window event on the
+    /// window that this widget is in. window events aren't consumed; every
+    /// widget receives them
+    fn on_window_event(&mut self, _event: &WindowEvent) {}
+
     /// called for all widgets each frame before any call to draw
-    fn update(&mut self, _event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, _event: WidgetUpdateEvent) -> UiResult<()> {
         Ok(())
     }
 
@@ -216,20 +324,148 @@ pub trait Widget {
     fn update_adjust_position(&mut self, _pos_delta: (i32, i32)) {}
 
     /// draw. called after all widgets are update each frame
-    fn draw(
+    fn draw(&mut self, canvas: &mut WindowCanvas, focus_manager: &FocusManager) -> UiResult<()>;
+
+    /// the overlay pass. called after the entire gui is drawn (see draw_gui),
+    /// so whatever is drawn here is on top of everything else (e.g. popups).
+    /// it isn't limited to the widget's position, and no clipping rect is set.
+    ///
+    /// widgets with an overlay should also handle mouse events over it. since
+    /// widgets are updated in order, an overlay is only guaranteed to get
+    /// those events first if its widget is updated before whatever the
+    /// overlay covers.
+    ///
+    /// by default, passes to the children
+    fn draw_overlay(
         &mut self,
         canvas: &mut WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String>;
+    ) -> UiResult<()> {
+        for child in self.children() {
+            child.draw_overlay(canvas, focus_manager)?;
+        }
+        Ok(())
+    }
+}
+
+/// draw a widget, followed by the overlay pass
+pub fn draw_gui(
+    widget: &mut dyn Widget,
+    canvas: &mut WindowCanvas,
+    focus_manager: &FocusManager,
+) -> UiResult<()> {
+    widget.draw(canvas, focus_manager)?;
+    canvas.set_clip_rect(ClippingRect::None);
+    widget.draw_overlay(canvas, focus_manager)
 }
 
-/// each frame after update_gui, the widget should be drawn with widget.draw()
+/// each frame after update_gui, the widget should be drawn with draw_gui (or
+/// widget.draw(), if nothing in the gui uses the overlay pass)
 pub fn update_gui(
     widget: &mut dyn Widget,
     events: &mut [SDLEvent],
     focus_manager: &mut FocusManager,
     canvas: &WindowCanvas,
-) -> Result<(), String> {
+) -> UiResult<()> {
+    update_gui_with_mouse_transform(
+        widget,
+        events,
+        focus_manager,
+        canvas,
+        &MouseTransform::Unchanged,
+    )
+}
+
+/// how mouse event coordinates are mapped before widgets see them, so they
+/// match the layout's coordinates
+#[derive(Default)]
+pub enum MouseTransform {
+    /// sdl already maps mouse coordinates when the canvas has a logical size
+    /// or render scale
+    #[default]
+    Unchanged,
+    /// scale from window coordinates to output pixels. for high dpi windows,
+    /// where the window's size is smaller than the canvas's output size
+    FromCanvas,
+    /// applied to each mouse position (x, y)
+    Custom(Box<dyn Fn(i32, i32) -> (i32, i32)>),
+}
+
+impl MouseTransform {
+    fn rewrite(&self, canvas: &WindowCanvas, window_id: u32, events: &mut [SDLEvent]) {
+        let apply: Box<dyn Fn(i32, i32) -> (i32, i32) + '_> = match self {
+            MouseTransform::Unchanged => return,
+            MouseTransform::FromCanvas => {
+                let (window_w, window_h) = canvas.window().size();
+                let (output_w, output_h) = match canvas.output_size() {
+                    Ok(v) => v,
+                    Err(_) => return,
+                };
+                if window_w == 0 || window_h == 0 {
+                    return;
+                }
+                let scale_x = output_w as f32 / window_w as f32;
+                let scale_y = output_h as f32 / window_h as f32;
+                Box::new(move |x, y| {
+                    (
+                        (x as f32 * scale_x).round() as i32,
+                        (y as f32 * scale_y).round() as i32,
+                    )
+                })
+            }
+            MouseTransform::Custom(f) => Box::new(f),
+        };
+
+        for sdl_event in events.iter_mut() {
+            match &mut sdl_event.e {
+                sdl2::event::Event::MouseMotion {
+                    window_id: event_window_id,
+                    x,
+                    y,
+                    xrel,
+                    yrel,
+                    ..
+                } if *event_window_id == window_id => {
+                    let (new_x, new_y) = apply(*x, *y);
+                    let (prev_x, prev_y) = apply(*x - *xrel, *y - *yrel);
+                    (*x, *y) = (new_x, new_y);
+                    (*xrel, *yrel) = (new_x - prev_x, new_y - prev_y);
+                }
+                sdl2::event::Event::MouseButtonDown {
+                    window_id: event_window_id,
+                    x,
+                    y,
+                    ..
+                }
+                | sdl2::event::Event::MouseButtonUp {
+                    window_id: event_window_id,
+                    x,
+                    y,
+                    ..
+                } if *event_window_id == window_id => {
+                    (*x, *y) = apply(*x, *y);
+                }
+                sdl2::event::Event::MouseWheel {
+                    window_id: event_window_id,
+                    mouse_x,
+                    mouse_y,
+                    ..
+                } if *event_window_id == window_id => {
+                    (*mouse_x, *mouse_y) = apply(*mouse_x, *mouse_y);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// the size of the area that widgets are laid out in. matches the coordinates
+/// that sdl gives mouse events in, when a logical size or render scale is set
+fn layout_size(canvas: &WindowCanvas) -> (f32, f32) {
+    let (logical_w, logical_h) = canvas.logical_size();
+    if logical_w != 0 && logical_h != 0 {
+        return (logical_w as f32, logical_h as f32);
+    }
     let (w, h) = match canvas.output_size() {
         Ok(v) => v,
         Err(msg) => {
@@ -237,32 +473,444 @@ pub fn update_gui(
             (320, 320)
         }
     };
+    let (scale_x, scale_y) = canvas.scale();
+    (w as f32 / scale_x, h as f32 / scale_y)
+}
 
-    let aspect_ratio_priority = AspectRatioPreferredDirection::default();
+/// same as update_gui, but first maps mouse event coordinates (for events in
+/// the canvas's window) with a transform
+pub fn update_gui_with_mouse_transform(
+    widget: &mut dyn Widget,
+    events: &mut [SDLEvent],
+    focus_manager: &mut FocusManager,
+    canvas: &WindowCanvas,
+    mouse_transform: &MouseTransform,
+) -> UiResult<()> {
+    let size = layout_size(canvas);
+    update_gui_at_size(
+        widget,
+        events,
+        focus_manager,
+        canvas,
+        mouse_transform,
+        RootPlacement::sized(size),
+        None,
+    )
+}
 
-    let position = place(
+/// same as update_gui, but first runs the callbacks of timers which are due.
+/// the timers' callbacks run before the widgets are updated, so changes they
+/// make are seen this frame
+pub fn update_gui_with_timers(
+    widget: &mut dyn Widget,
+    events: &mut [SDLEvent],
+    focus_manager: &mut FocusManager,
+    canvas: &WindowCanvas,
+    timers: &TimerQueue,
+) -> UiResult<()> {
+    let size = layout_size(canvas);
+    update_gui_at_size(
         widget,
-        FRect {
-            x: 0.,
-            y: 0.,
-            w: w as f32,
-            h: h as f32,
-        },
-        aspect_ratio_priority,
-    )?;
+        events,
+        focus_manager,
+        canvas,
+        &MouseTransform::Unchanged,
+        RootPlacement::sized(size),
+        Some(timers),
+    )
+}
+
+/// where the root widget is laid out
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RootPlacement {
+    /// the area the gui is laid out in, in layout coordinates
+    pub area: FRect,
+    /// given to the root widget
+    pub clipping_rect: ClippingRect,
+    pub aspect_ratio_priority: AspectRatioPreferredDirection,
+}
+
+impl RootPlacement {
+    /// the whole area of some size, not clipped
+    pub fn sized((w, h): (f32, f32)) -> Self {
+        Self {
+            area: FRect { x: 0., y: 0., w, h },
+            clipping_rect: ClippingRect::None,
+            aspect_ratio_priority: Default::default(),
+        }
+    }
+}
+
+/// updates and draws a gui, configured by builder methods. e.g. to lay out the
+/// gui in a panel beside a game's viewport, instead of the whole window.
+///
+/// a default driver is the same as update_gui and draw_gui
+pub struct GuiDriver<'a> {
+    area: Option<FRect>,
+    clipping_rect: ClippingRect,
+    aspect_ratio_priority: AspectRatioPreferredDirection,
+    mouse_transform: MouseTransform,
+    timers: Option<&'a TimerQueue<'a>>,
+    pre_draw: Vec<DrawHook<'a>>,
+    post_draw: Vec<DrawHook<'a>>,
+    /// positions from the most recent update, for the draw hooks
+    laid_out: Vec<LaidOut>,
+}
+
+impl<'a> Default for GuiDriver<'a> {
+    fn default() -> Self {
+        Self {
+            area: None,
+            clipping_rect: ClippingRect::None,
+            aspect_ratio_priority: Default::default(),
+            mouse_transform: Default::default(),
+            timers: None,
+            pre_draw: Vec::new(),
+            post_draw: Vec::new(),
+            laid_out: Vec::new(),
+        }
+    }
+}
+
+impl<'a> GuiDriver<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// lay out the gui in this area (in layout coordinates) instead of the
+    /// whole window. the area's position offsets the gui
+    pub fn area(mut self, area: FRect) -> Self {
+        self.area = Some(area);
+        self
+    }
 
+    /// the clipping rect given to the root widget, and set while drawing.
+    /// ClippingRect::None (the default) doesn't clip
+    pub fn clipping_rect(mut self, clipping_rect: ClippingRect) -> Self {
+        self.clipping_rect = clipping_rect;
+        self
+    }
+
+    /// clip to the area, so the gui doesn't draw outside of it. call after
+    /// area
+    pub fn clip_to_area(mut self) -> Self {
+        self.clipping_rect = match self.area {
+            Some(area) => match area.into() {
+                Some(rect) => ClippingRect::Some(rect),
+                None => ClippingRect::Zero,
+            },
+            None => ClippingRect::None,
+        };
+        self
+    }
+
+    /// if the width or height of the root widget (and by default, its
+    /// descendants) is figured out first
+    pub fn aspect_ratio_priority(mut self, priority: AspectRatioPreferredDirection) -> Self {
+        self.aspect_ratio_priority = priority;
+        self
+    }
+
+    /// see update_gui_with_mouse_transform
+    pub fn mouse_transform(mut self, mouse_transform: MouseTransform) -> Self {
+        self.mouse_transform = mouse_transform;
+        self
+    }
+
+    /// see update_gui_with_timers
+    pub fn timers(mut self, timers: &'a TimerQueue<'a>) -> Self {
+        self.timers = Some(timers);
+        self
+    }
+
+    /// called before the gui is drawn, e.g. to draw behind some widget. hooks
+    /// are called in the order they're added
+    pub fn pre_draw(mut self, hook: DrawHook<'a>) -> Self {
+        self.pre_draw.push(hook);
+        self
+    }
+
+    /// called after the gui (including the overlay pass) is drawn, e.g. for
+    /// a debug overlay
+    pub fn post_draw(mut self, hook: DrawHook<'a>) -> Self {
+        self.post_draw.push(hook);
+        self
+    }
+
+    /// same as update_gui
+    pub fn update_gui(
+        &mut self,
+        widget: &mut dyn Widget,
+        events: &mut [SDLEvent],
+        focus_manager: &mut FocusManager,
+        canvas: &WindowCanvas,
+    ) -> UiResult<()> {
+        let root = RootPlacement {
+            area: self
+                .area
+                .unwrap_or_else(|| RootPlacement::sized(layout_size(canvas)).area),
+            clipping_rect: self.clipping_rect,
+            aspect_ratio_priority: self.aspect_ratio_priority,
+        };
+        if self.pre_draw.is_empty() && self.post_draw.is_empty() {
+            return update_gui_at_size(
+                widget,
+                events,
+                focus_manager,
+                canvas,
+                &self.mouse_transform,
+                root,
+                self.timers,
+            );
+        }
+        let (update_result, laid_out) = recording_layout(|| {
+            update_gui_at_size(
+                widget,
+                events,
+                focus_manager,
+                canvas,
+                &self.mouse_transform,
+                root,
+                self.timers,
+            )
+        });
+        self.laid_out = laid_out;
+        update_result
+    }
+
+    /// same as draw_gui, but with the clipping rect set, and calling the draw
+    /// hooks
+    pub fn draw_gui(
+        &mut self,
+        widget: &mut dyn Widget,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let draw_result = (|| -> UiResult<()> {
+            for hook in self.pre_draw.iter_mut() {
+                canvas.set_clip_rect(self.clipping_rect);
+                hook(canvas, &self.laid_out)?;
+            }
+            canvas.set_clip_rect(self.clipping_rect);
+            widget.draw(canvas, focus_manager)?;
+            canvas.set_clip_rect(self.clipping_rect);
+            widget.draw_overlay(canvas, focus_manager)?;
+            for hook in self.post_draw.iter_mut() {
+                canvas.set_clip_rect(self.clipping_rect);
+                hook(canvas, &self.laid_out)?;
+            }
+            Ok(())
+        })();
+        canvas.set_clip_rect(ClippingRect::None); // restore
+        draw_result
+    }
+}
+
+/// update_gui, with the gui laid out in an area
+pub(crate) fn update_gui_at_size(
+    widget: &mut dyn Widget,
+    events: &mut [SDLEvent],
+    focus_manager: &mut FocusManager,
+    canvas: &WindowCanvas,
+    mouse_transform: &MouseTransform,
+    root: RootPlacement,
+    timers: Option<&TimerQueue>,
+) -> UiResult<()> {
+    let now = Instant::now();
+    set_frame_time(now);
+    if let Some(timers) = timers {
+        timers.process(now)?;
+    }
+    let window_id = canvas.window().id();
+    mouse_transform.rewrite(canvas, window_id, events);
+    let input = update_input_snapshot(events);
+    for sdl_event in events.iter() {
+        match &sdl_event.e {
+            sdl2::event::Event::Window {
+                window_id: event_window_id,
+                win_event,
+                ..
+            } if *event_window_id == window_id => {
+                dispatch_window_event(widget, win_event);
+            }
+            // the contents of textures are lost. everything must be
+            // re-rendered. not consumed; the application might also have
+            // textures to restore
+            sdl2::event::Event::RenderTargetsReset { .. }
+            | sdl2::event::Event::RenderDeviceReset { .. } => {
+                invalidate_texture_caches();
+            }
+            _ => {}
+        }
+    }
+
+    let window_area = root.area;
+    let position = place(widget, window_area, root.aspect_ratio_priority)?;
+
+    focus_manager.clear_reports();
+    record_layout(position, root.clipping_rect);
     let widget_event = WidgetUpdateEvent {
         position,
         events,
-        aspect_ratio_priority: AspectRatioPreferredDirection::default(),
+        aspect_ratio_priority: root.aspect_ratio_priority,
         focus_manager,
-        clipping_rect: ClippingRect::None,
-        window_id: canvas.window().id(),
+        clipping_rect: root.clipping_rect,
+        window_id,
+        window_area,
+        input,
     };
+    count(Counter::WidgetUpdate);
     widget.update(widget_event)?;
+    focus_manager.notify_focus_observers();
     Ok(())
 }
 
+/// while the window is being resized, the gui keeps the layout from before the
+/// resize, stretched to fill the window. once the size hasn't changed for a
+/// while, the gui is laid out again at the new size. this avoids a full layout
+/// (and re-rendering text at every intermediate size) each frame of the resize.
+///
+/// use in place of update_gui and draw_gui
+pub struct ResizeThrottle {
+    /// how long the size must stay the same before the layout catches up
+    pub settle: Duration,
+    /// the size that the gui is laid out at
+    layout_size: Option<(f32, f32)>,
+    /// the window's layout size, as of the most recent update
+    actual_size: (f32, f32),
+    /// when the actual size last changed
+    last_change: Option<Instant>,
+}
+
+impl Default for ResizeThrottle {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(150))
+    }
+}
+
+impl ResizeThrottle {
+    pub fn new(settle: Duration) -> Self {
+        Self {
+            settle,
+            layout_size: None,
+            actual_size: (0., 0.),
+            last_change: None,
+        }
+    }
+
+    /// if the layout is being stretched, waiting for the size to settle
+    pub fn resizing(&self) -> bool {
+        self.layout_size != Some(self.actual_size)
+    }
+
+    /// how much the layout is stretched to fit the window, along each axis
+    pub fn stretch(&self) -> (f32, f32) {
+        match self.layout_size {
+            Some((w, h)) if w > 0. && h > 0. => (self.actual_size.0 / w, self.actual_size.1 / h),
+            _ => (1., 1.), // guard div
+        }
+    }
+
+    /// the size to lay out at, given the current size
+    fn next_layout_size(&mut self, actual_size: (f32, f32)) -> (f32, f32) {
+        let now = Instant::now();
+        if actual_size != self.actual_size {
+            self.actual_size = actual_size;
+            self.last_change = Some(now);
+        }
+        let settled = match self.last_change {
+            Some(last_change) => now.duration_since(last_change) >= self.settle,
+            None => true,
+        };
+        match self.layout_size {
+            Some(layout_size) if layout_size != actual_size && !settled => {
+                // check again once it may have settled
+                request_redraw();
+                layout_size
+            }
+            _ => {
+                self.layout_size = Some(actual_size);
+                actual_size
+            }
+        }
+    }
+
+    /// same as update_gui
+    pub fn update_gui(
+        &mut self,
+        widget: &mut dyn Widget,
+        events: &mut [SDLEvent],
+        focus_manager: &mut FocusManager,
+        canvas: &WindowCanvas,
+    ) -> UiResult<()> {
+        let size = self.next_layout_size(layout_size(canvas));
+        let (stretch_x, stretch_y) = self.stretch();
+        let mouse_transform = if self.resizing() {
+            // from the window to the stretched layout
+            MouseTransform::Custom(Box::new(move |x, y| {
+                (
+                    (x as f32 / stretch_x).round() as i32,
+                    (y as f32 / stretch_y).round() as i32,
+                )
+            }))
+        } else {
+            MouseTransform::Unchanged
+        };
+        update_gui_at_size(
+            widget,
+            events,
+            focus_manager,
+            canvas,
+            &mouse_transform,
+            RootPlacement::sized(size),
+            None,
+        )
+    }
+
+    /// same as draw_gui, but stretched while resizing
+    pub fn draw_gui(
+        &self,
+        widget: &mut dyn Widget,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        if !self.resizing() {
+            return draw_gui(widget, canvas, focus_manager);
+        }
+        let (stretch_x, stretch_y) = self.stretch();
+        let (scale_x, scale_y) = canvas.scale();
+        canvas.set_scale(scale_x * stretch_x, scale_y * stretch_y)?;
+        let draw_result = draw_gui(widget, canvas, focus_manager);
+        canvas.set_scale(scale_x, scale_y)?; // restore
+        draw_result
+    }
+}
+
+/// call on_attach for a widget and everything it contains. should be called
+/// once when a gui is created or a widget is added to an existing gui
+pub fn attach(widget: &mut dyn Widget) {
+    widget.on_attach();
+    for child in widget.children() {
+        attach(child);
+    }
+}
+
+/// call on_detach for a widget and everything it contains, innermost first
+pub fn detach(widget: &mut dyn Widget) {
+    for child in widget.children() {
+        detach(child);
+    }
+    widget.on_detach();
+}
+
+/// give a window event to a widget and everything it contains
+pub fn dispatch_window_event(widget: &mut dyn Widget, event: &WindowEvent) {
+    widget.on_window_event(event);
+    for child in widget.children() {
+        dispatch_window_event(child, event);
+    }
+}
+
 /// given a widget's min, max lengths and fail policies, what's the widget's
 /// lengths and offset within the parent.
 ///
@@ -272,7 +920,18 @@ pub fn place(
     widget: &mut dyn Widget,
     parent: FRect,
     ratio_priority: AspectRatioPreferredDirection,
-) -> Result<FRect, String> {
+) -> UiResult<FRect> {
+    // percentages of the parent are of the parent given here
+    crate::util::length::with_parent_size((parent.w, parent.h), || {
+        place_in_parent(widget, parent, ratio_priority)
+    })
+}
+
+fn place_in_parent(
+    widget: &mut dyn Widget,
+    parent: FRect,
+    ratio_priority: AspectRatioPreferredDirection,
+) -> UiResult<FRect> {
     let (max_w, max_h) = widget.max()?;
     let (min_w, min_h) = widget.min()?;
     let (preferred_portion_w, preferred_portion_h) = widget.preferred_portion();
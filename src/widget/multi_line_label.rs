@@ -1,25 +1,60 @@
-use sdl2::{pixels::Color, rect::Rect, render::TextureCreator, video::WindowContext};
+use std::{collections::HashMap, ops::Range};
 
-use crate::util::{
-    focus::FocusManager,
-    font::MultiLineFontStyle,
-    length::{MaxLenFailPolicy, MinLenFailPolicy, PreferredPortion},
-    rect::rect_len_round, rust::CellRefOrCell,
+use sdl2::{
+    pixels::Color,
+    rect::Rect,
+    render::{ClippingRect, TextureCreator},
+    video::WindowContext,
 };
 
-use super::{Widget, WidgetUpdateEvent};
+use crate::{
+    layout::clipper::ClipScope,
+    util::{
+        clipboard::CopyOnFocus,
+        error::UiResult,
+        focus::FocusManager,
+        font::MultiLineFontStyle,
+        hit::{HitTest, HoverCache},
+        length::{MaxLenFailPolicy, MinLenFailPolicy, PreferredPortion},
+        perf::count_cache,
+        rect::rect_len_round,
+        render::{max_texture_size, record_max_texture_size, style_generation, TiledTexture},
+        rust::CellRefOrCell,
+        spellcheck::{draw_squiggle, SpellCheck},
+    },
+};
+
+use super::{single_line_label::cached_text_matches, Widget, WidgetUpdateEvent};
+
+/// a rendered paragraph (text between newlines)
+struct ParagraphCache<'sdl> {
+    pub text: String,
+    pub texture: TiledTexture<'sdl>,
+}
 
 struct MultiLineLabelCache<'sdl> {
     pub text_rendered: String,
+    /// if the text is observed, its version when rendered
+    pub version_rendered: Option<u64>,
     pub point_size: u16,
     pub wrap_width: u32,
     pub color: Color,
-    pub texture: sdl2::render::Texture<'sdl>,
+    /// see max_texture_size
+    pub max_texture_size: (u32, u32),
+    /// each paragraph is rendered separately, so a change only re-renders the
+    /// paragraphs which changed. paragraphs larger than the max texture size
+    /// are tiled
+    pub paragraphs: Vec<ParagraphCache<'sdl>>,
+    /// width and height of all paragraphs together
+    pub size: (u32, u32),
+    /// see style_generation
+    pub generation: u64,
 }
 
 /// a multiline label's sizing is flexible - it can be any size. if the
 /// width is too small, then it will wrap text. however, if the height is
 /// too large, what should happen?
+#[derive(Clone, Copy)]
 pub enum MultiLineMinHeightFailPolicy {
     /// cut off the text, to ensure it does not expand over the parent. contains
     /// a value from 0 to 1 inclusively, indicating if the text should be cut
@@ -45,7 +80,7 @@ pub struct MultiLineLabel<'sdl, 'state> {
     pub text: CellRefOrCell<'state, String>,
     /// a single line label infers an appropriate point size from the available
     /// height. this doesn't make sense for multiline text, so it's instead
-    /// stated literally
+    /// stated literally (in logical pixels, scaled by the ui scale)
     pub point_size: u16,
     pub color: Color,
 
@@ -57,6 +92,16 @@ pub struct MultiLineLabel<'sdl, 'state> {
     pub preferred_w: PreferredPortion,
     pub preferred_h: PreferredPortion,
 
+    /// if set, the label can be focused and copied with ctrl+c
+    pub copy_on_focus: Option<CopyOnFocus>,
+
+    /// if set, misspelled words are underlined
+    pub spell_check: Option<SpellCheck<'state>>,
+    /// each misspelled range and where it was drawn (a range wrapped over
+    /// several lines appears for each), as of the previous draw
+    misspellings: Vec<(Range<usize>, Rect)>,
+    hover: HoverCache,
+
     /// state stored for draw from update
     draw_pos: crate::util::rect::FRect,
 
@@ -83,9 +128,120 @@ impl<'sdl, 'state> MultiLineLabel<'sdl, 'state> {
             cache: Default::default(),
             min_h_policy: Default::default(),
             max_h_policy: Default::default(),
+            copy_on_focus: None,
+            spell_check: None,
+            misspellings: Vec::new(),
+            hover: Default::default(),
             draw_pos: Default::default(),
         }
     }
+
+    /// the misspelled range under the mouse, and where it was drawn (e.g. to
+    /// place a suggestion popup). as of the previous draw
+    pub fn misspelling_under_mouse(&self) -> Option<(Range<usize>, Rect)> {
+        let point = self.hover.position()?;
+        self.misspellings
+            .iter()
+            .find(|(_, rect)| rect.contains_point(point))
+            .cloned()
+    }
+
+    /// the point size which is actually rendered, after applying the ui scale
+    fn scaled_point_size(&self) -> u16 {
+        let point_size = crate::util::scale::scaled(self.point_size as f32).round();
+        if point_size >= u16::MAX as f32 {
+            u16::MAX
+        } else {
+            point_size as u16
+        }
+    }
+
+    /// the rendered text at a wrap width. reuses the previous render, or the
+    /// paragraphs from it which are unchanged
+    fn render(&mut self, wrap_width: u32) -> UiResult<&MultiLineLabelCache<'sdl>> {
+        let point_size = self.scaled_point_size();
+        let text = self.text.scope_take();
+        let generation = style_generation();
+        let version = self.text.version();
+        let max_size = max_texture_size();
+
+        let previous = self.cache.take().filter(|cache| {
+            cache.color == self.color
+                && cache.point_size == point_size
+                && cache.wrap_width == wrap_width
+                && cache.generation == generation
+                && cache.max_texture_size == max_size
+        });
+        let previous = match previous {
+            Some(cache)
+                if cached_text_matches(
+                    &cache.text_rendered,
+                    cache.version_rendered,
+                    text.as_str(),
+                    version,
+                ) =>
+            {
+                count_cache(true);
+                return Ok(self.cache.insert(cache));
+            }
+            Some(cache) => cache.paragraphs,
+            None => Vec::new(),
+        };
+        count_cache(false);
+
+        // if the text or the render properties have changed, then the changed
+        // paragraphs need to be re-rendered
+        let mut reusable: HashMap<String, Vec<ParagraphCache<'sdl>>> = HashMap::new();
+        for paragraph in previous.into_iter().rev() {
+            reusable
+                .entry(paragraph.text.clone())
+                .or_default()
+                .push(paragraph);
+        }
+
+        let mut paragraphs: Vec<ParagraphCache<'sdl>> = Vec::new();
+        let mut size = (0u32, 0u32);
+        for paragraph_text in text.split('\n') {
+            let paragraph = match reusable.get_mut(paragraph_text).and_then(|v| v.pop()) {
+                Some(v) => v,
+                None => {
+                    // an empty paragraph is still a blank line
+                    let render_text = if paragraph_text.is_empty() {
+                        " "
+                    } else {
+                        paragraph_text
+                    };
+                    ParagraphCache {
+                        text: paragraph_text.to_owned(),
+                        texture: self.font_interface.render_tiled(
+                            render_text,
+                            self.color,
+                            point_size,
+                            wrap_width,
+                            max_size,
+                            self.creator,
+                        )?,
+                    }
+                }
+            };
+            let (width, height) = paragraph.texture.size();
+            size.0 = size.0.max(width);
+            size.1 += height;
+            paragraphs.push(paragraph);
+        }
+
+        Ok(self.cache.insert(MultiLineLabelCache {
+            text_rendered: text.to_string(),
+            version_rendered: version,
+            point_size,
+            wrap_width,
+            color: self.color,
+            max_texture_size: max_size,
+            paragraphs,
+            size,
+            generation,
+        }))
+    }
 }
 
 impl<'sdl, 'state> Widget for MultiLineLabel<'sdl, 'state> {
@@ -114,7 +270,7 @@ impl<'sdl, 'state> Widget for MultiLineLabel<'sdl, 'state> {
         }
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         match self.min_h_policy {
             MultiLineMinHeightFailPolicy::None(_, _) => {
                 // match logic from draw, so that the same cache is used
@@ -122,52 +278,21 @@ impl<'sdl, 'state> Widget for MultiLineLabel<'sdl, 'state> {
                     Some(v) => v,
                     None => return Some(Ok(0.)), // doesn't matter
                 };
-                let text = self.text.scope_take();
                 // ok to use the same cache as draw, as once the pref_w is
                 // figured out, then that same one is used at draw as well
-                let cache = match self.cache.take().filter(|cache| {
-                    cache.text_rendered == text.as_str()
-                        && cache.color == self.color
-                        && cache.point_size == self.point_size
-                        && cache.wrap_width == pref_w
-                }) {
-                    Some(cache) => cache,
-                    None => {
-                        // if the text of the render properties have changed, then the
-                        // text needs to be re-rendered
-                        let texture = match self.font_interface.render(
-                            text.as_str(),
-                            self.color,
-                            self.point_size,
-                            pref_w,
-                            self.creator,
-                        ) {
-                            Ok(v) => v,
-                            Err(e) => return Some(Err(e)),
-                        };
-                        MultiLineLabelCache {
-                            text_rendered: text.to_string(),
-                            point_size: self.point_size,
-                            wrap_width: pref_w,
-                            color: self.color,
-                            texture,
-                        }
-                    }
-                };
-
-                let txt = &cache.texture;
-
-                let query = txt.query();
-
-                self.cache = Some(cache);
-                Some(Ok(query.height as f32))
+                Some(self.render(pref_w).map(|cache| cache.size.1 as f32))
             }
             _ => None,
         }
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
+        self.hover
+            .update(&HitTest::from_event(&event), event.events);
+        if let Some(copy_on_focus) = self.copy_on_focus.as_ref() {
+            copy_on_focus.update(&mut event, &self.text.scope_take())?;
+        }
         Ok(())
     }
 
@@ -180,99 +305,109 @@ impl<'sdl, 'state> Widget for MultiLineLabel<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         _focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
         let position: sdl2::rect::Rect = match self.draw_pos.into() {
             Some(v) => v,
             None => return Ok(()), // no input handling
         };
 
-        let text = self.text.scope_take();
+        record_max_texture_size(canvas);
+        let max_h_policy = self.max_h_policy;
+        let min_h_policy = self.min_h_policy;
+        let cache = self.render(position.width())?;
+        let height = cache.size.1;
 
-        let cache = match self.cache.take().filter(|cache| {
-            cache.text_rendered == text.as_str()
-                && cache.color == self.color
-                && cache.point_size == self.point_size
-                && cache.wrap_width == position.width()
-        }) {
-            Some(cache) => cache,
-            None => {
-                // if the text of the render properties have changed, then the
-                // text needs to be re-rendered
-                let texture = self.font_interface.render(
-                    text.as_str(),
-                    self.color,
-                    self.point_size,
-                    position.width(),
-                    self.creator,
-                )?;
-                MultiLineLabelCache {
-                    text_rendered: text.to_string(),
-                    point_size: self.point_size,
-                    wrap_width: position.width(),
-                    color: self.color,
-                    texture,
+        // where the top of the text is drawn, relative to the position
+        let offset: i32 = if height <= position.height() {
+            let excess = (position.height() - height) as f32;
+            (excess * max_h_policy.0).round() as i32
+        } else {
+            let excess = (height - position.height()) as f32;
+            match min_h_policy {
+                MultiLineMinHeightFailPolicy::CutOff(v) => -(excess * (1. - v)).round() as i32,
+                MultiLineMinHeightFailPolicy::AllowRunOff(v) => {
+                    (excess * (v.0 - 1.)).round() as i32
                 }
+                MultiLineMinHeightFailPolicy::None(_, _) => 0,
             }
         };
 
-        let txt = &cache.texture;
-
-        let query = txt.query();
-
-        if query.height <= position.height() {
-            let excess = position.height() - query.height;
-            let excess = excess as f32;
-            let excess = excess * self.max_h_policy.0;
-            let excess = excess.round() as i32;
-            canvas.copy(
-                txt,
-                None,
-                Some(Rect::new(
-                    position.x,
-                    position.y + excess,
-                    query.width,
-                    query.height,
-                )),
-            )?;
-        } else {
-            let excess = query.height - position.height();
-            let excess = excess as f32;
-            match self.min_h_policy {
-                MultiLineMinHeightFailPolicy::CutOff(v) => {
-                    let excess = excess * (1. - v);
-                    let excess = excess.round() as i32;
-                    canvas.copy(
-                        txt,
-                        Some(Rect::new(0, excess, query.width, position.height())),
-                        Some(Rect::new(
-                            position.x,
-                            position.y,
-                            query.width,
-                            position.height(),
-                        )),
-                    )?
-                }
-                MultiLineMinHeightFailPolicy::AllowRunOff(v) => {
-                    let excess = excess * (v.0 - 1.);
-                    let excess = excess.round() as i32;
-                    canvas.copy(
-                        txt,
-                        None,
-                        Some(Rect::new(
-                            position.x,
-                            position.y + excess,
-                            query.width,
-                            query.height,
-                        )),
-                    )?;
-                }
-                MultiLineMinHeightFailPolicy::None(_, _) => {
-                    canvas.copy(txt, None, self.draw_pos)?;
+        let mut canvas = match min_h_policy {
+            MultiLineMinHeightFailPolicy::CutOff(_) => ClipScope::intersect(canvas, Some(position)),
+            _ => ClipScope::unchanged(canvas),
+        };
+        // paragraphs outside of this aren't drawn
+        let visible = match canvas.clip_rect() {
+            ClippingRect::Some(rect) => Some(rect),
+            ClippingRect::Zero => return Ok(()),
+            ClippingRect::None => None,
+        };
+
+        let mut y = position.y + offset;
+        // the byte range of each paragraph, and its top relative to the text
+        let mut paragraphs: Vec<(Range<usize>, i32)> = Vec::new();
+        let mut paragraph_start = 0;
+        for paragraph in cache.paragraphs.iter() {
+            let paragraph_end = paragraph_start + paragraph.text.len();
+            paragraphs.push((paragraph_start..paragraph_end, y - position.y - offset));
+            paragraph_start = paragraph_end + 1; // newline
+            let (width, height) = paragraph.texture.size();
+            let dst = Rect::new(position.x, y, width, height);
+            y += height as i32;
+            if let Some(visible) = visible {
+                if !visible.has_intersection(dst) {
+                    continue;
                 }
             }
+            paragraph.texture.copy(&mut canvas, None, dst)?;
         }
+        let generation = cache.generation;
 
-        self.cache = Some(cache);
+        let point_size = self.scaled_point_size();
+        self.misspellings.clear();
+        let spell_check = match self.spell_check.as_mut() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let wrap_width = position.width();
+        let text = self.text.scope_take();
+        let font_interface = &mut self.font_interface;
+        let color = spell_check.color;
+        // relative to the top left of the text
+        let located =
+            spell_check.located(&text, (point_size, wrap_width, generation), |range| {
+                let mut rects = Vec::new();
+                for (paragraph, top) in paragraphs.iter() {
+                    let start = range.start.max(paragraph.start);
+                    let end = range.end.min(paragraph.end);
+                    if start >= end {
+                        continue;
+                    }
+                    let paragraph_rects = font_interface.range_rects(
+                        &text[paragraph.clone()],
+                        point_size,
+                        wrap_width,
+                        start - paragraph.start..end - paragraph.start,
+                    )?;
+                    rects.extend(paragraph_rects.into_iter().map(|mut rect| {
+                        rect.offset(0, *top);
+                        rect
+                    }));
+                }
+                Ok(rects)
+            })?;
+        for (range, rect) in located {
+            let mut rect = *rect;
+            rect.offset(position.x, position.y + offset);
+            draw_squiggle(
+                &mut canvas,
+                color,
+                rect.x(),
+                rect.right(),
+                rect.bottom() - 1,
+            )?;
+            self.misspellings.push((range.clone(), rect));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,382 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use sdl2::{
+    controller::Button, keyboard::Keycode, rect::Rect, render::TextureCreator, video::WindowContext,
+};
+
+use crate::{
+    layout::clipper::ClipScope,
+    util::{
+        animation::{request_redraw, Easing},
+        error::{UiError, UiResult},
+        focus::FocusManager,
+        length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+        rect::FRect,
+    },
+};
+
+use super::{swapper::LayerTexture, Widget, WidgetUpdateEvent};
+
+/// the stack of screens shown by a Router, by key. the top screen is shown.
+///
+/// referenced by the Router and by whatever navigates (e.g. a button's
+/// callback). changes are applied during the Router's next update (or the
+/// current one, if made from within the shown screen)
+pub struct Navigator {
+    stack: RefCell<Vec<String>>,
+}
+
+impl Navigator {
+    pub fn new(root: impl Into<String>) -> Self {
+        Self {
+            stack: RefCell::new(vec![root.into()]),
+        }
+    }
+
+    /// show a screen on top of the current one
+    pub fn push(&self, key: impl Into<String>) {
+        self.stack.borrow_mut().push(key.into());
+    }
+
+    /// go back to the previous screen. returns false if already at the root
+    pub fn pop(&self) -> bool {
+        let mut stack = self.stack.borrow_mut();
+        if stack.len() <= 1 {
+            return false;
+        }
+        stack.pop();
+        true
+    }
+
+    /// swap the current screen for another, without going deeper
+    pub fn replace(&self, key: impl Into<String>) {
+        let mut stack = self.stack.borrow_mut();
+        stack.pop();
+        stack.push(key.into());
+    }
+
+    /// go back to the root screen
+    pub fn pop_to_root(&self) {
+        self.stack.borrow_mut().truncate(1);
+    }
+
+    pub fn current(&self) -> String {
+        self.stack.borrow().last().cloned().unwrap_or_default()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.stack.borrow().len()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.depth() > 1
+    }
+}
+
+/// how a router animates between screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NavigationTransition {
+    #[default]
+    None,
+    /// crossfade between the screens
+    Fade,
+    /// pushed screens come in from the right; going back slides them out to
+    /// the right
+    Slide,
+}
+
+struct ScreenTransition {
+    /// the screen being left
+    from: String,
+    /// if going back (the stack became shallower)
+    back: bool,
+    start: Instant,
+}
+
+/// shows the top screen of a Navigator's stack (e.g. main menu → settings →
+/// keybinds).
+///
+/// screens are registered up front and kept while not shown, so each returns
+/// as it was left (including scroll positions). the focus is saved when a
+/// screen is left and restored when it's returned to.
+///
+/// the back action (escape, or B on a controller) pops the stack if nothing
+/// in the shown screen used it first. note that a focused widget uses escape
+/// to unfocus
+pub struct Router<'sdl, 'state> {
+    pub navigator: &'state Navigator,
+    pub transition: NavigationTransition,
+    pub duration: Duration,
+    pub easing: Easing,
+    /// keys which go back
+    pub back_keys: Vec<Keycode>,
+    /// controller buttons which go back
+    pub back_buttons: Vec<Button>,
+
+    screens: HashMap<String, Box<dyn Widget + 'sdl>>,
+    /// the top screen and depth as of the previous update
+    shown: Option<(String, usize)>,
+    /// the focus each screen had when it was left
+    focus: HashMap<String, Option<String>>,
+    screen_transition: Option<ScreenTransition>,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    /// state stored for draw from update
+    draw_pos: FRect,
+    /// for the outgoing and incoming screen, while transitioning
+    layers: [Option<LayerTexture<'sdl>>; 2],
+}
+
+impl<'sdl, 'state> Router<'sdl, 'state> {
+    pub fn new(navigator: &'state Navigator, creator: &'sdl TextureCreator<WindowContext>) -> Self {
+        Self {
+            navigator,
+            transition: Default::default(),
+            duration: Duration::from_millis(250),
+            easing: Default::default(),
+            back_keys: vec![Keycode::Escape, Keycode::AcBack],
+            back_buttons: vec![Button::B],
+            screens: Default::default(),
+            shown: None,
+            focus: Default::default(),
+            screen_transition: None,
+            creator,
+            draw_pos: Default::default(),
+            layers: Default::default(),
+        }
+    }
+
+    /// register a screen which can be navigated to. if the router is already
+    /// part of a gui, the screen should be attached (see attach)
+    pub fn add_screen(&mut self, key: impl Into<String>, screen: Box<dyn Widget + 'sdl>) {
+        self.screens.insert(key.into(), screen);
+    }
+
+    /// the screen at the top of the navigator's stack
+    fn current(&mut self) -> UiResult<&mut Box<dyn Widget + 'sdl>> {
+        let key = self.navigator.current();
+        self.screens
+            .get_mut(&key)
+            .ok_or_else(|| UiError::Custom(format!("no screen named \"{}\"", key)))
+    }
+
+    /// if the stack changed since the previous update, move the focus between
+    /// screens and start the transition
+    fn apply_navigation(&mut self, focus_manager: &mut FocusManager) {
+        let key = self.navigator.current();
+        let depth = self.navigator.depth();
+        let previous = match self.shown.replace((key.clone(), depth)) {
+            Some(v) => v,
+            None => return, // first update
+        };
+        if previous.0 == key {
+            return;
+        }
+        self.focus.insert(
+            previous.0.clone(),
+            focus_manager.focused().map(|v| v.to_owned()),
+        );
+        let restored = self.focus.get(&key).cloned().flatten();
+        focus_manager.set_focus(restored.as_deref());
+        self.screen_transition = match self.transition {
+            NavigationTransition::None => None,
+            _ => Some(ScreenTransition {
+                from: previous.0,
+                back: depth < previous.1,
+                start: Instant::now(),
+            }),
+        };
+    }
+
+    fn is_back(&self, e: &sdl2::event::Event) -> bool {
+        match e {
+            sdl2::event::Event::KeyDown {
+                keycode: Some(keycode),
+                repeat: false,
+                ..
+            } => self.back_keys.contains(keycode),
+            sdl2::event::Event::ControllerButtonDown { button, .. } => {
+                self.back_buttons.contains(button)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'sdl, 'state> Widget for Router<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        // all screens, so attach and detach are balanced
+        self.screens
+            .values_mut()
+            .map(|screen| screen.as_mut() as &mut dyn Widget)
+            .collect()
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        self.apply_navigation(event.focus_manager);
+        self.current()?.update(event.dup())?;
+        // the screen may have navigated
+        self.apply_navigation(event.focus_manager);
+
+        if !self.navigator.can_go_back() {
+            return Ok(());
+        }
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            if self.is_back(&sdl_event.e) {
+                sdl_event.set_consumed();
+                self.navigator.pop();
+                break;
+            }
+        }
+        self.apply_navigation(event.focus_manager);
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+        if let Ok(screen) = self.current() {
+            screen.update_adjust_position(pos_delta);
+        }
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let position: Option<Rect> = self.draw_pos.into();
+        let progress = self.screen_transition.as_ref().map(|transition| {
+            transition.start.elapsed().as_secs_f32() / self.duration.as_secs_f32()
+        });
+        let (position, progress) = match (position, progress) {
+            (Some(position), Some(progress)) if progress < 1. => {
+                (position, self.easing.apply(progress))
+            }
+            _ => {
+                // not transitioning
+                self.screen_transition = None;
+                self.layers = Default::default();
+                return self.current()?.draw(canvas, focus_manager);
+            }
+        };
+        request_redraw(); // still animating
+
+        let (from, back) = match self.screen_transition.as_ref() {
+            Some(transition) => (transition.from.clone(), transition.back),
+            None => return Ok(()),
+        };
+        let to = self.navigator.current();
+        let width = position.width() as f32;
+        // opacity and horizontal offset of the outgoing and incoming screen
+        let (from_layer, to_layer) = match (self.transition, back) {
+            (NavigationTransition::Slide, false) => {
+                ((1., -progress * width), (1., (1. - progress) * width))
+            }
+            (NavigationTransition::Slide, true) => {
+                ((1., progress * width), (1., -(1. - progress) * width))
+            }
+            _ => ((1. - progress, 0.), (progress, 0.)),
+        };
+
+        let mut canvas = ClipScope::intersect(canvas, Some(position));
+        for (layer, (key, (opacity, offset))) in
+            [(from, from_layer), (to, to_layer)].into_iter().enumerate()
+        {
+            let screen = match self.screens.get_mut(&key) {
+                Some(v) => v,
+                None => continue,
+            };
+            let mut dst = position;
+            dst.offset(offset.round() as i32, 0);
+            LayerTexture::draw(
+                &mut self.layers[layer],
+                self.creator,
+                screen.as_mut(),
+                opacity,
+                position,
+                dst,
+                &mut canvas,
+                focus_manager,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.current()?.draw_overlay(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.current()?.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.min_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.min_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.current()?.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.max_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.max_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.preferred_portion(),
+            None => (PreferredPortion::FULL, PreferredPortion::FULL),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        match self.current() {
+            Ok(screen) => screen.preferred_width_from_height(pref_h),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        match self.current() {
+            Ok(screen) => screen.preferred_height_from_width(pref_w),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match self.screens.get(&self.navigator.current()) {
+            Some(screen) => screen.preferred_link_allowed_exceed_portion(),
+            None => false,
+        }
+    }
+}
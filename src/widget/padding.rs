@@ -0,0 +1,204 @@
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rect::FRect,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// the length of one side of padding
+#[derive(Debug, Clone, Copy)]
+pub enum Inset {
+    /// in logical pixels (scaled by the ui scale)
+    Pixels(f32),
+    /// a portion (from 0 to 1) of the length given to the padding widget, in
+    /// the same direction as the side (left and right use the width)
+    Portion(f32),
+}
+
+impl Default for Inset {
+    fn default() -> Self {
+        Inset::Pixels(0.)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Insets {
+    pub left: Inset,
+    pub right: Inset,
+    pub top: Inset,
+    pub bottom: Inset,
+}
+
+impl Insets {
+    /// the same inset on every side
+    pub fn all(inset: Inset) -> Self {
+        Self {
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+        }
+    }
+
+    /// horizontal (left and right) and vertical (top and bottom) insets
+    pub fn symmetric(horizontal: Inset, vertical: Inset) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+/// the pixel and portion parts of two opposite insets, summed
+fn split(a: Inset, b: Inset) -> (f32, f32) {
+    let mut pixels = 0.;
+    let mut portion = 0.;
+    for inset in [a, b] {
+        match inset {
+            Inset::Pixels(v) => pixels += crate::util::scale::scaled(v),
+            Inset::Portion(v) => portion += v,
+        }
+    }
+    (pixels, portion)
+}
+
+/// length of the padding from the length of its contents
+fn outer_len(inner: f32, (pixels, portion): (f32, f32)) -> f32 {
+    // outer = inner + pixels + portion * outer
+    let remaining_portion = 1. - portion;
+    if remaining_portion <= 0. {
+        return inner + pixels; // insets take everything. guard div
+    }
+    (inner + pixels) / remaining_portion
+}
+
+/// length of the contents from the length of the padding
+fn inner_len(outer: f32, (pixels, portion): (f32, f32)) -> f32 {
+    (outer - pixels - portion * outer).max(0.)
+}
+
+/// one side's length, given the length of the padding in that direction
+fn side_len(inset: Inset, outer: f32) -> f32 {
+    match inset {
+        Inset::Pixels(v) => crate::util::scale::scaled(v),
+        Inset::Portion(v) => v * outer,
+    }
+}
+
+/// adds space around the contained widget. unlike a Border (with an Empty
+/// style), each side can be different, and sides can be a portion of the
+/// available length
+pub struct Padding<'sdl> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub insets: Insets,
+}
+
+impl<'sdl> Padding<'sdl> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, insets: Insets) -> Self {
+        Self { contained, insets }
+    }
+
+    fn horizontal(&self) -> (f32, f32) {
+        split(self.insets.left, self.insets.right)
+    }
+
+    fn vertical(&self) -> (f32, f32) {
+        split(self.insets.top, self.insets.bottom)
+    }
+}
+
+impl<'sdl> Widget for Padding<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        let horizontal = self.horizontal();
+        let inner_h = inner_len(pref_h, self.vertical());
+        self.contained
+            .preferred_width_from_height(inner_h)
+            .map(|some| some.map(|ok| outer_len(ok, horizontal)))
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        let vertical = self.vertical();
+        let inner_w = inner_len(pref_w, self.horizontal());
+        self.contained
+            .preferred_height_from_width(inner_w)
+            .map(|some| some.map(|ok| outer_len(ok, vertical)))
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let (w, h) = self.contained.min()?;
+        Ok((
+            MinLen(outer_len(w.0, self.horizontal())),
+            MinLen(outer_len(h.0, self.vertical())),
+        ))
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let (w, h) = self.contained.max()?;
+        let outer = |len: MaxLen, insets: (f32, f32)| {
+            if len.0 == f32::MAX {
+                MaxLen::LAX // don't overflow lax
+            } else {
+                MaxLen(outer_len(len.0, insets))
+            }
+        };
+        Ok((outer(w, self.horizontal()), outer(h, self.vertical())))
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        let left = side_len(self.insets.left, event.position.w);
+        let right = side_len(self.insets.right, event.position.w);
+        let top = side_len(self.insets.top, event.position.h);
+        let bottom = side_len(self.insets.bottom, event.position.h);
+        let position_for_child = FRect {
+            x: event.position.x + left,
+            y: event.position.y + top,
+            w: event.position.w - left - right,
+            h: event.position.h - top - bottom, // deliberately allow negative
+        };
+        self.contained.update(event.sub_event(position_for_child))
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+}
@@ -0,0 +1,201 @@
+//! styles which draw a hit shape, so the part of a button or checkbox that can
+//! be pressed matches how it looks (e.g. round buttons, or the wedges of a
+//! radial menu). set the widget's hit_shape to the same shape
+
+use sdl2::{
+    pixels::Color,
+    rect::Rect,
+    render::{Canvas, RenderTarget},
+    video::Window,
+};
+
+use crate::util::{
+    error::{UiError, UiResult},
+    hit::HitShape,
+    rect::FRect,
+};
+
+use super::{
+    button::{ButtonStyle, ButtonTextureVariant},
+    checkbox::{draw_long_press_bar, CheckBoxTextureVariant, TextureVariantStyle},
+    strut::Strut,
+    Widget,
+};
+
+/// fill the part of an area within a shape, against the center of each pixel
+pub fn fill_shape<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    shape: &HitShape,
+    area: FRect,
+    color: Color,
+) -> UiResult<()> {
+    let bounds: Option<Rect> = area.into();
+    let bounds = match bounds {
+        Some(v) => v,
+        None => return Ok(()), // nothing to fill
+    };
+    canvas.set_draw_color(color);
+    for y in bounds.top()..bounds.bottom() {
+        // a line per run of pixels within the shape
+        let mut run_start: Option<i32> = None;
+        for x in bounds.left()..=bounds.right() {
+            let inside =
+                x < bounds.right() && shape.contains(area, (x as f32 + 0.5, y as f32 + 0.5));
+            match (inside, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    canvas.fill_rect(Rect::new(start, y, (x - start) as u32, 1))?;
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// a portion of the area, with the same center
+fn inset(area: FRect, portion: f32) -> FRect {
+    let w = area.w * portion;
+    let h = area.h * portion;
+    FRect {
+        x: area.x + (area.w - w) / 2.,
+        y: area.y + (area.h - h) / 2.,
+        w,
+        h,
+    }
+}
+
+/// a button drawn as a filled shape. sizing is that of the contained (e.g. a
+/// strut)
+pub struct ShapedButtonStyle<'sdl> {
+    pub shape: HitShape,
+    pub idle: Color,
+    pub focused: Color,
+    pub pressed: Color,
+    /// drawn within the shape while a latching button is on
+    pub on: Color,
+    pub contained: Box<dyn Widget + 'sdl>,
+}
+
+impl<'sdl> ShapedButtonStyle<'sdl> {
+    /// a fixed size, in logical pixels
+    pub fn new(shape: HitShape, w: f32, h: f32) -> Self {
+        Self {
+            shape,
+            idle: Color::RGB(50, 50, 50),
+            focused: Color::RGB(118, 73, 206),
+            pressed: Color::RGB(200, 200, 200),
+            on: Color::RGB(0, 160, 0),
+            contained: Box::new(Strut::fixed(w, h)),
+        }
+    }
+}
+
+impl<'sdl> ButtonStyle<ButtonTextureVariant> for ShapedButtonStyle<'sdl> {
+    fn as_mut_widget(&mut self) -> &mut dyn Widget {
+        self.contained.as_mut()
+    }
+
+    fn as_widget(&self) -> &dyn Widget {
+        self.contained.as_ref()
+    }
+
+    fn as_mut_texture_variant_style(
+        &mut self,
+    ) -> &mut dyn TextureVariantStyle<ButtonTextureVariant> {
+        self
+    }
+}
+
+impl<'sdl> TextureVariantStyle<ButtonTextureVariant> for ShapedButtonStyle<'sdl> {
+    fn draw(&mut self, variant: ButtonTextureVariant, canvas: &mut Canvas<Window>) -> UiResult<()> {
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
+        let area = FRect {
+            x: 0.,
+            y: 0.,
+            w: size.0 as f32,
+            h: size.1 as f32,
+        };
+        let color = match variant {
+            ButtonTextureVariant::Idle | ButtonTextureVariant::IdleOn => self.idle,
+            ButtonTextureVariant::Focused | ButtonTextureVariant::FocusedOn => self.focused,
+            ButtonTextureVariant::FocusedPressed | ButtonTextureVariant::FocusedPressedOn => {
+                self.pressed
+            }
+        };
+        fill_shape(canvas, &self.shape, area, color)?;
+        if variant.on() {
+            fill_shape(canvas, &self.shape, inset(area, 0.5), self.on)?;
+        }
+        Ok(())
+    }
+
+    fn draw_long_press(
+        &mut self,
+        progress: f32,
+        canvas: &mut Canvas<Window>,
+        position: Rect,
+    ) -> UiResult<()> {
+        draw_long_press_bar(progress, self.focused, canvas, position)
+    }
+}
+
+/// a checkbox drawn as a filled shape, with a smaller shape inside while
+/// checked
+pub struct ShapedCheckBoxStyle {
+    pub shape: HitShape,
+    pub idle: Color,
+    pub focused: Color,
+    pub pressed: Color,
+    pub checked: Color,
+}
+
+impl ShapedCheckBoxStyle {
+    pub fn new(shape: HitShape) -> Self {
+        Self {
+            shape,
+            idle: Color::RGB(50, 50, 50),
+            focused: Color::RGB(118, 73, 206),
+            pressed: Color::RGB(200, 200, 200),
+            checked: Color::RGB(0, 160, 0),
+        }
+    }
+}
+
+impl TextureVariantStyle<CheckBoxTextureVariant> for ShapedCheckBoxStyle {
+    fn draw(
+        &mut self,
+        variant: CheckBoxTextureVariant,
+        canvas: &mut Canvas<Window>,
+    ) -> UiResult<()> {
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
+        let area = FRect {
+            x: 0.,
+            y: 0.,
+            w: size.0 as f32,
+            h: size.1 as f32,
+        };
+        let color = if variant.pressed() {
+            self.pressed
+        } else if variant.focused() {
+            self.focused
+        } else {
+            self.idle
+        };
+        fill_shape(canvas, &self.shape, area, color)?;
+        if variant.checked() {
+            fill_shape(canvas, &self.shape, inset(area, 0.5), self.checked)?;
+        }
+        Ok(())
+    }
+
+    fn draw_long_press(
+        &mut self,
+        progress: f32,
+        canvas: &mut Canvas<Window>,
+        position: Rect,
+    ) -> UiResult<()> {
+        draw_long_press_bar(progress, self.focused, canvas, position)
+    }
+}
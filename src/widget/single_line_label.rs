@@ -1,5 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize() {
+        // no quantization
+        assert_eq!(quantized_point_size(17, 1, Some(12)), 17);
+        // rounded down to the step
+        assert_eq!(quantized_point_size(17, 4, None), 16);
+        assert_eq!(quantized_point_size(3, 4, None), 3);
+        // kept until it grows by a full step, or shrinks below it
+        assert_eq!(quantized_point_size(19, 4, Some(16)), 16);
+        assert_eq!(quantized_point_size(18, 4, Some(15)), 15);
+        assert_eq!(quantized_point_size(19, 4, Some(15)), 16);
+        assert_eq!(quantized_point_size(15, 4, Some(16)), 12);
+    }
+}
+
+use std::rc::Rc;
+
 use sdl2::{render::TextureCreator, video::WindowContext};
 
+use crate::util::clipboard::CopyOnFocus;
+use crate::util::error::UiResult;
 use crate::util::focus::FocusManager;
 use crate::util::font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties};
 use crate::util::length::{
@@ -7,17 +30,59 @@ use crate::util::length::{
     MinLenFailPolicy, MinLenPolicy, PreferredPortion,
 };
 
+use crate::util::perf::count_cache;
+use crate::util::render::{style_generation, CanvasStateGuard, SharedTextCache};
 use crate::util::rust::CellRefOrCell;
 use crate::widget::texture::AspectRatioFailPolicy;
 
-use super::texture::texture_draw;
+use super::texture::texture_draw_rotated;
 use super::{Widget, WidgetUpdateEvent};
 
 /// caches the texture and what was used to create the texture
 pub(crate) struct SingleLineLabelCache<'sdl> {
     pub text_rendered: String,
+    /// if the text is observed, its version when rendered
+    pub version_rendered: Option<u64>,
     pub properties_rendered: TextRenderProperties,
-    pub texture: sdl2::render::Texture<'sdl>,
+    /// might be shared with other labels, see SharedTextCache
+    pub texture: Rc<crate::util::render::OwnedTexture<'sdl>>,
+    /// see style_generation
+    pub generation: u64,
+}
+
+/// is the cached text the same as the text. if the text is observed, only its
+/// version is compared
+pub(crate) fn cached_text_matches(
+    text_rendered: &str,
+    version_rendered: Option<u64>,
+    text: &str,
+    version: Option<u64>,
+) -> bool {
+    match (version_rendered, version) {
+        (Some(version_rendered), Some(version)) => version_rendered == version,
+        _ => text_rendered == text,
+    }
+}
+
+/// the point size to render at, given the exact point size which would fit.
+///
+/// the result is rounded down to a multiple of the step, so small changes in
+/// size (e.g. while resizing the window) don't each cause a re-render. the
+/// previously rendered size is kept as long as it still fits and is within a
+/// step of the exact size
+pub(crate) fn quantized_point_size(exact: u16, step: u16, previous: Option<u16>) -> u16 {
+    if step <= 1 {
+        return exact;
+    }
+    if let Some(previous) = previous {
+        if previous <= exact && exact - previous < step {
+            return previous;
+        }
+    }
+    if exact < step {
+        return exact;
+    }
+    exact - exact % step
 }
 
 /// caches size of the rendered text
@@ -45,12 +110,13 @@ pub(crate) struct SingleLineLabelSizeCache<'sdl> {
 
 impl<'sdl> SingleLineLabelSizeCache<'sdl> {
     /// might take a copy of label_font_interface it this cache doesn't already have one
-    pub fn get_size(&mut self, point_size: u16, text: &str) -> Result<(u32, u32), String> {
-        let cache = match self
+    pub fn get_size(&mut self, point_size: u16, text: &str) -> UiResult<(u32, u32)> {
+        let cache = self
             .cache
             .take()
-            .filter(|cache| cache.text_used == text && cache.point_size_used == point_size)
-        {
+            .filter(|cache| cache.text_used == text && cache.point_size_used == point_size);
+        count_cache(cache.is_some());
+        let cache = match cache {
             Some(cache) => cache, // cache is ok
             None => SingleLineLabelSizeCacheData {
                 point_size_used: point_size,
@@ -63,6 +129,53 @@ impl<'sdl> SingleLineLabelSizeCache<'sdl> {
     }
 }
 
+/// which way a label's text reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOrientation {
+    #[default]
+    Horizontal,
+    /// rotated 90 degrees counter clockwise; reads from bottom to top. e.g. a
+    /// tab on the left side
+    Up,
+    /// rotated 90 degrees clockwise; reads from top to bottom. e.g. a tab on
+    /// the right side
+    Down,
+}
+
+impl TextOrientation {
+    pub fn is_vertical(&self) -> bool {
+        *self != TextOrientation::Horizontal
+    }
+
+    /// clockwise, in degrees
+    fn angle(&self) -> f64 {
+        match self {
+            TextOrientation::Horizontal => 0.,
+            TextOrientation::Up => -90.,
+            TextOrientation::Down => 90.,
+        }
+    }
+
+    /// the policy to use for the text before it's rotated, so its alignment
+    /// is still relative to the screen
+    fn unrotated_policy(&self, policy: &AspectRatioFailPolicy) -> AspectRatioFailPolicy {
+        let unrotated = |(x, y): (f32, f32)| match self {
+            TextOrientation::Horizontal => (x, y),
+            TextOrientation::Up => (1. - y, x),
+            TextOrientation::Down => (y, 1. - x),
+        };
+        match policy {
+            AspectRatioFailPolicy::Stretch => AspectRatioFailPolicy::Stretch,
+            AspectRatioFailPolicy::ZoomOut(align) => {
+                AspectRatioFailPolicy::ZoomOut(unrotated(*align))
+            }
+            AspectRatioFailPolicy::ZoomIn(align) => {
+                AspectRatioFailPolicy::ZoomIn(unrotated(*align))
+            }
+        }
+    }
+}
+
 /// a widget that contains a single line of text.
 /// the font object and rendered font is cached - rendering only occurs when the
 /// text / style or dimensions change
@@ -73,6 +186,7 @@ pub struct SingleLineLabel<'sdl, 'state> {
 
     pub aspect_ratio_fail_policy: AspectRatioFailPolicy,
     pub request_aspect_ratio: bool,
+    pub orientation: TextOrientation,
 
     pub min_w_fail_policy: MinLenFailPolicy,
     pub max_w_fail_policy: MaxLenFailPolicy,
@@ -80,7 +194,8 @@ pub struct SingleLineLabel<'sdl, 'state> {
     pub max_h_fail_policy: MaxLenFailPolicy,
 
     // a label does it's sizing by receiving a height, and deriving what the
-    // corresponding width would be for that height
+    // corresponding width would be for that height. if the text is vertical,
+    // then it's the other way around; min_h and max_h bound the width
     pub min_h: MinLen,
     pub max_h: MaxLen,
     pub min_w_policy: MinLenPolicy,
@@ -88,10 +203,25 @@ pub struct SingleLineLabel<'sdl, 'state> {
     pub preferred_w: PreferredPortion,
     pub preferred_h: PreferredPortion,
 
+    /// the rendered point size is rounded down to a multiple of this. above 1,
+    /// the text is scaled up slightly to fill the space, but resizing doesn't
+    /// re-render for every pixel of change. see quantized_point_size
+    pub point_size_step: u16,
+    /// how many rendered sizes of the text are kept. going back to a recent
+    /// size (e.g. resizing back and forth) reuses its texture
+    pub cached_sizes: usize,
+
     creator: &'sdl TextureCreator<WindowContext>,
-    cache: Option<SingleLineLabelCache<'sdl>>,
+    /// most recently used last
+    cache: Vec<SingleLineLabelCache<'sdl>>,
     ratio_cache: SingleLineLabelSizeCache<'sdl>,
 
+    /// if set, the label can be focused and copied with ctrl+c
+    pub copy_on_focus: Option<CopyOnFocus>,
+    /// if set, the rendered text is shared with other labels using the same
+    /// cache (which must also use the same font)
+    pub shared_cache: Option<&'state SharedTextCache<'sdl>>,
+
     /// state stored for draw from update
     draw_pos: crate::util::rect::FRect,
 }
@@ -110,6 +240,9 @@ impl<'sdl, 'state> SingleLineLabel<'sdl, 'state> {
             font_interface,
             creator,
             request_aspect_ratio: true,
+            orientation: Default::default(),
+            point_size_step: 1,
+            cached_sizes: 3,
             cache: Default::default(),
             aspect_ratio_fail_policy: Default::default(),
             min_w_fail_policy: Default::default(),
@@ -126,18 +259,31 @@ impl<'sdl, 'state> SingleLineLabel<'sdl, 'state> {
             max_h: Default::default(),
             preferred_w: Default::default(),
             preferred_h: Default::default(),
+            copy_on_focus: None,
+            shared_cache: None,
             draw_pos: Default::default(),
         }
     }
 }
 
-impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
+impl<'sdl, 'state> SingleLineLabel<'sdl, 'state> {
+    /// width / height of the text, before it's rotated
+    fn text_ratio(&mut self) -> UiResult<f32> {
         let text = self.text.scope_take();
         let size = self.ratio_cache.get_size(u16::MAX, text.as_str())?;
-        let ratio = size.0 as f32 / size.1 as f32;
-        let min_w = AspectRatioPreferredDirection::width_from_height(ratio, self.min_h.0);
-        Ok((MinLen(min_w), self.min_h))
+        Ok(size.0 as f32 / size.1 as f32)
+    }
+}
+
+impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let ratio = self.text_ratio()?;
+        let min_h = self.min_h.scaled();
+        let min_w = AspectRatioPreferredDirection::width_from_height(ratio, min_h.0);
+        if self.orientation.is_vertical() {
+            return Ok((min_h, MinLen(min_w)));
+        }
+        Ok((MinLen(min_w), min_h))
     }
 
     fn min_w_fail_policy(&self) -> MinLenFailPolicy {
@@ -148,19 +294,14 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
         self.min_h_fail_policy
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        let text = self.text.take();
-        let size = match self.ratio_cache.get_size(u16::MAX, text.as_str()) {
-            Ok(size) => size,
-            Err(err) => {
-                self.text.set(text);
-                return Err(err);
-            }
-        };
-        self.text.set(text);
-        let ratio = size.0 as f32 / size.1 as f32;
-        let max_w = AspectRatioPreferredDirection::width_from_height(ratio, self.max_h.0);
-        Ok((MaxLen(max_w), self.max_h))
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let ratio = self.text_ratio()?;
+        let max_h = self.max_h.scaled();
+        let max_w = AspectRatioPreferredDirection::width_from_height(ratio, max_h.0);
+        if self.orientation.is_vertical() {
+            return Ok((max_h, MaxLen(max_w)));
+        }
+        Ok((MaxLen(max_w), max_h))
     }
 
     fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
@@ -175,45 +316,47 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
         (self.preferred_w, self.preferred_h)
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         if !self.request_aspect_ratio {
             return None;
         }
-        let text = self.text.scope_take();
-        let pref_size = match self
-            .ratio_cache
-            .get_size(u16::MAX, text.as_str())
-        {
+        let ratio = match self.text_ratio() {
             Ok(v) => v,
             Err(err) => return Some(Err(err)),
         };
-        let ratio = pref_size.0 as f32 / pref_size.1 as f32;
+        if self.orientation.is_vertical() {
+            return Some(Ok(AspectRatioPreferredDirection::height_from_width(
+                ratio, pref_h,
+            )));
+        }
         Some(Ok(AspectRatioPreferredDirection::width_from_height(
             ratio, pref_h,
         )))
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         if !self.request_aspect_ratio {
             return None;
         }
-        let text = self.text.scope_take();
-        let pref_size = match self
-            .ratio_cache
-            .get_size(u16::MAX, text.as_str())
-        {
+        let ratio = match self.text_ratio() {
             Ok(v) => v,
             Err(err) => return Some(Err(err)),
         };
-
-        let ratio = pref_size.0 as f32 / pref_size.1 as f32;
+        if self.orientation.is_vertical() {
+            return Some(Ok(AspectRatioPreferredDirection::width_from_height(
+                ratio, pref_w,
+            )));
+        }
         Some(Ok(AspectRatioPreferredDirection::height_from_width(
             ratio, pref_w,
         )))
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
+        if let Some(copy_on_focus) = self.copy_on_focus.as_ref() {
+            copy_on_focus.update(&mut event, &self.text.scope_take())?;
+        }
         Ok(())
     }
 
@@ -226,7 +369,8 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         _focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         let position: sdl2::rect::Rect = match self.draw_pos.into() {
             Some(v) => v,
             None => return Ok(()), // no input handling
@@ -234,22 +378,22 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
 
         // the point size to render isn't just the height. it's also influenced by the aspect ratio as it get crammed into the available space
 
-        let height_option_1 = position.height();
+        // the position's height and width, before the text is rotated
+        let (across, along) = if self.orientation.is_vertical() {
+            (position.width(), position.height())
+        } else {
+            (position.height(), position.width())
+        };
+
+        let height_option_1 = across;
 
-        let text = self.text.scope_take();
         let height_option_2 = {
-            let pref_size = match self
-                .ratio_cache
-                .get_size(u16::MAX, text.as_str())
-            {
-                Ok(v) => v,
-                Err(err) => return Err(err),
-            };
-            let ratio = pref_size.0 as f32 / pref_size.1 as f32;
+            let ratio = self.text_ratio()?;
             let height_from_width =
-                AspectRatioPreferredDirection::height_from_width(ratio, position.width() as f32);
+                AspectRatioPreferredDirection::height_from_width(ratio, along as f32);
             height_from_width.ceil() as u32
         };
+        let text = self.text.scope_take();
 
         let height_to_use = height_option_1.min(height_option_2);
 
@@ -257,6 +401,12 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
             Ok(v) => v,
             Err(_) => u16::MAX,
         };
+        let previous_point_size = self
+            .cache
+            .last()
+            .map(|cache| cache.properties_rendered.point_size);
+        let point_size =
+            quantized_point_size(point_size, self.point_size_step, previous_point_size);
 
         let properties = TextRenderProperties {
             point_size,
@@ -270,35 +420,73 @@ impl<'sdl, 'state> Widget for SingleLineLabel<'sdl, 'state> {
             canvas.fill_rect(position)?;
         }
 
-        let cache = match self.cache.take().filter(|cache| {
-            cache.text_rendered == text.as_str()
-                && cache.properties_rendered == properties
-        }) {
+        let generation = style_generation();
+        let version = self.text.version();
+        // renders of other text or styles won't be used again
+        self.cache.retain(|cache| {
+            cached_text_matches(
+                &cache.text_rendered,
+                cache.version_rendered,
+                text.as_str(),
+                version,
+            ) && cache.properties_rendered.render_type == properties.render_type
+                && cache.generation == generation
+        });
+        let cache = self
+            .cache
+            .iter()
+            .position(|cache| cache.properties_rendered == properties)
+            .map(|i| self.cache.remove(i));
+        count_cache(cache.is_some());
+        let cache = match cache {
             Some(cache) => cache,
             None => {
                 // if the text of the render properties have changed, then the
                 // text needs to be re-rendered
-                let texture =
-                    self.font_interface
-                        .render(text.as_str(), &properties, self.creator)?;
+                let font_interface = &mut self.font_interface;
+                let creator = self.creator;
+                let mut render = || font_interface.render(text.as_str(), &properties, creator);
+                let texture = match self.shared_cache {
+                    Some(shared_cache) => {
+                        shared_cache.get_or_render(text.as_str(), &properties, render)?
+                    }
+                    None => Rc::new(render()?),
+                };
                 SingleLineLabelCache {
                     text_rendered: text.to_string(),
+                    version_rendered: version,
                     texture,
                     properties_rendered: properties,
+                    generation,
                 }
             }
         };
 
+        // where the text is drawn before it's rotated about its center
+        let mut unrotated_pos = self.draw_pos;
+        if self.orientation.is_vertical() {
+            unrotated_pos.x += (self.draw_pos.w - self.draw_pos.h) / 2.;
+            unrotated_pos.y += (self.draw_pos.h - self.draw_pos.w) / 2.;
+            unrotated_pos.w = self.draw_pos.h;
+            unrotated_pos.h = self.draw_pos.w;
+        }
+
+        let policy = self
+            .orientation
+            .unrotated_policy(&self.aspect_ratio_fail_policy);
         let txt = &cache.texture;
-        let r = texture_draw(
+        let r = texture_draw_rotated(
             txt,
-            &self.aspect_ratio_fail_policy,
-            canvas,
+            &policy,
+            &mut canvas,
             None,
-            self.draw_pos,
+            unrotated_pos,
+            self.orientation.angle(),
         );
 
-        self.cache = Some(cache);
+        self.cache.push(cache);
+        let excess = self.cache.len().saturating_sub(self.cached_sizes.max(1));
+        self.cache.drain(..excess);
         r?;
 
         Ok(())
@@ -1,20 +1,40 @@
-use std::cell::Cell;
+use std::{borrow::Cow, cell::Cell, ops::Range, rc::Rc};
 
 use sdl2::{
     keyboard::{Keycode, Mod},
+    mouse::SystemCursor,
     pixels::{Color, PixelFormatEnum},
     rect::Point,
-    render::{Canvas, Texture, TextureCreator},
+    render::{Canvas, TextureCreator},
     video::{Window, WindowContext},
 };
 
-use crate::util::{
-    focus::{DefaultFocusBehaviorArg, FocusID, FocusManager},
-    font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties},
-    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion}, rust::CellRefOrCell,
+use crate::{
+    layout::clipper::ClipScope,
+    util::{
+        accessibility::{AccessibleDescription, AccessibleRole},
+        clipboard::single_line,
+        cursor::SystemCursorCache,
+        error::{UiError, UiResult},
+        feedback::UiFeedback,
+        focus::{DefaultFocusBehaviorArg, FocusID, FocusManager},
+        font::{SingleLineFontStyle, SingleLineTextRenderType, TextRenderProperties},
+        history::{EditHistory, EditKind, InputHistory},
+        hit::{HitShape, HitTest, HoverCache},
+        length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+        mask::InputMask,
+        render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+        rust::CellRefOrCell,
+        spellcheck::{draw_squiggle, range_at, SpellCheck},
+        timer::Debounce,
+        word::WordBoundaries,
+    },
 };
 
-use super::{single_line_label::SingleLineLabelCache, Widget, WidgetUpdateEvent};
+use super::{
+    single_line_label::{cached_text_matches, SingleLineLabelCache},
+    Widget, WidgetUpdateEvent,
+};
 
 pub trait SingleLineTextEditStyle {
     /// The texture will be redrawn only if the target dimensions change.
@@ -26,7 +46,7 @@ pub trait SingleLineTextEditStyle {
         text: &str,
         canvas: &mut Canvas<Window>,
         caret_position: f32,
-    ) -> Result<(), String>;
+    ) -> UiResult<()>;
 }
 
 /// a default provided single line text edit style
@@ -41,10 +61,10 @@ impl SingleLineTextEditStyle for DefaultSingleLineEditStyle {
         text: &str,
         canvas: &mut Canvas<Window>,
         caret_position: f32,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
         let _text = text; // todo!
 
-        let size = canvas.output_size().map_err(|e| e.to_string())?;
+        let size = canvas.output_size().map_err(UiError::Sdl)?;
 
         let amount_inward = 5i32;
 
@@ -126,9 +146,11 @@ impl SingleLineTextEditStyle for DefaultSingleLineEditStyle {
 
 /// A cache for managing and reusing textures based on size and text
 struct TextureVariantSizeCache<'sdl> {
-    pub cache: Option<sdl2::render::Texture<'sdl>>,
+    pub cache: Option<OwnedTexture<'sdl>>,
     /// if this changes, the cache needs to be recomputed
     pub text_used: String,
+    /// the style generation that the cache was rendered with
+    pub generation: u64,
 }
 
 impl<'sdl> Default for TextureVariantSizeCache<'sdl> {
@@ -136,6 +158,7 @@ impl<'sdl> Default for TextureVariantSizeCache<'sdl> {
         Self {
             cache: None,
             text_used: "".into(),
+            generation: style_generation(),
         }
     }
 }
@@ -154,10 +177,11 @@ impl<'sdl> TextureVariantSizeCache<'sdl> {
         creator: &'sdl TextureCreator<WindowContext>,
         canvas: &mut Canvas<Window>,
         caret_position: f32,
-    ) -> Result<&'_ Texture<'sdl>, String> {
+    ) -> UiResult<&'_ OwnedTexture<'sdl>> {
+        let generation = style_generation();
         let cache = match self.cache.take().filter(|cache| {
             let q = cache.query();
-            (q.width, q.height) == size && self.text_used == text
+            (q.width, q.height) == size && self.text_used == text && self.generation == generation
         }) {
             Some(cache) => cache, // reuse cache
             None => {
@@ -165,23 +189,22 @@ impl<'sdl> TextureVariantSizeCache<'sdl> {
                 // either way, needs re-render
                 let mut texture = creator
                     .create_texture_target(PixelFormatEnum::ARGB8888, size.0, size.1)
-                    .map_err(|e| e.to_string())?;
+                    .map(owned_texture)?;
                 texture.set_blend_mode(sdl2::render::BlendMode::Blend);
 
-                let mut e_out: Option<String> = None;
-                canvas
-                    .with_texture_canvas(&mut texture, |canvas| {
-                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                        canvas.clear(); // required to prevent flickering
+                let mut e_out: Option<UiError> = None;
+                canvas.with_texture_canvas(&mut texture, |canvas| {
+                    canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                    canvas.clear(); // required to prevent flickering
 
-                        e_out = style.draw(focused, &text, canvas, caret_position).err();
-                    })
-                    .map_err(|e| e.to_string())?;
+                    e_out = style.draw(focused, &text, canvas, caret_position).err();
+                })?;
 
                 if let Some(e) = e_out {
                     return Err(e);
                 }
                 self.text_used = text.to_owned();
+                self.generation = generation;
                 texture
             }
         };
@@ -202,14 +225,45 @@ pub enum SingleLineTextInputSoundVariant {
 pub struct EmptySingleLineTextInputSoundStyle {}
 
 impl SingleLineTextInputSoundStyle for EmptySingleLineTextInputSoundStyle {
-    fn play_sound(&mut self, _which: SingleLineTextInputSoundVariant) -> Result<(), String> {
+    fn play_sound(&mut self, _which: SingleLineTextInputSoundVariant) -> UiResult<()> {
         // nothing
         Ok(())
     }
+
+    fn is_silent(&self) -> bool {
+        true
+    }
 }
 
 pub trait SingleLineTextInputSoundStyle {
-    fn play_sound(&mut self, which: SingleLineTextInputSoundVariant) -> Result<(), String>;
+    fn play_sound(&mut self, which: SingleLineTextInputSoundVariant) -> UiResult<()>;
+
+    /// a silent style leaves the feedback to the focus manager's feedback
+    /// sink. see util::feedback
+    fn is_silent(&self) -> bool {
+        false
+    }
+}
+
+/// play a text input's sound, or give the feedback to the focus manager if
+/// its style is silent
+fn play_text_input_sound(
+    sounds: &mut dyn SingleLineTextInputSoundStyle,
+    focus_manager: &mut FocusManager,
+    which: SingleLineTextInputSoundVariant,
+) -> UiResult<()> {
+    if !sounds.is_silent() {
+        return sounds.play_sound(which);
+    }
+    let feedback = match which {
+        SingleLineTextInputSoundVariant::Focus => UiFeedback::Focus,
+        SingleLineTextInputSoundVariant::Enter => UiFeedback::Activate,
+        // typing isn't given as feedback
+        SingleLineTextInputSoundVariant::TextAdded
+        | SingleLineTextInputSoundVariant::TextRemoved => return Ok(()),
+    };
+    focus_manager.feedback(feedback);
+    Ok(())
 }
 
 #[cfg(feature = "sdl2-mixer")]
@@ -224,7 +278,7 @@ pub struct DefaultSingleLineTextInputSoundStyle<'sdl> {
 
 #[cfg(feature = "sdl2-mixer")]
 impl<'sdl> SingleLineTextInputSoundStyle for DefaultSingleLineTextInputSoundStyle<'sdl> {
-    fn play_sound(&mut self, which: SingleLineTextInputSoundVariant) -> Result<(), String> {
+    fn play_sound(&mut self, which: SingleLineTextInputSoundVariant) -> UiResult<()> {
         let maybe_sound_path: Option<&std::path::Path> = match which {
             SingleLineTextInputSoundVariant::Focus => self.focus_sound_path,
             SingleLineTextInputSoundVariant::TextAdded => self.text_added_sound_path,
@@ -240,7 +294,11 @@ impl<'sdl> SingleLineTextInputSoundStyle for DefaultSingleLineTextInputSoundStyl
         let manager = match maybe_manager.as_mut() {
             Some(v) => v,
             // should never error, as it will always be returned to the cell
-            None => return Err("couldn't reference sound manager".to_owned()),
+            None => {
+                return Err(UiError::Custom(
+                    "couldn't reference sound manager".to_owned(),
+                ))
+            }
         };
         let maybe_r = manager.get(sound_path);
         self.sound_manager.set(maybe_manager);
@@ -251,10 +309,36 @@ impl<'sdl> SingleLineTextInputSoundStyle for DefaultSingleLineTextInputSoundStyl
     }
 }
 
+fn ctrl_held(keymod: Mod) -> bool {
+    keymod.contains(Mod::LCTRLMOD) || keymod.contains(Mod::RCTRLMOD)
+}
+
+fn shift_held(keymod: Mod) -> bool {
+    keymod.contains(Mod::LSHIFTMOD) || keymod.contains(Mod::RSHIFTMOD)
+}
+
+/// the byte index of the caret in the text. none, or an index which is no
+/// longer valid (the text was changed elsewhere), is the end of the text
+fn caret_index(caret: Option<usize>, text: &str) -> usize {
+    match caret {
+        Some(caret) if caret < text.len() && text.is_char_boundary(caret) => caret,
+        _ => text.len(),
+    }
+}
+
+/// the stored caret for an index. at the end is stored as none, so the caret
+/// stays at the end as the text changes elsewhere
+fn caret_after(index: usize, text: &str) -> Option<usize> {
+    Some(index).filter(|index| *index < text.len())
+}
+
 /// contains a single line label which is editable
 pub struct SingleLineTextInput<'sdl, 'state> {
     /// what happens when return key pressed
-    pub functionality: Box<dyn FnMut() -> Result<(), String> + 'state>,
+    pub functionality: Box<dyn FnMut() -> UiResult<()> + 'state>,
+    /// keys which trigger the functionality when released. space is not
+    /// included by default, since it's typed into the text
+    pub activation_keys: Vec<Keycode>,
 
     pub focus_id: FocusID,
     /// internal state for sound
@@ -271,8 +355,41 @@ pub struct SingleLineTextInput<'sdl, 'state> {
     not_focused: TextureVariantSizeCache<'sdl>,
 
     pub text: CellRefOrCell<'state, String>,
+    /// byte index of the caret, moved with the left and right arrows. none is
+    /// at the end of the text. with a mask, the caret is always at the end
+    caret: Option<usize>,
+    /// where ctrl+left / ctrl+right move the caret to, and what ctrl+backspace
+    /// / ctrl+delete remove
+    pub word_boundaries: WordBoundaries,
+    /// ctrl+z to undo, ctrl+y or ctrl+shift+z to redo. consecutive typing is
+    /// undone together
+    pub edit_history: EditHistory,
+    /// if set, submitted text is recorded, and can be recalled with the up and
+    /// down arrows (e.g. for a console or chat input)
+    pub input_history: Option<InputHistory>,
+    /// from VideoSubsystem::clipboard. if set, ctrl+v pastes (as a single
+    /// line) and ctrl+c copies the text
+    pub clipboard: Option<sdl2::clipboard::ClipboardUtil>,
     pub text_properties: SingleLineTextRenderType,
+    /// what the text input is called, for screen readers
+    pub accessible_label: String,
+    /// the mouse cursor shown while the text input is hovered. None leaves the
+    /// cursor as is
+    pub hover_cursor: Option<SystemCursor>,
+    hover: HoverCache,
+    cursor_cache: SystemCursorCache,
     font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+    /// if set, misspelled words are underlined
+    pub spell_check: Option<SpellCheck<'state>>,
+    /// each misspelled range and where it was drawn, as of the previous draw
+    misspellings: Vec<(Range<usize>, sdl2::rect::Rect)>,
+    /// if set, the text is restricted to a pattern (e.g. a date). typed and
+    /// pasted text is fit to the mask, and the remaining slots are shown as
+    /// placeholders
+    pub mask: Option<InputMask>,
+    /// if set, notified once the text has stopped being edited for a while
+    /// (e.g. to search as the user types, without searching on every key)
+    pub on_change: Option<Debounce<'state, 'sdl>>,
 
     pub min_h: MinLen,
     pub max_h: MaxLen,
@@ -290,7 +407,7 @@ pub struct SingleLineTextInput<'sdl, 'state> {
 
 impl<'sdl, 'state> SingleLineTextInput<'sdl, 'state> {
     pub fn new(
-        functionality: Box<dyn FnMut() -> Result<(), String> + 'state>,
+        functionality: Box<dyn FnMut() -> UiResult<()> + 'state>,
         style: Box<dyn SingleLineTextEditStyle + 'sdl>,
         sounds: Box<dyn SingleLineTextInputSoundStyle + 'sdl>,
         focus_id: FocusID,
@@ -301,6 +418,7 @@ impl<'sdl, 'state> SingleLineTextInput<'sdl, 'state> {
     ) -> Self {
         Self {
             functionality,
+            activation_keys: vec![Keycode::Return, Keycode::KpEnter],
             style,
             sounds,
             focused: Default::default(),
@@ -309,8 +427,21 @@ impl<'sdl, 'state> SingleLineTextInput<'sdl, 'state> {
             focused_previous_frame: false,
             previous_text_input_timestamp: 0,
             text,
+            caret: None,
+            word_boundaries: Default::default(),
+            edit_history: Default::default(),
+            input_history: None,
+            clipboard: None,
             text_properties,
+            accessible_label: String::new(),
+            hover_cursor: Some(SystemCursor::IBeam),
+            hover: Default::default(),
+            cursor_cache: Default::default(),
             font_interface,
+            spell_check: None,
+            misspellings: Vec::new(),
+            mask: None,
+            on_change: None,
             creator,
             cache: None,
             min_h: Default::default(),
@@ -322,23 +453,35 @@ impl<'sdl, 'state> SingleLineTextInput<'sdl, 'state> {
             draw_pos: Default::default(),
         }
     }
+
+    /// if every slot of the mask is filled. true if there's no mask
+    pub fn is_complete(&self) -> bool {
+        match self.mask.as_ref() {
+            Some(mask) => mask.is_complete(&self.text.scope_take()),
+            None => true,
+        }
+    }
+
+    /// the misspelled range which the caret is on, and where it was drawn
+    /// (e.g. to place a suggestion popup). as of the previous draw
+    pub fn misspelling_at_caret(&self) -> Option<(Range<usize>, sdl2::rect::Rect)> {
+        let ranges: Vec<Range<usize>> = self.misspellings.iter().map(|m| m.0.clone()).collect();
+        let range = range_at(&ranges, caret_index(self.caret, &self.text.scope_take()))?;
+        self.misspellings.iter().find(|m| m.0 == range).cloned()
+    }
 }
 
 impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
-    fn min(
-        &mut self,
-    ) -> Result<(crate::util::length::MinLen, crate::util::length::MinLen), String> {
-        Ok((MinLen::LAX, self.min_h))
+    fn min(&mut self) -> UiResult<(crate::util::length::MinLen, crate::util::length::MinLen)> {
+        Ok((MinLen::LAX, self.min_h.scaled()))
     }
 
     fn min_h_fail_policy(&self) -> crate::util::length::MinLenFailPolicy {
         self.min_h_fail_policy
     }
 
-    fn max(
-        &mut self,
-    ) -> Result<(crate::util::length::MaxLen, crate::util::length::MaxLen), String> {
-        Ok((MaxLen::LAX, self.max_h))
+    fn max(&mut self) -> UiResult<(crate::util::length::MaxLen, crate::util::length::MaxLen)> {
+        Ok((MaxLen::LAX, self.max_h.scaled()))
     }
 
     fn max_h_fail_policy(&self) -> crate::util::length::MaxLenFailPolicy {
@@ -354,8 +497,13 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
         (self.preferred_w, self.preferred_h)
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
+        event.focus_manager.report_rect(&self.focus_id, event.position);
+        self.hover
+            .update(&HitTest::from_event(&event), event.events);
+        self.cursor_cache
+            .set_if(self.hover_cursor.filter(|_| self.hover.hovered()));
 
         // keys:
         // - only applicable if currently focused
@@ -367,10 +515,19 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
         if previously_focused && !self.focused_previous_frame {
             // detect if focus was sent to this widget for any reason by
             // something else since the last time it was updated
-            self.sounds
-                .play_sound(SingleLineTextInputSoundVariant::Focus)?;
+            play_text_input_sound(
+                self.sounds.as_mut(),
+                event.focus_manager,
+                SingleLineTextInputSoundVariant::Focus,
+            )?;
         }
 
+        // compared after, to notify on_change
+        let text_before = self
+            .on_change
+            .as_ref()
+            .map(|_| self.text.scope_take().clone());
+
         for sdl_event in event.events.iter_mut().filter(|event| event.available()) {
             FocusManager::default_widget_focus_behavior(
                 &self.focus_id,
@@ -380,6 +537,8 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                     event: sdl_event,
                     clipping_rect: event.clipping_rect,
                     window_id: event.window_id,
+                    hit_expansion: 0.,
+                    hit_shape: &HitShape::Rect,
                 },
             );
 
@@ -392,8 +551,11 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
 
             if !previously_focused {
                 previously_focused = true;
-                self.sounds
-                    .play_sound(SingleLineTextInputSoundVariant::Focus)?;
+                play_text_input_sound(
+                    self.sounds.as_mut(),
+                    event.focus_manager,
+                    SingleLineTextInputSoundVariant::Focus,
+                )?;
             }
 
             if sdl_event.consumed() {
@@ -402,23 +564,28 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
 
             static SOUND_LIMITER: u32 = 50; // too frequent sounds bad
 
-            let (consume_event, maybe_err): (bool, Option<String>) = (|| {
+            let (consume_event, maybe_err): (bool, Option<UiError>) = (|| {
                 match &mut sdl_event.e {
                     // if enter key is released and this widget has focus then trigger the functionality
                     sdl2::event::Event::KeyUp {
                         repeat,
-                        keycode: Some(Keycode::Return),
+                        keycode: Some(keycode),
                         ..
-                    } => {
+                    } if self.activation_keys.contains(keycode) => {
                         if *repeat {
                             return (true, None);
                         }
+                        self.edit_history.break_group();
+                        if let Some(input_history) = self.input_history.as_mut() {
+                            input_history.submit(&self.text.scope_take());
+                        }
                         // generally, try to play the sound before the
                         // functionality happens
-                        if let Err(err) = self
-                            .sounds
-                            .play_sound(SingleLineTextInputSoundVariant::Enter)
-                        {
+                        if let Err(err) = play_text_input_sound(
+                            self.sounds.as_mut(),
+                            event.focus_manager,
+                            SingleLineTextInputSoundVariant::Enter,
+                        ) {
                             return (true, Some(err));
                         }
 
@@ -427,7 +594,8 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                             Err(e) => (true, Some(e)),
                         }
                     }
-                    // if backspace is pressed then pop the last character
+                    // if backspace is pressed then remove the character before
+                    // the caret
                     sdl2::event::Event::KeyDown {
                         keycode: Some(Keycode::Backspace),
                         keymod,
@@ -435,7 +603,11 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                         ..
                     } => {
                         let mut text = self.text.scope_take();
-                        if !text.is_empty()
+                        let caret = match self.mask {
+                            Some(_) => text.len(),
+                            None => caret_index(self.caret, &text),
+                        };
+                        if caret != 0
                             && timestamp
                                 .checked_sub(self.previous_text_input_timestamp)
                                 .unwrap_or(SOUND_LIMITER)
@@ -449,10 +621,200 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                                 return (true, Some(err));
                             }
                         }
-                        if keymod.contains(Mod::LCTRLMOD) || keymod.contains(Mod::RCTRLMOD) {
-                            text.clear();
+                        if caret == 0 {
+                            return (true, None);
+                        }
+                        match self.mask.as_ref() {
+                            Some(_) if ctrl_held(*keymod) => {
+                                self.edit_history.record(&text, EditKind::Other, *timestamp);
+                                text.clear();
+                            }
+                            Some(mask) => {
+                                self.edit_history
+                                    .record(&text, EditKind::Removing, *timestamp);
+                                mask.pop(&mut text);
+                            }
+                            None => {
+                                let start = if ctrl_held(*keymod) {
+                                    self.edit_history.record(&text, EditKind::Other, *timestamp);
+                                    self.word_boundaries.previous(&text, caret)
+                                } else {
+                                    self.edit_history
+                                        .record(&text, EditKind::Removing, *timestamp);
+                                    // an emoji sequence is removed as a whole
+                                    let mut before = text[..caret].to_owned();
+                                    crate::util::emoji::pop_char(&mut before);
+                                    before.len()
+                                };
+                                text.replace_range(start..caret, "");
+                                self.caret = caret_after(start, &text);
+                            }
+                        }
+                        (true, None)
+                    }
+                    // if delete is pressed then remove the character after the
+                    // caret
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::Delete),
+                        keymod,
+                        timestamp,
+                        ..
+                    } if self.mask.is_none() => {
+                        let mut text = self.text.scope_take();
+                        let caret = caret_index(self.caret, &text);
+                        let end = match text[caret..].chars().next() {
+                            None => return (true, None), // at the end
+                            Some(_) if ctrl_held(*keymod) => {
+                                self.word_boundaries.next(&text, caret)
+                            }
+                            Some(c) => caret + c.len_utf8(),
+                        };
+                        if timestamp
+                            .checked_sub(self.previous_text_input_timestamp)
+                            .unwrap_or(SOUND_LIMITER)
+                            >= SOUND_LIMITER
+                        {
+                            self.previous_text_input_timestamp = *timestamp;
+                            if let Err(err) = self
+                                .sounds
+                                .play_sound(SingleLineTextInputSoundVariant::TextRemoved)
+                            {
+                                return (true, Some(err));
+                            }
+                        }
+                        let kind = if ctrl_held(*keymod) {
+                            EditKind::Other
                         } else {
-                            text.pop();
+                            EditKind::Removing
+                        };
+                        self.edit_history.record(&text, kind, *timestamp);
+                        text.replace_range(caret..end, "");
+                        self.caret = caret_after(caret, &text);
+                        (true, None)
+                    }
+                    // move the caret by a character, or by a word with ctrl
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(keycode @ (Keycode::Left | Keycode::Right)),
+                        keymod,
+                        ..
+                    } if self.mask.is_none() => {
+                        let text = self.text.scope_take();
+                        let caret = caret_index(self.caret, &text);
+                        let moved = match (*keycode == Keycode::Left, ctrl_held(*keymod)) {
+                            (true, true) => self.word_boundaries.previous(&text, caret),
+                            (true, false) => text[..caret]
+                                .char_indices()
+                                .next_back()
+                                .map(|(i, _)| i)
+                                .unwrap_or(caret),
+                            (false, true) => self.word_boundaries.next(&text, caret),
+                            (false, false) => text[caret..]
+                                .chars()
+                                .next()
+                                .map(|c| caret + c.len_utf8())
+                                .unwrap_or(caret),
+                        };
+                        if moved == caret {
+                            // already at the start or end. not consumed, so it
+                            // can still move the focus
+                            return (false, None);
+                        }
+                        self.edit_history.break_group();
+                        self.caret = caret_after(moved, &text);
+                        (true, None)
+                    }
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::Z),
+                        keymod,
+                        ..
+                    } if ctrl_held(*keymod) && !shift_held(*keymod) => {
+                        let mut text = self.text.scope_take();
+                        if let Some(previous) = self.edit_history.undo(&text) {
+                            *text = previous;
+                            self.caret = None;
+                        }
+                        (true, None)
+                    }
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::Y),
+                        keymod,
+                        ..
+                    }
+                    | sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::Z),
+                        keymod,
+                        ..
+                    } if ctrl_held(*keymod) => {
+                        let mut text = self.text.scope_take();
+                        if let Some(next) = self.edit_history.redo(&text) {
+                            *text = next;
+                            self.caret = None;
+                        }
+                        (true, None)
+                    }
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::V),
+                        keymod,
+                        timestamp,
+                        ..
+                    } if self.clipboard.is_some() && ctrl_held(*keymod) => {
+                        let pasted = match self.clipboard.as_ref().map(|c| c.clipboard_text()) {
+                            Some(Ok(v)) => single_line(&v),
+                            Some(Err(e)) => return (true, Some(UiError::Sdl(e))),
+                            None => return (true, None),
+                        };
+                        if pasted.is_empty() {
+                            return (true, None);
+                        }
+                        if let Err(err) = self
+                            .sounds
+                            .play_sound(SingleLineTextInputSoundVariant::TextAdded)
+                        {
+                            return (true, Some(err));
+                        }
+                        let mut text = self.text.scope_take();
+                        self.edit_history.record(&text, EditKind::Other, *timestamp);
+                        match self.mask.as_ref() {
+                            Some(mask) => {
+                                mask.insert(&mut text, &pasted);
+                            }
+                            None => {
+                                let caret = caret_index(self.caret, &text);
+                                text.insert_str(caret, &pasted);
+                                self.caret = self.caret.map(|_| caret + pasted.len());
+                            }
+                        }
+                        (true, None)
+                    }
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(Keycode::C),
+                        keymod,
+                        ..
+                    } if self.clipboard.is_some() && ctrl_held(*keymod) => {
+                        let text = self.text.scope_take();
+                        match self.clipboard.as_ref().map(|c| c.set_clipboard_text(&text)) {
+                            Some(Err(e)) => (true, Some(UiError::Sdl(e))),
+                            _ => (true, None),
+                        }
+                    }
+                    // recall previously submitted entries
+                    sdl2::event::Event::KeyDown {
+                        keycode: Some(keycode @ (Keycode::Up | Keycode::Down)),
+                        timestamp,
+                        ..
+                    } if self.input_history.is_some() => {
+                        let mut text = self.text.scope_take();
+                        let entry = match self.input_history.as_mut() {
+                            Some(input_history) if *keycode == Keycode::Up => {
+                                input_history.older(&text)
+                            }
+                            Some(input_history) => input_history.newer(),
+                            None => None,
+                        };
+                        if let Some(entry) = entry {
+                            self.edit_history.record(&text, EditKind::Other, *timestamp);
+                            *text = entry;
+                            self.caret = None;
                         }
                         (true, None)
                     }
@@ -476,7 +838,18 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                         }
 
                         let mut content = self.text.scope_take();
-                        *content += text;
+                        self.edit_history
+                            .record(&content, EditKind::Typing, *timestamp);
+                        match self.mask.as_ref() {
+                            Some(mask) => {
+                                mask.insert(&mut content, text);
+                            }
+                            None => {
+                                let caret = caret_index(self.caret, &content);
+                                content.insert_str(caret, text);
+                                self.caret = self.caret.map(|_| caret + text.len());
+                            }
+                        }
                         (true, None)
                     }
                     _ => {
@@ -496,11 +869,30 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
             }
         }
 
+        if let (Some(on_change), Some(text_before)) = (self.on_change.as_mut(), text_before) {
+            if *self.text.scope_take() != text_before {
+                on_change.changed();
+            }
+        }
+
         self.focused_previous_frame = event.focus_manager.is_focused(&self.focus_id);
+        event
+            .focus_manager
+            .report_description(&self.focus_id, self.accessible_description());
 
         Ok(())
     }
 
+    fn accessible_description(&self) -> Option<AccessibleDescription> {
+        let text = self.text.scope_take();
+        Some(AccessibleDescription {
+            role: AccessibleRole::TextInput,
+            label: self.accessible_label.clone(),
+            value: Some(text.clone()),
+            state: Default::default(),
+        })
+    }
+
     fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
         self.draw_pos.x += pos_delta.0 as f32;
         self.draw_pos.y += pos_delta.1 as f32;
@@ -510,7 +902,8 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
         let position: sdl2::rect::Rect = match self.draw_pos.into() {
             Some(v) => v,
             None => return Ok(()),
@@ -534,22 +927,37 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
         }
 
         let text = self.text.scope_take();
+        // with a mask, the remaining slots are shown as placeholders
+        let shown: Cow<str> = match self.mask.as_ref() {
+            Some(mask) => Cow::Owned(mask.display(&text)),
+            None => Cow::Borrowed(text.as_str()),
+        };
 
+        let generation = style_generation();
+        // the mask could have changed, so compare what's shown instead
+        let version = self.text.version().filter(|_| self.mask.is_none());
         let cache = match self.cache.take().filter(|cache| {
-            cache.text_rendered == text.as_str()
-                && cache.properties_rendered == properties
+            cached_text_matches(
+                &cache.text_rendered,
+                cache.version_rendered,
+                &shown,
+                version,
+            ) && cache.properties_rendered == properties
+                && cache.generation == generation
         }) {
             Some(cache) => cache,
             None => {
                 // if the text of the render properties have changed, then the
                 // text needs to be re-rendered
-                let texture =
-                    self.font_interface
-                        .render(text.as_str(), &properties, self.creator)?;
+                let texture = self
+                    .font_interface
+                    .render(&shown, &properties, self.creator)?;
                 SingleLineLabelCache {
-                    text_rendered: text.to_string(),
-                    texture,
+                    text_rendered: shown.to_string(),
+                    version_rendered: version,
+                    texture: Rc::new(texture),
                     properties_rendered: properties,
+                    generation,
                 }
             }
         };
@@ -570,6 +978,9 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
 
         // the implementation of SingleLineFontStyle typically gives a 1x1
         // replacement texture for rendering text of zero length
+        // maps from the texture's x to the text input's x: shift, then scale
+        let mut text_transform: Option<(f32, f32)> = None;
+
         let caret_position = if !cache.text_rendered.is_empty() && query.height != 0 {
             let new_height = position.height() as f32;
 
@@ -594,6 +1005,7 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                         new_height,
                     ),
                 )?;
+                text_transform = Some((0., scaler));
                 CaretPosition::Other(new_width)
             } else {
                 let width_portion = if new_width == 0. {
@@ -616,12 +1028,38 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
                     ),
                     position,
                 )?;
+                if width_amount != 0 {
+                    text_transform = Some((
+                        (query.width - width_amount) as f32,
+                        position.width() as f32 / width_amount as f32,
+                    ));
+                }
                 CaretPosition::Right
             }
         } else {
             CaretPosition::Left
         };
 
+        // with a mask, the caret is after the typed text rather than the
+        // placeholders
+        let caret_end = match self.mask {
+            Some(_) => Some(text.len()),
+            None => self.caret.map(|caret| caret_index(Some(caret), &text)),
+        };
+        let caret_position = match (caret_end, text_transform) {
+            (Some(caret_end), Some((shift, scale))) => {
+                let width = if caret_end == 0 {
+                    0
+                } else {
+                    self.font_interface
+                        .render_dimensions(&text[..caret_end], point_size)?
+                        .0
+                };
+                CaretPosition::Other(((width as f32 - shift) * scale).max(0.))
+            }
+            _ => caret_position,
+        };
+
         self.cache = Some(cache);
 
         // apply the style
@@ -639,7 +1077,7 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
             (position.width(), position.height()),
             &text,
             self.creator,
-            canvas,
+            &mut canvas,
             match caret_position {
                 CaretPosition::Left => 0.,
                 CaretPosition::Right => position.width().saturating_sub(1) as f32,
@@ -649,6 +1087,47 @@ impl<'sdl, 'state> Widget for SingleLineTextInput<'sdl, 'state> {
 
         canvas.copy(txt, None, Some(position))?;
 
+        self.misspellings.clear();
+        if let (Some(spell_check), Some((shift, scale))) =
+            (self.spell_check.as_mut(), text_transform)
+        {
+            let color = spell_check.color;
+            let font_interface = &mut self.font_interface;
+            let mut width = |text: &str| -> UiResult<u32> {
+                if text.is_empty() {
+                    return Ok(0);
+                }
+                Ok(font_interface.render_dimensions(text, point_size)?.0)
+            };
+            // in the texture's coordinates
+            let located = spell_check.located(&text, (point_size, 0, generation), |range| {
+                let x = width(&text[..range.start])?;
+                let x_end = width(&text[..range.end])?;
+                Ok(vec![sdl2::rect::Rect::new(
+                    x as i32,
+                    0,
+                    x_end.saturating_sub(x).max(1),
+                    query.height,
+                )])
+            })?;
+            let mut canvas = ClipScope::intersect(&mut canvas, Some(position));
+            for (range, rect) in located {
+                let text_x = |x: i32| position.x() + ((x as f32 - shift) * scale).round() as i32;
+                let x = text_x(rect.x());
+                let x_end = text_x(rect.right());
+                draw_squiggle(&mut canvas, color, x, x_end, position.bottom() - 1)?;
+                self.misspellings.push((
+                    range.clone(),
+                    sdl2::rect::Rect::new(
+                        x,
+                        position.y(),
+                        (x_end - x).max(1) as u32,
+                        position.height(),
+                    ),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,265 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded() {
+        let mut values = SparklineValues::new(3);
+        assert_eq!(values.range(), None);
+        values.push(1.);
+        values.push(5.);
+        values.push(2.);
+        values.push(3.); // the 1 is dropped
+        assert_eq!(values.iter().collect::<Vec<_>>(), vec![5., 2., 3.]);
+        assert_eq!(values.range(), Some((2., 5.)));
+        values.push(f32::NAN); // ignored
+        assert_eq!(values.len(), 3);
+
+        let mut none = SparklineValues::new(0);
+        none.push(1.);
+        assert_eq!(none.len(), 0);
+    }
+}
+
+use std::collections::VecDeque;
+
+use sdl2::{pixels::Color, rect::Rect};
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy},
+    rect::FRect,
+    render::CanvasStateGuard,
+    rust::CellRefOrCell,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// the most recent values, up to some capacity. pushing past the capacity
+/// drops the oldest
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparklineValues {
+    values: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl Default for SparklineValues {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+impl SparklineValues {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// add the newest value. non finite values are ignored
+    pub fn push(&mut self, value: f32) {
+        if !value.is_finite() || self.capacity == 0 {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = f32> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// the smallest and largest value. none if empty
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.iter().fold(None, |range, v| match range {
+            None => Some((v, v)),
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SparklineKind {
+    #[default]
+    Line,
+    Bar,
+}
+
+/// a small chart of recent values (e.g. fps or network throughput), meant to
+/// sit beside labels. it's drawn directly each frame instead of cached, so
+/// the values can change every frame. the newest value is on the right
+pub struct Sparkline<'state> {
+    pub values: CellRefOrCell<'state, SparklineValues>,
+    pub kind: SparklineKind,
+    pub color: Color,
+    /// drawn behind the chart. none draws nothing
+    pub background: Option<Color>,
+    /// the values at the bottom and top of the chart. none fits the chart to
+    /// the values shown
+    pub range: Option<(f32, f32)>,
+    /// in logical pixels (scaled by the ui scale). like a label, the size is
+    /// fixed
+    pub width: f32,
+    /// in logical pixels (scaled by the ui scale)
+    pub height: f32,
+
+    pub min_w_fail_policy: MinLenFailPolicy,
+    pub max_w_fail_policy: MaxLenFailPolicy,
+    pub min_h_fail_policy: MinLenFailPolicy,
+    pub max_h_fail_policy: MaxLenFailPolicy,
+
+    /// state stored for draw from update
+    draw_pos: FRect,
+}
+
+impl<'state> Sparkline<'state> {
+    pub fn new(values: impl Into<CellRefOrCell<'state, SparklineValues>>) -> Self {
+        Self {
+            values: values.into(),
+            kind: Default::default(),
+            color: Color::RGB(118, 73, 206),
+            background: None,
+            range: None,
+            width: 60.,
+            height: 16.,
+            min_w_fail_policy: Default::default(),
+            max_w_fail_policy: Default::default(),
+            min_h_fail_policy: Default::default(),
+            max_h_fail_policy: Default::default(),
+            draw_pos: Default::default(),
+        }
+    }
+}
+
+impl<'state> Widget for Sparkline<'state> {
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        Ok((MinLen(self.width).scaled(), MinLen(self.height).scaled()))
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.min_w_fail_policy
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.min_h_fail_policy
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        Ok((MaxLen(self.width).scaled(), MaxLen(self.height).scaled()))
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.max_w_fail_policy
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.max_h_fail_policy
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        _focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let position: Option<Rect> = self.draw_pos.into();
+        let position = match position {
+            Some(v) => v,
+            None => return Ok(()), // can't draw zero size
+        };
+        let mut canvas = CanvasStateGuard::new(canvas);
+        if let Some(background) = self.background {
+            canvas.set_draw_color(background);
+            canvas.fill_rect(position)?;
+        }
+
+        let values = self.values.scope_take();
+        let (bottom, top) = match self.range.or_else(|| values.range()) {
+            Some(v) => v,
+            None => return Ok(()), // nothing to draw
+        };
+        let capacity = values.capacity().max(1);
+        // each value gets a slot, and the newest is in the rightmost slot
+        let slot_w = self.draw_pos.w / capacity as f32;
+        let first_slot = capacity.saturating_sub(values.len());
+        // from a value to the distance from the bottom, from 0 to 1
+        let portion = |v: f32| {
+            if top > bottom {
+                ((v - bottom) / (top - bottom)).clamp(0., 1.)
+            } else {
+                0.5 // guard div; flat
+            }
+        };
+
+        canvas.set_draw_color(self.color);
+        let bottom_y = self.draw_pos.y + self.draw_pos.h;
+        match self.kind {
+            SparklineKind::Line => {
+                let points: Vec<sdl2::rect::Point> = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        let x = self.draw_pos.x + ((first_slot + i) as f32 + 0.5) * slot_w;
+                        // the top of the chart is the last row of pixels
+                        let y = bottom_y - 1. - portion(v) * (self.draw_pos.h - 1.);
+                        sdl2::rect::Point::new(x.round() as i32, y.round() as i32)
+                    })
+                    .collect();
+                match points.len() {
+                    0 => {}
+                    1 => canvas.draw_point(points[0])?,
+                    _ => canvas.draw_lines(points.as_slice())?,
+                }
+            }
+            SparklineKind::Bar => {
+                let rects: Vec<Rect> = values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, v)| {
+                        let left = self.draw_pos.x + (first_slot + i) as f32 * slot_w;
+                        let right = left + slot_w;
+                        let bar_top = bottom_y - portion(v) * self.draw_pos.h;
+                        let w = (right.round() - left.round()).max(1.) as u32;
+                        let h = (bottom_y.round() - bar_top.round()) as u32;
+                        if h == 0 {
+                            return None;
+                        }
+                        Some(Rect::new(left.round() as i32, bar_top.round() as i32, w, h))
+                    })
+                    .collect();
+                canvas.fill_rects(rects.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+}
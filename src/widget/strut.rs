@@ -1,4 +1,5 @@
 use crate::util::{
+    error::UiResult,
     focus::FocusManager,
     length::{MaxLen, MinLen, PreferredPortion},
 };
@@ -44,16 +45,16 @@ impl Widget for Strut {
         &mut self,
         _canvas: &mut sdl2::render::WindowCanvas,
         _focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
         Ok(())
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        Ok((self.max_w, self.max_h))
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        Ok((self.max_w.scaled(), self.max_h.scaled()))
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        Ok((self.min_w, self.min_h))
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        Ok((self.min_w.scaled(), self.min_h.scaled()))
     }
 
     fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
@@ -0,0 +1,268 @@
+use sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::{BlendMode, TextureCreator, WindowCanvas},
+    video::WindowContext,
+};
+
+use crate::util::{
+    error::{UiError, UiResult},
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    render::{owned_texture, style_generation, CanvasStateGuard, OwnedTexture},
+    rust::CellRefOrCell,
+};
+
+use super::{checkbox::VariantTransition, Widget, WidgetUpdateEvent};
+
+/// a texture the size of the window, which a widget is drawn into so it can be
+/// composited with some opacity or offset (e.g. while fading between views)
+pub(crate) struct LayerTexture<'sdl> {
+    texture: OwnedTexture<'sdl>,
+    size: (u32, u32),
+    /// see style_generation
+    generation: u64,
+}
+
+impl<'sdl> LayerTexture<'sdl> {
+    /// draw a widget into the layer, then copy src from the layer to dst on
+    /// the canvas with some opacity. the slot's texture is reused if still
+    /// valid
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn draw(
+        slot: &mut Option<Self>,
+        creator: &'sdl TextureCreator<WindowContext>,
+        widget: &mut dyn Widget,
+        opacity: f32,
+        src: Rect,
+        dst: Rect,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let size = canvas.output_size()?;
+        let generation = style_generation();
+        let layer = match slot
+            .take()
+            .filter(|layer| layer.size == size && layer.generation == generation)
+        {
+            Some(v) => v,
+            None => {
+                let mut texture = creator
+                    .create_texture_target(PixelFormatEnum::ARGB8888, size.0, size.1)
+                    .map(owned_texture)?;
+                texture.set_blend_mode(BlendMode::Blend);
+                LayerTexture {
+                    texture,
+                    size,
+                    generation,
+                }
+            }
+        };
+        let layer = slot.insert(layer);
+
+        let clip_rect = canvas.clip_rect();
+        let mut e_out: Option<UiError> = None;
+        canvas.with_texture_canvas(&mut layer.texture, |canvas| {
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+            canvas.clear();
+            canvas.set_clip_rect(clip_rect);
+            if let Err(e) = widget.draw(canvas, focus_manager) {
+                e_out = Some(e);
+            }
+        })?;
+        if let Some(e) = e_out {
+            return Err(e);
+        }
+
+        layer
+            .texture
+            .set_alpha_mod((opacity.clamp(0., 1.) * 255.).round() as u8);
+        let r = canvas.copy(&layer.texture, src, dst);
+        layer.texture.set_alpha_mod(0xFF);
+        r.map_err(UiError::Sdl)
+    }
+}
+
+/// contains several views, and shows only one of them at a time (e.g. the
+/// pages of a wizard, or screens which depend on the application's state).
+///
+/// only the shown view is updated and drawn, and the sizing is that of the
+/// shown view. if the index is out of range, nothing is shown
+pub struct Swapper<'sdl, 'state> {
+    pub views: Vec<Box<dyn Widget + 'sdl>>,
+    /// the index of the shown view
+    pub index: CellRefOrCell<'state, usize>,
+    /// crossfade between views when the index changes. disabled by default
+    pub transition: VariantTransition<usize>,
+
+    creator: &'sdl TextureCreator<WindowContext>,
+    /// state stored for draw from update
+    draw_pos: crate::util::rect::FRect,
+    /// for the outgoing and incoming view, while fading
+    fade_textures: [Option<LayerTexture<'sdl>>; 2],
+}
+
+impl<'sdl, 'state> Swapper<'sdl, 'state> {
+    pub fn new(
+        views: Vec<Box<dyn Widget + 'sdl>>,
+        index: impl Into<CellRefOrCell<'state, usize>>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            views,
+            index: index.into(),
+            transition: Default::default(),
+            creator,
+            draw_pos: Default::default(),
+            fade_textures: Default::default(),
+        }
+    }
+
+    fn active(&mut self) -> Option<&mut Box<dyn Widget + 'sdl>> {
+        let index = self.index.get();
+        self.views.get_mut(index)
+    }
+}
+
+impl<'sdl, 'state> Widget for Swapper<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        // all views, so attach and detach are balanced
+        self.views
+            .iter_mut()
+            .map(|view| view.as_mut() as &mut dyn Widget)
+            .collect()
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.draw_pos = event.position;
+        match self.active() {
+            Some(view) => view.update(event),
+            None => Ok(()),
+        }
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        self.draw_pos.x += pos_delta.0 as f32;
+        self.draw_pos.y += pos_delta.1 as f32;
+        if let Some(view) = self.active() {
+            view.update_adjust_position(pos_delta);
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas, focus_manager: &FocusManager) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let index = self.index.get();
+        let layers = self.transition.layers(index);
+        let position: Option<Rect> = self.draw_pos.into();
+        let position = match (layers, position) {
+            ([Some(_), _], Some(position)) => position,
+            _ => {
+                // not fading
+                self.fade_textures = Default::default();
+                return match self.views.get_mut(index) {
+                    Some(view) => view.draw(&mut canvas, focus_manager),
+                    None => Ok(()),
+                };
+            }
+        };
+        // the outgoing view is drawn where it was when it was last updated
+        for (layer, (view_index, opacity)) in layers
+            .into_iter()
+            .enumerate()
+            .filter_map(|(layer, l)| l.map(|l| (layer, l)))
+        {
+            let view = match self.views.get_mut(view_index) {
+                Some(v) => v,
+                None => continue,
+            };
+            LayerTexture::draw(
+                &mut self.fade_textures[layer],
+                self.creator,
+                view.as_mut(),
+                opacity,
+                position,
+                position,
+                &mut canvas,
+                focus_manager,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        match self.active() {
+            Some(view) => view.draw_overlay(canvas, focus_manager),
+            None => Ok(()),
+        }
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        match self.active() {
+            Some(view) => view.min(),
+            None => Ok((MinLen::LAX, MinLen::LAX)),
+        }
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.min_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.min_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        match self.active() {
+            Some(view) => view.max(),
+            None => Ok((MaxLen::LAX, MaxLen::LAX)),
+        }
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.max_w_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.max_h_fail_policy(),
+            None => Default::default(),
+        }
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.preferred_portion(),
+            None => (PreferredPortion::FULL, PreferredPortion::FULL),
+        }
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.active()
+            .and_then(|view| view.preferred_width_from_height(pref_h))
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.active()
+            .and_then(|view| view.preferred_height_from_width(pref_w))
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        match self.views.get(self.index.get()) {
+            Some(view) => view.preferred_link_allowed_exceed_portion(),
+            None => false,
+        }
+    }
+}
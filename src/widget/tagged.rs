@@ -0,0 +1,160 @@
+use std::any::Any;
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    hit::HitTest,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+/// application data attached to a widget, from Widget::user_data
+pub struct UserData<'a> {
+    pub data: &'a dyn Any,
+    /// where the widget was during the most recent update. None if it hasn't
+    /// been updated yet
+    pub hit: Option<&'a HitTest>,
+}
+
+/// attaches application data to a widget (e.g. a row index, or an entity id),
+/// so it can be recovered while walking the widget tree. see user_data_at and
+/// collect_user_data
+pub struct Tagged<'sdl, T: 'static> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub data: T,
+    /// state stored from update
+    hit: Option<HitTest>,
+}
+
+impl<'sdl, T: 'static> Tagged<'sdl, T> {
+    pub fn new(contained: Box<dyn Widget + 'sdl>, data: T) -> Self {
+        Self {
+            contained,
+            data,
+            hit: None,
+        }
+    }
+}
+
+/// the data of the innermost tagged widget (with data of type T) that is under
+/// a point, in window coordinates. e.g. the mouse position, or the center of
+/// the focus manager's focused_rect
+pub fn user_data_at<T: Clone + 'static>(
+    widget: &mut dyn Widget,
+    window_id: u32,
+    x: i32,
+    y: i32,
+) -> Option<T> {
+    let mut found = None;
+    if let Some(user_data) = widget.user_data() {
+        let hit = user_data
+            .hit
+            .map(|hit| hit.contains(window_id, x, y))
+            .unwrap_or(false);
+        if !hit {
+            // the children of a tagged widget are within its position
+            return None;
+        }
+        found = user_data.data.downcast_ref::<T>().cloned();
+    }
+    for child in widget.children() {
+        if let Some(inner) = user_data_at(child, window_id, x, y) {
+            return Some(inner);
+        }
+    }
+    found
+}
+
+/// the data of all tagged widgets (with data of type T), in tree order
+pub fn collect_user_data<T: Clone + 'static>(widget: &mut dyn Widget) -> Vec<T> {
+    let mut out = Vec::new();
+    collect_user_data_impl(widget, &mut out);
+    out
+}
+
+fn collect_user_data_impl<T: Clone + 'static>(widget: &mut dyn Widget, out: &mut Vec<T>) {
+    if let Some(data) = widget
+        .user_data()
+        .and_then(|user_data| user_data.data.downcast_ref::<T>())
+    {
+        out.push(data.clone());
+    }
+    for child in widget.children() {
+        collect_user_data_impl(child, out);
+    }
+}
+
+impl<'sdl, T: 'static> Widget for Tagged<'sdl, T> {
+    fn user_data(&self) -> Option<UserData<'_>> {
+        Some(UserData {
+            data: &self.data,
+            hit: self.hit.as_ref(),
+        })
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        self.hit = Some(HitTest::from_event(&event));
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        if let Some(hit) = &mut self.hit {
+            hit.position.x += pos_delta.0 as f32;
+            hit.position.y += pos_delta.1 as f32;
+        }
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}
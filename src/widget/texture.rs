@@ -1,10 +1,11 @@
 use std::ops::Not;
 
 use crate::util::{
+    error::{UiError, UiResult},
     focus::FocusManager,
     length::{
-        AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy, MaxLenPolicy, MinLen,
-        MinLenFailPolicy, MinLenPolicy, PreferredPortion,
+        parent_height, parent_width, AspectRatioPreferredDirection, MaxLen, MaxLenFailPolicy,
+        MaxLenPolicy, MinLen, MinLenFailPolicy, MinLenPolicy, PreferredPortion,
     },
 };
 
@@ -42,10 +43,10 @@ impl Default for AspectRatioFailPolicy {
     }
 }
 
-/// widget for a static sdl2 texture
+/// widget for a static sdl2 texture. to only register the mouse where the
+/// texture is opaque, wrap it in an AlphaHit
 pub struct Texture<'sdl> {
-    // use unsafe textures instead!
-    pub texture: &'sdl sdl2::render::Texture<'sdl>,
+    pub texture: &'sdl crate::util::render::SdlTexture<'sdl>,
     /// none means use the entire texture
     pub texture_src: Option<sdl2::rect::Rect>,
 
@@ -72,7 +73,7 @@ pub struct Texture<'sdl> {
 }
 
 impl<'sdl> Texture<'sdl> {
-    pub fn new(texture: &'sdl sdl2::render::Texture<'sdl>) -> Texture<'sdl> {
+    pub fn new(texture: &'sdl crate::util::render::SdlTexture<'sdl>) -> Texture<'sdl> {
         Texture {
             texture,
             texture_src: Default::default(),
@@ -99,24 +100,18 @@ impl<'sdl> Widget for Texture<'sdl> {
         self.preferred_link_allowed_exceed_portion
     }
 
-    fn min(&mut self) -> Result<(MinLen, MinLen), String> {
-        if let MinLenPolicy::Literal(w) = self.min_w_policy {
-            if let MinLenPolicy::Literal(h) = self.min_h_policy {
-                return Ok((w, h)); // no need to query texture
-            }
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let w = self.min_w_policy.resolve(parent_width());
+        let h = self.min_h_policy.resolve(parent_height());
+        if let (Some(w), Some(h)) = (w, h) {
+            return Ok((w, h)); // no need to query texture
         }
 
         // texture querying is fast. just does a struct lookup
         let query = self.texture.query();
         Ok((
-            match self.min_w_policy {
-                MinLenPolicy::Children => MinLen(query.width as f32),
-                MinLenPolicy::Literal(min_len) => min_len,
-            },
-            match self.min_h_policy {
-                MinLenPolicy::Children => MinLen(query.height as f32),
-                MinLenPolicy::Literal(min_len) => min_len,
-            },
+            w.unwrap_or(MinLen(query.width as f32).scaled()),
+            h.unwrap_or(MinLen(query.height as f32).scaled()),
         ))
     }
 
@@ -128,24 +123,18 @@ impl<'sdl> Widget for Texture<'sdl> {
         self.min_h_fail_policy
     }
 
-    fn max(&mut self) -> Result<(MaxLen, MaxLen), String> {
-        if let MaxLenPolicy::Literal(w) = self.max_w_policy {
-            if let MaxLenPolicy::Literal(h) = self.max_h_policy {
-                return Ok((w, h)); // no need to query texture
-            }
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let w = self.max_w_policy.resolve(parent_width());
+        let h = self.max_h_policy.resolve(parent_height());
+        if let (Some(w), Some(h)) = (w, h) {
+            return Ok((w, h)); // no need to query texture
         }
 
         // texture querying is fast. just does a struct lookup
         let query = self.texture.query();
         Ok((
-            match self.max_w_policy {
-                MaxLenPolicy::Children => MaxLen(query.width as f32),
-                MaxLenPolicy::Literal(max_len) => max_len,
-            },
-            match self.max_h_policy {
-                MaxLenPolicy::Children => MaxLen(query.height as f32),
-                MaxLenPolicy::Literal(max_len) => max_len,
-            },
+            w.unwrap_or(MaxLen(query.width as f32).scaled()),
+            h.unwrap_or(MaxLen(query.height as f32).scaled()),
         ))
     }
 
@@ -161,7 +150,7 @@ impl<'sdl> Widget for Texture<'sdl> {
         (self.pref_w, self.pref_h)
     }
 
-    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<Result<f32, String>> {
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
         if self.request_aspect_ratio.not() {
             return None;
         }
@@ -173,7 +162,7 @@ impl<'sdl> Widget for Texture<'sdl> {
         )))
     }
 
-    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<Result<f32, String>> {
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
         if self.request_aspect_ratio.not() {
             return None;
         }
@@ -186,7 +175,7 @@ impl<'sdl> Widget for Texture<'sdl> {
         )))
     }
 
-    fn update(&mut self, event: WidgetUpdateEvent) -> Result<(), String> {
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
         self.draw_pos = event.position;
         Ok(())
     }
@@ -200,7 +189,7 @@ impl<'sdl> Widget for Texture<'sdl> {
         &mut self,
         canvas: &mut sdl2::render::WindowCanvas,
         _focus_manager: &FocusManager,
-    ) -> Result<(), String> {
+    ) -> UiResult<()> {
         texture_draw(
             self.texture,
             &self.aspect_ratio_fail_policy,
@@ -217,7 +206,20 @@ pub(crate) fn texture_draw(
     canvas: &mut sdl2::render::WindowCanvas,
     src: Option<sdl2::rect::Rect>,
     dst: crate::util::rect::FRect,
-) -> Result<(), String> {
+) -> UiResult<()> {
+    texture_draw_rotated(texture, aspect_ratio_fail_policy, canvas, src, dst, 0.)
+}
+
+/// texture_draw, except the texture is rotated clockwise by some degrees about
+/// the center of dst. dst and the policy are before rotation
+pub(crate) fn texture_draw_rotated(
+    texture: &sdl2::render::Texture,
+    aspect_ratio_fail_policy: &AspectRatioFailPolicy,
+    canvas: &mut sdl2::render::WindowCanvas,
+    src: Option<sdl2::rect::Rect>,
+    dst: crate::util::rect::FRect,
+    angle: f64,
+) -> UiResult<()> {
     // dst is kept as float form until just before canvas copy. needed or else
     // it is jumpy
 
@@ -232,6 +234,26 @@ pub(crate) fn texture_draw(
     if src_w == 0 || src_h == 0 {
         return Ok(()); // can't draw empty. also guards against div by 0
     }
+    crate::util::perf::count(crate::util::perf::Counter::CanvasCopy);
+
+    let rotation_center = dst.center();
+    let copy = |canvas: &mut sdl2::render::WindowCanvas,
+                src: Option<sdl2::rect::Rect>,
+                dst: Option<sdl2::rect::Rect>| {
+        if angle == 0. {
+            return canvas.copy(texture, src, dst).map_err(UiError::Sdl);
+        }
+        // relative to the drawn rect, which might be offset by the policy
+        let center = dst.map(|dst| {
+            sdl2::rect::Point::new(
+                (rotation_center.0 - dst.x() as f32).round() as i32,
+                (rotation_center.1 - dst.y() as f32).round() as i32,
+            )
+        });
+        canvas
+            .copy_ex(texture, src, dst, angle, center, false, false)
+            .map_err(UiError::Sdl)
+    };
 
     match aspect_ratio_fail_policy {
         AspectRatioFailPolicy::Stretch => {
@@ -239,7 +261,7 @@ pub(crate) fn texture_draw(
                 None => return Ok(()), // can't draw zero size
                 Some(v) => v,
             };
-            canvas.copy(texture, src, Some(dst))
+            copy(canvas, src, Some(dst))
         }
         AspectRatioFailPolicy::ZoomOut((zoom_x, zoom_y)) => {
             let src_w = src_w as f32;
@@ -261,8 +283,8 @@ pub(crate) fn texture_draw(
                 }
 
                 let dst_y_offset = ((dst.h - dst_height as f32) * zoom_y).round() as i32;
-                canvas.copy(
-                    texture,
+                copy(
+                    canvas,
                     src,
                     Some(sdl2::rect::Rect::new(
                         dst.x.round() as i32,
@@ -282,8 +304,8 @@ pub(crate) fn texture_draw(
                 }
 
                 let dst_x_offset = ((dst.w - dst_width as f32) * zoom_x) as i32;
-                canvas.copy(
-                    texture,
+                copy(
+                    canvas,
                     src,
                     Some(sdl2::rect::Rect::new(
                         dst.x.round() as i32 + dst_x_offset,
@@ -312,8 +334,8 @@ pub(crate) fn texture_draw(
                     return Ok(()); // too extreme of a ratio
                 }
                 let x = ((src_w_f - width as f32) * zoom_x) as i32;
-                canvas.copy(
-                    texture,
+                copy(
+                    canvas,
                     Some(sdl2::rect::Rect::new(src_x + x, src_y, width, src_h)),
                     Some(dst_sdl2),
                 )
@@ -324,8 +346,8 @@ pub(crate) fn texture_draw(
                     return Ok(()); // too extreme of a ratio
                 }
                 let y = ((src_h_f - height as f32) * zoom_y) as i32;
-                canvas.copy(
-                    texture,
+                copy(
+                    canvas,
                     Some(sdl2::rect::Rect::new(src_x, src_y + y, src_w, height)),
                     Some(dst_sdl2),
                 )
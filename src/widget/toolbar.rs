@@ -0,0 +1,482 @@
+use sdl2::{
+    keyboard::Keycode,
+    mouse::MouseButton,
+    render::{TextureCreator, WindowCanvas},
+    video::WindowContext,
+};
+
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    font::SingleLineFontStyle,
+    length::{clamp, MaxLen, MinLen},
+    rect::FRect,
+    render::CanvasStateGuard,
+};
+
+use super::{
+    menu::{MenuAction, MenuStyle, MenuText, PopupLayout},
+    Widget, WidgetUpdateEvent,
+};
+
+/// the text of the button which opens the overflow list
+const OVERFLOW_LABEL: &str = "»";
+
+pub struct ToolbarItem<'sdl> {
+    /// shown in the toolbar while it fits (e.g. an icon button)
+    pub widget: Box<dyn Widget + 'sdl>,
+    /// shown in the overflow list instead, when it doesn't fit
+    pub label: String,
+    /// run when picked from the overflow list. typically does the same as the
+    /// widget
+    pub action: MenuAction<'sdl>,
+}
+
+impl<'sdl> ToolbarItem<'sdl> {
+    pub fn new(widget: Box<dyn Widget + 'sdl>, label: &str, action: MenuAction<'sdl>) -> Self {
+        Self {
+            widget,
+            label: label.to_owned(),
+            action,
+        }
+    }
+}
+
+/// lays out items in a row. items that don't fit are moved (from the end) into
+/// an overflow list, opened with a button at the end of the row. this is
+/// recalculated each update, so it follows resizing.
+///
+/// items in the overflow list aren't updated or drawn. the list is drawn in
+/// the overlay pass (see draw_gui), so the toolbar should be updated before
+/// the widgets the list covers
+///
+/// while the list is open: arrows navigate, return or space activates, escape
+/// closes
+pub struct Toolbar<'sdl> {
+    pub items: Vec<ToolbarItem<'sdl>>,
+    /// the overflow button and list are drawn with this
+    pub style: MenuStyle,
+    /// space between items, in logical pixels (scaled by the ui scale)
+    pub gap: f32,
+
+    /// how many items fit, as of the most recent update
+    visible: usize,
+    overflow_open: bool,
+    /// the highlighted entry in the overflow list
+    highlighted: Option<usize>,
+
+    /// state stored for draw from update
+    position: FRect,
+    item_positions: Vec<FRect>,
+    overflow_button: Option<FRect>,
+    popup: Option<PopupLayout>,
+
+    text: MenuText<'sdl>,
+}
+
+impl<'sdl> Toolbar<'sdl> {
+    pub fn new(
+        items: Vec<ToolbarItem<'sdl>>,
+        font_interface: Box<dyn SingleLineFontStyle<'sdl> + 'sdl>,
+        creator: &'sdl TextureCreator<WindowContext>,
+    ) -> Self {
+        Self {
+            items,
+            style: Default::default(),
+            gap: 4.,
+            visible: 0,
+            overflow_open: false,
+            highlighted: None,
+            position: Default::default(),
+            item_positions: Vec::new(),
+            overflow_button: None,
+            popup: None,
+            text: MenuText::new(font_interface, creator),
+        }
+    }
+
+    /// how many items are shown in the row, as of the most recent update. the
+    /// rest are in the overflow list
+    pub fn visible_count(&self) -> usize {
+        self.visible
+    }
+
+    pub fn is_overflow_open(&self) -> bool {
+        self.overflow_open
+    }
+
+    pub fn close_overflow(&mut self) {
+        self.overflow_open = false;
+        self.highlighted = None;
+        self.popup = None;
+    }
+
+    fn overflow_button_width(&mut self, h: f32) -> UiResult<f32> {
+        let point_size = self.style.scaled_point_size();
+        let w = self.text.size(OVERFLOW_LABEL, point_size)?.0 + 2. * self.style.scaled_padding();
+        Ok(w.max(h))
+    }
+
+    /// the width of each item at some height
+    fn item_widths(&mut self, h: f32) -> UiResult<Vec<f32>> {
+        let mut widths = Vec::new();
+        for item in self.items.iter_mut() {
+            let (min_w, _) = item.widget.min()?;
+            let (max_w, _) = item.widget.max()?;
+            let w = match item.widget.preferred_width_from_height(h) {
+                Some(w) => w?,
+                None => min_w.0,
+            };
+            widths.push(clamp(w, min_w, max_w));
+        }
+        Ok(widths)
+    }
+
+    /// decide what fits and where everything goes
+    fn compute_layout(&mut self, position: FRect, window_area: FRect) -> UiResult<()> {
+        let gap = crate::util::scale::scaled(self.gap);
+        let widths = self.item_widths(position.h)?;
+        let total: f32 = widths.iter().sum::<f32>() + gap * widths.len().saturating_sub(1) as f32;
+
+        let available = if total <= position.w {
+            position.w
+        } else {
+            // leave room for the overflow button
+            position.w - self.overflow_button_width(position.h)? - gap
+        };
+
+        self.item_positions.clear();
+        let mut x = position.x;
+        for w in widths.iter() {
+            if x + w > position.x + available {
+                break;
+            }
+            self.item_positions.push(FRect {
+                x,
+                y: position.y,
+                w: *w,
+                h: position.h,
+            });
+            x += w + gap;
+        }
+        self.visible = self.item_positions.len();
+
+        if self.visible == self.items.len() {
+            self.overflow_button = None;
+            self.close_overflow();
+            return Ok(());
+        }
+
+        let button_w = self.overflow_button_width(position.h)?;
+        let button = FRect {
+            x: position.x + position.w - button_w,
+            y: position.y,
+            w: button_w,
+            h: position.h,
+        };
+        self.overflow_button = Some(button);
+
+        if !self.overflow_open {
+            self.popup = None;
+            return Ok(());
+        }
+
+        // the list hangs below the button, right aligned with it
+        let padding = self.style.scaled_padding();
+        let point_size = self.style.scaled_point_size();
+        let line_height = self.style.line_height();
+        let mut width: f32 = 0.;
+        for i in self.visible..self.items.len() {
+            let label = self.items[i].label.clone();
+            width = width.max(self.text.size(&label, point_size)?.0 + 2. * padding);
+        }
+        let x = (button.right() - width).max(window_area.x);
+        let y = button.bottom();
+        let items: Vec<FRect> = (0..self.items.len() - self.visible)
+            .map(|i| FRect {
+                x,
+                y: y + i as f32 * line_height,
+                w: width,
+                h: line_height,
+            })
+            .collect();
+        self.popup = Some(PopupLayout {
+            position: FRect {
+                x,
+                y,
+                w: width,
+                h: items.len() as f32 * line_height,
+            },
+            items,
+        });
+        Ok(())
+    }
+
+    /// run the action of an entry in the overflow list
+    fn activate(&mut self, index: usize) -> UiResult<()> {
+        let item = match self.items.get_mut(self.visible + index) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let r = (item.action)();
+        self.close_overflow();
+        r
+    }
+
+    fn move_highlight(&mut self, direction: isize) {
+        let len = (self.items.len() - self.visible) as isize;
+        if len <= 0 {
+            return;
+        }
+        let index = match self.highlighted {
+            Some(v) => v as isize + direction,
+            None if direction > 0 => 0,
+            None => len - 1,
+        };
+        self.highlighted = Some(index.rem_euclid(len) as usize);
+    }
+
+    fn in_overflow_button(&self, x: i32, y: i32) -> bool {
+        let r: Option<sdl2::rect::Rect> = self.overflow_button.and_then(|b| b.into());
+        r.map(|r| r.contains_point((x, y))).unwrap_or(false)
+    }
+
+    /// handle the overflow button and list. before the items are updated, so
+    /// the list gets the events over it first
+    fn update_overflow(&mut self, event: &mut WidgetUpdateEvent) -> UiResult<()> {
+        for sdl_event in event.events.iter_mut().filter(|e| e.available()) {
+            match sdl_event.e {
+                sdl2::event::Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    window_id,
+                    ..
+                } => {
+                    if window_id != event.window_id {
+                        continue; // not for me!
+                    }
+                    if self.in_overflow_button(x, y) {
+                        sdl_event.set_consumed();
+                        if self.overflow_open {
+                            self.close_overflow();
+                        } else {
+                            self.overflow_open = true;
+                            self.compute_layout(event.position, event.window_area)?;
+                        }
+                    } else if self.overflow_open {
+                        // clicks on the list are handled on release. clicking
+                        // elsewhere closes it
+                        sdl_event.set_consumed();
+                        if !self.popup.as_ref().is_some_and(|p| p.contains(x, y)) {
+                            self.close_overflow();
+                        }
+                    }
+                }
+                sdl2::event::Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    window_id,
+                    ..
+                } => {
+                    if window_id != event.window_id || !self.overflow_open {
+                        continue;
+                    }
+                    let popup = match self.popup.as_ref() {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    if let Some(index) = popup.item_under(x, y) {
+                        sdl_event.set_consumed();
+                        self.activate(index)?;
+                    } else if popup.contains(x, y) {
+                        sdl_event.set_consumed();
+                    }
+                }
+                sdl2::event::Event::MouseMotion {
+                    x, y, window_id, ..
+                } => {
+                    if window_id != event.window_id || !self.overflow_open {
+                        continue;
+                    }
+                    if let Some(index) = self.popup.as_ref().and_then(|p| p.item_under(x, y)) {
+                        self.highlighted = Some(index);
+                    }
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if self.overflow_open => {
+                    match keycode {
+                        Keycode::Down => self.move_highlight(1),
+                        Keycode::Up => self.move_highlight(-1),
+                        Keycode::Return | Keycode::KpEnter | Keycode::Space => {
+                            if let Some(index) = self.highlighted {
+                                self.activate(index)?;
+                            }
+                        }
+                        Keycode::Escape => self.close_overflow(),
+                        _ => continue, // not for me
+                    }
+                    sdl_event.set_consumed();
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'sdl> Widget for Toolbar<'sdl> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        self.items
+            .iter_mut()
+            .map(|item| item.widget.as_mut() as &mut dyn Widget)
+            .collect()
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        let mut h = MinLen(self.style.line_height());
+        for item in self.items.iter_mut() {
+            h = h.strictest(item.widget.min()?.1);
+        }
+        let w = if self.items.is_empty() {
+            MinLen::LAX
+        } else {
+            // everything can overflow, but the button must fit
+            MinLen(self.overflow_button_width(h.0)?)
+        };
+        Ok((w, h))
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        let (_, min_h) = self.min()?;
+        Ok((MaxLen::LAX, MaxLen(min_h.0)))
+    }
+
+    fn update(&mut self, mut event: WidgetUpdateEvent) -> UiResult<()> {
+        self.position = event.position;
+        self.compute_layout(event.position, event.window_area)?;
+        self.update_overflow(&mut event)?;
+
+        for (item, position) in self.items.iter_mut().zip(self.item_positions.iter()) {
+            let position = crate::widget::place(
+                item.widget.as_mut(),
+                *position,
+                crate::util::length::AspectRatioPreferredDirection::WidthFromHeight,
+            )?;
+            let mut sub_event = event.sub_event(position);
+            sub_event.aspect_ratio_priority =
+                crate::util::length::AspectRatioPreferredDirection::WidthFromHeight;
+            item.widget.update(sub_event)?;
+        }
+        Ok(())
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        let (dx, dy) = (pos_delta.0 as f32, pos_delta.1 as f32);
+        self.position.x += dx;
+        self.position.y += dy;
+        for position in self.item_positions.iter_mut() {
+            position.x += dx;
+            position.y += dy;
+        }
+        if let Some(button) = self.overflow_button.as_mut() {
+            button.x += dx;
+            button.y += dy;
+        }
+        if let Some(popup) = self.popup.as_mut() {
+            popup.adjust_position(dx, dy);
+        }
+        for item in self.items.iter_mut().take(self.visible) {
+            item.widget.update_adjust_position(pos_delta);
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas, focus_manager: &FocusManager) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        let bar: Option<sdl2::rect::Rect> = self.position.into();
+        if let Some(bar) = bar {
+            canvas.set_draw_color(self.style.background);
+            canvas.fill_rect(bar)?;
+        }
+        for item in self.items.iter_mut().take(self.visible) {
+            item.widget.draw(&mut canvas, focus_manager)?;
+        }
+
+        let button = match self.overflow_button {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        if self.overflow_open {
+            let r: Option<sdl2::rect::Rect> = button.into();
+            if let Some(r) = r {
+                canvas.set_draw_color(self.style.highlight);
+                canvas.fill_rect(r)?;
+            }
+        }
+        let point_size = self.style.scaled_point_size();
+        let w = self.text.size(OVERFLOW_LABEL, point_size)?.0;
+        self.text.draw(
+            &mut canvas,
+            OVERFLOW_LABEL,
+            self.style.text,
+            point_size,
+            button.x + (button.w - w) / 2.,
+            button.y + button.h / 2.,
+        )?;
+        Ok(())
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        let mut canvas = CanvasStateGuard::new(canvas);
+        for item in self.items.iter_mut().take(self.visible) {
+            item.widget.draw_overlay(&mut canvas, focus_manager)?;
+        }
+
+        let popup = match self.popup.as_ref() {
+            Some(v) if self.overflow_open => v,
+            _ => return Ok(()),
+        };
+        let r: Option<sdl2::rect::Rect> = popup.position.into();
+        let r = match r {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        canvas.set_draw_color(self.style.background);
+        canvas.fill_rect(r)?;
+        canvas.set_draw_color(self.style.border);
+        canvas.draw_rect(r)?;
+
+        let padding = self.style.scaled_padding();
+        let point_size = self.style.scaled_point_size();
+        let rects = popup.items.clone();
+        for (index, rect) in rects.into_iter().enumerate() {
+            if self.highlighted == Some(index) {
+                let r: Option<sdl2::rect::Rect> = rect.into();
+                if let Some(r) = r {
+                    canvas.set_draw_color(self.style.highlight);
+                    canvas.fill_rect(r)?;
+                }
+            }
+            let label = match self.items.get(self.visible + index) {
+                Some(item) => item.label.clone(),
+                None => break,
+            };
+            self.text.draw(
+                &mut canvas,
+                &label,
+                self.style.text,
+                point_size,
+                rect.x + padding,
+                rect.y + rect.h / 2.,
+            )?;
+        }
+        Ok(())
+    }
+}
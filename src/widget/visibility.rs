@@ -0,0 +1,141 @@
+use crate::util::{
+    error::UiResult,
+    focus::FocusManager,
+    length::{MaxLen, MaxLenFailPolicy, MinLen, MinLenFailPolicy, PreferredPortion},
+    rust::CellRefOrCell,
+};
+
+use super::{Widget, WidgetUpdateEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    #[default]
+    Visible,
+    /// not drawn or updated (so it doesn't receive events), but still takes up
+    /// space in the layout
+    Hidden,
+    /// not drawn or updated, and takes no space; the layout reflows as if it
+    /// wasn't there
+    Collapsed,
+}
+
+/// shows, hides or collapses the contained, without rebuilding the widget tree.
+/// the state is typically bound to a Cell the application changes
+pub struct VisibilityToggle<'sdl, 'state> {
+    pub contained: Box<dyn Widget + 'sdl>,
+    pub visibility: CellRefOrCell<'state, Visibility>,
+}
+
+impl<'sdl, 'state> VisibilityToggle<'sdl, 'state> {
+    pub fn new(
+        contained: Box<dyn Widget + 'sdl>,
+        visibility: impl Into<CellRefOrCell<'state, Visibility>>,
+    ) -> Self {
+        Self {
+            contained,
+            visibility: visibility.into(),
+        }
+    }
+
+    fn collapsed(&self) -> bool {
+        self.visibility.get() == Visibility::Collapsed
+    }
+}
+
+impl<'sdl, 'state> Widget for VisibilityToggle<'sdl, 'state> {
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        // regardless of visibility, so attach and detach are balanced
+        vec![self.contained.as_mut() as &mut dyn Widget]
+    }
+
+    fn update(&mut self, event: WidgetUpdateEvent) -> UiResult<()> {
+        if self.visibility.get() != Visibility::Visible {
+            return Ok(());
+        }
+        self.contained.update(event)
+    }
+
+    fn update_adjust_position(&mut self, pos_delta: (i32, i32)) {
+        if self.visibility.get() != Visibility::Visible {
+            return;
+        }
+        self.contained.update_adjust_position(pos_delta);
+    }
+
+    fn draw(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        if self.visibility.get() != Visibility::Visible {
+            return Ok(());
+        }
+        self.contained.draw(canvas, focus_manager)
+    }
+
+    fn draw_overlay(
+        &mut self,
+        canvas: &mut sdl2::render::WindowCanvas,
+        focus_manager: &FocusManager,
+    ) -> UiResult<()> {
+        if self.visibility.get() != Visibility::Visible {
+            return Ok(());
+        }
+        self.contained.draw_overlay(canvas, focus_manager)
+    }
+
+    fn min(&mut self) -> UiResult<(MinLen, MinLen)> {
+        if self.collapsed() {
+            return Ok((MinLen::LAX, MinLen::LAX));
+        }
+        self.contained.min()
+    }
+
+    fn min_w_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_w_fail_policy()
+    }
+
+    fn min_h_fail_policy(&self) -> MinLenFailPolicy {
+        self.contained.min_h_fail_policy()
+    }
+
+    fn max(&mut self) -> UiResult<(MaxLen, MaxLen)> {
+        if self.collapsed() {
+            return Ok((MaxLen(0.), MaxLen(0.)));
+        }
+        self.contained.max()
+    }
+
+    fn max_w_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_w_fail_policy()
+    }
+
+    fn max_h_fail_policy(&self) -> MaxLenFailPolicy {
+        self.contained.max_h_fail_policy()
+    }
+
+    fn preferred_portion(&self) -> (PreferredPortion, PreferredPortion) {
+        if self.collapsed() {
+            return (PreferredPortion(0.), PreferredPortion(0.));
+        }
+        self.contained.preferred_portion()
+    }
+
+    fn preferred_width_from_height(&mut self, pref_h: f32) -> Option<UiResult<f32>> {
+        if self.collapsed() {
+            return None;
+        }
+        self.contained.preferred_width_from_height(pref_h)
+    }
+
+    fn preferred_height_from_width(&mut self, pref_w: f32) -> Option<UiResult<f32>> {
+        if self.collapsed() {
+            return None;
+        }
+        self.contained.preferred_height_from_width(pref_w)
+    }
+
+    fn preferred_link_allowed_exceed_portion(&self) -> bool {
+        self.contained.preferred_link_allowed_exceed_portion()
+    }
+}